@@ -0,0 +1,97 @@
+use crate::device::ADB;
+use crate::error::ADBResult;
+use crate::settings::Namespace;
+use log::debug;
+
+/// TalkBack 服务组件名
+pub const TALKBACK_SERVICE: &str =
+    "com.google.android.marvin.talkback/com.google.android.marvin.talkback.TalkBackService";
+
+const ENABLED_SERVICES: &str = "enabled_accessibility_services";
+const HIGH_TEXT_CONTRAST: &str = "high_text_contrast_enabled";
+const DISPLAY_INVERSION: &str = "accessibility_display_inversion_enabled";
+
+impl ADB {
+    /// 读取 secure 命名空间中的设置，未设置时返回空字符串
+    fn get_secure_setting(&self, device_id: &str, key: &str) -> ADBResult<String> {
+        Ok(self.get_setting(device_id, Namespace::Secure, key)?.unwrap_or_default())
+    }
+
+    /// 读取 secure 命名空间中的开关设置
+    fn get_secure_flag(&self, device_id: &str, key: &str) -> ADBResult<bool> {
+        Ok(self.get_secure_setting(device_id, key)? == "1")
+    }
+
+    /// 写入 secure 命名空间中的开关设置
+    fn set_secure_flag(&self, device_id: &str, key: &str, enabled: bool) -> ADBResult<()> {
+        self.put_setting(device_id, Namespace::Secure, key, if enabled { "1" } else { "0" })
+    }
+
+    /// 列出已启用的无障碍服务
+    pub fn list_accessibility_services(&self, device_id: &str) -> ADBResult<Vec<String>> {
+        Ok(self
+            .get_secure_setting(device_id, ENABLED_SERVICES)?
+            .split(':')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect())
+    }
+
+    /// 启用或禁用指定的无障碍服务，保留其他已启用的服务
+    pub fn set_accessibility_service(
+        &self,
+        device_id: &str,
+        component: &str,
+        enabled: bool,
+    ) -> ADBResult<()> {
+        let mut services = self.list_accessibility_services(device_id)?;
+        services.retain(|s| s != component);
+        if enabled {
+            services.push(component.to_string());
+        }
+
+        if services.is_empty() {
+            self.delete_setting(device_id, Namespace::Secure, ENABLED_SERVICES)?;
+            self.set_secure_flag(device_id, "accessibility_enabled", false)?;
+        } else {
+            self.put_setting(device_id, Namespace::Secure, ENABLED_SERVICES, &services.join(":"))?;
+            self.set_secure_flag(device_id, "accessibility_enabled", true)?;
+        }
+
+        debug!("设备 {} 无障碍服务 {}: {}", device_id, component, enabled);
+        Ok(())
+    }
+
+    /// 开启或关闭 TalkBack（需要设备已安装 TalkBack）
+    pub fn set_talkback(&self, device_id: &str, enabled: bool) -> ADBResult<()> {
+        self.set_accessibility_service(device_id, TALKBACK_SERVICE, enabled)
+    }
+
+    /// 查询 TalkBack 是否开启
+    pub fn is_talkback_enabled(&self, device_id: &str) -> ADBResult<bool> {
+        Ok(self
+            .list_accessibility_services(device_id)?
+            .iter()
+            .any(|s| s == TALKBACK_SERVICE))
+    }
+
+    /// 开启或关闭高对比度文字
+    pub fn set_high_contrast(&self, device_id: &str, enabled: bool) -> ADBResult<()> {
+        self.set_secure_flag(device_id, HIGH_TEXT_CONTRAST, enabled)
+    }
+
+    /// 查询高对比度文字是否开启
+    pub fn is_high_contrast_enabled(&self, device_id: &str) -> ADBResult<bool> {
+        self.get_secure_flag(device_id, HIGH_TEXT_CONTRAST)
+    }
+
+    /// 开启或关闭颜色反转
+    pub fn set_color_inversion(&self, device_id: &str, enabled: bool) -> ADBResult<()> {
+        self.set_secure_flag(device_id, DISPLAY_INVERSION, enabled)
+    }
+
+    /// 查询颜色反转是否开启
+    pub fn is_color_inversion_enabled(&self, device_id: &str) -> ADBResult<bool> {
+        self.get_secure_flag(device_id, DISPLAY_INVERSION)
+    }
+}
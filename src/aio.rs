@@ -0,0 +1,86 @@
+//! 基于 tokio 的异步接口（需要 `aio` 特性）
+//!
+//! 所有操作都在阻塞线程池中调用同步接口，与同步接口走同一条命令执行路径。
+//! 常用操作提供了直接 await 的方法，其他操作可通过 [`ADB::spawn_blocking`] 调用。
+
+use crate::config::ADBConfig;
+use crate::device::ADBDevice;
+use crate::error::{ADBError, ADBResult};
+use crate::transfer::{TransferOptions, TransferStats};
+
+/// 异步 ADB 客户端
+#[derive(Clone, Debug)]
+pub struct ADB {
+    inner: crate::ADB,
+}
+
+impl From<crate::ADB> for ADB {
+    fn from(inner: crate::ADB) -> Self {
+        Self { inner }
+    }
+}
+
+impl ADB {
+    /// 创建新的异步 ADB 实例
+    pub fn new(config: Option<ADBConfig>) -> Self {
+        Self {
+            inner: crate::ADB::new(config),
+        }
+    }
+
+    /// 对应的同步 ADB 实例
+    pub fn blocking(&self) -> &crate::ADB {
+        &self.inner
+    }
+
+    /// 在阻塞线程池中执行同步操作
+    pub async fn spawn_blocking<F, T>(&self, f: F) -> ADBResult<T>
+    where
+        F: FnOnce(&crate::ADB) -> ADBResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let adb = self.inner.clone();
+        tokio::task::spawn_blocking(move || f(&adb))
+            .await
+            .map_err(|e| ADBError::UnknownError(format!("阻塞任务执行失败: {}", e)))?
+    }
+
+    /// 在设备上执行 shell 命令
+    pub async fn shell(&self, device_id: &str, command: &str) -> ADBResult<String> {
+        let (device_id, command) = (device_id.to_string(), command.to_string());
+        self.spawn_blocking(move |adb| adb.shell(&device_id, &command)).await
+    }
+
+    /// 列出可用设备
+    pub async fn list_devices(&self) -> ADBResult<Vec<ADBDevice>> {
+        self.spawn_blocking(|adb| adb.list_devices()).await
+    }
+
+    /// 文件推送
+    pub async fn push(
+        &self,
+        device_id: &str,
+        local_path: &str,
+        device_path: &str,
+        options: Option<TransferOptions>,
+    ) -> ADBResult<TransferStats> {
+        let (device_id, local_path, device_path) =
+            (device_id.to_string(), local_path.to_string(), device_path.to_string());
+        self.spawn_blocking(move |adb| adb.push(&device_id, &local_path, &device_path, options))
+            .await
+    }
+
+    /// 文件拉取
+    pub async fn pull(
+        &self,
+        device_id: &str,
+        device_path: &str,
+        local_path: &str,
+        options: Option<TransferOptions>,
+    ) -> ADBResult<TransferStats> {
+        let (device_id, device_path, local_path) =
+            (device_id.to_string(), device_path.to_string(), local_path.to_string());
+        self.spawn_blocking(move |adb| adb.pull(&device_id, &device_path, &local_path, options))
+            .await
+    }
+}
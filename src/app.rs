@@ -1,11 +1,344 @@
 use crate::device::ADB;
 use crate::error::{ADBError, ADBResult};
+use crate::install::InstallOptions;
+use crate::intent::IntentBuilder;
+use crate::parsers::{parse_anr_trace, AnrTrace};
+use crate::utils::shell_quote;
+use crate::wait::Condition;
 use log::{debug, info, warn};
+use once_cell::sync::Lazy;
 use regex::Regex;
-use std::process::Command;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::{Duration, Instant};
 
+// mCurrentFocus=Window{1a2b3c u0 com.foo/com.foo.MainActivity}
+static FOCUS_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"mCurrentFocus=Window\{\S+ \S+ ([^}\s]+)\}").unwrap());
+
+// FCM 消息广播的 action
+const FCM_RECEIVE_ACTION: &str = "com.google.android.c2dm.intent.RECEIVE";
+
+// 支持 `cmd game mode` 的最低 SDK 版本 (Android 12)
+const GAME_MODE_MIN_SDK: u32 = 31;
+// 支持 `cmd game list-modes` 的最低 SDK 版本 (Android 13)
+const GAME_MODE_LIST_MIN_SDK: u32 = 33;
+
+// 设备上保存应用数据快照的目录
+const SNAPSHOT_DIR: &str = "/data/local/tmp/adbkit-snapshots";
+
+// dumpsys account 中的账户: "Account {name=user@example.com, type=com.example.account}"
+static ACCOUNT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Account \{name=(.+?), type=([^}]+)\}").unwrap());
+
+// 账户验证器: "ServiceInfo: AuthenticatorDescription {type=com.example.account}, ComponentInfo{com.example/...}"
+static AUTHENTICATOR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"AuthenticatorDescription \{type=([^}]+)\}, ComponentInfo\{([^/]+)/").unwrap()
+});
+
+// JOB #u0a123/1000: 2d3c4b5 com.foo/androidx.work.impl.background.systemjob.SystemJobService
+static JOB_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"JOB #([^/\s]+)/(-?\d+): \S+ ([^/\s]+)/(\S+)").unwrap());
+
+/// `am send-trim-memory` 的内存级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrimMemoryLevel {
+    RunningModerate,
+    RunningLow,
+    RunningCritical,
+    UiHidden,
+    Background,
+    Moderate,
+    Complete,
+}
+
+impl TrimMemoryLevel {
+    /// 全部级别
+    pub fn all() -> [Self; 7] {
+        [
+            TrimMemoryLevel::RunningModerate,
+            TrimMemoryLevel::RunningLow,
+            TrimMemoryLevel::RunningCritical,
+            TrimMemoryLevel::UiHidden,
+            TrimMemoryLevel::Background,
+            TrimMemoryLevel::Moderate,
+            TrimMemoryLevel::Complete,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrimMemoryLevel::RunningModerate => "RUNNING_MODERATE",
+            TrimMemoryLevel::RunningLow => "RUNNING_LOW",
+            TrimMemoryLevel::RunningCritical => "RUNNING_CRITICAL",
+            TrimMemoryLevel::UiHidden => "HIDDEN",
+            TrimMemoryLevel::Background => "BACKGROUND",
+            TrimMemoryLevel::Moderate => "MODERATE",
+            TrimMemoryLevel::Complete => "COMPLETE",
+        }
+    }
+}
+
+/// 整机内存压力级别，对应占用当前可用内存的比例
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryPressureLevel {
+    /// 占用 50% 可用内存
+    Moderate,
+    /// 占用 75% 可用内存
+    Low,
+    /// 占用 90% 可用内存
+    Critical,
+}
+
+impl MemoryPressureLevel {
+    fn fraction(&self) -> f64 {
+        match self {
+            MemoryPressureLevel::Moderate => 0.5,
+            MemoryPressureLevel::Low => 0.75,
+            MemoryPressureLevel::Critical => 0.9,
+        }
+    }
+}
+
+/// 设备上占用内存的后台进程，释放或超出作用域时结束
+pub struct MemoryPressure {
+    adb: ADB,
+    device_id: String,
+    pid: u32,
+    /// 占用的字节数
+    pub bytes: u64,
+    // 已结束占用进程，PID 可能已被复用，不能再次 kill
+    released: bool,
+}
+
+impl MemoryPressure {
+    /// 占用内存的进程 ID
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// 结束占用进程，释放内存
+    pub fn release(mut self) -> ADBResult<()> {
+        self.kill()
+    }
+
+    fn kill(&mut self) -> ADBResult<()> {
+        if self.released {
+            return Ok(());
+        }
+        self.adb
+            .shell(&self.device_id, &format!("kill {} 2>/dev/null; true", self.pid))?;
+        self.released = true;
+        debug!("设备 {} 已释放 {} 字节内存压力", self.device_id, self.bytes);
+        Ok(())
+    }
+}
+
+impl Drop for MemoryPressure {
+    fn drop(&mut self) {
+        let _ = self.kill();
+    }
+}
+
+/// 进程死亡模拟结果
+#[derive(Debug, Clone)]
+pub struct ProcessDeathReport {
+    pub package_name: String,
+    /// 被杀前的进程 ID
+    pub pid_before: i32,
+    /// 恢复后的进程 ID
+    pub pid_after: Option<i32>,
+    /// 进程是否确实被杀死
+    pub killed: bool,
+    /// 被杀前的前台 Activity
+    pub activity_before: Option<String>,
+    /// 恢复后的前台 Activity
+    pub activity_after: Option<String>,
+    /// 从重新启动到回到前台的耗时
+    pub restart_time: Option<Duration>,
+}
+
+impl ProcessDeathReport {
+    /// 应用是否以新进程恢复到了被杀前的 Activity
+    pub fn restored(&self) -> bool {
+        self.killed
+            && self.pid_after.is_some_and(|pid| pid != self.pid_before)
+            && self.activity_before.is_some()
+            && self.activity_before == self.activity_after
+    }
+}
+
+/// `JobScheduler` 中登记的任务（来自 `dumpsys jobscheduler`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledJob {
+    pub job_id: i32,
+    /// 所属 UID，如 "u0a123"
+    pub uid: String,
+    pub package_name: String,
+    /// 执行任务的 JobService 类名
+    pub service: String,
+    /// 要求的约束，如 CONNECTIVITY、CHARGING
+    pub required_constraints: Vec<String>,
+    /// 尚未满足的约束
+    pub unsatisfied_constraints: Vec<String>,
+    /// 是否为周期任务
+    pub periodic: bool,
+    /// 是否已满足条件等待执行
+    pub ready: Option<bool>,
+}
+
+impl ScheduledJob {
+    /// 是否为 WorkManager 调度的任务
+    pub fn is_work_manager(&self) -> bool {
+        self.service.starts_with("androidx.work.")
+    }
+}
+
+/// 游戏模式 (`cmd game mode`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameMode {
+    Standard,
+    Performance,
+    Battery,
+    /// 自定义模式，需要 Android 14 (API 34) 及以上
+    Custom,
+}
+
+impl GameMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameMode::Standard => "standard",
+            GameMode::Performance => "performance",
+            GameMode::Battery => "battery",
+            GameMode::Custom => "custom",
+        }
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = ADBError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "standard" | "1" => Ok(GameMode::Standard),
+            "performance" | "2" => Ok(GameMode::Performance),
+            "battery" | "3" => Ok(GameMode::Battery),
+            "custom" | "4" => Ok(GameMode::Custom),
+            other => Err(ADBError::ParseError(format!("未知的游戏模式: {}", other))),
+        }
+    }
+}
+
+/// 应用的游戏模式信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameModeInfo {
+    pub package_name: String,
+    /// 当前模式，Android 12L 及以下无法查询时为 None
+    pub current: Option<GameMode>,
+    /// 应用支持的模式，Android 12L 及以下无法查询时为空
+    pub available: Vec<GameMode>,
+}
+
+/// 解析 `cmd game list-modes <包名>` 输出，如
+/// "com.example current mode: standard, available game modes: [standard,performance,battery]"
+fn parse_game_modes(package_name: &str, output: &str) -> GameModeInfo {
+    let current = output
+        .split("current mode:")
+        .nth(1)
+        .and_then(|rest| rest.split(',').next())
+        .and_then(|mode| mode.parse().ok());
+    let available = output
+        .split("available game modes:")
+        .nth(1)
+        .map(|rest| rest.trim().trim_start_matches('[').split(']').next().unwrap_or(""))
+        .map(|list| list.split(',').filter_map(|m| m.parse().ok()).collect())
+        .unwrap_or_default();
+
+    GameModeInfo {
+        package_name: package_name.to_string(),
+        current,
+        available,
+    }
+}
+
+/// 应用数据快照的创建方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotMethod {
+    /// 以 root 身份打包，恢复后需要重新设置 SELinux 上下文
+    Root,
+    /// 通过 `run-as` 以应用身份打包，只适用于可调试的应用
+    RunAs,
+}
+
+/// 应用数据快照，由 [`ADB::snapshot_data`] 创建，保存在设备上
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotId {
+    pub device_id: String,
+    pub package_name: String,
+    /// 应用所属的用户
+    pub user: u32,
+    /// 应用数据目录
+    pub data_dir: String,
+    /// 设备上的快照文件
+    pub path: String,
+    pub method: SnapshotMethod,
+    pub size_bytes: u64,
+}
+
+impl SnapshotId {
+    /// `run-as` 命令前缀
+    fn run_as(&self) -> String {
+        run_as_prefix(&self.package_name, self.user)
+    }
+}
+
+fn run_as_prefix(package_name: &str, user: u32) -> String {
+    if user == 0 {
+        format!("run-as {}", shell_quote(package_name))
+    } else {
+        format!("run-as --user {} {}", user, shell_quote(package_name))
+    }
+}
+
+/// 卸载后残留的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResidueKind {
+    /// `Android/obb/<包名>`
+    ObbDir,
+    /// `Android/data/<包名>`
+    ExternalDataDir,
+    /// `Android/media/<包名>`
+    MediaDir,
+    /// 应用的账户验证器创建的账户
+    Account,
+}
+
+/// 一项卸载残留
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Residue {
+    pub kind: ResidueKind,
+    /// 目录路径，或账户的 "类型/名称"
+    pub location: String,
+    /// 是否已清除
+    pub removed: bool,
+}
+
+/// [`ADB::uninstall_clean`] 的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UninstallReport {
+    pub package_name: String,
+    /// 本次是否执行了卸载（应用原本未安装时为 false）
+    pub uninstalled: bool,
+    pub residue: Vec<Residue>,
+}
+
+impl UninstallReport {
+    /// 是否所有残留都已清除
+    pub fn is_clean(&self) -> bool {
+        self.residue.iter().all(|r| r.removed)
+    }
+}
+
 /// 包信息结构体
 #[derive(Debug, Clone)]
 pub struct PackageInfo {
@@ -150,135 +483,9 @@ impl ADB {
         let command = format!("dumpsys package {}", package_name);
         let output = self.shell(device_id, &command)?;
 
-        // 存储原始输出以便调试和完整访问
-        let mut info = PackageInfo::new(package_name);
-        info.raw_data = Some(output.clone());
-
-        // 使用正则表达式解析更多信息
-        if let Some(re_version) = Regex::new(r"versionName=([^\s]+)").ok() {
-            if let Some(caps) = re_version.captures(&output) {
-                if let Some(ver) = caps.get(1) {
-                    info.version_name = Some(ver.as_str().to_string());
-                }
-            }
-        }
-
-        if let Some(re_code) = Regex::new(r"versionCode=(\d+)").ok() {
-            if let Some(caps) = re_code.captures(&output) {
-                if let Some(code) = caps.get(1) {
-                    if let Ok(code_int) = i32::from_str(code.as_str()) {
-                        info.version_code = Some(code_int);
-                    }
-                }
-            }
-        }
-
-        // 提取首次安装时间
-        if let Some(re_install) = Regex::new(r"firstInstallTime=([^\s]+)").ok() {
-            if let Some(caps) = re_install.captures(&output) {
-                if let Some(time) = caps.get(1) {
-                    info.install_time = Some(time.as_str().to_string());
-                }
-            }
-        }
-
-        // 提取最后更新时间
-        if let Some(re_update) = Regex::new(r"lastUpdateTime=([^\s]+)").ok() {
-            if let Some(caps) = re_update.captures(&output) {
-                if let Some(time) = caps.get(1) {
-                    info.update_time = Some(time.as_str().to_string());
-                }
-            }
-        }
-
-        // 提取 UID
-        if let Some(re_uid) = Regex::new(r"userId=(\d+)").ok() {
-            if let Some(caps) = re_uid.captures(&output) {
-                if let Some(uid) = caps.get(1) {
-                    if let Ok(uid_int) = i32::from_str(uid.as_str()) {
-                        info.uid = Some(uid_int);
-                    }
-                }
-            }
-        }
-
-        // 提取 SDK 版本信息
-        if let Some(re_target_sdk) = Regex::new(r"targetSdk=(\d+)").ok() {
-            if let Some(caps) = re_target_sdk.captures(&output) {
-                if let Some(sdk) = caps.get(1) {
-                    if let Ok(sdk_int) = i32::from_str(sdk.as_str()) {
-                        info.target_sdk = Some(sdk_int);
-                    }
-                }
-            }
-        }
-
-        if let Some(re_min_sdk) = Regex::new(r"minSdk=(\d+)").ok() {
-            if let Some(caps) = re_min_sdk.captures(&output) {
-                if let Some(sdk) = caps.get(1) {
-                    if let Ok(sdk_int) = i32::from_str(sdk.as_str()) {
-                        info.min_sdk = Some(sdk_int);
-                    }
-                }
-            }
-        }
-
-        // 提取安装来源
-        if let Some(re_install_source) = Regex::new(r"installerPackageName=([^\s]+)").ok() {
-            if let Some(caps) = re_install_source.captures(&output) {
-                if let Some(source) = caps.get(1) {
-                    info.install_source = Some(source.as_str().to_string());
-                }
-            }
-        }
-
-        // 提取权限
-        let lines = output.lines().collect::<Vec<&str>>();
-        let mut in_permissions = false;
-
-        for line in &lines {
-            if line.contains("requested permissions:") {
-                in_permissions = true;
-                continue;
-            } else if in_permissions && line.trim().is_empty() {
-                in_permissions = false;
-                continue;
-            }
-
-            if in_permissions && line.contains(": granted=") {
-                if let Some(perm) = line.split(':').next() {
-                    let perm = perm.trim();
-                    if !perm.is_empty() {
-                        info.permissions.push(perm.to_string());
-                    }
-                }
-            }
-        }
-
-        // 提取 Activities
-        let mut in_activities = false;
-        for line in &lines {
-            if line.contains("Activity Resolver Table:") {
-                in_activities = true;
-                continue;
-            } else if in_activities && line.trim().is_empty() {
-                in_activities = false;
-                continue;
-            }
-
-            if in_activities && line.contains(package_name) {
-                if let Some(activity) = Regex::new(r"/([^/\s]+)")
-                    .ok()
-                    .and_then(|re| re.captures(line))
-                    .and_then(|caps| caps.get(1))
-                    .map(|m| m.as_str())
-                {
-                    info.activities.push(activity.to_string());
-                }
-            }
-        }
-
-        Ok(info)
+        let parser = self.parser_for::<PackageInfo>(device_id, crate::parsers::PACKAGE_INFO)?;
+        let context = self.parse_context(device_id, Some(package_name))?;
+        parser.parse(&context, &output)
     }
 
     /// 检查包是否运行
@@ -287,31 +494,14 @@ impl ADB {
         device_id: &str,
         package_name: &str,
     ) -> ADBResult<(bool, Option<i32>)> {
-        // 先尝试使用更准确的方法检查是否运行中
-        if let Ok(Some(pid)) = self.get_pid(device_id, package_name) {
+        if let Some(pid) = self.get_pid(device_id, package_name)? {
             return Ok((true, Some(pid)));
         }
 
-        // 备用方法 - 使用 ps 命令
-        let command = format!("ps -A | grep -i {}", package_name);
-        let output = self.shell(device_id, &command)?;
-
-        // 如果找到输出，则尝试提取 PID
-        if !output.trim().is_empty() {
-            let lines = output.lines().next();
-            if let Some(line) = lines {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() > 1 {
-                    if let Ok(pid) = i32::from_str(parts[1]) {
-                        debug!("找到 PID: {}", pid);
-                        return Ok((true, Some(pid)));
-                    }
-                }
-            }
+        // 应用的主进程不在时，可能仍有 `包名:进程名` 形式的子进程或服务在运行
+        if !self.app_processes(device_id, package_name)?.is_empty() {
             return Ok((true, None));
         }
-
-        // 最后的检查 - 使用 dumpsys
         let command = format!("dumpsys activity services | grep -i {}", package_name);
         let output = self.shell(device_id, &command)?;
         if !output.trim().is_empty() {
@@ -321,60 +511,26 @@ impl ADB {
         Ok((false, None))
     }
 
-    /// 获取进程 ID
+    /// 获取应用主进程的进程 ID
     pub fn get_pid(&self, device_id: &str, package_name: &str) -> ADBResult<Option<i32>> {
-        // 尝试多种方法获取 PID
-        let methods = [
-            // 方法1: 使用 pidof (适用于较新的 Android 系统)
-            format!("pidof {}", package_name),
-            // 方法2: 使用 ps 带有 grep 过滤 (兼容不同 Android 版本)
-            format!("ps -A | grep {} | grep -v grep", package_name),
-            // 方法3: 使用 ps -o 带有 NAME 过滤 (Android 8+)
-            format!("ps -A -o PID,NAME | grep {} | grep -v grep", package_name),
-            // 方法4: 使用 ps -o 带有 CMDLINE 过滤 (用于某些特殊情况)
-            format!(
-                "ps -A -o PID,CMDLINE | grep {} | grep -v grep",
-                package_name
-            ),
-        ];
-
-        for method in &methods {
-            let output = self.shell(device_id, method);
-
-            if let Ok(pid_str) = output {
-                if pid_str.trim().is_empty() {
-                    continue;
-                }
-
-                // 尝试解析 PID
-                if let Some(line) = pid_str.lines().next() {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-
-                    // 处理不同命令的不同输出格式
-                    if parts.is_empty() {
-                        continue;
-                    }
-
-                    // pidof 直接返回 PID
-                    if method.starts_with("pidof") {
-                        if let Ok(pid) = i32::from_str(parts[0]) {
-                            debug!("通过 pidof 获取 PID: {}", pid);
-                            return Ok(Some(pid));
-                        }
-                    }
-                    // ps 命令通常在第二列返回 PID
-                    else if parts.len() > 1 {
-                        if let Ok(pid) = i32::from_str(parts[0]) {
-                            debug!("通过 ps 获取 PID: {}", pid);
-                            return Ok(Some(pid));
-                        }
-                    }
-                }
+        // pidof 只需一次查找，不可靠的版本上退回进程列表
+        if self.device_quirks(device_id)?.has_pidof {
+            let output = self.shell(device_id, &format!("pidof {}; true", package_name))?;
+            if let Some(pid) = output
+                .split_whitespace()
+                .next()
+                .and_then(|v| v.parse::<i32>().ok())
+            {
+                debug!("通过 pidof 获取 PID: {}", pid);
+                return Ok(Some(pid));
             }
         }
 
-        debug!("无法找到包 {} 的 PID", package_name);
-        Ok(None)
+        let pid = self.find_process(device_id, package_name)?.map(|p| p.pid);
+        if pid.is_none() {
+            debug!("无法找到包 {} 的 PID", package_name);
+        }
+        Ok(pid)
     }
 
     /// 启动一个应用并等待直到完全启动
@@ -386,7 +542,6 @@ impl ADB {
         timeout_secs: Option<u64>,
     ) -> ADBResult<bool> {
         let timeout = timeout_secs.unwrap_or(30);
-        let start_time = Instant::now();
 
         // 构建启动命令
         let command = if let Some(act) = activity {
@@ -410,24 +565,19 @@ impl ADB {
         // 等待应用完全启动
         info!("等待应用 {} 完全启动...", package_name);
 
-        loop {
-            if start_time.elapsed().as_secs() > timeout {
-                warn!("等待应用启动超时 ({} 秒)", timeout);
-                return Ok(false);
-            }
-
-            // 检查应用是否在前台运行
-            let current_app_cmd = "dumpsys window windows | grep -E 'mCurrentFocus'";
-            let current_app = self.shell(device_id, current_app_cmd)?;
-
-            if current_app.contains(package_name) {
-                debug!("应用 {} 已成功启动并处于前台", package_name);
-                return Ok(true);
-            }
+        let started = self.wait_until(
+            device_id,
+            &Condition::activity_focused(package_name),
+            Duration::from_secs(timeout),
+        )?;
 
-            // 短暂休眠后再次检查
-            std::thread::sleep(Duration::from_millis(500));
+        if started {
+            debug!("应用 {} 已成功启动并处于前台", package_name);
+        } else {
+            warn!("等待应用启动超时 ({} 秒)", timeout);
         }
+
+        Ok(started)
     }
 
     /// 启动应用程序
@@ -451,60 +601,211 @@ impl ADB {
         // 分析输出以确定启动是否成功
         if output.contains("Error") || output.contains("Exception") || output.contains("failed") {
             debug!("启动应用程序失败: {}", output);
-            return Ok(false);
+            Ok(false)
         } else {
             debug!("应用程序启动命令执行成功");
-            return Ok(true);
+            Ok(true)
         }
     }
 
-    /// 强制停止应用程序
-    pub fn stop_app(&self, device_id: &str, package_name: &str) -> ADBResult<()> {
-        let command = format!("am force-stop {}", package_name);
-        self.shell(device_id, &command)?;
-        Ok(())
+    /// 获取应用的启动 Activity 组件名（如 "com.example/.MainActivity"）
+    ///
+    /// 需要 Android 7.0 (API 24) 及以上
+    pub fn get_launch_activity(&self, device_id: &str, package_name: &str) -> ADBResult<String> {
+        let output = self.shell(
+            device_id,
+            &format!(
+                "cmd package resolve-activity --brief -c android.intent.category.LAUNCHER {}",
+                package_name
+            ),
+        )?;
+
+        output
+            .lines()
+            .map(|l| l.trim())
+            .rfind(|l| l.contains('/'))
+            .map(|l| l.to_string())
+            .ok_or_else(|| ADBError::AppNotFound(format!("找不到 {} 的启动 Activity", package_name)))
     }
 
-    /// 安装应用程序
-    pub fn install_app(&self, device_id: &str, apk_path: &str) -> ADBResult<()> {
-        self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
-            if !device_id.is_empty() {
-                cmd.arg("-s").arg(device_id);
-            }
+    /// 获取当前获得焦点的窗口组件（如 "com.example/com.example.MainActivity"）
+    pub fn foreground_activity(&self, device_id: &str) -> ADBResult<Option<String>> {
+        let output = self.shell(device_id, "dumpsys window | grep mCurrentFocus; true")?;
+        Ok(FOCUS_RE.captures(&output).map(|caps| caps[1].to_string()))
+    }
 
-            let output = cmd
-                .arg("install")
-                .arg("-r") // Replace existing app
-                .arg(apk_path)
-                .output()
-                .map_err(|e| ADBError::CommandError(format!("无法安装 APK: {}", e)))?;
+    /// 模拟系统回收后台进程（"不保留活动"场景）
+    ///
+    /// 将应用切到后台后执行 `am kill`，确认进程已退出，再从启动器重新打开应用，
+    /// 检查是否以新进程恢复到原来的 Activity。`am kill` 只会杀死系统认为可以安全回收的
+    /// 后台进程，与低内存时系统的行为一致。
+    pub fn simulate_process_death(
+        &self,
+        device_id: &str,
+        package_name: &str,
+    ) -> ADBResult<ProcessDeathReport> {
+        let pid_before = self.get_pid(device_id, package_name)?.ok_or_else(|| {
+            ADBError::AppNotFound(format!("应用 {} 未运行", package_name))
+        })?;
+        let activity_before = self
+            .foreground_activity(device_id)?
+            .filter(|a| a.starts_with(&format!("{}/", package_name)));
+
+        self.key_event(device_id, crate::input::KeyCode::Home)?;
+        std::thread::sleep(Duration::from_millis(500));
+        self.shell(device_id, &format!("am kill {}", package_name))?;
+
+        let killed = self.wait_until(
+            device_id,
+            &Condition::package_running(package_name).negate(),
+            Duration::from_secs(5),
+        )?;
+        if !killed {
+            warn!("应用 {} 的进程 {} 未被回收", package_name, pid_before);
+        }
 
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let start = Instant::now();
+        let started = self.start_app_and_wait(device_id, package_name, None, Some(30))?;
+        let restart_time = started.then(|| start.elapsed());
 
-            if !output.status.success() || stdout.contains("Failure") || stderr.contains("Failure")
-            {
-                let error_msg = if stdout.contains("Failure") {
-                    format!("APK 安装失败: {}", stdout)
-                } else if !stderr.is_empty() {
-                    format!("APK 安装失败: {}", stderr)
-                } else {
-                    "APK 安装失败，未知错误".to_string()
-                };
+        let report = ProcessDeathReport {
+            package_name: package_name.to_string(),
+            pid_before,
+            pid_after: self.get_pid(device_id, package_name)?,
+            killed,
+            activity_before,
+            activity_after: self.foreground_activity(device_id)?,
+            restart_time,
+        };
 
-                return Err(ADBError::CommandError(error_msg));
-            }
+        info!(
+            "应用 {} 进程死亡模拟完成: 进程 {} -> {:?}，恢复 {}",
+            package_name,
+            pid_before,
+            report.pid_after,
+            if report.restored() { "成功" } else { "失败" }
+        );
+        Ok(report)
+    }
 
-            debug!("成功安装 APK: {}", apk_path);
-            Ok(())
+    /// 向应用发送内存整理通知 (`am send-trim-memory`)
+    pub fn send_trim_memory(
+        &self,
+        device_id: &str,
+        package_name: &str,
+        level: TrimMemoryLevel,
+    ) -> ADBResult<()> {
+        let output = self.shell(
+            device_id,
+            &format!("am send-trim-memory {} {}", package_name, level.as_str()),
+        )?;
+        if output.contains("Error") || output.contains("Unknown") {
+            return Err(ADBError::CommandError(format!(
+                "send-trim-memory 失败: {}",
+                output.trim()
+            )));
+        }
+
+        debug!("已向 {} 发送 {:?}", package_name, level);
+        Ok(())
+    }
+
+    /// 在设备上占用一部分可用内存，制造整机内存压力
+    ///
+    /// 在后台启动一个缓存 `/dev/zero` 数据的 shell 进程，返回的句柄释放时结束该进程
+    pub fn fill_memory_pressure(
+        &self,
+        device_id: &str,
+        level: MemoryPressureLevel,
+    ) -> ADBResult<MemoryPressure> {
+        let available_kb = self
+            .get_system_meminfo(device_id)?
+            .available_kb
+            .ok_or_else(|| ADBError::ParseError("无法读取 MemAvailable".to_string()))?;
+
+        let bytes = (available_kb as f64 * 1024.0 * level.fraction()) as u64;
+        // tail 会缓存没有换行的整段输入，直到被结束
+        let output = self.shell(
+            device_id,
+            &format!("head -c {} /dev/zero | tail >/dev/null 2>&1 & echo $!", bytes),
+        )?;
+        let pid = output
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| ADBError::ParseError(format!("无法解析进程 ID: {}", output.trim())))?;
+
+        info!("设备 {} 占用 {} 字节内存 ({:?})", device_id, bytes, level);
+        Ok(MemoryPressure {
+            adb: self.clone(),
+            device_id: device_id.to_string(),
+            pid,
+            bytes,
+            released: false,
         })
     }
 
+    /// 获取已安装应用的 APK 路径（包括拆分 APK）
+    pub fn get_apk_paths(&self, device_id: &str, package_name: &str) -> ADBResult<Vec<String>> {
+        let output = self.shell(device_id, &format!("pm path {}; true", package_name))?;
+        let paths = parse_package_paths(&output);
+
+        if paths.is_empty() {
+            return Err(ADBError::AppNotFound(format!("找不到应用 {} 的 APK", package_name)));
+        }
+        Ok(paths)
+    }
+
+    /// 将已安装应用的全部 APK（基础包和拆分包）拉取到本地目录，返回保存的文件列表
+    ///
+    /// 无法直接读取 APK 时，会先复制到设备临时目录再拉取
+    pub fn pull_apk(
+        &self,
+        device_id: &str,
+        package_name: &str,
+        local_dir: &str,
+    ) -> ADBResult<Vec<PathBuf>> {
+        let paths = self.get_apk_paths(device_id, package_name)?;
+        fs::create_dir_all(local_dir)
+            .map_err(|e| ADBError::FileError(format!("无法创建目录 {}: {}", local_dir, e)))?;
+
+        let mut saved = Vec::with_capacity(paths.len());
+        for device_path in &paths {
+            let file_name = device_path.rsplit('/').next().unwrap_or("base.apk");
+            let local_path = Path::new(local_dir).join(file_name);
+            let local = local_path.to_string_lossy().to_string();
+
+            if let Err(e) = self.pull(device_id, device_path, &local, None) {
+                debug!("直接拉取 {} 失败，改为经临时文件拉取: {}", device_path, e);
+                self.with_resources(device_id, |resources| {
+                    let temp = resources.create_temp_file("apk_", ".apk")?;
+                    self.shell(device_id, &format!("cp {} {}", shell_quote(device_path), temp))?;
+                    self.pull(device_id, &temp, &local, None)
+                })?;
+            }
+
+            saved.push(local_path);
+        }
+
+        info!("已拉取应用 {} 的 {} 个 APK 到 {}", package_name, saved.len(), local_dir);
+        Ok(saved)
+    }
+
+    /// 强制停止应用程序
+    pub fn stop_app(&self, device_id: &str, package_name: &str) -> ADBResult<()> {
+        let command = format!("am force-stop {}", package_name);
+        self.shell(device_id, &command)?;
+        Ok(())
+    }
+
+    /// 安装应用程序（覆盖安装），需要更多安装参数时请使用 [`ADB::install_app_with_options`]
+    pub fn install_app(&self, device_id: &str, apk_path: &str) -> ADBResult<()> {
+        self.install_app_with_options(device_id, apk_path, &InstallOptions::default())
+    }
+
     /// 卸载应用程序
     pub fn uninstall_app(&self, device_id: &str, package_name: &str) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
             if !device_id.is_empty() {
                 cmd.arg("-s").arg(device_id);
             }
@@ -533,6 +834,7 @@ impl ADB {
                 return Err(ADBError::CommandError(error_msg));
             }
 
+            self.invalidate_cache(device_id);
             debug!("成功卸载应用: {}", package_name);
             Ok(())
         })
@@ -555,7 +857,7 @@ impl ADB {
 
         // 执行卸载
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
             if !device_id.is_empty() {
                 cmd.arg("-s").arg(device_id);
             }
@@ -592,11 +894,103 @@ impl ADB {
                 return Err(ADBError::CommandError(error_msg));
             }
 
+            self.invalidate_cache(device_id);
             debug!("成功卸载应用: {}", package_name);
             Ok(())
         })
     }
 
+    /// 卸载应用并清除残留的外部存储目录，返回发现的残留
+    ///
+    /// 检查内部存储和各外部存储卷上的 `Android/obb`、`Android/data`、`Android/media` 目录，
+    /// 以及应用的账户验证器创建的账户。账户无法通过 adb 删除，只记录在报告中；
+    /// 系统会在下次重启、找不到对应验证器时清除这些账户。应用未安装时只清理残留。
+    pub fn uninstall_clean(&self, device_id: &str, package_name: &str) -> ADBResult<UninstallReport> {
+        // 卸载后无法再查到验证器属于哪个应用，先记录应用的账户类型
+        let accounts_before = self.shell(device_id, "dumpsys account")?;
+        let mut account_types: Vec<String> = AUTHENTICATOR_RE
+            .captures_iter(&accounts_before)
+            .filter(|caps| &caps[2] == package_name)
+            .map(|caps| caps[1].trim().to_string())
+            .collect();
+        if !account_types.iter().any(|t| t == package_name) {
+            account_types.push(package_name.to_string());
+        }
+
+        // get_apk_paths 在未安装时返回错误，这里直接解析 pm path 的输出
+        let path_command = format!("pm path {}; true", package_name);
+        let uninstalled = !parse_package_paths(&self.shell(device_id, &path_command)?).is_empty();
+        if uninstalled {
+            self.uninstall_app(device_id, package_name)?;
+            if !parse_package_paths(&self.shell(device_id, &path_command)?).is_empty() {
+                return Err(ADBError::CommandError(format!("卸载后仍能找到应用 {}", package_name)));
+            }
+        }
+
+        let mut residue: Vec<Residue> = Vec::new();
+        let user = self.current_user(device_id)?;
+        let package_q = shell_quote(package_name);
+        for (kind, dir) in [
+            (ResidueKind::ObbDir, "obb"),
+            (ResidueKind::ExternalDataDir, "data"),
+            (ResidueKind::MediaDir, "media"),
+        ] {
+            let command = format!(
+                "for d in /storage/emulated/{u}/Android/{d}/{p} /storage/*-*/Android/{d}/{p}; do \
+                 [ -e \"$d\" ] && echo \"$d\"; done; true",
+                u = user,
+                d = dir,
+                p = package_q
+            );
+            for path in self.shell(device_id, &command)?.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                let path_q = shell_quote(path);
+                let check = self.shell(
+                    device_id,
+                    &format!("rm -rf {p}; [ -e {p} ] || echo removed; true", p = path_q),
+                )?;
+                let removed = check.trim() == "removed";
+                if !removed {
+                    warn!("无法删除残留目录 {}", path);
+                }
+                residue.push(Residue {
+                    kind,
+                    location: path.to_string(),
+                    removed,
+                });
+            }
+        }
+
+        let accounts_after = self.shell(device_id, "dumpsys account")?;
+        let mut accounts: Vec<String> = Vec::new();
+        for caps in ACCOUNT_RE.captures_iter(&accounts_after) {
+            let account = format!("{}/{}", caps[2].trim(), caps[1].trim());
+            if account_types.iter().any(|t| t == caps[2].trim()) && !accounts.contains(&account) {
+                accounts.push(account);
+            }
+        }
+        for account in accounts {
+            warn!("应用 {} 卸载后残留账户 {}，将在设备重启后由系统清除", package_name, account);
+            residue.push(Residue {
+                kind: ResidueKind::Account,
+                location: account,
+                removed: false,
+            });
+        }
+
+        info!(
+            "设备 {} 已{}清理应用 {}，发现 {} 项残留",
+            device_id,
+            if uninstalled { "卸载并" } else { "" },
+            package_name,
+            residue.len()
+        );
+        Ok(UninstallReport {
+            package_name: package_name.to_string(),
+            uninstalled,
+            residue,
+        })
+    }
+
     /// 获取设备上已安装的应用列表
     pub fn list_packages(
         &self,
@@ -613,15 +1007,573 @@ impl ADB {
         }
 
         let output = self.shell(device_id, &command)?;
-        let mut packages = Vec::new();
+        let parser = self.parser_for::<Vec<String>>(device_id, crate::parsers::PACKAGE_LIST)?;
+        let context = self.parse_context(device_id, None)?;
+        parser.parse(&context, &output)
+    }
+
+    /// 列出应用在 `JobScheduler` 中登记的任务（包括 WorkManager 任务）
+    pub fn list_scheduled_jobs(&self, device_id: &str, package_name: &str) -> ADBResult<Vec<ScheduledJob>> {
+        let output = self.shell(
+            device_id,
+            &format!("dumpsys jobscheduler {}", shell_quote(package_name)),
+        )?;
+        let jobs: Vec<ScheduledJob> = parse_scheduled_jobs(&output)
+            .into_iter()
+            .filter(|job| job.package_name == package_name)
+            .collect();
+
+        debug!("应用 {} 有 {} 个已登记的任务", package_name, jobs.len());
+        Ok(jobs)
+    }
+
+    /// 忽略约束条件立即执行任务 (`cmd jobscheduler run -f`)
+    ///
+    /// 任务在后台异步执行，本方法返回时任务可能尚未完成
+    pub fn force_run_job(&self, device_id: &str, package_name: &str, job_id: i32) -> ADBResult<()> {
+        let output = self.shell(
+            device_id,
+            &format!(
+                "cmd jobscheduler run -f {} {} 2>&1; true",
+                shell_quote(package_name),
+                job_id
+            ),
+        )?;
+
+        let lower = output.to_lowercase();
+        if lower.contains("not found") || lower.contains("could not find") || lower.contains("error") {
+            return Err(ADBError::CommandError(format!(
+                "执行任务 {}/{} 失败: {}",
+                package_name,
+                job_id,
+                output.trim()
+            )));
+        }
+
+        info!("已强制执行任务 {}/{}", package_name, job_id);
+        Ok(())
+    }
+
+    /// 查找应用中接收 FCM 消息的广播接收器组件（`包名/类名`）
+    pub fn find_fcm_receiver(&self, device_id: &str, package_name: &str) -> ADBResult<Option<String>> {
+        let output = self.shell(device_id, &format!("pm dump {}", shell_quote(package_name)))?;
+        Ok(parse_action_receiver(&output, FCM_RECEIVE_ACTION, package_name))
+    }
+
+    /// 模拟收到 FCM 数据消息
+    ///
+    /// 向应用的 FCM 接收器（通常是 `FirebaseInstanceIdReceiver`）发送
+    /// `com.google.android.c2dm.intent.RECEIVE` 广播，`payload` 作为消息数据。
+    /// 接收器要求发送方持有 `com.google.android.c2dm.permission.SEND`，非 root 设备上
+    /// 广播可能被系统拒绝（logcat 中出现 Permission Denial）。
+    pub fn simulate_fcm_message(
+        &self,
+        device_id: &str,
+        package_name: &str,
+        payload: &[(&str, &str)],
+    ) -> ADBResult<String> {
+        let receiver = self.find_fcm_receiver(device_id, package_name)?.ok_or_else(|| {
+            ADBError::AppNotFound(format!("应用 {} 没有声明 FCM 接收器", package_name))
+        })?;
+
+        let (package, class) = receiver.split_once('/').unwrap_or((package_name, receiver.as_str()));
+        let mut intent = IntentBuilder::with_action(FCM_RECEIVE_ACTION)
+            .component(package, class)
+            .flag(IntentBuilder::FLAG_INCLUDE_STOPPED_PACKAGES | IntentBuilder::FLAG_RECEIVER_FOREGROUND)
+            .extra_string("from", "adbkit")
+            .extra_string("google.message_id", &format!("adbkit-{:08x}", rand::random::<u32>()));
+        for (key, value) in payload {
+            intent = intent.extra_string(key, value);
+        }
+
+        let output = self.send_broadcast(device_id, &intent)?;
+        debug!("已向 {} 发送模拟 FCM 消息: {}", receiver, output.trim());
+        Ok(output)
+    }
+
+    /// 获取应用的游戏模式信息
+    ///
+    /// 需要 Android 12 (API 31) 及以上；Android 13 起才能查询当前模式和支持的模式
+    pub fn get_game_mode_info(&self, device_id: &str, package_name: &str) -> ADBResult<GameModeInfo> {
+        let sdk = self.device_profile(device_id)?.sdk_int;
+        if sdk < GAME_MODE_MIN_SDK {
+            return Err(ADBError::DeviceError(format!(
+                "设备 {} (SDK {}) 不支持游戏模式，需要 Android 12 及以上",
+                device_id, sdk
+            )));
+        }
+        if sdk < GAME_MODE_LIST_MIN_SDK {
+            return Ok(GameModeInfo {
+                package_name: package_name.to_string(),
+                current: None,
+                available: Vec::new(),
+            });
+        }
+
+        let output = self.shell(device_id, &format!("cmd game list-modes {}", shell_quote(package_name)))?;
+        let info = parse_game_modes(package_name, &output);
+        if info.current.is_none() && info.available.is_empty() {
+            return Err(ADBError::AppNotFound(format!(
+                "无法获取应用 {} 的游戏模式: {}",
+                package_name,
+                output.trim()
+            )));
+        }
+        Ok(info)
+    }
+
+    /// 设置应用的游戏模式
+    ///
+    /// 应用需要声明为游戏（`android:appCategory="game"`），且支持所设置的模式
+    pub fn set_game_mode(&self, device_id: &str, package_name: &str, mode: GameMode) -> ADBResult<()> {
+        let info = self.get_game_mode_info(device_id, package_name)?;
+        if !info.available.is_empty() && !info.available.contains(&mode) {
+            return Err(ADBError::ConfigError(format!(
+                "应用 {} 不支持游戏模式 {}，支持的模式: {:?}",
+                package_name,
+                mode.as_str(),
+                info.available
+            )));
+        }
+
+        let output = self.shell(
+            device_id,
+            &format!("cmd game mode {} {}", mode.as_str(), shell_quote(package_name)),
+        )?;
+        if output.contains("Error") || output.contains("not supported") || output.contains("Invalid") {
+            return Err(ADBError::CommandError(format!(
+                "设置应用 {} 游戏模式失败: {}",
+                package_name,
+                output.trim()
+            )));
+        }
+
+        debug!("应用 {} 游戏模式已设置为 {}", package_name, mode.as_str());
+        Ok(())
+    }
+
+    /// 获取应用最近一次 ANR 的 trace
+    ///
+    /// 优先读取 `/data/anr` 中的 trace 文件（需要 root），否则从 dropbox 的
+    /// `data_app_anr` 记录中读取。没有找到时返回 None。
+    pub fn get_latest_anr(&self, device_id: &str, package_name: &str) -> ADBResult<Option<AnrTrace>> {
+        let pattern = shell_quote(&format!("Cmd line: {}", package_name));
+        let command = format!(
+            "cd /data/anr 2>/dev/null && for f in $(ls -t); do \
+             grep -q {p} \"$f\" 2>/dev/null && {{ cat \"$f\"; break; }}; done; true",
+            p = pattern
+        );
+        let command = self.root_command(device_id, &command).unwrap_or(command);
+        let output = self.shell(device_id, &command)?;
+        if !output.trim().is_empty() {
+            debug!("从 /data/anr 读取应用 {} 的 ANR trace", package_name);
+            return Ok(Some(parse_anr_trace(&output)));
+        }
+
+        // dropbox 记录之间以 "====...====" 分隔，最新的记录在最后
+        let output = self.shell(device_id, "dumpsys dropbox --print data_app_anr")?;
+        let process_line = format!("Process: {}", package_name);
+        let entry = output
+            .split("========================================")
+            .filter(|entry| entry.lines().any(|l| l.trim() == process_line))
+            .last();
+
+        Ok(entry.map(|entry| {
+            debug!("从 dropbox 读取应用 {} 的 ANR trace", package_name);
+            parse_anr_trace(entry)
+        }))
+    }
+
+    /// 把应用数据目录打包为设备上的快照，用于在测试用例之间快速恢复应用状态
+    ///
+    /// 有 root 权限时直接打包数据目录，否则对可调试应用使用 `run-as`。打包前会停止应用，
+    /// `lib` 目录（指向安装目录的链接）不包含在快照中。
+    pub fn snapshot_data(&self, device_id: &str, package_name: &str) -> ADBResult<SnapshotId> {
+        if self.get_apk_paths(device_id, package_name)?.is_empty() {
+            return Err(ADBError::AppNotFound(package_name.to_string()));
+        }
+        self.stop_app(device_id, package_name)?;
+
+        let user = self.current_user(device_id)?;
+        let data_dir = if user == 0 {
+            format!("/data/data/{}", package_name)
+        } else {
+            format!("/data/user/{}/{}", user, package_name)
+        };
+        let path = format!(
+            "{}/{}-{}-{}.tar",
+            SNAPSHOT_DIR,
+            package_name,
+            user,
+            chrono::Local::now().format("%Y%m%d%H%M%S%3f")
+        );
+        let path_q = shell_quote(&path);
+        self.shell(device_id, &format!("mkdir -p {}", SNAPSHOT_DIR))?;
+
+        let archive = format!(
+            "cd {} && tar -cf {} --exclude ./lib . && echo done",
+            shell_quote(&data_dir),
+            path_q
+        );
+        let (method, output) = match self.root_command(device_id, &archive) {
+            Some(command) => (SnapshotMethod::Root, self.shell(device_id, &command)?),
+            None => {
+                // stderr 先重定向到原来的 stdout，以便读取 run-as 的错误信息
+                let command = format!(
+                    "{} tar -cf - --exclude ./lib . 2>&1 > {} && echo done; true",
+                    run_as_prefix(package_name, user),
+                    path_q
+                );
+                (SnapshotMethod::RunAs, self.shell(device_id, &command)?)
+            }
+        };
+        if output.lines().last().map(str::trim) != Some("done") {
+            let _ = self.shell(device_id, &format!("rm -f {}", path_q));
+            return Err(match method {
+                SnapshotMethod::RunAs if output.contains("not debuggable") => ADBError::PermissionDenied(format!(
+                    "快照应用 {} 的数据需要 root 权限或可调试的应用",
+                    package_name
+                )),
+                _ => ADBError::CommandError(format!("打包应用 {} 的数据失败: {}", package_name, output.trim())),
+            });
+        }
+
+        let size_bytes = self
+            .shell(device_id, &format!("stat -c %s {} 2>/dev/null; true", path_q))?
+            .trim()
+            .parse()
+            .unwrap_or(0);
+        info!(
+            "设备 {} 已通过 {:?} 创建应用 {} 的数据快照 {} ({} 字节)",
+            device_id, method, package_name, path, size_bytes
+        );
+        Ok(SnapshotId {
+            device_id: device_id.to_string(),
+            package_name: package_name.to_string(),
+            user,
+            data_dir,
+            path,
+            method,
+            size_bytes,
+        })
+    }
 
-        for line in output.lines() {
-            if line.starts_with("package:") {
-                let package = line.trim_start_matches("package:").trim();
-                packages.push(package.to_string());
+    /// 用快照替换应用的数据目录
+    ///
+    /// 恢复前停止应用并清空数据目录（保留 `lib`）；以 root 恢复时重新设置文件的 SELinux 上下文。
+    pub fn restore_data(&self, snapshot: &SnapshotId) -> ADBResult<()> {
+        let device_id = snapshot.device_id.as_str();
+        let path_q = shell_quote(&snapshot.path);
+        let exists = self.shell(device_id, &format!("[ -f {} ] && echo yes; true", path_q))?;
+        if exists.trim() != "yes" {
+            return Err(ADBError::FileError(format!("快照文件不存在: {}", snapshot.path)));
+        }
+        self.stop_app(device_id, &snapshot.package_name)?;
+
+        let wipe = "find . -mindepth 1 -maxdepth 1 ! -name lib -exec rm -rf {} +";
+        let command = match snapshot.method {
+            SnapshotMethod::Root => {
+                let data_dir_q = shell_quote(&snapshot.data_dir);
+                let restore = format!(
+                    "cd {} && {} && tar -xf {} && restorecon -RFD {} && echo done",
+                    data_dir_q, wipe, path_q, data_dir_q
+                );
+                self.root_command(device_id, &restore).ok_or_else(|| {
+                    ADBError::PermissionDenied(format!("恢复快照 {} 需要 root 权限", snapshot.path))
+                })?
             }
+            SnapshotMethod::RunAs => format!(
+                "{} sh -c {} < {} 2>&1 && echo done",
+                snapshot.run_as(),
+                shell_quote(&format!("{} && tar -xf -", wipe)),
+                path_q
+            ),
+        };
+
+        let output = self.shell(device_id, &format!("{} 2>&1; true", command))?;
+        if output.lines().last().map(str::trim) != Some("done") {
+            return Err(ADBError::CommandError(format!(
+                "恢复应用 {} 的数据失败: {}",
+                snapshot.package_name,
+                output.trim()
+            )));
         }
 
-        Ok(packages)
+        debug!("设备 {} 已从快照 {} 恢复应用 {} 的数据", device_id, snapshot.path, snapshot.package_name);
+        Ok(())
     }
-}
\ No newline at end of file
+
+    /// 删除设备上的快照文件
+    pub fn delete_snapshot(&self, snapshot: &SnapshotId) -> ADBResult<()> {
+        self.shell(&snapshot.device_id, &format!("rm -f {}", shell_quote(&snapshot.path)))?;
+        Ok(())
+    }
+}
+
+/// 解析 `dumpsys package <pkg>` 输出
+pub(crate) fn parse_package_dump(package_name: &str, output: &str) -> ADBResult<PackageInfo> {
+    // 存储原始输出以便调试和完整访问
+    let mut info = PackageInfo::new(package_name);
+    info.raw_data = Some(output.to_string());
+
+    // 使用正则表达式解析更多信息
+    if let Ok(re_version) = Regex::new(r"versionName=([^\s]+)") {
+        if let Some(caps) = re_version.captures(output) {
+            if let Some(ver) = caps.get(1) {
+                info.version_name = Some(ver.as_str().to_string());
+            }
+        }
+    }
+
+    if let Ok(re_code) = Regex::new(r"versionCode=(\d+)") {
+        if let Some(caps) = re_code.captures(output) {
+            if let Some(code) = caps.get(1) {
+                if let Ok(code_int) = i32::from_str(code.as_str()) {
+                    info.version_code = Some(code_int);
+                }
+            }
+        }
+    }
+
+    // 提取首次安装时间
+    if let Ok(re_install) = Regex::new(r"firstInstallTime=([^\s]+)") {
+        if let Some(caps) = re_install.captures(output) {
+            if let Some(time) = caps.get(1) {
+                info.install_time = Some(time.as_str().to_string());
+            }
+        }
+    }
+
+    // 提取最后更新时间
+    if let Ok(re_update) = Regex::new(r"lastUpdateTime=([^\s]+)") {
+        if let Some(caps) = re_update.captures(output) {
+            if let Some(time) = caps.get(1) {
+                info.update_time = Some(time.as_str().to_string());
+            }
+        }
+    }
+
+    // 提取 UID
+    if let Ok(re_uid) = Regex::new(r"userId=(\d+)") {
+        if let Some(caps) = re_uid.captures(output) {
+            if let Some(uid) = caps.get(1) {
+                if let Ok(uid_int) = i32::from_str(uid.as_str()) {
+                    info.uid = Some(uid_int);
+                }
+            }
+        }
+    }
+
+    // 提取 SDK 版本信息
+    if let Ok(re_target_sdk) = Regex::new(r"targetSdk=(\d+)") {
+        if let Some(caps) = re_target_sdk.captures(output) {
+            if let Some(sdk) = caps.get(1) {
+                if let Ok(sdk_int) = i32::from_str(sdk.as_str()) {
+                    info.target_sdk = Some(sdk_int);
+                }
+            }
+        }
+    }
+
+    if let Ok(re_min_sdk) = Regex::new(r"minSdk=(\d+)") {
+        if let Some(caps) = re_min_sdk.captures(output) {
+            if let Some(sdk) = caps.get(1) {
+                if let Ok(sdk_int) = i32::from_str(sdk.as_str()) {
+                    info.min_sdk = Some(sdk_int);
+                }
+            }
+        }
+    }
+
+    // 提取安装来源
+    if let Ok(re_install_source) = Regex::new(r"installerPackageName=([^\s]+)") {
+        if let Some(caps) = re_install_source.captures(output) {
+            if let Some(source) = caps.get(1) {
+                info.install_source = Some(source.as_str().to_string());
+            }
+        }
+    }
+
+    // 提取权限
+    let lines = output.lines().collect::<Vec<&str>>();
+    let mut in_permissions = false;
+
+    for line in &lines {
+        if line.contains("requested permissions:") {
+            in_permissions = true;
+            continue;
+        } else if in_permissions && line.trim().is_empty() {
+            in_permissions = false;
+            continue;
+        }
+
+        if in_permissions && line.contains(": granted=") {
+            if let Some(perm) = line.split(':').next() {
+                let perm = perm.trim();
+                if !perm.is_empty() {
+                    info.permissions.push(perm.to_string());
+                }
+            }
+        }
+    }
+
+    // 提取 Activities
+    let re_activity = Regex::new(r"/([^/\s]+)")?;
+    let mut in_activities = false;
+    for line in &lines {
+        if line.contains("Activity Resolver Table:") {
+            in_activities = true;
+            continue;
+        } else if in_activities && line.trim().is_empty() {
+            in_activities = false;
+            continue;
+        }
+
+        if in_activities && line.contains(package_name) {
+            if let Some(activity) = re_activity
+                .captures(line)
+                .and_then(|caps| caps.get(1))
+                .map(|m| m.as_str())
+            {
+                info.activities.push(activity.to_string());
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+/// 解析 `pm list packages` 输出
+pub(crate) fn parse_package_list(output: &str) -> Vec<String> {
+    let mut packages = Vec::new();
+
+    for line in output.lines() {
+        if line.starts_with("package:") {
+            let package = line.trim_start_matches("package:").trim();
+            packages.push(package.to_string());
+        }
+    }
+
+    packages
+}
+
+/// 解析 `pm path` 输出，去除多用户导致的重复项
+pub(crate) fn parse_package_paths(output: &str) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+
+    for line in output.lines() {
+        if let Some(path) = line.trim().strip_prefix("package:") {
+            let path = path.trim().to_string();
+            if !path.is_empty() && !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+
+    paths
+}
+
+/// 解析 `dumpsys jobscheduler` 中 "Registered N jobs:" 部分
+fn parse_scheduled_jobs(output: &str) -> Vec<ScheduledJob> {
+    let mut jobs: Vec<ScheduledJob> = Vec::new();
+    let mut section_indent: Option<usize> = None;
+
+    let constraints = |value: &str| -> Vec<String> {
+        value.split_whitespace().map(|c| c.to_string()).collect()
+    };
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+
+        if trimmed.starts_with("Registered") && trimmed.ends_with("jobs:") {
+            section_indent = Some(indent);
+            continue;
+        }
+        match section_indent {
+            Some(section) if indent > section => {}
+            Some(_) => {
+                section_indent = None;
+                continue;
+            }
+            None => continue,
+        }
+
+        if let Some(caps) = JOB_RE.captures(trimmed) {
+            jobs.push(ScheduledJob {
+                job_id: caps[2].parse().unwrap_or(0),
+                uid: caps[1].to_string(),
+                package_name: caps[3].to_string(),
+                service: caps[4].to_string(),
+                required_constraints: Vec::new(),
+                unsatisfied_constraints: Vec::new(),
+                periodic: false,
+                ready: None,
+            });
+            continue;
+        }
+
+        let job = match jobs.last_mut() {
+            Some(job) => job,
+            None => continue,
+        };
+        if let Some(value) = trimmed.strip_prefix("Required constraints:") {
+            job.required_constraints = constraints(value);
+        } else if let Some(value) = trimmed.strip_prefix("Unsatisfied constraints:") {
+            job.unsatisfied_constraints = constraints(value);
+        } else if trimmed.starts_with("PERIODIC:") || trimmed.starts_with("Periodic:") {
+            job.periodic = true;
+        } else if let Some(value) = trimmed.strip_prefix("Ready:") {
+            job.ready = Some(value.trim_start().starts_with("true"));
+        }
+    }
+
+    jobs
+}
+
+/// 从 `pm dump` 的 Receiver Resolver Table 中查找处理指定 action 的接收器
+fn parse_action_receiver(output: &str, action: &str, package_name: &str) -> Option<String> {
+    let table = output.split("Receiver Resolver Table:").nth(1)?;
+    let mut in_action = false;
+    let mut action_indent = 0;
+
+    for line in table.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+
+        if trimmed == format!("{}:", action) {
+            in_action = true;
+            action_indent = indent;
+            continue;
+        }
+        if !in_action {
+            continue;
+        }
+        if indent <= action_indent {
+            // 表格后面的其他部分，或同一 action 之后的下一个 action
+            in_action = false;
+            continue;
+        }
+
+        // "1a2b3c4 com.foo/com.google.firebase.iid.FirebaseInstanceIdReceiver filter 5d6e7f"
+        if let Some(component) = trimmed
+            .split_whitespace()
+            .find(|part| part.starts_with(&format!("{}/", package_name)))
+        {
+            let (package, class) = component.split_once('/')?;
+            let class = match class.strip_prefix('.') {
+                Some(relative) => format!("{}.{}", package, relative),
+                None => class.to_string(),
+            };
+            return Some(format!("{}/{}", package, class));
+        }
+    }
+
+    None
+}
@@ -0,0 +1,200 @@
+//! Android Automotive (AAOS) 车机
+//!
+//! 车机服务通过 `cmd car_service` 操作：行驶状态由注入的车辆属性（档位、车速、手刹）
+//! 推导，用户体验限制 (UXR) 随之生效。车机通常运行无界面的系统用户 0，
+//! 前台用户（驾驶员、乘客、访客）从 10 开始，切换用户需要经过车机服务。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::inventory::DeviceClass;
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// 车辆属性 ID (VehicleProperty)
+const PERF_VEHICLE_SPEED: &str = "0x11600207";
+const GEAR_SELECTION: &str = "0x11400400";
+const PARKING_BRAKE_ON: &str = "0x11200402";
+// 档位 (VehicleGear)
+const GEAR_PARK: u32 = 0x4;
+const GEAR_DRIVE: u32 = 0x8;
+// 模拟行驶时的车速（米/秒）
+const DRIVING_SPEED: f32 = 10.0;
+// 等待用户切换完成时的轮询间隔（毫秒）
+const USER_SWITCH_POLL_INTERVAL: u64 = 500;
+
+// `pm list users` 的行，如 "UserInfo{10:Driver:c13} running"
+static USER_INFO_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"UserInfo\{(\d+):([^:]*):([0-9a-fA-F]+)\}(\s+running)?").unwrap());
+
+/// 行驶状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrivingState {
+    /// 驻车：P 档、车速为零、手刹拉起
+    Parked,
+    /// 行驶：D 档、车速不为零、手刹松开
+    Driving,
+}
+
+/// 车机用户
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CarUser {
+    pub id: u32,
+    pub name: String,
+    /// 用户标志位 (UserInfo.flags)
+    pub flags: u32,
+    pub running: bool,
+}
+
+impl CarUser {
+    /// 是否为访客用户
+    pub fn is_guest(&self) -> bool {
+        self.flags & 0x4 != 0
+    }
+
+    /// 是否为无界面的系统用户
+    pub fn is_headless_system(&self) -> bool {
+        self.id == 0
+    }
+}
+
+/// 解析 `pm list users` 输出
+fn parse_users(output: &str) -> Vec<CarUser> {
+    USER_INFO_RE
+        .captures_iter(output)
+        .filter_map(|caps| {
+            Some(CarUser {
+                id: caps[1].parse().ok()?,
+                name: caps[2].to_string(),
+                flags: u32::from_str_radix(&caps[3], 16).ok()?,
+                running: caps.get(4).is_some(),
+            })
+        })
+        .collect()
+}
+
+impl ADB {
+    /// 设备是否为车机
+    pub fn is_automotive(&self, device_id: &str) -> ADBResult<bool> {
+        Ok(self.device_class(device_id)? == DeviceClass::Automotive)
+    }
+
+    /// 列出车机服务
+    pub fn list_car_services(&self, device_id: &str) -> ADBResult<Vec<String>> {
+        let output = self.shell(device_id, "dumpsys car_service --list")?;
+        if output.contains("Can't find service") {
+            return Err(ADBError::DeviceError(format!("设备 {} 不是车机", device_id)));
+        }
+
+        Ok(output
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.contains(' '))
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    /// 通过注入档位、车速和手刹属性设置行驶状态
+    pub fn set_driving_state(&self, device_id: &str, state: DrivingState) -> ADBResult<()> {
+        let (gear, speed, brake) = match state {
+            DrivingState::Parked => (GEAR_PARK, 0.0, 1),
+            DrivingState::Driving => (GEAR_DRIVE, DRIVING_SPEED, 0),
+        };
+
+        for (property, value) in [
+            (GEAR_SELECTION, gear.to_string()),
+            (PERF_VEHICLE_SPEED, speed.to_string()),
+            (PARKING_BRAKE_ON, brake.to_string()),
+        ] {
+            let output = self.shell(
+                device_id,
+                &format!("cmd car_service inject-vhal-event {} {}", property, value),
+            )?;
+            if output.contains("Failed") || output.contains("Unknown") {
+                return Err(ADBError::CommandError(format!(
+                    "注入车辆属性 {} 失败: {}",
+                    property,
+                    output.trim()
+                )));
+            }
+        }
+
+        debug!("车机 {} 行驶状态: {:?}", device_id, state);
+        Ok(())
+    }
+
+    /// 是否以无界面系统用户运行（AAOS 默认）
+    pub fn is_headless_system_user(&self, device_id: &str) -> ADBResult<bool> {
+        Ok(self.get_prop(device_id, "ro.fw.mu.headless_system_user")?.trim() == "true")
+    }
+
+    /// 列出车机用户
+    pub fn list_car_users(&self, device_id: &str) -> ADBResult<Vec<CarUser>> {
+        let output = self.shell(device_id, "pm list users")?;
+        let users = parse_users(&output);
+        if users.is_empty() {
+            return Err(ADBError::ParseError(format!("无法解析用户列表: {}", output.trim())));
+        }
+        Ok(users)
+    }
+
+    /// 通过车机服务创建用户，返回新用户 ID
+    pub fn create_car_user(&self, device_id: &str, name: &str, guest: bool) -> ADBResult<u32> {
+        let output = self.shell(
+            device_id,
+            &format!(
+                "cmd car_service create-user {}{}",
+                if guest { "--guest " } else { "" },
+                crate::utils::shell_quote(name)
+            ),
+        )?;
+
+        let user_id = self
+            .list_car_users(device_id)?
+            .into_iter()
+            .filter(|u| u.name == name)
+            .map(|u| u.id)
+            .max()
+            .ok_or_else(|| ADBError::CommandError(format!("创建用户 {} 失败: {}", name, output.trim())))?;
+
+        info!("车机 {} 已创建用户 {} ({})", device_id, name, user_id);
+        Ok(user_id)
+    }
+
+    /// 通过车机服务切换前台用户并等待切换完成
+    ///
+    /// 车机切换用户比手机慢得多，等待时间按设备形态放大（见 [`ADB::device_timeout`]）。
+    pub fn switch_car_user(&self, device_id: &str, user_id: u32) -> ADBResult<()> {
+        if user_id == 0 && self.is_headless_system_user(device_id)? {
+            return Err(ADBError::ConfigError("无法切换到无界面的系统用户 0".to_string()));
+        }
+
+        let output = self.shell(device_id, &format!("cmd car_service switch-user {}", user_id))?;
+        if !output.contains("SUCCESSFUL") {
+            return Err(ADBError::CommandError(format!(
+                "切换到用户 {} 失败: {}",
+                user_id,
+                output.trim()
+            )));
+        }
+
+        let timeout = self.device_timeout(device_id);
+        let switched = crate::utils::wait_with_polling(
+            timeout.as_millis() as u64,
+            USER_SWITCH_POLL_INTERVAL,
+            || Ok(self.current_user(device_id)? == user_id),
+            None::<fn(u64)>,
+        )?;
+        if !switched {
+            return Err(ADBError::TimeoutError {
+                message: format!("车机 {} 未能切换到用户 {}", device_id, user_id),
+                duration: timeout,
+            });
+        }
+
+        // 进程、PID 等缓存属于切换前的用户
+        self.invalidate_cache(device_id);
+        info!("车机 {} 已切换到用户 {}", device_id, user_id);
+        Ok(())
+    }
+}
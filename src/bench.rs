@@ -0,0 +1,240 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::{debug, info};
+use rand::RngCore;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// I/O 基准测试选项
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    /// 测试的文件大小（字节）
+    pub file_sizes: Vec<u64>,
+    /// 每个文件大小重复传输的次数
+    pub iterations: u32,
+    /// shell 往返延迟采样次数
+    pub shell_samples: u32,
+    /// 用于测量安装时间的参考 APK（可选）
+    pub reference_apk: Option<String>,
+    /// 设备上存放测试文件的目录
+    pub device_dir: String,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            file_sizes: vec![1024 * 1024, 10 * 1024 * 1024, 50 * 1024 * 1024],
+            iterations: 3,
+            shell_samples: 10,
+            reference_apk: None,
+            device_dir: "/data/local/tmp".to_string(),
+        }
+    }
+}
+
+/// 耗时统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LatencyStats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+}
+
+impl LatencyStats {
+    /// 由采样计算统计值，采样为空时全部为零
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let total: Duration = sorted.iter().sum();
+
+        LatencyStats {
+            min: sorted[0],
+            median: sorted[sorted.len() / 2],
+            max: sorted[sorted.len() - 1],
+            mean: total / sorted.len() as u32,
+        }
+    }
+}
+
+/// 单个文件大小的传输测试结果
+#[derive(Debug, Clone)]
+pub struct TransferBench {
+    pub size: u64,
+    pub push: LatencyStats,
+    pub pull: LatencyStats,
+    /// 推送吞吐量（字节/秒，按中位数计算）
+    pub push_throughput: f64,
+    /// 拉取吞吐量（字节/秒，按中位数计算）
+    pub pull_throughput: f64,
+}
+
+/// 安装耗时测试结果
+#[derive(Debug, Clone)]
+pub struct InstallBench {
+    pub apk_path: String,
+    pub apk_size: u64,
+    pub duration: Duration,
+}
+
+/// I/O 基准测试报告
+#[derive(Debug, Clone)]
+pub struct IoBenchReport {
+    pub device_id: String,
+    pub transfers: Vec<TransferBench>,
+    pub shell_latency: LatencyStats,
+    pub install: Option<InstallBench>,
+    pub total_duration: Duration,
+}
+
+impl IoBenchReport {
+    /// 各文件大小中最低的推送吞吐量（字节/秒）
+    pub fn min_push_throughput(&self) -> Option<f64> {
+        self.transfers
+            .iter()
+            .map(|t| t.push_throughput)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    /// 各文件大小中最低的拉取吞吐量（字节/秒）
+    pub fn min_pull_throughput(&self) -> Option<f64> {
+        self.transfers
+            .iter()
+            .map(|t| t.pull_throughput)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+}
+
+fn throughput(bytes: u64, duration: Duration) -> f64 {
+    let secs = duration.as_secs_f64();
+    if secs > 0.0 {
+        bytes as f64 / secs
+    } else {
+        0.0
+    }
+}
+
+/// 写入指定大小的随机内容文件
+fn write_random_file(path: &Path, size: u64) -> ADBResult<()> {
+    use std::io::Write;
+
+    let mut file = fs::File::create(path)
+        .map_err(|e| ADBError::FileError(format!("无法创建测试文件: {}", e)))?;
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let mut remaining = size;
+
+    while remaining > 0 {
+        let len = remaining.min(buffer.len() as u64) as usize;
+        rand::rng().fill_bytes(&mut buffer[..len]);
+        file.write_all(&buffer[..len])?;
+        remaining -= len as u64;
+    }
+
+    Ok(())
+}
+
+impl ADB {
+    /// 测量设备的推送/拉取吞吐量、shell 往返延迟和（可选）APK 安装耗时
+    ///
+    /// 用于发现数据线、USB Hub 等链路退化
+    pub fn run_io_benchmark(&self, device_id: &str, options: BenchOptions) -> ADBResult<IoBenchReport> {
+        let started = Instant::now();
+        info!("开始设备 {} 的 I/O 基准测试", device_id);
+
+        // shell 往返延迟
+        let mut shell_samples = Vec::with_capacity(options.shell_samples as usize);
+        for _ in 0..options.shell_samples {
+            let start = Instant::now();
+            self.shell(device_id, "true")?;
+            shell_samples.push(start.elapsed());
+        }
+        let shell_latency = LatencyStats::from_samples(&shell_samples);
+        debug!("shell 往返延迟: {:?}", shell_latency);
+
+        // 推送/拉取吞吐量
+        let local_dir = crate::utils::create_temp_dir_path("adb_bench")?;
+        let transfers = self.with_resources(device_id, |resources| {
+            let mut transfers = Vec::new();
+
+            for &size in &options.file_sizes {
+                let local_file = local_dir.join(format!("bench_{}.bin", size));
+                let pulled_file = local_dir.join(format!("bench_{}.pulled", size));
+                write_random_file(&local_file, size)?;
+
+                let device_path = format!(
+                    "{}/adbkit_bench_{}_{}.bin",
+                    options.device_dir.trim_end_matches('/'),
+                    size,
+                    rand::random::<u32>()
+                );
+                resources.track_temp_file(&device_path);
+
+                let mut push_samples = Vec::new();
+                let mut pull_samples = Vec::new();
+                for _ in 0..options.iterations.max(1) {
+                    let stats = self.push(device_id, local_file.to_str().unwrap_or_default(), &device_path, None)?;
+                    push_samples.push(stats.duration);
+
+                    let stats = self.pull(device_id, &device_path, pulled_file.to_str().unwrap_or_default(), None)?;
+                    pull_samples.push(stats.duration);
+                }
+
+                let push = LatencyStats::from_samples(&push_samples);
+                let pull = LatencyStats::from_samples(&pull_samples);
+                let bench = TransferBench {
+                    size,
+                    push_throughput: throughput(size, push.median),
+                    pull_throughput: throughput(size, pull.median),
+                    push,
+                    pull,
+                };
+                debug!(
+                    "{}: 推送 {:.2} MB/s, 拉取 {:.2} MB/s",
+                    crate::utils::format_size(size),
+                    bench.push_throughput / 1_048_576.0,
+                    bench.pull_throughput / 1_048_576.0
+                );
+                transfers.push(bench);
+
+                let _ = fs::remove_file(&local_file);
+                let _ = fs::remove_file(&pulled_file);
+            }
+
+            Ok(transfers)
+        });
+        let _ = fs::remove_dir_all(&local_dir);
+        let transfers = transfers?;
+
+        // 安装耗时
+        let install = match &options.reference_apk {
+            Some(apk) => {
+                let apk_size = fs::metadata(apk)
+                    .map(|m| m.len())
+                    .map_err(|e| ADBError::FileError(format!("无法读取参考 APK {}: {}", apk, e)))?;
+                let start = Instant::now();
+                self.install_app(device_id, apk)?;
+                Some(InstallBench {
+                    apk_path: apk.clone(),
+                    apk_size,
+                    duration: start.elapsed(),
+                })
+            }
+            None => None,
+        };
+
+        let report = IoBenchReport {
+            device_id: device_id.to_string(),
+            transfers,
+            shell_latency,
+            install,
+            total_duration: started.elapsed(),
+        };
+        info!("设备 {} 的 I/O 基准测试完成，耗时 {:?}", device_id, report.total_duration);
+        Ok(report)
+    }
+}
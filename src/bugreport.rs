@@ -0,0 +1,140 @@
+//! 错误报告 (bugreport)
+//!
+//! `adb bugreport <路径>` 在设备上生成 zip 报告后拉取到本地，生成过程通常需要数分钟，
+//! 期间 adb 输出 `[ 42%] generating bugreport-....zip` 形式的进度行。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 生成报告的最短超时，配置的超时更长时使用配置值
+const BUGREPORT_TIMEOUT: Duration = Duration::from_secs(600);
+// 检查超时的间隔
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+static PROGRESS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[\s*(\d+)%\]").unwrap());
+
+// 完成时的提示，如 "Bug report copied to /tmp/bugreport-xxx.zip"
+static COPIED_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Bug report copied to (.+)$").unwrap());
+
+impl ADB {
+    /// 生成错误报告并保存到 `output_path`，生成过程中以百分比回调 `progress`
+    ///
+    /// `output_path` 为目录时由 adb 生成文件名，返回实际保存的路径。
+    /// 超时不小于 10 分钟。
+    pub fn bugreport<F>(&self, device_id: &str, output_path: &str, mut progress: F) -> ADBResult<PathBuf>
+    where
+        F: FnMut(u8),
+    {
+        let timeout = Duration::from_millis(self.config.timeout).max(BUGREPORT_TIMEOUT);
+
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+        let mut child = cmd
+            .arg("bugreport")
+            .arg(output_path)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ADBError::CommandError(format!("无法执行 ADB bugreport: {}", e)))?;
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取 bugreport 输出".to_string()))?;
+
+        info!("设备 {} 开始生成错误报告: {}", device_id, output_path);
+        let start = Instant::now();
+        let child = Mutex::new(child);
+        let done = AtomicBool::new(false);
+        let timed_out = AtomicBool::new(false);
+        let mut saved_path = None;
+        let mut messages = Vec::new();
+
+        thread::scope(|scope| {
+            // 读取输出会阻塞，由单独的线程在超时时结束进程
+            scope.spawn(|| {
+                while !done.load(Ordering::SeqCst) {
+                    if start.elapsed() > timeout {
+                        timed_out.store(true, Ordering::SeqCst);
+                        let _ = child.lock().unwrap().kill();
+                        break;
+                    }
+                    thread::sleep(TIMEOUT_POLL_INTERVAL);
+                }
+            });
+
+            // 进度行以 '\r' 或 '\n' 结尾
+            let mut last_percent = None;
+            let mut line = Vec::new();
+            let mut buffer = [0u8; 4096];
+            let mut handle_line = |line: &[u8]| {
+                let text = String::from_utf8_lossy(line);
+                let text = text.trim();
+                if text.is_empty() {
+                    return;
+                }
+                if let Some(percent) = PROGRESS_RE
+                    .captures(text)
+                    .and_then(|caps| caps[1].parse::<u8>().ok())
+                {
+                    if last_percent != Some(percent) {
+                        last_percent = Some(percent);
+                        progress(percent.min(100));
+                    }
+                } else if let Some(caps) = COPIED_RE.captures(text) {
+                    saved_path = Some(PathBuf::from(caps[1].trim()));
+                } else {
+                    messages.push(text.to_string());
+                }
+            };
+
+            loop {
+                match stdout.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        for &byte in &buffer[..n] {
+                            if byte == b'\r' || byte == b'\n' {
+                                handle_line(&line);
+                                line.clear();
+                            } else {
+                                line.push(byte);
+                            }
+                        }
+                    }
+                }
+            }
+            handle_line(&line);
+            done.store(true, Ordering::SeqCst);
+        });
+
+        let output = child.into_inner().unwrap().wait_with_output()?;
+        if timed_out.load(Ordering::SeqCst) {
+            return Err(ADBError::TimeoutError {
+                message: format!("设备 {} 生成错误报告超时", device_id),
+                duration: timeout,
+            });
+        }
+        if !output.status.success() {
+            return Err(ADBError::CommandError(format!(
+                "ADB bugreport 命令失败: {} {}",
+                String::from_utf8_lossy(&output.stderr).trim(),
+                messages.join("; ")
+            )));
+        }
+
+        let path = saved_path.unwrap_or_else(|| PathBuf::from(output_path));
+        debug!("设备 {} 错误报告已保存到 {:?}，耗时 {:?}", device_id, path, start.elapsed());
+        Ok(path)
+    }
+}
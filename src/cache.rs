@@ -0,0 +1,183 @@
+//! 命令输出缓存
+//!
+//! 短时间内连续执行的只读查询（`pm list packages`、`dumpsys package`、`getprop` 等）
+//! 可以通过 [`ADB::cached`] 复用结果。缓存按设备和显式的键存放，由同一 ADB 实例的
+//! 所有克隆共享；安装、卸载应用后会清除对应设备的缓存。
+
+use crate::device::ADB;
+use crate::error::ADBResult;
+use log::trace;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// `cached()` 的默认有效期
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5);
+
+struct CacheEntry {
+    value: Arc<dyn Any + Send + Sync>,
+    /// 过期时间，None 表示一直有效直到被清除
+    expires_at: Option<Instant>,
+}
+
+/// 按设备存放的缓存：设备 ID -> 键 -> 值
+#[derive(Default)]
+pub(crate) struct CommandCache {
+    entries: Mutex<HashMap<String, HashMap<String, CacheEntry>>>,
+}
+
+impl fmt::Debug for CommandCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries.lock().unwrap();
+        f.debug_struct("CommandCache")
+            .field("devices", &entries.len())
+            .field("entries", &entries.values().map(|e| e.len()).sum::<usize>())
+            .finish()
+    }
+}
+
+impl CommandCache {
+    /// 读取未过期的值，类型不匹配时视为未命中
+    pub(crate) fn get<T: Clone + 'static>(&self, device_id: &str, key: &str) -> Option<T> {
+        let mut entries = self.entries.lock().unwrap();
+        let device = entries.get_mut(device_id)?;
+        let entry = device.get(key)?;
+        if entry.expires_at.is_some_and(|t| Instant::now() >= t) {
+            device.remove(key);
+            return None;
+        }
+        entry.value.downcast_ref::<T>().cloned()
+    }
+
+    /// 写入值，`ttl` 为 None 时一直有效直到被清除
+    pub(crate) fn insert<T: Send + Sync + 'static>(
+        &self,
+        device_id: &str,
+        key: &str,
+        value: T,
+        ttl: Option<Duration>,
+    ) {
+        self.entries
+            .lock()
+            .unwrap()
+            .entry(device_id.to_string())
+            .or_default()
+            .insert(
+                key.to_string(),
+                CacheEntry {
+                    value: Arc::new(value),
+                    expires_at: ttl.map(|ttl| Instant::now() + ttl),
+                },
+            );
+    }
+
+    /// 清除单个键，返回键是否存在
+    pub(crate) fn invalidate(&self, device_id: &str, key: &str) -> bool {
+        self.entries
+            .lock()
+            .unwrap()
+            .get_mut(device_id)
+            .is_some_and(|device| device.remove(key).is_some())
+    }
+
+    /// 清除所有设备上的同名键
+    pub(crate) fn invalidate_key(&self, key: &str) {
+        for device in self.entries.lock().unwrap().values_mut() {
+            device.remove(key);
+        }
+    }
+
+    /// 清除设备的全部缓存
+    pub(crate) fn clear_device(&self, device_id: &str) {
+        self.entries.lock().unwrap().remove(device_id);
+    }
+
+    /// 清除全部缓存
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// 带缓存的查询接口，由 [`ADB::cached`] 创建
+///
+/// 只应用于只读查询；执行失败的结果不会被缓存。
+pub struct CachedADB<'a> {
+    adb: &'a ADB,
+    ttl: Duration,
+}
+
+impl CachedADB<'_> {
+    /// 设置缓存有效期
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// 执行 shell 命令，有效期内相同的命令直接返回缓存的输出
+    pub fn shell(&self, device_id: &str, command: &str) -> ADBResult<String> {
+        self.get_or_fetch(device_id, &format!("shell:{}", command), || {
+            self.adb.shell(device_id, command)
+        })
+    }
+
+    /// 按显式的键读取缓存，未命中时调用 `fetch` 并缓存结果
+    ///
+    /// 键由调用方定义，同一设备上不同类型的值应使用不同的键。
+    pub fn get_or_fetch<T, F>(&self, device_id: &str, key: &str, fetch: F) -> ADBResult<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> ADBResult<T>,
+    {
+        if let Some(value) = self.adb.cache.get::<T>(device_id, key) {
+            trace!("使用缓存: {} {}", device_id, key);
+            return Ok(value);
+        }
+
+        let value = fetch()?;
+        self.adb
+            .cache
+            .insert(device_id, key, value.clone(), Some(self.ttl));
+        Ok(value)
+    }
+
+    /// 清除单个键，返回键是否存在
+    pub fn invalidate(&self, device_id: &str, key: &str) -> bool {
+        self.adb.cache.invalidate(device_id, key)
+    }
+
+    /// 清除 shell 命令的缓存，返回是否存在
+    pub fn invalidate_shell(&self, device_id: &str, command: &str) -> bool {
+        self.invalidate(device_id, &format!("shell:{}", command))
+    }
+}
+
+impl ADB {
+    /// 返回带缓存的查询接口，默认有效期 5 秒
+    ///
+    /// ```no_run
+    /// use adb_kit::ADB;
+    /// use std::time::Duration;
+    ///
+    /// let adb = ADB::new(None);
+    /// let cached = adb.cached().ttl(Duration::from_secs(30));
+    /// let packages = cached.shell("emulator-5554", "pm list packages").unwrap();
+    /// ```
+    pub fn cached(&self) -> CachedADB<'_> {
+        CachedADB {
+            adb: self,
+            ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+
+    /// 清除设备的全部缓存，包括 PID、设备画像等内部缓存
+    pub fn invalidate_cache(&self, device_id: &str) {
+        self.cache.clear_device(device_id);
+    }
+
+    /// 清除所有设备的缓存
+    pub fn clear_cache(&self) {
+        self.cache.clear();
+    }
+}
@@ -0,0 +1,364 @@
+//! 故障注入
+//!
+//! 在测试运行期间按随机间隔注入故障：断网、旋转屏幕、撤销权限、杀死应用进程、
+//! 模拟内存不足。随机数生成器可指定种子，相同种子和配置会产生相同的故障序列，
+//! 配合事件日志可以复现问题。
+
+use crate::app::TrimMemoryLevel;
+use crate::device::ADB;
+use crate::error::ADBResult;
+use crate::monitor::sleep_unless_stopped;
+use crate::utils::shell_quote;
+use log::{debug, info, warn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+/// 可注入的故障类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChaosAction {
+    /// 关闭 Wi-Fi 和移动数据，保持 `fault_duration` 后恢复
+    ///
+    /// 断开和恢复在设备端作为一条后台命令执行，通过无线调试连接时也能恢复
+    ToggleNetwork,
+    /// 旋转到随机方向
+    RotateScreen,
+    /// 撤销一个已配置的运行时权限
+    RevokePermission,
+    /// 强制停止应用进程
+    KillApp,
+    /// 发送随机级别的 `am send-trim-memory`
+    TrimMemory,
+}
+
+impl ChaosAction {
+    /// 全部故障类型
+    pub fn all() -> Vec<Self> {
+        vec![
+            ChaosAction::ToggleNetwork,
+            ChaosAction::RotateScreen,
+            ChaosAction::RevokePermission,
+            ChaosAction::KillApp,
+            ChaosAction::TrimMemory,
+        ]
+    }
+}
+
+/// 故障注入配置
+#[derive(Debug, Clone)]
+pub struct ChaosConfig {
+    /// 被测应用包名
+    pub package: String,
+    /// 允许注入的故障类型
+    pub actions: Vec<ChaosAction>,
+    /// 两次注入之间的最短间隔
+    pub min_interval: Duration,
+    /// 两次注入之间的最长间隔
+    pub max_interval: Duration,
+    /// 断网等可恢复故障的持续时间
+    pub fault_duration: Duration,
+    /// 可撤销的权限
+    pub permissions: Vec<String>,
+    /// 随机数种子，为 None 时随机生成（实际使用的种子记录在 [`ChaosMonkey::seed`]）
+    pub seed: Option<u64>,
+}
+
+impl ChaosConfig {
+    pub fn new(package: &str) -> Self {
+        Self {
+            package: package.to_string(),
+            actions: ChaosAction::all(),
+            min_interval: Duration::from_secs(5),
+            max_interval: Duration::from_secs(30),
+            fault_duration: Duration::from_secs(5),
+            permissions: Vec::new(),
+            seed: None,
+        }
+    }
+
+    pub fn actions(mut self, actions: &[ChaosAction]) -> Self {
+        self.actions = actions.to_vec();
+        self
+    }
+
+    pub fn interval(mut self, min: Duration, max: Duration) -> Self {
+        self.min_interval = min;
+        self.max_interval = max.max(min);
+        self
+    }
+
+    pub fn fault_duration(mut self, duration: Duration) -> Self {
+        self.fault_duration = duration;
+        self
+    }
+
+    pub fn permission(mut self, permission: &str) -> Self {
+        self.permissions.push(permission.to_string());
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// 一次故障注入记录
+#[derive(Debug, Clone)]
+pub struct ChaosEvent {
+    /// 注入序号，从 0 开始
+    pub index: u32,
+    pub action: ChaosAction,
+    /// 注入时间
+    pub timestamp: SystemTime,
+    /// 距离开始的时间
+    pub elapsed: Duration,
+    /// 故障参数（权限名、方向、内存级别等）
+    pub detail: String,
+    /// 执行失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 注入前的网络和旋转设置，读取失败的项为 None，恢复时跳过
+#[derive(Debug, Clone, Default)]
+struct ChaosBaseline {
+    wifi_on: Option<bool>,
+    mobile_data: Option<bool>,
+    accelerometer_rotation: Option<u8>,
+    user_rotation: Option<u8>,
+}
+
+impl ChaosBaseline {
+    /// 从 `settings get` 的输出解析，每行一个值
+    fn parse(output: &str) -> Self {
+        let mut values = output.lines().map(|l| l.trim().parse::<u8>().ok());
+        let mut next = || values.next().flatten();
+        Self {
+            wifi_on: next().map(|v| v != 0),
+            mobile_data: next().map(|v| v != 0),
+            accelerometer_rotation: next(),
+            user_rotation: next(),
+        }
+    }
+
+    /// 恢复网络状态的命令
+    fn network_command(&self) -> String {
+        let mut commands = Vec::new();
+        if let Some(on) = self.wifi_on {
+            commands.push(format!("svc wifi {}", if on { "enable" } else { "disable" }));
+        }
+        if let Some(on) = self.mobile_data {
+            commands.push(format!("svc data {}", if on { "enable" } else { "disable" }));
+        }
+        commands.push("true".to_string());
+        commands.join("; ")
+    }
+
+    /// 恢复旋转设置的命令
+    fn rotation_command(&self) -> String {
+        let mut commands = Vec::new();
+        if let Some(value) = self.user_rotation {
+            commands.push(format!("settings put system user_rotation {}", value));
+        }
+        if let Some(value) = self.accelerometer_rotation {
+            commands.push(format!("settings put system accelerometer_rotation {}", value));
+        }
+        commands.push("true".to_string());
+        commands.join("; ")
+    }
+}
+
+/// 后台故障注入任务
+///
+/// 在 `stop()`、超出作用域或 [`ADB::shutdown`] 时停止，并恢复注入前的网络和旋转设置
+pub struct ChaosMonkey {
+    seed: u64,
+    stop: Arc<AtomicBool>,
+    events: Arc<Mutex<Vec<ChaosEvent>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl ChaosMonkey {
+    /// 实际使用的随机数种子
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// 已注入的故障
+    pub fn events(&self) -> Vec<ChaosEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// 停止注入并等待后台线程退出，返回完整的事件日志
+    pub fn stop(&mut self) -> Vec<ChaosEvent> {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+            debug!("故障注入已停止");
+        }
+        self.events()
+    }
+}
+
+impl Drop for ChaosMonkey {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl ADB {
+    /// 启动后台故障注入
+    pub fn start_chaos(&self, device_id: &str, config: ChaosConfig) -> ChaosMonkey {
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let stop = Arc::new(AtomicBool::new(false));
+        self.jobs.register_stop_flag(stop.clone());
+        let events: Arc<Mutex<Vec<ChaosEvent>>> = Arc::new(Mutex::new(Vec::new()));
+
+        info!("设备 {} 开始故障注入，种子 {}", device_id, seed);
+
+        let baseline = match self.shell(
+            device_id,
+            "settings get global wifi_on; settings get global mobile_data; \
+             settings get system accelerometer_rotation; settings get system user_rotation",
+        ) {
+            Ok(output) => ChaosBaseline::parse(&output),
+            Err(e) => {
+                warn!("无法读取设备 {} 的网络和旋转设置，停止后不会恢复: {}", device_id, e);
+                ChaosBaseline::default()
+            }
+        };
+        debug!("设备 {} 注入前的设置: {:?}", device_id, baseline);
+
+        let worker = {
+            let adb = self.clone();
+            let device_id = device_id.to_string();
+            let stop = stop.clone();
+            let events = events.clone();
+
+            thread::spawn(move || {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let start = Instant::now();
+                let mut index = 0;
+
+                while !config.actions.is_empty() {
+                    let interval = if config.max_interval > config.min_interval {
+                        rng.random_range(config.min_interval..=config.max_interval)
+                    } else {
+                        config.min_interval
+                    };
+                    if !sleep_unless_stopped(interval, &stop) {
+                        break;
+                    }
+
+                    let action = config.actions[rng.random_range(0..config.actions.len())];
+                    let timestamp = SystemTime::now();
+                    let (detail, result) =
+                        adb.inject_fault(&device_id, action, &config, &baseline, &mut rng, &stop);
+
+                    let event = ChaosEvent {
+                        index,
+                        action,
+                        timestamp,
+                        elapsed: start.elapsed(),
+                        detail,
+                        error: result.err().map(|e| e.to_string()),
+                    };
+                    match &event.error {
+                        Some(e) => warn!("设备 {} 注入 {:?} 失败: {}", device_id, action, e),
+                        None => info!("设备 {} 注入 {:?} {}", device_id, action, event.detail),
+                    }
+                    events.lock().unwrap().push(event);
+                    index += 1;
+                }
+
+                adb.restore_chaos(&device_id, &baseline);
+            })
+        };
+
+        ChaosMonkey {
+            seed,
+            stop,
+            events,
+            worker: Some(worker),
+        }
+    }
+
+    /// 执行一次故障注入，返回故障参数和执行结果
+    fn inject_fault(
+        &self,
+        device_id: &str,
+        action: ChaosAction,
+        config: &ChaosConfig,
+        baseline: &ChaosBaseline,
+        rng: &mut StdRng,
+        stop: &AtomicBool,
+    ) -> (String, ADBResult<()>) {
+        let package = shell_quote(&config.package);
+
+        match action {
+            ChaosAction::ToggleNetwork => {
+                // 断网后 adb 可能随之断开，恢复命令必须在设备端执行
+                let script = format!(
+                    "svc wifi disable; svc data disable; sleep {}; {}",
+                    config.fault_duration.as_secs().max(1),
+                    baseline.network_command()
+                );
+                let result = self
+                    .shell(
+                        device_id,
+                        &format!("nohup sh -c {} >/dev/null 2>&1 &", shell_quote(&script)),
+                    )
+                    .map(|_| {
+                        sleep_unless_stopped(config.fault_duration, stop);
+                    });
+                (format!("断网 {:?}", config.fault_duration), result)
+            }
+            ChaosAction::RotateScreen => {
+                let rotation = rng.random_range(0..4u8);
+                let result = self
+                    .shell(
+                        device_id,
+                        &format!(
+                            "settings put system accelerometer_rotation 0; settings put system user_rotation {}",
+                            rotation
+                        ),
+                    )
+                    .map(|_| ());
+                (format!("rotation={}", rotation), result)
+            }
+            ChaosAction::RevokePermission => {
+                if config.permissions.is_empty() {
+                    return ("未配置可撤销的权限".to_string(), Ok(()));
+                }
+                let permission = &config.permissions[rng.random_range(0..config.permissions.len())];
+                let result = self
+                    .shell(device_id, &format!("pm revoke {} {}", package, shell_quote(permission)))
+                    .map(|_| ());
+                (permission.clone(), result)
+            }
+            ChaosAction::KillApp => {
+                let result = self
+                    .shell(device_id, &format!("am force-stop {}", package))
+                    .map(|_| ());
+                (config.package.clone(), result)
+            }
+            ChaosAction::TrimMemory => {
+                let levels = TrimMemoryLevel::all();
+                let level = levels[rng.random_range(0..levels.len())];
+                let result = self.send_trim_memory(device_id, &config.package, level);
+                (level.as_str().to_string(), result)
+            }
+        }
+    }
+
+    /// 恢复注入前的网络和旋转设置
+    fn restore_chaos(&self, device_id: &str, baseline: &ChaosBaseline) {
+        let command = format!("{}; {}", baseline.network_command(), baseline.rotation_command());
+        if let Err(e) = self.shell(device_id, &command) {
+            warn!("设备 {} 故障注入后恢复失败: {}", device_id, e);
+        }
+    }
+}
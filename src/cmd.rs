@@ -2,26 +2,125 @@ use crate::device::ADB;
 use crate::error::{ADBError, ADBResult};
 use log::{debug, info, trace, warn};
 use std::collections::HashMap;
-use std::process::Command;
+use crate::runner::AdbCommand;
+use crate::scheduler::Priority;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::Stdio;
 use std::str;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::{Duration, Instant};
 use once_cell::sync::Lazy;
+use regex::Regex;
 
-// 缓存 Android 版本号
-static ANDROID_VERSION_CACHE: Lazy<Mutex<HashMap<String, f32>>> = Lazy::new(|| {
-    Mutex::new(HashMap::new())
-});
+// PID 缓存超时时间（3秒）
+const PID_CACHE_TIMEOUT: Duration = Duration::from_secs(3);
 
-// 缓存 PID 信息
-static PID_CACHE: Lazy<Mutex<HashMap<String, (i32, Instant)>>> = Lazy::new(|| {
-    Mutex::new(HashMap::new())
-});
+// 流式 shell 检查取消标志的间隔
+const STREAM_CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// 不支持 shell v2 时，在输出末尾附加退出码的标记
+const EXIT_CODE_MARKER: &str = "__ADBKIT_EXIT__";
+
+// `adb pair` 成功时的输出，如 "Successfully paired to 192.168.1.5:37123 [guid=adb-XXXX-abc]"
+static PAIRED_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"Successfully paired to (\S+)(?:\s+\[guid=([^\]]+)\])?").unwrap());
+// `adb mdns services` 中的无线调试连接服务
+static TLS_CONNECT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^(\S+)\s+_adb-tls-connect\._tcp\.?\s+(\S+:\d+)").unwrap());
+
+// 配对后等待设备出现连接服务的时间
+const WIRELESS_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+// 查询 mDNS 服务的间隔
+const MDNS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// shell 命令的完整结果
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ShellOutput {
+    pub stdout: Vec<u8>,
+    /// 标准错误；设备不支持 shell v2 时 stderr 会合并到 stdout，此处为空
+    pub stderr: Vec<u8>,
+    pub exit_code: i32,
+}
+
+impl ShellOutput {
+    /// 退出码是否为 0
+    pub fn success(&self) -> bool {
+        self.exit_code == 0
+    }
 
-// 缓存超时时间（3秒）
-const PID_CACHE_TIMEOUT: Duration = Duration::from_secs(3);
+    /// 以 UTF-8 解码的标准输出
+    pub fn stdout_str(&self) -> String {
+        String::from_utf8_lossy(&self.stdout).to_string()
+    }
+
+    /// 以 UTF-8 解码的标准错误
+    pub fn stderr_str(&self) -> String {
+        String::from_utf8_lossy(&self.stderr).to_string()
+    }
+}
+
+/// 从附加了退出码标记的输出中分离退出码
+fn split_exit_code(mut stdout: Vec<u8>) -> ADBResult<(Vec<u8>, i32)> {
+    let marker = EXIT_CODE_MARKER.as_bytes();
+    let position = stdout
+        .windows(marker.len())
+        .rposition(|w| w == marker)
+        .ok_or_else(|| ADBError::ParseError("shell 输出中缺少退出码".to_string()))?;
+
+    let code = String::from_utf8_lossy(&stdout[position + marker.len()..])
+        .trim()
+        .parse::<i32>()?;
+    stdout.truncate(position);
+    Ok((stdout, code))
+}
+
+/// 解析 `adb devices -l` 输出中的一行设备信息
+pub(crate) fn parse_device_line(line: &str) -> Option<crate::device::ADBDevice> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 2 {
+        return None;
+    }
+
+    let id = parts[0];
+    let status = crate::device::DeviceStatus::from(parts[1]);
+
+    // 创建基础设备
+    let mut device = crate::device::ADBDevice::new(id, status);
+
+    // 提取设备型号，并使用型号作为设备名称
+    if let Some(model_part) = parts.iter().find(|p| p.starts_with("model:")) {
+        let model = model_part.trim_start_matches("model:");
+        device = device.with_model(model).with_name(model);
+    }
+
+    // 提取产品信息
+    if let Some(product_part) = parts.iter().find(|p| p.starts_with("product:")) {
+        device = device.with_product(product_part.trim_start_matches("product:"));
+    }
+
+    // 提取传输 ID
+    if let Some(transport_part) = parts.iter().find(|p| p.starts_with("transport_id:")) {
+        device = device.with_transport_id(transport_part.trim_start_matches("transport_id:"));
+    }
+
+    Some(device)
+}
 
 impl ADB {
+    /// 创建 ADB 命令，并附加配置中的全局参数
+    pub(crate) fn adb_command(&self) -> AdbCommand {
+        let mut cmd = AdbCommand::new(self.config.path.as_os_str(), self.runner.clone());
+        cmd.args(self.config.server_args());
+
+        if let Some(additional_args) = &self.config.additional_args {
+            cmd.args(additional_args);
+        }
+
+        cmd
+    }
+
     /// 使用指数退避策略重试操作
     pub fn with_retry<F, T>(&self, f: F) -> ADBResult<T>
     where
@@ -42,7 +141,7 @@ impl ADB {
     /// 检查 ADB 是否可用并获取版本
     pub fn check_adb(&self) -> ADBResult<String> {
         self.with_retry(|| {
-            let output = Command::new(&self.config.path)
+            let output = self.adb_command()
                 .arg("version")
                 .output()
                 .map_err(|e| ADBError::CommandError(format!("无法执行 ADB: {}", e)))?;
@@ -69,7 +168,7 @@ impl ADB {
     /// 列出可用设备
     pub fn list_devices(&self) -> ADBResult<Vec<crate::device::ADBDevice>> {
         self.with_retry(|| {
-            let output = Command::new(&self.config.path)
+            let output = self.adb_command()
                 .arg("devices")
                 .arg("-l") // 长格式以获取更多详细信息
                 .output()
@@ -95,42 +194,10 @@ impl ADB {
                 }
 
                 // 解析设备行
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    let id = parts[0].to_string();
-                    let status_str = parts[1];
-                    let status = crate::device::DeviceStatus::from(status_str);
-
-                    // 创建基础设备
-                    let mut device = crate::device::ADBDevice::new(&id, status);
-
-                    // 提取设备名称和其他属性
-                    if parts.len() > 2 {
-                        // 提取设备型号
-                        if let Some(model_part) = parts.iter().find(|p| p.starts_with("model:")) {
-                            let model = model_part.trim_start_matches("model:");
-                            device = device.with_model(model);
-
-                            // 使用型号作为设备名称
-                            device = device.with_name(model);
-                        }
-
-                        // 提取产品信息
-                        if let Some(product_part) = parts.iter().find(|p| p.starts_with("product:")) {
-                            let product = product_part.trim_start_matches("product:");
-                            device = device.with_product(product);
-                        }
-
-                        // 提取传输 ID
-                        if let Some(transport_part) = parts.iter().find(|p| p.starts_with("transport_id:")) {
-                            let transport = transport_part.trim_start_matches("transport_id:");
-                            device = device.with_transport_id(transport);
-                        }
-                    }
-
+                if let Some(mut device) = parse_device_line(line) {
                     // 如果名称还是默认的设备 ID，尝试获取更好的名称
-                    if device.name == format!("Device {}", id) && device.is_online() {
-                        if let Ok(model) = self.shell(&id, "getprop ro.product.model") {
+                    if device.name == format!("Device {}", device.id) && device.is_online() {
+                        if let Ok(model) = self.shell(&device.id, "getprop ro.product.model") {
                             let model = model.trim();
                             if !model.is_empty() {
                                 device = device.with_name(model);
@@ -150,7 +217,7 @@ impl ADB {
     /// 连接到远程设备
     pub fn connect(&self, ip: &str, port: u16) -> ADBResult<()> {
         self.with_retry(|| {
-            let output = Command::new(&self.config.path)
+            let output = self.adb_command()
                 .arg("connect")
                 .arg(format!("{}:{}", ip, port))
                 .output()
@@ -175,10 +242,109 @@ impl ADB {
         })
     }
 
+    /// 与开启了无线调试的设备配对 (Android 11+)
+    ///
+    /// `host_port` 和 `pairing_code` 为设备「使用配对码配对设备」对话框中显示的地址和六位配对码，
+    /// 配对端口与之后连接使用的端口不同。成功时返回设备的 GUID（adb 版本较旧时可能没有）。
+    pub fn pair(&self, host_port: &str, pairing_code: &str) -> ADBResult<Option<String>> {
+        if pairing_code.len() != 6 || !pairing_code.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ADBError::ConfigError(format!("无效的配对码: {}", pairing_code)));
+        }
+
+        let output = self
+            .adb_command()
+            .arg("pair")
+            .arg(host_port)
+            .arg(pairing_code)
+            .output()
+            .map_err(|e| ADBError::CommandError(format!("无法执行 adb pair: {}", e)))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+        match PAIRED_RE.captures(&stdout) {
+            Some(caps) if output.status.success() => {
+                let guid = caps.get(2).map(|m| m.as_str().to_string());
+                info!("成功与设备 {} 配对 (guid: {:?})", &caps[1], guid);
+                Ok(guid)
+            }
+            _ => {
+                let message = if stdout.trim().is_empty() { stderr } else { stdout };
+                Err(ADBError::ConnectionError(format!(
+                    "与 {} 配对失败: {}",
+                    host_port,
+                    message.trim()
+                )))
+            }
+        }
+    }
+
+    /// 配对并连接无线调试设备，返回连接后的设备序列号
+    ///
+    /// 指定 `connect_port` 时直接连接配对地址的该端口；否则等待设备通过 mDNS 公布连接服务
+    /// （adb 可能已自动连接，此时返回 `adb-<guid>._adb-tls-connect._tcp` 形式的序列号）。
+    pub fn connect_wireless(
+        &self,
+        host_port: &str,
+        pairing_code: &str,
+        connect_port: Option<u16>,
+    ) -> ADBResult<String> {
+        let guid = self.pair(host_port, pairing_code)?;
+        let host = host_port
+            .rsplit_once(':')
+            .map(|(host, _)| host)
+            .unwrap_or(host_port);
+
+        if let Some(port) = connect_port {
+            self.connect(host, port)?;
+            return Ok(format!("{}:{}", host, port));
+        }
+
+        let guid = guid.ok_or_else(|| {
+            ADBError::ConnectionError(
+                "adb 版本过旧，配对结果中没有设备 GUID，请指定连接端口".to_string(),
+            )
+        })?;
+
+        let deadline = Instant::now() + WIRELESS_CONNECT_TIMEOUT;
+        while Instant::now() < deadline {
+            // adb 服务器可能已通过 mDNS 自动连接
+            if let Some(device) = self
+                .list_devices()?
+                .into_iter()
+                .find(|d| d.id.starts_with(&guid) && d.is_online())
+            {
+                return Ok(device.id);
+            }
+
+            let output = self.adb_command().arg("mdns").arg("services").output().map_err(|e| {
+                ADBError::CommandError(format!("无法查询 mDNS 服务: {}", e))
+            })?;
+            let services = String::from_utf8_lossy(&output.stdout);
+            if let Some(address) = TLS_CONNECT_RE
+                .captures_iter(&services)
+                .find(|caps| caps[1].starts_with(&guid))
+                .map(|caps| caps[2].to_string())
+            {
+                let (ip, port) = address.rsplit_once(':').unwrap_or((&address, ""));
+                let port = port.parse::<u16>()?;
+                self.connect(ip, port)?;
+                return Ok(address);
+            }
+
+            thread::sleep(MDNS_POLL_INTERVAL);
+        }
+
+        Err(ADBError::TimeoutError {
+            message: format!("等待设备 {} 公布无线调试连接端口超时", guid),
+            duration: WIRELESS_CONNECT_TIMEOUT,
+        })
+    }
+
     /// 断开与远程设备的连接
     pub fn disconnect(&self, ip: &str, port: Option<u16>) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
             cmd.arg("disconnect");
 
             if let Some(p) = port {
@@ -207,7 +373,7 @@ impl ADB {
     /// 断开所有远程连接
     pub fn disconnect_all(&self) -> ADBResult<()> {
         self.with_retry(|| {
-            let output = Command::new(&self.config.path)
+            let output = self.adb_command()
                 .arg("disconnect")
                 .output()
                 .map_err(|e| {
@@ -230,16 +396,16 @@ impl ADB {
     /// 在设备上执行 shell 命令
     pub fn shell(&self, device_id: &str, command: &str) -> ADBResult<String> {
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
 
             // 添加设备 ID
             if !device_id.is_empty() {
                 cmd.arg("-s").arg(device_id);
             }
 
-            let output = cmd.arg("shell").arg(command).output().map_err(|e| {
-                ADBError::DeviceError(format!("无法执行 ADB shell: {}", e))
-            })?;
+            let output = self
+                .schedule(device_id, Priority::Interactive, || cmd.arg("shell").arg(self.with_tool_path(device_id, command)).output())
+                .map_err(|e| ADBError::DeviceError(format!("无法执行 ADB shell: {}", e)))?;
 
             let stdout = String::from_utf8_lossy(&output.stdout).to_string();
             let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -260,10 +426,322 @@ impl ADB {
         })
     }
 
+    /// 执行 shell 命令并逐行回调输出，直到命令结束
+    ///
+    /// 适合 `top`、`logcat`、长时间运行的 instrumentation 等持续输出的命令。
+    /// 调用 [`ADB::shutdown`] 会终止命令；需要自行取消时使用 [`ADB::shell_stream_until`]。
+    pub fn shell_stream<F>(&self, device_id: &str, command: &str, on_line: F) -> ADBResult<()>
+    where
+        F: FnMut(&str),
+    {
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.jobs.register_stop_flag(cancel.clone());
+        let result = self.shell_stream_until(device_id, command, &cancel, on_line);
+        // 标记为已结束，以便从后台任务列表中移除
+        cancel.store(true, Ordering::SeqCst);
+        result
+    }
+
+    /// 执行 shell 命令并逐行回调输出，直到命令结束或 `cancel` 被置为 true
+    ///
+    /// 取消时终止设备上的命令并返回 `Ok(())`；命令以非零状态退出时返回错误
+    pub fn shell_stream_until<F>(
+        &self,
+        device_id: &str,
+        command: &str,
+        cancel: &AtomicBool,
+        mut on_line: F,
+    ) -> ADBResult<()>
+    where
+        F: FnMut(&str),
+    {
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+        let mut child = cmd
+            .arg("shell")
+            .arg(self.with_tool_path(device_id, command))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ADBError::DeviceError(format!("无法执行 ADB shell: {}", e)))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取 shell 输出".to_string()))?;
+        let mut stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取 shell 输出".to_string()))?;
+
+        debug!("设备 {} 开始流式执行: {}", device_id, command);
+        let child = Mutex::new(child);
+        let done = AtomicBool::new(false);
+        let cancelled = AtomicBool::new(false);
+
+        let stderr = thread::scope(|scope| {
+            // stderr 由单独的线程读取，避免管道写满后设备上的命令阻塞
+            let stderr_reader = scope.spawn(move || {
+                let mut buffer = Vec::new();
+                let _ = stderr.read_to_end(&mut buffer);
+                buffer
+            });
+
+            // 读取输出会阻塞，由单独的线程在取消时结束进程
+            scope.spawn(|| {
+                while !done.load(Ordering::SeqCst) {
+                    if cancel.load(Ordering::SeqCst) {
+                        cancelled.store(true, Ordering::SeqCst);
+                        let _ = child.lock().unwrap().kill();
+                        break;
+                    }
+                    thread::sleep(STREAM_CANCEL_POLL_INTERVAL);
+                }
+            });
+
+            let mut reader = BufReader::new(stdout);
+            let mut buffer = Vec::new();
+            while !cancelled.load(Ordering::SeqCst) {
+                buffer.clear();
+                match reader.read_until(b'\n', &mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        let line = String::from_utf8_lossy(&buffer);
+                        on_line(line.trim_end_matches(['\r', '\n']));
+                    }
+                }
+            }
+            done.store(true, Ordering::SeqCst);
+            stderr_reader.join().unwrap_or_default()
+        });
+
+        let status = child.into_inner().unwrap().wait()?;
+        if cancelled.load(Ordering::SeqCst) {
+            debug!("设备 {} 流式命令已取消: {}", device_id, command);
+            return Ok(());
+        }
+        if !status.success() {
+            return Err(ADBError::CommandError(format!(
+                "命令 {} 执行失败 ({}): {}",
+                command,
+                status,
+                String::from_utf8_lossy(&stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// 执行设备命令并把 `reader` 的内容作为其标准输入，适用于 `dd of=...`、`sh -s`、`sqlite3` 等
+    ///
+    /// 数据以流的方式传输，主机和设备上都不会产生中间文件。设备支持 shell v2 协议时使用
+    /// `adb shell -T`，返回完整的输出和退出码；否则使用 `adb exec-in`，此时命令的输出不会传回，
+    /// `stdout` 为空，退出码只反映 adb 本身是否成功。
+    pub fn shell_with_stdin<R: Read>(
+        &self,
+        device_id: &str,
+        command: &str,
+        mut reader: R,
+    ) -> ADBResult<ShellOutput> {
+        let v2 = self.supports_shell_v2(device_id)?;
+
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+        if v2 {
+            cmd.arg("shell").arg("-T");
+        } else {
+            cmd.arg("exec-in");
+        }
+        let mut child = cmd
+            .arg(self.with_tool_path(device_id, command))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ADBError::DeviceError(format!("无法执行 ADB shell: {}", e)))?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法写入命令的标准输入".to_string()))?;
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取 shell 输出".to_string()))?;
+        let mut stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取 shell 输出".to_string()))?;
+
+        // 输出由单独的线程读取，避免管道写满后与写入标准输入互相阻塞
+        let (written, stdout, stderr) = thread::scope(|scope| {
+            let stdout_reader = scope.spawn(move || {
+                let mut buffer = Vec::new();
+                stdout.read_to_end(&mut buffer).map(|_| buffer)
+            });
+            let stderr_reader = scope.spawn(move || {
+                let mut buffer = Vec::new();
+                stderr.read_to_end(&mut buffer).map(|_| buffer)
+            });
+
+            let written = match io::copy(&mut reader, &mut stdin) {
+                // 命令提前退出（如 `head`）时不再需要剩余的输入
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(0),
+                result => result,
+            };
+            // 关闭标准输入，设备上的命令才能读到 EOF
+            drop(stdin);
+
+            (
+                written,
+                stdout_reader.join().unwrap_or_else(|_| Ok(Vec::new())),
+                stderr_reader.join().unwrap_or_else(|_| Ok(Vec::new())),
+            )
+        });
+
+        let status = child.wait()?;
+        let written = written?;
+        let (stdout, stderr) = (stdout?, stderr?);
+        let exit_code = status.code().unwrap_or(-1);
+
+        if exit_code != 0 && (!v2 || stderr.starts_with(b"error: ")) {
+            return Err(ADBError::DeviceError(format!(
+                "ADB shell 命令失败: {}",
+                String::from_utf8_lossy(&stderr).trim()
+            )));
+        }
+
+        trace!(
+            "命令 '{}' 写入 {} 字节标准输入，退出码 {}",
+            command,
+            written,
+            exit_code
+        );
+        Ok(ShellOutput {
+            stdout,
+            stderr,
+            exit_code,
+        })
+    }
+
+    /// 检查设备是否支持 shell v2 协议（`adb features` 中的 `shell_v2`），结果会被缓存
+    pub fn supports_shell_v2(&self, device_id: &str) -> ADBResult<bool> {
+        if let Some(supported) = self.cache.get::<bool>(device_id, "shell_v2") {
+            return Ok(supported);
+        }
+
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+        let output = cmd
+            .arg("features")
+            .output()
+            .map_err(|e| ADBError::CommandError(format!("无法获取设备特性: {}", e)))?;
+        if !output.status.success() {
+            return Err(ADBError::DeviceError(format!(
+                "获取设备特性失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let supported = String::from_utf8_lossy(&output.stdout)
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .any(|f| f == "shell_v2");
+        self.cache.insert(device_id, "shell_v2", supported, None);
+        Ok(supported)
+    }
+
+    /// 执行 shell 命令并返回分开的 stdout、stderr 和退出码
+    ///
+    /// 命令以非零状态退出不视为错误，由调用方根据 `exit_code` 判断。设备支持 shell v2
+    /// 协议时直接使用其退出码；否则在命令后附加 `echo $?` 获取退出码，stderr 合并在 stdout 中。
+    pub fn shell_v2(&self, device_id: &str, command: &str) -> ADBResult<ShellOutput> {
+        let v2 = self.supports_shell_v2(device_id)?;
+        let full_command = if v2 {
+            command.to_string()
+        } else {
+            format!("{}; echo {}$?", command, EXIT_CODE_MARKER)
+        };
+
+        self.with_retry(|| {
+            let mut cmd = self.adb_command();
+            if !device_id.is_empty() {
+                cmd.arg("-s").arg(device_id);
+            }
+
+            let output = self
+                .schedule(device_id, Priority::Interactive, || cmd.arg("shell").arg(self.with_tool_path(device_id, &full_command)).output())
+                .map_err(|e| ADBError::DeviceError(format!("无法执行 ADB shell: {}", e)))?;
+
+            let result = if v2 {
+                let exit_code = output.status.code().unwrap_or(-1);
+                // adb 自身的错误（设备离线等）同样以非零状态退出
+                if exit_code != 0 && output.stderr.starts_with(b"error: ") {
+                    return Err(ADBError::DeviceError(format!(
+                        "ADB shell 命令失败: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    )));
+                }
+                ShellOutput {
+                    stdout: output.stdout,
+                    stderr: output.stderr,
+                    exit_code,
+                }
+            } else {
+                if !output.status.success() {
+                    return Err(ADBError::DeviceError(format!(
+                        "ADB shell 命令失败: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    )));
+                }
+                let (stdout, exit_code) = split_exit_code(output.stdout)?;
+                ShellOutput {
+                    stdout,
+                    stderr: output.stderr,
+                    exit_code,
+                }
+            };
+
+            trace!("Shell 命令 '{}' 退出码 {}", command, result.exit_code);
+            Ok(result)
+        })
+    }
+
+    /// 通过 `adb exec-out` 执行命令并返回原始输出
+    ///
+    /// 与 [`ADB::shell`] 不同，输出不经过 PTY 和换行转换，也不按 UTF-8 解码，适合读取
+    /// 二进制数据（如 `screencap -p`、`cat` 二进制文件）
+    pub fn exec_out(&self, device_id: &str, command: &str) -> ADBResult<Vec<u8>> {
+        self.with_retry(|| {
+            let mut cmd = self.adb_command();
+
+            if !device_id.is_empty() {
+                cmd.arg("-s").arg(device_id);
+            }
+
+            let output = self
+                .schedule(device_id, Priority::Interactive, || cmd.arg("exec-out").arg(self.with_tool_path(device_id, command)).output())
+                .map_err(|e| ADBError::DeviceError(format!("无法执行 ADB exec-out: {}", e)))?;
+
+            if !output.status.success() {
+                return Err(ADBError::DeviceError(format!(
+                    "ADB exec-out 命令失败: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                )));
+            }
+
+            trace!("exec-out 命令 '{}' 输出 {} 字节", command, output.stdout.len());
+            Ok(output.stdout)
+        })
+    }
+
     /// 执行 shell 命令但不等待完成
     pub fn shell_no_wait(&self, device_id: &str, command: &str) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
 
             // 添加设备 ID
             if !device_id.is_empty() {
@@ -271,15 +749,15 @@ impl ADB {
             }
 
             // 启动进程但不等待
-            let child = cmd.arg("shell").arg(command).spawn().map_err(|e| {
+            let child = cmd.arg("shell").arg(self.with_tool_path(device_id, command)).spawn().map_err(|e| {
                 ADBError::DeviceError(format!("无法执行 ADB shell: {}", e))
             })?;
 
             debug!("在设备 {} 上启动命令: {}", device_id, command);
 
-            // 如果启用了连接池，可以在这里存储子进程
+            // 存储子进程，由 shutdown 或最后一个 ADB 实例释放时终止
             if let Ok(mut pool) = self.connections.lock() {
-                pool.insert(format!("{}:{}", device_id, command), std::sync::Arc::new(std::sync::Mutex::new(child)));
+                pool.insert(format!("{}:{}", device_id, command), child);
             }
 
             Ok(())
@@ -339,7 +817,7 @@ impl ADB {
     pub fn restart_server(&self) -> ADBResult<()> {
         self.with_retry(|| {
             // 首先停止服务器
-            let output = Command::new(&self.config.path)
+            let output = self.adb_command()
                 .arg("kill-server")
                 .output()
                 .map_err(|e| ADBError::CommandError(format!("无法停止 ADB 服务器: {}", e)))?;
@@ -354,7 +832,7 @@ impl ADB {
             std::thread::sleep(Duration::from_millis(500));
 
             // 启动服务器
-            let output = Command::new(&self.config.path)
+            let output = self.adb_command()
                 .arg("start-server")
                 .output()
                 .map_err(|e| ADBError::CommandError(format!("无法启动 ADB 服务器: {}", e)))?;
@@ -379,14 +857,7 @@ impl ADB {
     /// 执行任意 ADB 命令
     pub fn run_command(&self, args: &[&str]) -> ADBResult<String> {
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
-
-            // 添加全局附加参数（如果有）
-            if let Some(additional_args) = &self.config.additional_args {
-                for arg in additional_args {
-                    cmd.arg(arg);
-                }
-            }
+            let mut cmd = self.adb_command();
 
             // 添加命令特定参数
             for arg in args {
@@ -421,28 +892,14 @@ impl ADB {
     /// 等待设备连接
     pub fn wait_for_device(&self, device_id: &str, timeout_ms: Option<u64>) -> ADBResult<bool> {
         let timeout = timeout_ms.unwrap_or(30000); // 默认30秒
-        let poll_interval = 500; // 500毫秒
 
         info!("等待设备 {} 连接...", device_id);
 
-        let result = crate::utils::wait_with_polling(
-            timeout,
-            poll_interval,
-            || self.is_device_online(device_id),
-            Some(|elapsed| {
-                if elapsed % 5000 == 0 { // 每5秒打印一次日志
-                    debug!("等待设备 {} 连接，已等待 {}s...", device_id, elapsed / 1000);
-                }
-            })
-        )?;
-
-        if result {
-            info!("设备 {} 已连接", device_id);
-        } else {
-            warn!("等待设备 {} 连接超时", device_id);
-        }
-
-        Ok(result)
+        self.wait_until(
+            device_id,
+            &crate::wait::Condition::DeviceOnline,
+            Duration::from_millis(timeout),
+        )
     }
 
     /// 获取 ADB 服务器版本
@@ -466,91 +923,20 @@ impl ADB {
 
     /// 优化版的进程 ID 获取
     pub fn get_pid_optimized(&self, device_id: &str, package_name: &str) -> ADBResult<Option<i32>> {
-        let cache_key = format!("{}:{}", device_id, package_name);
+        let cache_key = format!("pid:{}", package_name);
 
         // 检查缓存
-        if let Ok(cache) = PID_CACHE.lock() {
-            if let Some((pid, timestamp)) = cache.get(&cache_key) {
-                if Instant::now().duration_since(*timestamp) < PID_CACHE_TIMEOUT {
-                    trace!("使用缓存的 PID: {} -> {}", package_name, pid);
-                    return Ok(Some(*pid));
-                }
-            }
-        }
-
-        // 在 Android 8+ 系统上，首选 pidof 命令
-        let android_version = self.get_android_version(device_id)?;
-
-        if android_version >= 8.0 {
-            // 使用 pidof（Android 8+ 的首选方法）
-            let command = format!("pidof {}", package_name);
-            let output = self.shell(device_id, &command)?;
-
-            if !output.trim().is_empty() {
-                if let Ok(pid) = output.trim().parse::<i32>() {
-                    // 更新缓存
-                    if let Ok(mut cache) = PID_CACHE.lock() {
-                        cache.insert(cache_key, (pid, Instant::now()));
-                    }
-                    return Ok(Some(pid));
-                }
-            }
-        }
-
-        // 尝试使用 ps 命令（更通用的方法）
-        let ps_command = if android_version >= 7.0 {
-            // Android 7+ 系统使用不同的 ps 格式
-            format!("ps -A | grep {} | grep -v grep", package_name)
-        } else {
-            // 较旧的 Android 版本使用传统 ps 格式
-            format!("ps | grep {} | grep -v grep", package_name)
-        };
-
-        let output = self.shell(device_id, &ps_command)?;
-
-        if !output.trim().is_empty() {
-            let lines = output.lines().collect::<Vec<&str>>();
-
-            for line in lines {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-
-                // 确定 PID 位置（根据 Android 版本有所不同）
-                let pid_index = if android_version >= 7.0 { 1 } else { 2 };
-
-                if parts.len() > pid_index {
-                    if let Ok(pid) = std::str::FromStr::from_str(parts[pid_index]) {
-                        // 更新缓存
-                        if let Ok(mut cache) = PID_CACHE.lock() {
-                            cache.insert(cache_key, (pid, Instant::now()));
-                        }
-                        return Ok(Some(pid));
-                    }
-                }
-            }
+        if let Some(pid) = self.cache.get::<i32>(device_id, &cache_key) {
+            trace!("使用缓存的 PID: {} -> {}", package_name, pid);
+            return Ok(Some(pid));
         }
 
-        // 最后的尝试 - 使用 dumpsys
-        let dumpsys_command = format!("dumpsys activity services | grep -i {}", package_name);
-        let output = self.shell(device_id, &dumpsys_command)?;
-
-        if !output.trim().is_empty() {
-            let re = regex::Regex::new(r"pid=(\d+)")?;
-
-            if let Some(caps) = re.captures(&output) {
-                if let Some(pid_match) = caps.get(1) {
-                    if let Ok(pid) = std::str::FromStr::from_str(pid_match.as_str()) {
-                        // 更新缓存
-                        if let Ok(mut cache) = PID_CACHE.lock() {
-                            cache.insert(cache_key, (pid, Instant::now()));
-                        }
-                        return Ok(Some(pid));
-                    }
-                }
-            }
+        let pid = self.get_pid(device_id, package_name)?;
+        if let Some(pid) = pid {
+            self.cache
+                .insert(device_id, &cache_key, pid, Some(PID_CACHE_TIMEOUT));
         }
-
-        debug!("无法找到包 {} 的 PID", package_name);
-        Ok(None)
+        Ok(pid)
     }
 
     /// 检查包是否运行的优化版本
@@ -584,42 +970,4 @@ impl ADB {
 
         Ok((false, None))
     }
-
-    /// 获取设备的 Android 版本
-    fn get_android_version(&self, device_id: &str) -> ADBResult<f32> {
-        // 先检查缓存
-        if let Ok(cache) = ANDROID_VERSION_CACHE.lock() {
-            if let Some(version) = cache.get(device_id) {
-                return Ok(*version);
-            }
-        }
-
-        // 如果缓存中没有，则查询设备
-        let output = self.shell(device_id, "getprop ro.build.version.release")?;
-        let version_str = output.trim();
-
-        // 解析版本号
-        let version = match version_str.split('.').next() {
-            Some(major) => {
-                if let Ok(major_num) = major.parse::<f32>() {
-                    major_num
-                } else {
-                    // 默认返回一个保守的版本号
-                    warn!("无法解析 Android 版本 '{}', 默认使用 5.0", version_str);
-                    5.0
-                }
-            },
-            None => {
-                warn!("无法获取 Android 版本, 默认使用 5.0");
-                5.0
-            }
-        };
-
-        // 更新缓存
-        if let Ok(mut cache) = ANDROID_VERSION_CACHE.lock() {
-            cache.insert(device_id.to_string(), version);
-        }
-
-        Ok(version)
-    }
 }
\ No newline at end of file
@@ -0,0 +1,176 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::debug;
+use std::collections::HashMap;
+
+/// 兼容性变更（来自 `dumpsys platform_compat`）
+#[derive(Debug, Clone)]
+pub struct CompatChange {
+    pub change_id: u64,
+    pub name: Option<String>,
+    /// 从该 targetSdk 起默认启用
+    pub enable_since_target_sdk: Option<i32>,
+    /// 默认禁用
+    pub disabled: bool,
+    /// 仅记录日志，不改变行为
+    pub logging_only: bool,
+    /// 是否允许在发布版本上覆盖
+    pub overridable: bool,
+    /// 各包的覆盖设置
+    pub package_overrides: HashMap<String, bool>,
+    /// 对指定包是否生效（根据覆盖设置和 targetSdk 推算）
+    pub enabled_for_package: Option<bool>,
+}
+
+impl CompatChange {
+    /// 推算对给定 targetSdk 的包是否生效
+    fn effective_for(&self, package_name: &str, target_sdk: Option<i32>) -> Option<bool> {
+        if let Some(enabled) = self.package_overrides.get(package_name) {
+            return Some(*enabled);
+        }
+        if self.disabled {
+            return Some(false);
+        }
+        match (self.enable_since_target_sdk, target_sdk) {
+            (Some(since), Some(target)) => Some(target >= since),
+            (Some(_), None) => None,
+            (None, _) => Some(true),
+        }
+    }
+}
+
+/// 解析 packageOverrides={a=true, b=false}
+fn parse_overrides(value: &str) -> HashMap<String, bool> {
+    value
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(',')
+        .filter_map(|pair| {
+            let (package, enabled) = pair.trim().split_once('=')?;
+            Some((package.trim().to_string(), enabled.trim() == "true"))
+        })
+        .collect()
+}
+
+/// 解析 `dumpsys platform_compat` 输出
+fn parse_platform_compat(output: &str) -> Vec<CompatChange> {
+    let mut changes = Vec::new();
+
+    for line in output.lines() {
+        let line = line.trim();
+        let inner = match line
+            .strip_prefix("ChangeId(")
+            .and_then(|l| l.strip_suffix(')'))
+        {
+            Some(inner) => inner,
+            None => continue,
+        };
+
+        let mut fields = inner.split("; ");
+        let change_id = match fields.next().and_then(|id| id.trim().parse::<u64>().ok()) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        let mut change = CompatChange {
+            change_id,
+            name: None,
+            enable_since_target_sdk: None,
+            disabled: false,
+            logging_only: false,
+            overridable: false,
+            package_overrides: HashMap::new(),
+            enabled_for_package: None,
+        };
+
+        for field in fields {
+            match field.split_once('=') {
+                Some(("name", name)) => change.name = Some(name.to_string()),
+                Some(("enableSinceTargetSdk", sdk)) => change.enable_since_target_sdk = sdk.parse().ok(),
+                // 旧版本的 "after" 表示 targetSdk 大于该值时启用
+                Some(("enableAfterTargetSdk", sdk)) => {
+                    change.enable_since_target_sdk = sdk.parse::<i32>().ok().map(|sdk| sdk + 1)
+                }
+                Some(("packageOverrides", overrides)) => {
+                    change.package_overrides = parse_overrides(overrides)
+                }
+                _ => match field {
+                    "disabled" => change.disabled = true,
+                    "loggingOnly" => change.logging_only = true,
+                    "overridable" => change.overridable = true,
+                    _ => {}
+                },
+            }
+        }
+
+        changes.push(change);
+    }
+
+    changes
+}
+
+impl ADB {
+    /// 为应用启用或禁用兼容性变更 (`am compat enable/disable`)
+    ///
+    /// `change` 可以是变更 ID 或名称
+    pub fn set_compat_change(
+        &self,
+        device_id: &str,
+        package_name: &str,
+        change: &str,
+        enabled: bool,
+    ) -> ADBResult<()> {
+        let action = if enabled { "enable" } else { "disable" };
+        let output = self.shell(
+            device_id,
+            &format!("am compat {} {} {}", action, change, package_name),
+        )?;
+
+        if output.contains("Exception") || output.contains("Error") {
+            return Err(ADBError::CommandError(format!(
+                "设置兼容性变更 {} 失败: {}",
+                change,
+                output.trim()
+            )));
+        }
+
+        debug!("已{} {} 的兼容性变更 {}", if enabled { "启用" } else { "禁用" }, package_name, change);
+        Ok(())
+    }
+
+    /// 重置应用的兼容性变更覆盖 (`am compat reset`)
+    pub fn reset_compat_change(
+        &self,
+        device_id: &str,
+        package_name: &str,
+        change: Option<&str>,
+    ) -> ADBResult<()> {
+        let command = match change {
+            Some(change) => format!("am compat reset {} {}", change, package_name),
+            None => format!("am compat reset-all {}", package_name),
+        };
+        self.shell(device_id, &command)?;
+        Ok(())
+    }
+
+    /// 列出所有兼容性变更，并推算其对指定包是否生效
+    pub fn list_compat_changes(
+        &self,
+        device_id: &str,
+        package_name: &str,
+    ) -> ADBResult<Vec<CompatChange>> {
+        let output = self.shell(device_id, "dumpsys platform_compat")?;
+        let mut changes = parse_platform_compat(&output);
+
+        let target_sdk = self
+            .get_package_info(device_id, package_name)
+            .ok()
+            .and_then(|info| info.target_sdk);
+
+        for change in &mut changes {
+            change.enabled_for_package = change.effective_for(package_name, target_sdk);
+        }
+
+        Ok(changes)
+    }
+}
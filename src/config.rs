@@ -19,6 +19,28 @@ pub struct ADBConfig {
     /// 额外的命令行参数
     #[serde(skip_serializing_if = "Option::is_none")]
     pub additional_args: Option<Vec<String>>,
+    /// ADB 服务器地址 (`-H`)，用于连接其他主机或容器中的 adb 服务器
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_host: Option<String>,
+    /// ADB 服务器端口 (`-P`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_port: Option<u16>,
+}
+
+impl ADBConfig {
+    /// 每次调用 adb 时附加的服务器参数
+    pub(crate) fn server_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(host) = &self.server_host {
+            args.push("-H".to_string());
+            args.push(host.clone());
+        }
+        if let Some(port) = self.server_port {
+            args.push("-P".to_string());
+            args.push(port.to_string());
+        }
+        args
+    }
 }
 
 impl Default for ADBConfig {
@@ -30,6 +52,8 @@ impl Default for ADBConfig {
             timeout: 30000, // 30秒超时
             log_level: None,
             additional_args: None,
+            server_host: None,
+            server_port: None,
         }
     }
 }
@@ -43,6 +67,8 @@ pub struct ADBConfigBuilder {
     timeout: Option<u64>,
     log_level: Option<String>,
     additional_args: Option<Vec<String>>,
+    server_host: Option<String>,
+    server_port: Option<u16>,
 }
 
 impl ADBConfigBuilder {
@@ -89,6 +115,35 @@ impl ADBConfigBuilder {
         self
     }
 
+    /// 设置 ADB 服务器地址
+    pub fn server_host(mut self, host: &str) -> Self {
+        self.server_host = Some(host.to_string());
+        self
+    }
+
+    /// 设置 ADB 服务器端口
+    pub fn server_port(mut self, port: u16) -> Self {
+        self.server_port = Some(port);
+        self
+    }
+
+    /// 从 `ANDROID_ADB_SERVER_ADDRESS` 和 `ANDROID_ADB_SERVER_PORT` 环境变量读取服务器地址，
+    /// 未设置的变量不影响已有配置
+    pub fn server_from_env(mut self) -> Self {
+        if let Ok(host) = std::env::var("ANDROID_ADB_SERVER_ADDRESS") {
+            if !host.trim().is_empty() {
+                self.server_host = Some(host.trim().to_string());
+            }
+        }
+        if let Some(port) = std::env::var("ANDROID_ADB_SERVER_PORT")
+            .ok()
+            .and_then(|p| p.trim().parse().ok())
+        {
+            self.server_port = Some(port);
+        }
+        self
+    }
+
     /// 构建 ADB 配置
     pub fn build(self) -> ADBConfig {
         let default = ADBConfig::default();
@@ -100,6 +155,8 @@ impl ADBConfigBuilder {
             timeout: self.timeout.unwrap_or(default.timeout),
             log_level: self.log_level,
             additional_args: self.additional_args,
+            server_host: self.server_host,
+            server_port: self.server_port,
         }
     }
 }
\ No newline at end of file
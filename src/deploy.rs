@@ -0,0 +1,372 @@
+//! 增量部署
+//!
+//! 部署计划把主机上的构建产物（APK、native 库、资源文件）映射到设备上的目标位置，
+//! 每一项可以选择安装、推送或仅在内容变化时推送。
+//!
+//! 入口是 [`ADB::deploy`]。与本 crate 的其他功能一样，部署作为 `ADB` 的方法提供，
+//! 复用实例的配置、命令执行后端和缓存，因此没有单独的 `deploy::apply(device_id, plan)` 函数。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::install::InstallOptions;
+use crate::utils::shell_quote;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+// `dumpsys package` 中的 native 库目录和主 ABI
+static NATIVE_LIB_DIR_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"legacyNativeLibraryDir=(\S+)").unwrap());
+static PRIMARY_ABI_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"primaryCpuAbi=(\S+)").unwrap());
+// 应用私有目录中存放替换库的位置（相对于数据目录）
+const NATIVE_OVERRIDE_DIR: &str = "code_cache/native_override";
+
+/// native 库的替换方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NativeLibMethod {
+    /// 通过 root 权限直接覆盖应用 lib 目录中的文件
+    Root,
+    /// 通过 `run-as` 复制到应用私有目录，需要应用自行优先加载该目录中的库
+    RunAs,
+}
+
+/// native 库替换结果
+#[derive(Debug, Clone)]
+pub struct NativeLibPush {
+    pub method: NativeLibMethod,
+    /// 设备上的库文件路径
+    pub device_path: String,
+    pub bytes: u64,
+    /// 应用是否已重新启动
+    pub restarted: bool,
+}
+
+/// 从 `dumpsys package` 输出中解析 lib 目录（含 ABI 子目录）
+fn parse_native_lib_dir(output: &str) -> Option<String> {
+    let dir = NATIVE_LIB_DIR_RE.captures(output)?[1].to_string();
+    let abi = PRIMARY_ABI_RE
+        .captures(output)
+        .map(|caps| caps[1].to_string())
+        .filter(|abi| abi != "null")?;
+
+    let arch = match abi.as_str() {
+        "arm64-v8a" => "arm64",
+        "armeabi-v7a" | "armeabi" => "arm",
+        "x86" => "x86",
+        "x86_64" => "x86_64",
+        _ => return None,
+    };
+    Some(format!("{}/{}", dir, arch))
+}
+
+/// 部署方式
+#[derive(Debug, Clone)]
+pub enum DeployStrategy {
+    /// 作为 APK 安装
+    Install(InstallOptions),
+    /// 总是推送
+    Push,
+    /// 大小或 MD5 不同时才推送
+    PushIfChanged,
+}
+
+/// 部署计划中的一项
+#[derive(Debug, Clone)]
+pub struct DeployEntry {
+    /// 主机上的构建产物
+    pub source: PathBuf,
+    /// 设备上的目标路径，安装时不使用
+    pub destination: String,
+    pub strategy: DeployStrategy,
+}
+
+/// 部署计划
+#[derive(Debug, Clone, Default)]
+pub struct DeployPlan {
+    pub entries: Vec<DeployEntry>,
+    /// 出现失败后是否继续部署剩余项
+    pub continue_on_error: bool,
+}
+
+impl DeployPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 安装 APK
+    pub fn install(self, apk_path: impl Into<PathBuf>) -> Self {
+        self.install_with_options(apk_path, InstallOptions::default())
+    }
+
+    /// 使用指定选项安装 APK
+    pub fn install_with_options(mut self, apk_path: impl Into<PathBuf>, options: InstallOptions) -> Self {
+        self.entries.push(DeployEntry {
+            source: apk_path.into(),
+            destination: String::new(),
+            strategy: DeployStrategy::Install(options),
+        });
+        self
+    }
+
+    /// 推送文件或目录
+    pub fn push(mut self, source: impl Into<PathBuf>, destination: &str) -> Self {
+        self.entries.push(DeployEntry {
+            source: source.into(),
+            destination: destination.to_string(),
+            strategy: DeployStrategy::Push,
+        });
+        self
+    }
+
+    /// 内容变化时推送文件
+    pub fn push_if_changed(mut self, source: impl Into<PathBuf>, destination: &str) -> Self {
+        self.entries.push(DeployEntry {
+            source: source.into(),
+            destination: destination.to_string(),
+            strategy: DeployStrategy::PushIfChanged,
+        });
+        self
+    }
+
+    pub fn continue_on_error(mut self, enabled: bool) -> Self {
+        self.continue_on_error = enabled;
+        self
+    }
+}
+
+/// 单项部署结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeployOutcome {
+    Installed,
+    Pushed { bytes: u64 },
+    /// 内容未变化，已跳过
+    Unchanged,
+    Failed(String),
+    /// 之前的项失败，未执行
+    NotAttempted,
+}
+
+/// 单项部署记录
+#[derive(Debug, Clone)]
+pub struct DeployItem {
+    pub source: PathBuf,
+    pub destination: String,
+    pub outcome: DeployOutcome,
+    pub duration: Duration,
+}
+
+/// 部署报告
+#[derive(Debug, Clone, Default)]
+pub struct DeployReport {
+    pub items: Vec<DeployItem>,
+    pub duration: Duration,
+}
+
+impl DeployReport {
+    /// 所有项是否都成功（包括未变化跳过的项）
+    pub fn is_success(&self) -> bool {
+        self.items.iter().all(|item| {
+            !matches!(item.outcome, DeployOutcome::Failed(_) | DeployOutcome::NotAttempted)
+        })
+    }
+
+    /// 失败的项
+    pub fn failed(&self) -> Vec<&DeployItem> {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.outcome, DeployOutcome::Failed(_)))
+            .collect()
+    }
+
+    /// 实际推送的字节数
+    pub fn bytes_pushed(&self) -> u64 {
+        self.items
+            .iter()
+            .map(|item| match item.outcome {
+                DeployOutcome::Pushed { bytes } => bytes,
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+impl ADB {
+    /// 按部署计划逐项部署，返回逐项报告
+    ///
+    /// 单项失败记录在报告中；未设置 `continue_on_error` 时剩余项标记为未执行
+    pub fn deploy(&self, device_id: &str, plan: &DeployPlan) -> DeployReport {
+        let start = Instant::now();
+        let mut items = Vec::with_capacity(plan.entries.len());
+        let mut aborted = false;
+
+        for entry in &plan.entries {
+            let item_start = Instant::now();
+            let outcome = if aborted {
+                DeployOutcome::NotAttempted
+            } else {
+                match self.deploy_entry(device_id, entry) {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        warn!("部署 {} 失败: {}", entry.source.display(), e);
+                        aborted = !plan.continue_on_error;
+                        DeployOutcome::Failed(e.to_string())
+                    }
+                }
+            };
+
+            debug!("部署 {} -> {:?}", entry.source.display(), outcome);
+            items.push(DeployItem {
+                source: entry.source.clone(),
+                destination: entry.destination.clone(),
+                outcome,
+                duration: item_start.elapsed(),
+            });
+        }
+
+        let report = DeployReport {
+            items,
+            duration: start.elapsed(),
+        };
+        info!(
+            "设备 {} 部署完成: {} 项，推送 {} 字节，耗时 {:?}",
+            device_id,
+            report.items.len(),
+            report.bytes_pushed(),
+            report.duration
+        );
+        report
+    }
+
+    fn deploy_entry(&self, device_id: &str, entry: &DeployEntry) -> ADBResult<DeployOutcome> {
+        let source = entry.source.to_string_lossy();
+        if !Path::new(&entry.source).exists() {
+            return Err(ADBError::FileError(format!("构建产物不存在: {}", source)));
+        }
+
+        match &entry.strategy {
+            DeployStrategy::Install(options) => {
+                self.install_app_with_options(device_id, &source, options)?;
+                Ok(DeployOutcome::Installed)
+            }
+            DeployStrategy::Push => {
+                let stats = self.push(device_id, &source, &entry.destination, None)?;
+                Ok(DeployOutcome::Pushed { bytes: stats.bytes })
+            }
+            DeployStrategy::PushIfChanged => {
+                if entry.source.is_dir() {
+                    return Err(ADBError::FileError(format!(
+                        "PushIfChanged 只支持文件: {}",
+                        source
+                    )));
+                }
+                if !self.file_changed(device_id, &entry.source, &entry.destination)? {
+                    return Ok(DeployOutcome::Unchanged);
+                }
+                let stats = self.push(device_id, &source, &entry.destination, None)?;
+                Ok(DeployOutcome::Pushed { bytes: stats.bytes })
+            }
+        }
+    }
+
+    /// 替换应用的 native 库并只重启该应用
+    ///
+    /// 有 root 权限时直接覆盖应用 lib 目录中的同名库。否则对可调试应用使用 `run-as`
+    /// 把库复制到 `<数据目录>/code_cache/native_override/`，此时应用需要在
+    /// `System.loadLibrary` 之前检查该目录并改用 `System.load` 加载。
+    /// 库未解压（`extractNativeLibs=false`）且没有可用方式时返回错误。
+    pub fn push_native_lib(&self, device_id: &str, package: &str, so_path: &str) -> ADBResult<NativeLibPush> {
+        let file_name = Path::new(so_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .filter(|n| n.ends_with(".so"))
+            .ok_or_else(|| ADBError::FileError(format!("不是 native 库文件: {}", so_path)))?;
+        if !Path::new(so_path).is_file() {
+            return Err(ADBError::FileError(format!("文件不存在: {}", so_path)));
+        }
+
+        let dump = self.shell(device_id, &format!("dumpsys package {}", shell_quote(package)))?;
+        if !dump.contains("Package [") {
+            return Err(ADBError::AppNotFound(package.to_string()));
+        }
+        let lib_dir = parse_native_lib_dir(&dump);
+        let debuggable = dump
+            .lines()
+            .any(|l| l.trim_start().starts_with("flags=") && l.contains("DEBUGGABLE"));
+
+        let staging = format!("/data/local/tmp/{}", file_name);
+        let bytes = self.push(device_id, so_path, &staging, None)?.bytes;
+        let staging_q = shell_quote(&staging);
+
+        // 只有库已解压到 lib 目录时才能直接覆盖
+        let installed_dir = match &lib_dir {
+            Some(dir) => {
+                let check = self.shell(device_id, &format!("[ -d {} ] && echo yes; true", shell_quote(dir)))?;
+                check.trim() == "yes"
+            }
+            None => false,
+        };
+        let root_copy = lib_dir.as_ref().filter(|_| installed_dir).and_then(|dir| {
+            let target = format!("{}/{}", dir, file_name);
+            let copy = format!(
+                "cp {} {} && chmod 755 {} && chown system:system {} && restorecon {}",
+                staging_q,
+                shell_quote(&target),
+                shell_quote(&target),
+                shell_quote(&target),
+                shell_quote(&target)
+            );
+            self.root_command(device_id, &copy).map(|command| (target, command))
+        });
+
+        let result = if let Some((target, command)) = root_copy {
+            self.shell(device_id, &command).map(|_| (NativeLibMethod::Root, target))
+        } else if debuggable {
+            let package_q = shell_quote(package);
+            let target = format!("{}/{}", NATIVE_OVERRIDE_DIR, file_name);
+            let command = format!(
+                "run-as {} mkdir -p {} && run-as {} cp {} {} && run-as {} chmod 700 {}",
+                package_q,
+                NATIVE_OVERRIDE_DIR,
+                package_q,
+                staging_q,
+                shell_quote(&target),
+                package_q,
+                shell_quote(&target)
+            );
+            self.shell(device_id, &command).and_then(|_| {
+                let data_dir = self.shell(device_id, &format!("run-as {} pwd", package_q))?;
+                Ok((NativeLibMethod::RunAs, format!("{}/{}", data_dir.trim(), target)))
+            })
+        } else if !installed_dir {
+            Err(ADBError::PermissionDenied(format!(
+                "应用 {} 的 native 库未解压到 lib 目录，且应用不可调试，无法替换 {}",
+                package, file_name
+            )))
+        } else {
+            Err(ADBError::PermissionDenied(format!(
+                "替换应用 {} 的 native 库需要 root 权限或可调试的应用",
+                package
+            )))
+        };
+
+        let _ = self.shell(device_id, &format!("rm -f {}", staging_q));
+        let (method, device_path) = result?;
+        debug!("设备 {} 已通过 {:?} 替换 {}", device_id, method, device_path);
+
+        self.stop_app(device_id, package)?;
+        let restarted = self.start_app(device_id, package, None)?;
+        info!(
+            "设备 {} 已替换应用 {} 的 native 库 {} ({} 字节)",
+            device_id, package, file_name, bytes
+        );
+
+        Ok(NativeLibPush {
+            method,
+            device_path,
+            bytes,
+            restarted,
+        })
+    }
+}
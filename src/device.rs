@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::sync::{Arc, Mutex};
 
@@ -118,14 +118,61 @@ impl ADBDevice {
     }
 }
 
-/// ADB 连接池类型
-type DevicePool = HashMap<String, Arc<Mutex<std::process::Child>>>;
+/// ADB 连接池，保存后台启动的子进程
+///
+/// 最后一个 ADB 实例释放时终止所有仍在运行的子进程
+#[derive(Debug, Default)]
+pub(crate) struct DevicePool {
+    children: HashMap<String, Arc<Mutex<std::process::Child>>>,
+}
+
+impl DevicePool {
+    /// 添加子进程，键重复时不会覆盖已有的进程
+    pub(crate) fn insert(&mut self, key: String, child: std::process::Child) {
+        let key = if self.children.contains_key(&key) {
+            format!("{}#{}", key, child.id())
+        } else {
+            key
+        };
+        self.children.insert(key, Arc::new(Mutex::new(child)));
+    }
+
+    /// 终止并回收所有子进程，返回终止的数量
+    pub(crate) fn kill_all(&mut self) -> usize {
+        let mut killed = 0;
+        for (key, child) in self.children.drain() {
+            if let Ok(mut child) = child.lock() {
+                if matches!(child.try_wait(), Ok(None)) {
+                    let _ = child.kill();
+                    killed += 1;
+                    log::debug!("已终止后台进程: {}", key);
+                }
+                let _ = child.wait();
+            }
+        }
+        killed
+    }
+}
+
+impl Drop for DevicePool {
+    fn drop(&mut self) {
+        self.kill_all();
+    }
+}
 
 /// ADB 主结构体
 #[derive(Clone, Debug)]
 pub struct ADB {
     pub config: ADBConfig,
     pub(crate) connections: Arc<Mutex<DevicePool>>,
+    pub(crate) jobs: Arc<crate::resource::BackgroundJobs>,
+    pub(crate) cache: Arc<crate::cache::CommandCache>,
+    /// 已补齐命令的设备，shell 命令会把补齐目录加入 `PATH`
+    pub(crate) shim_devices: Arc<Mutex<HashSet<String>>>,
+    /// 文件传输改走的通道：设备 ID -> 实际使用的设备 ID
+    pub(crate) transfer_routes: Arc<Mutex<HashMap<String, String>>>,
+    pub(crate) runner: Arc<dyn crate::runner::CommandRunner>,
+    pub(crate) scheduler: Option<Arc<crate::scheduler::CommandScheduler>>,
 }
 
 impl ADB {
@@ -133,7 +180,21 @@ impl ADB {
     pub fn new(config: Option<ADBConfig>) -> Self {
         Self {
             config: config.unwrap_or_default(),
-            connections: Arc::new(Mutex::new(HashMap::new())),
+            connections: Arc::new(Mutex::new(DevicePool::default())),
+            jobs: Arc::new(crate::resource::BackgroundJobs::default()),
+            cache: Arc::new(crate::cache::CommandCache::default()),
+            shim_devices: Arc::new(Mutex::new(HashSet::new())),
+            transfer_routes: Arc::new(Mutex::new(HashMap::new())),
+            runner: Arc::new(crate::runner::ProcessRunner),
+            scheduler: None,
+        }
+    }
+
+    /// 使用指定的命令执行后端创建 ADB 实例，用于注入模拟后端或录制/回放
+    pub fn with_runner(config: Option<ADBConfig>, runner: Arc<dyn crate::runner::CommandRunner>) -> Self {
+        Self {
+            runner,
+            ..Self::new(config)
         }
     }
 
@@ -141,4 +202,38 @@ impl ADB {
     pub fn adb_path(&self) -> &std::path::PathBuf {
         &self.config.path
     }
-}
\ No newline at end of file
+}
+
+/// 已就绪设备的句柄
+///
+/// 由 [`ADB::connect_and_prepare`] 等方法在设备确认可用后返回
+#[derive(Clone, Debug)]
+pub struct DeviceHandle {
+    adb: ADB,
+    device_id: String,
+}
+
+impl DeviceHandle {
+    /// 创建设备句柄
+    pub fn new(adb: ADB, device_id: &str) -> Self {
+        Self {
+            adb,
+            device_id: device_id.to_string(),
+        }
+    }
+
+    /// 获取设备 ID
+    pub fn id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// 获取关联的 ADB 实例
+    pub fn adb(&self) -> &ADB {
+        &self.adb
+    }
+
+    /// 在设备上执行 shell 命令
+    pub fn shell(&self, command: &str) -> crate::error::ADBResult<String> {
+        self.adb.shell(&self.device_id, command)
+    }
+}
@@ -0,0 +1,286 @@
+//! dumpsys 快照对比
+//!
+//! 把 `dumpsys` 输出按缩进拆成层级路径：`key=value` 和 `key: value` 记为键值，
+//! 其他行记为所在段落的成员。两次快照对比后得到新增、消失和变化的条目，
+//! 例如新出现的 wakelock、包标志变化、内存增长等。
+
+use crate::device::ADB;
+use crate::error::ADBResult;
+use crate::utils::shell_quote;
+use log::debug;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::SystemTime;
+
+// 行内的 key=value
+static KEY_VALUE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"([A-Za-z_][\w.\-]*)=(\S+)").unwrap());
+// 数值及可选单位，如 "12,345K"、"1.5MB"、"-3"
+static NUMBER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^-?[\d,]+(\.\d+)?").unwrap());
+
+// `key: value` 中键的最大长度，超过时视为普通文本
+const MAX_KEY_LEN: usize = 40;
+
+/// 单个服务的 dumpsys 输出
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServiceSnapshot {
+    /// 原始输出
+    pub raw: String,
+    /// 键值条目，键为 `段落/子段落/键`
+    pub values: BTreeMap<String, String>,
+    /// 不含键值的行，格式为 `段落/子段落/行内容`
+    pub lines: BTreeSet<String>,
+}
+
+/// 段落名：去掉冒号、等号或括号之后的内容（如 "Wake Locks: size=2" -> "Wake Locks"）
+fn section_name(line: &str) -> &str {
+    let end = line.find([':', '=', '(']).unwrap_or(line.len());
+    let name = line[..end].trim();
+    if name.is_empty() {
+        line
+    } else {
+        name
+    }
+}
+
+/// 插入键值，同一段落中的重复键加上序号
+fn insert_value(values: &mut BTreeMap<String, String>, key: String, value: &str) {
+    let mut unique = key.clone();
+    let mut index = 2;
+    while values.contains_key(&unique) {
+        unique = format!("{}#{}", key, index);
+        index += 1;
+    }
+    values.insert(unique, value.to_string());
+}
+
+/// 按缩进解析 dumpsys 输出
+fn parse_dumpsys(output: &str) -> ServiceSnapshot {
+    let mut snapshot = ServiceSnapshot {
+        raw: output.to_string(),
+        ..Default::default()
+    };
+    let mut stack: Vec<(usize, String)> = Vec::new();
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        while stack.last().is_some_and(|(level, _)| *level >= indent) {
+            stack.pop();
+        }
+        let path = stack
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .collect::<Vec<_>>()
+            .join("/");
+        let prefix = if path.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", path)
+        };
+
+        let pairs: Vec<_> = KEY_VALUE_RE.captures_iter(trimmed).collect();
+        if !pairs.is_empty() {
+            for caps in pairs {
+                insert_value(&mut snapshot.values, format!("{}{}", prefix, &caps[1]), &caps[2]);
+            }
+        } else if let Some((key, value)) = trimmed
+            .split_once(": ")
+            .filter(|(key, value)| key.len() <= MAX_KEY_LEN && !value.trim().is_empty())
+        {
+            insert_value(&mut snapshot.values, format!("{}{}", prefix, key.trim()), value.trim());
+        } else if !trimmed.ends_with(':') {
+            snapshot.lines.insert(format!("{}{}", prefix, trimmed));
+        }
+
+        // 每一行都可能是更深缩进行的父段落
+        stack.push((indent, section_name(trimmed).to_string()));
+    }
+
+    snapshot
+}
+
+/// 解析值开头的数字，忽略千位分隔符和单位
+fn parse_number(value: &str) -> Option<f64> {
+    NUMBER_RE
+        .find(value)
+        .and_then(|m| m.as_str().replace(',', "").parse().ok())
+}
+
+/// dumpsys 快照
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub device_id: String,
+    pub taken_at: SystemTime,
+    /// 按服务名（含参数，如 "meminfo com.foo"）索引
+    pub services: BTreeMap<String, ServiceSnapshot>,
+}
+
+impl Snapshot {
+    /// 与之后的快照对比，`self` 为之前的状态
+    pub fn diff(&self, other: &Snapshot) -> DiffReport {
+        let mut entries = Vec::new();
+        let empty = ServiceSnapshot::default();
+
+        let names: BTreeSet<&String> = self.services.keys().chain(other.services.keys()).collect();
+        for name in names {
+            let before = self.services.get(name).unwrap_or(&empty);
+            let after = other.services.get(name).unwrap_or(&empty);
+
+            let keys: BTreeSet<&String> = before.values.keys().chain(after.values.keys()).collect();
+            for key in keys {
+                let kind = match (before.values.get(key), after.values.get(key)) {
+                    (None, Some(value)) => DiffKind::Added(value.clone()),
+                    (Some(value), None) => DiffKind::Removed(value.clone()),
+                    (Some(old), Some(new)) if old != new => DiffKind::Changed {
+                        before: old.clone(),
+                        after: new.clone(),
+                    },
+                    _ => continue,
+                };
+                entries.push(DiffEntry {
+                    service: name.clone(),
+                    key: key.clone(),
+                    kind,
+                });
+            }
+
+            for line in after.lines.difference(&before.lines) {
+                entries.push(DiffEntry {
+                    service: name.clone(),
+                    key: line.clone(),
+                    kind: DiffKind::Added(String::new()),
+                });
+            }
+            for line in before.lines.difference(&after.lines) {
+                entries.push(DiffEntry {
+                    service: name.clone(),
+                    key: line.clone(),
+                    kind: DiffKind::Removed(String::new()),
+                });
+            }
+        }
+
+        DiffReport { entries }
+    }
+}
+
+/// 差异类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffKind {
+    /// 新出现的条目（普通行时值为空）
+    Added(String),
+    /// 消失的条目（普通行时值为空）
+    Removed(String),
+    /// 值发生变化
+    Changed { before: String, after: String },
+}
+
+/// 一条差异
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub service: String,
+    /// 键的层级路径，普通行时为行内容
+    pub key: String,
+    pub kind: DiffKind,
+}
+
+impl DiffEntry {
+    /// 数值变化量（前后都是数字时）
+    pub fn delta(&self) -> Option<f64> {
+        match &self.kind {
+            DiffKind::Changed { before, after } => Some(parse_number(after)? - parse_number(before)?),
+            _ => None,
+        }
+    }
+}
+
+/// 快照差异报告
+#[derive(Debug, Clone, Default)]
+pub struct DiffReport {
+    pub entries: Vec<DiffEntry>,
+}
+
+impl DiffReport {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 指定服务的差异
+    pub fn for_service(&self, service: &str) -> Vec<&DiffEntry> {
+        self.entries.iter().filter(|e| e.service == service).collect()
+    }
+
+    /// 新出现的条目
+    pub fn added(&self) -> Vec<&DiffEntry> {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e.kind, DiffKind::Added(_)))
+            .collect()
+    }
+
+    /// 消失的条目
+    pub fn removed(&self) -> Vec<&DiffEntry> {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e.kind, DiffKind::Removed(_)))
+            .collect()
+    }
+
+    /// 值变化的条目
+    pub fn changed(&self) -> Vec<&DiffEntry> {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e.kind, DiffKind::Changed { .. }))
+            .collect()
+    }
+
+    /// 数值增长的条目，按增长量从大到小排列
+    pub fn grown(&self) -> Vec<(&DiffEntry, f64)> {
+        let mut grown: Vec<(&DiffEntry, f64)> = self
+            .entries
+            .iter()
+            .filter_map(|e| e.delta().filter(|d| *d > 0.0).map(|d| (e, d)))
+            .collect();
+        grown.sort_by(|a, b| b.1.total_cmp(&a.1));
+        grown
+    }
+
+    /// 排除键路径匹配正则的条目（用于忽略时间戳等易变字段）
+    pub fn ignore(mut self, pattern: &Regex) -> Self {
+        self.entries.retain(|e| !pattern.is_match(&e.key));
+        self
+    }
+}
+
+impl ADB {
+    /// 采集多个服务的 dumpsys 快照
+    ///
+    /// `services` 中每项可以带参数，如 `"power"`、`"meminfo com.example"`
+    pub fn dumpsys_snapshot(&self, device_id: &str, services: &[&str]) -> ADBResult<Snapshot> {
+        let mut snapshot = Snapshot {
+            device_id: device_id.to_string(),
+            taken_at: SystemTime::now(),
+            services: BTreeMap::new(),
+        };
+
+        for service in services {
+            let args: Vec<String> = service.split_whitespace().map(shell_quote).collect();
+            let output = self.shell(device_id, &format!("dumpsys {}", args.join(" ")))?;
+            let parsed = parse_dumpsys(&output);
+            debug!(
+                "dumpsys {}: {} 个键值，{} 行",
+                service,
+                parsed.values.len(),
+                parsed.lines.len()
+            );
+            snapshot.services.insert(service.to_string(), parsed);
+        }
+
+        Ok(snapshot)
+    }
+}
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// ADB 操作相关的错误类型
@@ -39,6 +40,14 @@ pub enum ADBError {
     #[error("权限不足: {0}")]
     PermissionDenied(String),
 
+    /// 操作需要在设备屏幕上确认（如 MIUI 的 USB 安装提示）
+    #[error("需要在设备上确认: {message}")]
+    NeedsOnDeviceConfirmation {
+        message: String,
+        /// 检测到确认对话框时的截图（本地路径）
+        screenshot: Option<PathBuf>,
+    },
+
     /// 连接错误
     #[error("连接错误: {0}")]
     ConnectionError(String),
@@ -0,0 +1,270 @@
+use crate::device::ADB;
+use crate::error::ADBResult;
+use crate::logcat::{
+    parse_epoch_logs, LogBuffer, LogFormat, LogSource, LogcatQuery, LogcatStream, TimelineEntry,
+};
+use log::{debug, trace};
+
+/// events 缓冲区中的设备事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceEvent {
+    /// 进程启动 (am_proc_start)
+    ProcStart {
+        pid: u32,
+        uid: u32,
+        process: String,
+        hosting_type: Option<String>,
+    },
+    /// 进程死亡 (am_proc_died)
+    ProcDied { pid: u32, process: String },
+    /// 进程被杀 (am_kill)
+    Kill {
+        pid: u32,
+        process: String,
+        reason: Option<String>,
+    },
+    /// 应用无响应 (am_anr)
+    Anr {
+        pid: u32,
+        package: String,
+        reason: Option<String>,
+    },
+    /// 应用崩溃 (am_crash)
+    Crash {
+        pid: u32,
+        process: String,
+        exception: Option<String>,
+        message: Option<String>,
+    },
+    /// 启动阶段 (boot_progress_*)
+    BootProgress { stage: String, uptime_ms: u64 },
+    /// 其他未解析的事件
+    Other { tag: String, payload: String },
+}
+
+impl DeviceEvent {
+    /// 事件涉及的进程 PID（如有）
+    pub fn pid(&self) -> Option<u32> {
+        match self {
+            DeviceEvent::ProcStart { pid, .. }
+            | DeviceEvent::ProcDied { pid, .. }
+            | DeviceEvent::Kill { pid, .. }
+            | DeviceEvent::Anr { pid, .. }
+            | DeviceEvent::Crash { pid, .. } => Some(*pid),
+            _ => None,
+        }
+    }
+
+    /// 事件涉及的进程名或包名（如有）
+    pub fn process(&self) -> Option<&str> {
+        match self {
+            DeviceEvent::ProcStart { process, .. }
+            | DeviceEvent::ProcDied { process, .. }
+            | DeviceEvent::Kill { process, .. }
+            | DeviceEvent::Crash { process, .. } => Some(process),
+            DeviceEvent::Anr { package, .. } => Some(package),
+            _ => None,
+        }
+    }
+}
+
+/// 带时间戳的设备事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRecord {
+    /// Unix 纪元秒（设备时钟）
+    pub timestamp: f64,
+    pub event: DeviceEvent,
+}
+
+/// 拆分事件负载 "[a,b,c]"，最多拆为 `limit` 段
+fn split_payload(payload: &str, limit: usize) -> Vec<&str> {
+    payload
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .splitn(limit, ',')
+        .map(|f| f.trim())
+        .collect()
+}
+
+fn non_empty(value: Option<&&str>) -> Option<String> {
+    value.filter(|v| !v.is_empty()).map(|v| v.to_string())
+}
+
+/// 将单个事件标签和负载解析为 [`DeviceEvent`]
+pub fn parse_event(tag: &str, payload: &str) -> DeviceEvent {
+    let other = || DeviceEvent::Other {
+        tag: tag.to_string(),
+        payload: payload.trim().to_string(),
+    };
+
+    match tag {
+        // [user, pid, uid, process, hostingType, hostingName, ...]
+        "am_proc_start" => {
+            let fields = split_payload(payload, 6);
+            let pid = fields.get(1).and_then(|p| p.parse().ok());
+            let uid = fields.get(2).and_then(|u| u.parse().ok());
+            match (pid, uid, fields.get(3)) {
+                (Some(pid), Some(uid), Some(process)) => DeviceEvent::ProcStart {
+                    pid,
+                    uid,
+                    process: process.to_string(),
+                    hosting_type: non_empty(fields.get(4)),
+                },
+                _ => other(),
+            }
+        }
+        // [user, pid, process, ...]
+        "am_proc_died" => {
+            let fields = split_payload(payload, 4);
+            match (fields.get(1).and_then(|p| p.parse().ok()), fields.get(2)) {
+                (Some(pid), Some(process)) => DeviceEvent::ProcDied {
+                    pid,
+                    process: process.to_string(),
+                },
+                _ => other(),
+            }
+        }
+        // [user, pid, process, oomAdj, reason]
+        "am_kill" => {
+            let fields = split_payload(payload, 5);
+            match (fields.get(1).and_then(|p| p.parse().ok()), fields.get(2)) {
+                (Some(pid), Some(process)) => DeviceEvent::Kill {
+                    pid,
+                    process: process.to_string(),
+                    reason: non_empty(fields.get(4)),
+                },
+                _ => other(),
+            }
+        }
+        // [user, pid, package, flags, reason]
+        "am_anr" => {
+            let fields = split_payload(payload, 5);
+            match (fields.get(1).and_then(|p| p.parse().ok()), fields.get(2)) {
+                (Some(pid), Some(package)) => DeviceEvent::Anr {
+                    pid,
+                    package: package.to_string(),
+                    reason: non_empty(fields.get(4)),
+                },
+                _ => other(),
+            }
+        }
+        // [user, pid, process, flags, exception, message, file, line(, recoverable)]
+        "am_crash" => {
+            let fields = split_payload(payload, 6);
+            match (fields.get(1).and_then(|p| p.parse().ok()), fields.get(2)) {
+                (Some(pid), Some(process)) => DeviceEvent::Crash {
+                    pid,
+                    process: process.to_string(),
+                    exception: non_empty(fields.get(4)),
+                    message: fields
+                        .get(5)
+                        .map(|rest| strip_crash_location(rest))
+                        .filter(|m| !m.is_empty()),
+                },
+                _ => other(),
+            }
+        }
+        _ if tag.starts_with("boot_progress_") => match payload.trim().parse() {
+            Ok(uptime_ms) => DeviceEvent::BootProgress {
+                stage: tag.trim_start_matches("boot_progress_").to_string(),
+                uptime_ms,
+            },
+            Err(_) => other(),
+        },
+        _ => other(),
+    }
+}
+
+/// 去掉 am_crash 消息末尾的文件名、行号和可恢复标记
+fn strip_crash_location(rest: &str) -> String {
+    let mut parts: Vec<&str> = rest.rsplitn(4, ',').collect();
+    let is_number = |s: &str| s.trim().parse::<i64>().is_ok();
+
+    // rsplitn 结果为倒序: [recoverable?, line, file, message]
+    let drop = match parts.as_slice() {
+        [last, line, _, _] if is_number(last) && is_number(line) => 3,
+        [last, _, _, ..] if is_number(last) => 2,
+        _ => 0,
+    };
+    if drop == 0 {
+        return rest.trim().to_string();
+    }
+    parts.drain(..drop);
+    parts.reverse();
+    parts.join(",").trim().to_string()
+}
+
+/// 将 events 缓冲区条目转换为事件记录
+fn to_record(entry: TimelineEntry) -> EventRecord {
+    EventRecord {
+        timestamp: entry.timestamp,
+        event: parse_event(entry.tag.as_deref().unwrap_or(""), &entry.message),
+    }
+}
+
+/// 解析 `logcat -b events -v epoch` 输出
+pub fn parse_events(output: &str) -> Vec<EventRecord> {
+    parse_epoch_logs(output, LogSource::Events)
+        .into_iter()
+        .map(to_record)
+        .collect()
+}
+
+/// 实时事件流，迭代时阻塞等待新事件
+///
+/// 基于 events 缓冲区的 [`LogcatStream`]，丢弃时会结束设备上的 logcat 进程
+pub struct EventStream {
+    logs: LogcatStream,
+}
+
+impl Iterator for EventStream {
+    type Item = EventRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for entry in self.logs.by_ref() {
+            trace!("事件: {} {}", entry.tag, entry.message);
+            match entry.timestamp.parse() {
+                Ok(timestamp) => {
+                    return Some(EventRecord {
+                        timestamp,
+                        event: parse_event(&entry.tag, &entry.message),
+                    })
+                }
+                Err(_) => trace!("跳过时间戳无法解析的事件: {}", entry.timestamp),
+            }
+        }
+        None
+    }
+}
+
+impl ADB {
+    /// 读取 events 缓冲区中已有的事件
+    ///
+    /// `since` 格式与 [`LogcatQuery::since`] 相同
+    pub fn read_events(&self, device_id: &str, since: Option<&str>) -> ADBResult<Vec<EventRecord>> {
+        let mut query = LogcatQuery::new()
+            .buffer(LogBuffer::Events)
+            .format(LogFormat::Epoch);
+        if let Some(since) = since {
+            query = query.since(since);
+        }
+
+        let output = self.query_logs(device_id, &query)?;
+        Ok(parse_events(&output))
+    }
+
+    /// 订阅设备事件流（只包含订阅之后产生的事件）
+    pub fn event_stream(&self, device_id: &str) -> ADBResult<EventStream> {
+        let (now, _) = self.device_clock(device_id)?;
+
+        let query = LogcatQuery::new()
+            .buffer(LogBuffer::Events)
+            .format(LogFormat::Epoch)
+            .since(&format!("{:.3}", now));
+        let logs = self.logcat_stream(device_id, &query)?;
+
+        debug!("已订阅设备 {} 的事件流", device_id);
+        Ok(EventStream { logs })
+    }
+}
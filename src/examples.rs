@@ -0,0 +1,240 @@
+//! examples/ 中示例场景的库函数版本（需要 `examples_harness` 特性）
+//!
+//! 每个函数执行与同名示例相同的操作，但通过参数指定设备并返回结构化结果，
+//! 便于下游 CI 在真实设备上直接复用这些场景做冒烟测试。
+
+use crate::device::{DeviceStatus, ADB};
+use crate::error::{ADBError, ADBResult};
+use crate::transfer::TransferOptions;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 单个设备的概要信息
+#[derive(Debug, Clone)]
+pub struct DeviceSummary {
+    pub id: String,
+    pub name: String,
+    pub status: DeviceStatus,
+    pub android_version: Option<String>,
+    pub third_party_apps: Option<usize>,
+}
+
+/// `basic_usage` 场景结果
+#[derive(Debug, Clone)]
+pub struct BasicUsageReport {
+    pub adb_version: String,
+    pub devices: Vec<DeviceSummary>,
+}
+
+/// `file_transfer` 场景结果
+#[derive(Debug, Clone)]
+pub struct FileTransferReport {
+    pub device_path: String,
+    pub bytes: u64,
+    /// 推送后设备上的文件大小
+    pub remote_size: u64,
+    /// 拉回的内容与原始内容一致
+    pub round_trip_ok: bool,
+    /// 删除后文件已不存在
+    pub removed: bool,
+}
+
+/// `app_management` 场景结果
+#[derive(Debug, Clone)]
+pub struct AppManagementReport {
+    pub package: String,
+    pub version_name: Option<String>,
+    pub version_code: Option<i32>,
+    pub permission_count: usize,
+    pub running_before: bool,
+    pub running_after_start: bool,
+    pub running_after_stop: bool,
+}
+
+/// `screen_capture` 场景结果
+#[derive(Debug, Clone)]
+pub struct ScreenCaptureReport {
+    pub screenshot_path: PathBuf,
+    pub screenshot_bytes: u64,
+    pub recording_path: PathBuf,
+    pub recording_bytes: u64,
+}
+
+/// `parallel_operations` 场景结果
+#[derive(Debug, Clone)]
+pub struct ParallelReport {
+    /// 各设备型号，失败时为错误信息
+    pub models: HashMap<String, Result<String, String>>,
+    pub online: Vec<String>,
+    /// 各设备是否成功读取电池信息
+    pub battery: HashMap<String, Result<(), String>>,
+}
+
+fn local_file_size(path: &Path) -> ADBResult<u64> {
+    fs::metadata(path)
+        .map(|m| m.len())
+        .map_err(|e| ADBError::FileError(format!("无法读取 {}: {}", path.display(), e)))
+}
+
+/// 检查 ADB 并收集所有设备的概要信息
+pub fn run_basic_usage(adb: &ADB) -> ADBResult<BasicUsageReport> {
+    let adb_version = adb.check_adb()?;
+    let devices = adb
+        .list_devices()?
+        .into_iter()
+        .map(|device| {
+            let online = device.is_online();
+            DeviceSummary {
+                android_version: online
+                    .then(|| adb.get_prop(&device.id, "ro.build.version.release").ok())
+                    .flatten(),
+                third_party_apps: online
+                    .then(|| adb.list_packages(&device.id, false, true).ok().map(|a| a.len()))
+                    .flatten(),
+                id: device.id,
+                name: device.name,
+                status: device.status,
+            }
+        })
+        .collect();
+
+    Ok(BasicUsageReport {
+        adb_version,
+        devices,
+    })
+}
+
+/// 推送、校验、拉回并删除一个测试文件
+pub fn run_file_transfer(adb: &ADB, device_id: &str) -> ADBResult<FileTransferReport> {
+    let local_dir = crate::utils::create_temp_dir_path("adb_example")?;
+    let local_file = local_dir.join("test_file.txt");
+    let downloaded = local_dir.join("downloaded_test_file.txt");
+    let content = "这是一个测试文件内容";
+    let device_path = format!("/data/local/tmp/adbkit_example_{}.txt", rand::random::<u32>());
+
+    let result = (|| {
+        fs::write(&local_file, content)?;
+        let options = TransferOptions::default();
+
+        adb.push(
+            device_id,
+            local_file.to_str().unwrap_or_default(),
+            &device_path,
+            Some(options.clone()),
+        )?;
+        let remote_size = adb.get_file_size(device_id, &device_path)?;
+
+        adb.pull(
+            device_id,
+            &device_path,
+            downloaded.to_str().unwrap_or_default(),
+            Some(options),
+        )?;
+        let round_trip_ok = fs::read_to_string(&downloaded).map(|c| c == content)?;
+
+        adb.remove_path(device_id, &device_path, false)?;
+        let removed = !adb.file_exists(device_id, &device_path)?;
+
+        Ok(FileTransferReport {
+            device_path: device_path.clone(),
+            bytes: content.len() as u64,
+            remote_size,
+            round_trip_ok,
+            removed,
+        })
+    })();
+
+    let _ = fs::remove_dir_all(&local_dir);
+    result
+}
+
+/// 读取应用信息，并启动、停止应用
+///
+/// 未指定包名时使用第一个第三方应用
+pub fn run_app_management(
+    adb: &ADB,
+    device_id: &str,
+    package: Option<&str>,
+) -> ADBResult<AppManagementReport> {
+    let package = match package {
+        Some(p) => p.to_string(),
+        None => adb
+            .list_packages(device_id, false, true)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ADBError::AppNotFound("设备上没有第三方应用".to_string()))?,
+    };
+
+    let info = adb.get_package_info(device_id, &package)?;
+    let (running_before, _) = adb.is_package_running(device_id, &package)?;
+
+    adb.start_app(device_id, &package, None)?;
+    std::thread::sleep(Duration::from_secs(2));
+    let (running_after_start, _) = adb.is_package_running(device_id, &package)?;
+
+    adb.stop_app(device_id, &package)?;
+    std::thread::sleep(Duration::from_secs(1));
+    let (running_after_stop, _) = adb.is_package_running(device_id, &package)?;
+
+    Ok(AppManagementReport {
+        package,
+        version_name: info.version_name,
+        version_code: info.version_code,
+        permission_count: info.permissions.len(),
+        running_before,
+        running_after_start,
+        running_after_stop,
+    })
+}
+
+/// 截图并录制屏幕，文件保存到 `output_dir`
+pub fn run_screen_capture(
+    adb: &ADB,
+    device_id: &str,
+    output_dir: &Path,
+    record_secs: u32,
+) -> ADBResult<ScreenCaptureReport> {
+    fs::create_dir_all(output_dir)?;
+    let screenshot_path = output_dir.join("screenshot.png");
+    let recording_path = output_dir.join("screen_recording.mp4");
+
+    adb.take_screenshot_managed(device_id, screenshot_path.to_str().unwrap_or_default())?;
+    adb.record_screen_managed(
+        device_id,
+        recording_path.to_str().unwrap_or_default(),
+        record_secs,
+        None,
+    )?;
+
+    Ok(ScreenCaptureReport {
+        screenshot_bytes: local_file_size(&screenshot_path)?,
+        recording_bytes: local_file_size(&recording_path)?,
+        screenshot_path,
+        recording_path,
+    })
+}
+
+/// 在多个设备上并行执行命令
+pub fn run_parallel_operations(adb: &ADB, device_ids: &[&str]) -> ADBResult<ParallelReport> {
+    let models = adb
+        .parallel_shell(device_ids, "getprop ro.product.model")
+        .into_iter()
+        .map(|(id, r)| (id, r.map(|o| o.trim().to_string()).map_err(|e| e.to_string())))
+        .collect();
+
+    let online = adb.filter_online_devices(device_ids)?;
+
+    let battery = adb
+        .on_all_online_devices(|device_id| adb.shell(device_id, "dumpsys battery"))?
+        .into_iter()
+        .map(|(id, r)| (id, r.map(|_| ()).map_err(|e| e.to_string())))
+        .collect();
+
+    Ok(ParallelReport {
+        models,
+        online,
+        battery,
+    })
+}
@@ -1,7 +1,6 @@
 use crate::device::ADB;
 use crate::error::{ADBError, ADBResult};
 use log::debug;
-use std::process::Command;
 
 impl ADB {
     /// 将本地端口转发到设备端口
@@ -12,7 +11,7 @@ impl ADB {
         device_port: u16,
     ) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
 
             // 如果指定了设备 ID 则添加
             if !device_id.is_empty() {
@@ -45,7 +44,7 @@ impl ADB {
     /// 移除端口转发
     pub fn remove_forward(&self, local_port: u16) -> ADBResult<()> {
         self.with_retry(|| {
-            let output = Command::new(&self.config.path)
+            let output = self.adb_command()
                 .arg("forward")
                 .arg("--remove")
                 .arg(format!("tcp:{}", local_port))
@@ -67,10 +66,41 @@ impl ADB {
         })
     }
 
+    /// 设置带标签的端口转发
+    ///
+    /// 标签相同的旧转发会先被移除；带标签的转发在 [`ADB::shutdown`] 时自动移除
+    pub fn forward_labeled(
+        &self,
+        device_id: &str,
+        label: &str,
+        local_port: u16,
+        device_port: u16,
+    ) -> ADBResult<()> {
+        if let Some((_, old_port)) = self.jobs.take_forward(label) {
+            let _ = self.remove_forward(old_port);
+        }
+
+        self.forward(device_id, local_port, device_port)?;
+        self.jobs.register_forward(label, device_id, local_port);
+        debug!("端口转发 {} 已注册: localhost:{}", label, local_port);
+        Ok(())
+    }
+
+    /// 移除带标签的端口转发，标签不存在时返回 false
+    pub fn remove_labeled_forward(&self, label: &str) -> ADBResult<bool> {
+        match self.jobs.take_forward(label) {
+            Some((_, local_port)) => {
+                self.remove_forward(local_port)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     /// 移除所有端口转发
     pub fn remove_all_forwards(&self) -> ADBResult<()> {
         self.with_retry(|| {
-            let output = Command::new(&self.config.path)
+            let output = self.adb_command()
                 .arg("forward")
                 .arg("--remove-all")
                 .output()
@@ -94,7 +124,7 @@ impl ADB {
     /// 列出所有端口转发
     pub fn list_forwards(&self) -> ADBResult<String> {
         self.with_retry(|| {
-            let output = Command::new(&self.config.path)
+            let output = self.adb_command()
                 .arg("forward")
                 .arg("--list")
                 .output()
@@ -123,7 +153,7 @@ impl ADB {
         local_port: u16,
     ) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
 
             // 如果指定了设备 ID 则添加
             if !device_id.is_empty() {
@@ -156,7 +186,7 @@ impl ADB {
     /// 移除反向端口转发
     pub fn remove_reverse(&self, device_id: &str, remote_port: u16) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
 
             // 如果指定了设备 ID 则添加
             if !device_id.is_empty() {
@@ -188,7 +218,7 @@ impl ADB {
     /// 移除所有反向端口转发
     pub fn remove_all_reverses(&self, device_id: &str) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
 
             // 如果指定了设备 ID 则添加
             if !device_id.is_empty() {
@@ -0,0 +1,153 @@
+//! 渲染帧统计
+//!
+//! 解析 `dumpsys gfxinfo <包名> framestats`：汇总部分给出自上次重置以来的总帧数和卡顿帧数，
+//! `---PROFILEDATA---` 之间是最近 120 帧左右的逐帧时间戳（纳秒）。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::debug;
+use std::time::Duration;
+
+// 60Hz 下一帧的时间预算，用于在汇总缺失时统计卡顿帧
+const FRAME_BUDGET: Duration = Duration::from_nanos(16_666_667);
+// 逐帧数据的分隔行
+const PROFILE_DATA_MARKER: &str = "---PROFILEDATA---";
+
+/// 帧统计
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FrameStats {
+    pub package_name: String,
+    /// 自上次重置以来渲染的总帧数
+    pub total_frames: u64,
+    /// 自上次重置以来的卡顿帧数
+    pub janky_frames: u64,
+    /// 最近各帧的耗时（从预期 vsync 到帧完成）
+    pub frame_durations: Vec<Duration>,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl FrameStats {
+    /// 卡顿帧比例（0-100）
+    pub fn jank_percent(&self) -> f64 {
+        if self.total_frames == 0 {
+            return 0.0;
+        }
+        self.janky_frames as f64 * 100.0 / self.total_frames as f64
+    }
+}
+
+/// 计算百分位（最近秩法），`sorted` 须已排序
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// 解析汇总中的 "Janky frames: 12 (3.45%)"、"90th percentile: 14ms" 等行
+fn summary_value(output: &str, key: &str) -> Option<u64> {
+    output.lines().find_map(|l| {
+        let value = l.trim().strip_prefix(key)?.trim_start_matches(':').trim();
+        value
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .and_then(|v| v.parse().ok())
+    })
+}
+
+/// 解析逐帧数据，跳过标志位非零（非正常绘制）的帧
+fn parse_profile_data(output: &str) -> Vec<Duration> {
+    let mut durations = Vec::new();
+    let mut columns: Option<(usize, usize)> = None;
+
+    for section in output.split(PROFILE_DATA_MARKER).skip(1).step_by(2) {
+        for line in section.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields[0] == "Flags" {
+                let index = |name: &str| fields.iter().position(|f| *f == name);
+                columns = index("IntendedVsync").zip(index("FrameCompleted"));
+                continue;
+            }
+
+            let Some((vsync, completed)) = columns else {
+                continue;
+            };
+            let value = |i: usize| fields.get(i).and_then(|v| v.parse::<u64>().ok());
+            // 第一列为 Flags
+            if value(0) != Some(0) {
+                continue;
+            }
+            if let (Some(start), Some(end)) = (value(vsync), value(completed)) {
+                if end > start {
+                    durations.push(Duration::from_nanos(end - start));
+                }
+            }
+        }
+    }
+
+    durations
+}
+
+/// 解析 `dumpsys gfxinfo <包名> framestats` 输出
+fn parse_frame_stats(package_name: &str, output: &str) -> FrameStats {
+    let frame_durations = parse_profile_data(output);
+    let mut sorted = frame_durations.clone();
+    sorted.sort();
+
+    // 没有逐帧数据时使用汇总中的百分位
+    let pick = |p: f64, key: &str| {
+        if sorted.is_empty() {
+            Duration::from_millis(summary_value(output, key).unwrap_or(0))
+        } else {
+            percentile(&sorted, p)
+        }
+    };
+
+    FrameStats {
+        package_name: package_name.to_string(),
+        total_frames: summary_value(output, "Total frames rendered")
+            .unwrap_or(frame_durations.len() as u64),
+        janky_frames: summary_value(output, "Janky frames").unwrap_or_else(|| {
+            frame_durations.iter().filter(|d| **d > FRAME_BUDGET).count() as u64
+        }),
+        p50: pick(50.0, "50th percentile"),
+        p90: pick(90.0, "90th percentile"),
+        p95: pick(95.0, "95th percentile"),
+        p99: pick(99.0, "99th percentile"),
+        frame_durations,
+    }
+}
+
+impl ADB {
+    /// 获取应用的渲染帧统计
+    pub fn get_frame_stats(&self, device_id: &str, package_name: &str) -> ADBResult<FrameStats> {
+        let output = self.shell(device_id, &format!("dumpsys gfxinfo {} framestats", package_name))?;
+        if output.contains("No process found") {
+            return Err(ADBError::AppNotFound(format!(
+                "应用 {} 没有运行中的进程",
+                package_name
+            )));
+        }
+
+        let stats = parse_frame_stats(package_name, &output);
+        debug!(
+            "应用 {} 帧统计: {} 帧，卡顿 {} ({:.1}%)，p90 {:?}",
+            package_name,
+            stats.total_frames,
+            stats.janky_frames,
+            stats.jank_percent(),
+            stats.p90
+        );
+        Ok(stats)
+    }
+
+    /// 重置应用的帧统计，之后的统计从零开始
+    pub fn reset_frame_stats(&self, device_id: &str, package_name: &str) -> ADBResult<()> {
+        self.shell(device_id, &format!("dumpsys gfxinfo {} reset", package_name))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,483 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::debug;
+
+// Linux 输入事件类型
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+const SYN_REPORT: u16 = 0x00;
+
+/// 手柄按键（Linux `BTN_*` 键码）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    L1,
+    R1,
+    L2,
+    R2,
+    Select,
+    Start,
+    Mode,
+    ThumbLeft,
+    ThumbRight,
+}
+
+impl GamepadButton {
+    /// Linux 键码
+    pub fn code(&self) -> u16 {
+        match self {
+            GamepadButton::A => 0x130,
+            GamepadButton::B => 0x131,
+            GamepadButton::X => 0x133,
+            GamepadButton::Y => 0x134,
+            GamepadButton::L1 => 0x136,
+            GamepadButton::R1 => 0x137,
+            GamepadButton::L2 => 0x138,
+            GamepadButton::R2 => 0x139,
+            GamepadButton::Select => 0x13a,
+            GamepadButton::Start => 0x13b,
+            GamepadButton::Mode => 0x13c,
+            GamepadButton::ThumbLeft => 0x13d,
+            GamepadButton::ThumbRight => 0x13e,
+        }
+    }
+}
+
+/// 手柄轴（Linux `ABS_*` 轴码）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+    /// 十字键水平方向（-1 左，1 右）
+    HatX,
+    /// 十字键垂直方向（-1 上，1 下）
+    HatY,
+}
+
+impl GamepadAxis {
+    /// Linux 轴码
+    pub fn code(&self) -> u16 {
+        match self {
+            GamepadAxis::LeftX => 0x00,
+            GamepadAxis::LeftY => 0x01,
+            GamepadAxis::RightX => 0x03,
+            GamepadAxis::RightY => 0x04,
+            GamepadAxis::LeftTrigger => 0x02,
+            GamepadAxis::RightTrigger => 0x05,
+            GamepadAxis::HatX => 0x10,
+            GamepadAxis::HatY => 0x11,
+        }
+    }
+}
+
+/// 手柄输入事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadEvent {
+    /// 按下或松开按键
+    Button { button: GamepadButton, pressed: bool },
+    /// 点按（按下后立即松开）
+    Press(GamepadButton),
+    /// 设置轴的原始值（取值范围取决于设备）
+    Axis { axis: GamepadAxis, value: i32 },
+}
+
+impl GamepadEvent {
+    /// 转换为 (type, code, value) 序列，每组后跟同步事件
+    fn to_raw_events(self) -> Vec<(u16, u16, i32)> {
+        let sync = (EV_SYN, SYN_REPORT, 0);
+        match self {
+            GamepadEvent::Button { button, pressed } => {
+                vec![(EV_KEY, button.code(), pressed as i32), sync]
+            }
+            GamepadEvent::Press(button) => vec![
+                (EV_KEY, button.code(), 1),
+                sync,
+                (EV_KEY, button.code(), 0),
+                sync,
+            ],
+            GamepadEvent::Axis { axis, value } => vec![(EV_ABS, axis.code(), value), sync],
+        }
+    }
+}
+
+/// 模拟器传感器类型（对应模拟器控制台的 `sensor set` 名称）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SensorType {
+    Acceleration,
+    Gyroscope,
+    MagneticField,
+    Orientation,
+    Temperature,
+    Proximity,
+    Light,
+    Pressure,
+    Humidity,
+    HeartRate,
+    /// 其他传感器名称
+    Custom(String),
+}
+
+impl SensorType {
+    /// 模拟器控制台中的传感器名称
+    pub fn as_str(&self) -> &str {
+        match self {
+            SensorType::Acceleration => "acceleration",
+            SensorType::Gyroscope => "gyroscope",
+            SensorType::MagneticField => "magnetic-field",
+            SensorType::Orientation => "orientation",
+            SensorType::Temperature => "temperature",
+            SensorType::Proximity => "proximity",
+            SensorType::Light => "light",
+            SensorType::Pressure => "pressure",
+            SensorType::Humidity => "humidity",
+            SensorType::HeartRate => "heart-rate",
+            SensorType::Custom(name) => name,
+        }
+    }
+}
+
+/// 传感器读数
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    pub sensor: SensorType,
+    pub values: Vec<f32>,
+}
+
+impl SensorReading {
+    /// 创建传感器读数
+    pub fn new(sensor: SensorType, values: &[f32]) -> Self {
+        Self {
+            sensor,
+            values: values.to_vec(),
+        }
+    }
+
+    /// 加速度 (m/s²)
+    pub fn acceleration(x: f32, y: f32, z: f32) -> Self {
+        Self::new(SensorType::Acceleration, &[x, y, z])
+    }
+
+    /// 陀螺仪 (rad/s)
+    pub fn gyroscope(x: f32, y: f32, z: f32) -> Self {
+        Self::new(SensorType::Gyroscope, &[x, y, z])
+    }
+
+    /// 光照强度 (lux)
+    pub fn light(lux: f32) -> Self {
+        Self::new(SensorType::Light, &[lux])
+    }
+
+    /// 距离 (cm)
+    pub fn proximity(cm: f32) -> Self {
+        Self::new(SensorType::Proximity, &[cm])
+    }
+
+    /// 心率 (bpm)
+    pub fn heart_rate(bpm: f32) -> Self {
+        Self::new(SensorType::HeartRate, &[bpm])
+    }
+
+    /// 模拟器控制台参数格式 "x:y:z"
+    fn console_value(&self) -> String {
+        self.values
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+}
+
+/// Android 按键码 (`KEYCODE_*`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Home,
+    Back,
+    Menu,
+    Search,
+    Power,
+    Camera,
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+    Enter,
+    Delete,
+    ForwardDelete,
+    Tab,
+    Space,
+    Escape,
+    DpadUp,
+    DpadDown,
+    DpadLeft,
+    DpadRight,
+    DpadCenter,
+    MoveHome,
+    MoveEnd,
+    PageUp,
+    PageDown,
+    AppSwitch,
+    Notification,
+    MediaPlayPause,
+    MediaNext,
+    MediaPrevious,
+    Wakeup,
+    Sleep,
+    /// 其他按键码
+    Other(u32),
+}
+
+impl KeyCode {
+    /// 数值按键码
+    pub fn code(&self) -> u32 {
+        match self {
+            KeyCode::Home => 3,
+            KeyCode::Back => 4,
+            KeyCode::Menu => 82,
+            KeyCode::Search => 84,
+            KeyCode::Power => 26,
+            KeyCode::Camera => 27,
+            KeyCode::VolumeUp => 24,
+            KeyCode::VolumeDown => 25,
+            KeyCode::VolumeMute => 164,
+            KeyCode::Enter => 66,
+            KeyCode::Delete => 67,
+            KeyCode::ForwardDelete => 112,
+            KeyCode::Tab => 61,
+            KeyCode::Space => 62,
+            KeyCode::Escape => 111,
+            KeyCode::DpadUp => 19,
+            KeyCode::DpadDown => 20,
+            KeyCode::DpadLeft => 21,
+            KeyCode::DpadRight => 22,
+            KeyCode::DpadCenter => 23,
+            KeyCode::MoveHome => 122,
+            KeyCode::MoveEnd => 123,
+            KeyCode::PageUp => 92,
+            KeyCode::PageDown => 93,
+            KeyCode::AppSwitch => 187,
+            KeyCode::Notification => 83,
+            KeyCode::MediaPlayPause => 85,
+            KeyCode::MediaNext => 87,
+            KeyCode::MediaPrevious => 88,
+            KeyCode::Wakeup => 224,
+            KeyCode::Sleep => 223,
+            KeyCode::Other(code) => *code,
+        }
+    }
+}
+
+/// 转义 `input text` 的参数：空格替换为 `%s`，shell 特殊字符加反斜杠
+fn escape_input_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() * 2);
+    for c in text.chars() {
+        match c {
+            ' ' => escaped.push_str("%s"),
+            '\\' | '\'' | '"' | '`' | '$' | '&' | '|' | ';' | '<' | '>' | '(' | ')' | '*' | '?'
+            | '[' | ']' | '{' | '}' | '~' | '#' | '!' | '%' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 从 `getevent -lp` 输出中找出手柄输入节点
+fn find_gamepad_node(output: &str) -> Option<String> {
+    let mut current: Option<&str> = None;
+
+    for line in output.lines() {
+        if let Some(path) = line.strip_prefix("add device ").and_then(|l| l.split_once(": ")) {
+            current = Some(path.1.trim());
+            continue;
+        }
+        if line.contains("BTN_GAMEPAD") || line.contains("BTN_SOUTH") || line.contains("BTN_A ") {
+            if let Some(path) = current {
+                return Some(path.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+impl ADB {
+    /// 查找设备上的手柄输入节点（如 `/dev/input/event5`），结果会被缓存
+    pub fn find_gamepad_device(&self, device_id: &str) -> ADBResult<String> {
+        if let Some(path) = self.cache.get::<String>(device_id, "gamepad_device") {
+            return Ok(path);
+        }
+
+        let output = self.shell(device_id, "getevent -lp")?;
+        let path = find_gamepad_node(&output).ok_or_else(|| {
+            ADBError::DeviceError(format!("设备 {} 上没有找到手柄输入设备", device_id))
+        })?;
+
+        debug!("设备 {} 的手柄输入节点: {}", device_id, path);
+        self.cache.insert(device_id, "gamepad_device", path.clone(), None);
+
+        Ok(path)
+    }
+
+    /// 向设备上连接的 HID 手柄注入输入事件
+    ///
+    /// 通过 `sendevent` 写入输入节点，通常需要 root 权限
+    pub fn inject_gamepad(&self, device_id: &str, event: GamepadEvent) -> ADBResult<()> {
+        let input_device = self.find_gamepad_device(device_id)?;
+        self.inject_gamepad_on(device_id, &input_device, event)
+    }
+
+    /// 向指定输入节点注入手柄事件
+    pub fn inject_gamepad_on(
+        &self,
+        device_id: &str,
+        input_device: &str,
+        event: GamepadEvent,
+    ) -> ADBResult<()> {
+        let command = event
+            .to_raw_events()
+            .into_iter()
+            .map(|(ev_type, code, value)| {
+                format!("sendevent {} {} {} {}", input_device, ev_type, code, value)
+            })
+            .collect::<Vec<_>>()
+            .join(" && ");
+
+        let output = self.shell(device_id, &command)?;
+        if output.contains("Permission denied") || output.contains("could not open") {
+            return Err(ADBError::PermissionDenied(format!(
+                "无法写入输入设备 {}: {}",
+                input_device,
+                output.trim()
+            )));
+        }
+
+        debug!("已向设备 {} 注入手柄事件 {:?}", device_id, event);
+        Ok(())
+    }
+
+    /// 通过模拟器控制台设置传感器读数
+    ///
+    /// 仅支持模拟器（设备 ID 形如 `emulator-5554`）
+    pub fn inject_sensor(&self, emulator_id: &str, reading: &SensorReading) -> ADBResult<()> {
+        if !emulator_id.starts_with("emulator-") {
+            return Err(ADBError::DeviceError(format!(
+                "传感器注入仅支持模拟器: {}",
+                emulator_id
+            )));
+        }
+
+        let value = reading.console_value();
+        let output = self.run_command(&[
+            "-s",
+            emulator_id,
+            "emu",
+            "sensor",
+            "set",
+            reading.sensor.as_str(),
+            &value,
+        ])?;
+
+        if output.contains("KO") {
+            return Err(ADBError::CommandError(format!(
+                "设置传感器 {} 失败: {}",
+                reading.sensor.as_str(),
+                output.trim()
+            )));
+        }
+
+        debug!("已设置模拟器 {} 的传感器 {} = {}", emulator_id, reading.sensor.as_str(), value);
+        Ok(())
+    }
+
+    /// 点击屏幕坐标
+    pub fn tap(&self, device_id: &str, x: i32, y: i32) -> ADBResult<()> {
+        self.shell(device_id, &format!("input tap {} {}", x, y))?;
+        debug!("设备 {} 点击 ({}, {})", device_id, x, y);
+        Ok(())
+    }
+
+    /// 从 (x1, y1) 滑动到 (x2, y2)
+    pub fn swipe(
+        &self,
+        device_id: &str,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        duration_ms: u32,
+    ) -> ADBResult<()> {
+        self.shell(
+            device_id,
+            &format!("input swipe {} {} {} {} {}", x1, y1, x2, y2, duration_ms),
+        )?;
+        Ok(())
+    }
+
+    /// 长按屏幕坐标（原地滑动实现）
+    pub fn long_press(&self, device_id: &str, x: i32, y: i32, duration_ms: u32) -> ADBResult<()> {
+        self.swipe(device_id, x, y, x, y, duration_ms)
+    }
+
+    /// 输入文本，自动转义空格和 shell 特殊字符
+    ///
+    /// `input text` 只支持 ASCII 字符，包含其他字符时返回错误
+    pub fn input_text(&self, device_id: &str, text: &str) -> ADBResult<()> {
+        if !text.is_ascii() {
+            return Err(ADBError::CommandError(format!(
+                "input text 不支持非 ASCII 字符: {}",
+                text
+            )));
+        }
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        self.shell(device_id, &format!("input text {}", escape_input_text(text)))?;
+        Ok(())
+    }
+
+    /// 发送按键事件
+    pub fn key_event(&self, device_id: &str, key: KeyCode) -> ADBResult<()> {
+        self.shell(device_id, &format!("input keyevent {}", key.code()))?;
+        debug!("设备 {} 按键 {:?}", device_id, key);
+        Ok(())
+    }
+
+    /// 长按按键
+    pub fn long_key_event(&self, device_id: &str, key: KeyCode) -> ADBResult<()> {
+        self.shell(device_id, &format!("input keyevent --longpress {}", key.code()))?;
+        Ok(())
+    }
+
+    /// 按自然方向（竖屏）坐标点击，自动换算为当前旋转下的坐标
+    ///
+    /// 为竖屏编写的坐标在设备旋转后仍然点击同一个物理位置
+    pub fn tap_natural(&self, device_id: &str, x: i32, y: i32) -> ADBResult<()> {
+        let geometry = self.screen_geometry(device_id)?;
+        let (dx, dy) = geometry.to_display(x, y);
+        self.tap(device_id, dx, dy)
+    }
+
+    /// 按自然方向（竖屏）坐标滑动，自动换算为当前旋转下的坐标
+    pub fn swipe_natural(
+        &self,
+        device_id: &str,
+        from: (i32, i32),
+        to: (i32, i32),
+        duration_ms: u32,
+    ) -> ADBResult<()> {
+        let geometry = self.screen_geometry(device_id)?;
+        let (x1, y1) = geometry.to_display(from.0, from.1);
+        let (x2, y2) = geometry.to_display(to.0, to.1);
+        self.swipe(device_id, x1, y1, x2, y2, duration_ms)
+    }
+}
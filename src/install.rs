@@ -0,0 +1,926 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::ui::{DialogRule, Selector};
+use log::{debug, info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 支持增量安装的最低 SDK 版本 (Android 11)
+const INCREMENTAL_MIN_SDK: u32 = 30;
+
+/// 缓存中记录设备标准安装速率（字节/秒）的键
+const STANDARD_INSTALL_RATE_KEY: &str = "standard_install_rate";
+
+/// 安装方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallMethod {
+    /// 标准安装（完整传输后安装）
+    Standard,
+    /// 增量安装（按需流式传输）
+    Incremental,
+}
+
+/// 安装结果
+#[derive(Debug, Clone)]
+pub struct InstallResult {
+    pub apk_path: String,
+    /// 实际使用的安装方式
+    pub method: InstallMethod,
+    /// APK 大小（字节）
+    pub apk_size: u64,
+    /// 安装耗时
+    pub duration: Duration,
+    /// 是否从增量安装回退到了标准安装
+    pub fell_back: bool,
+    /// 回退原因
+    pub fallback_reason: Option<String>,
+    /// 增量安装相对标准安装的加速比，按该设备上次标准安装的实测速率推算，
+    /// 仅增量安装且有实测数据时提供
+    pub expected_speedup: Option<f64>,
+}
+
+/// 安装位置 (--install-location)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallLocation {
+    /// 由系统决定
+    Auto,
+    /// 内部存储
+    Internal,
+    /// 外部存储（优先）
+    External,
+}
+
+impl InstallLocation {
+    fn code(&self) -> u32 {
+        match self {
+            InstallLocation::Auto => 0,
+            InstallLocation::Internal => 1,
+            InstallLocation::External => 2,
+        }
+    }
+}
+
+/// 安装过程中出现屏幕确认对话框时的处理方式
+///
+/// MIUI、ColorOS 等系统通过 adb 安装时会弹出确认对话框，`adb install` 会一直等待
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InstallConfirmation {
+    /// 不检测，与直接执行 `adb install` 相同
+    #[default]
+    Ignore,
+    /// 通过 UI 自动化点击确认按钮
+    AutoAccept,
+    /// 立即终止安装并返回 [`ADBError::NeedsOnDeviceConfirmation`]（附截图）
+    FailFast,
+}
+
+/// 安装确认对话框的检查间隔
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 各厂商的安装确认对话框
+fn install_confirmation_rules() -> Vec<DialogRule> {
+    let accept_buttons = |rule: DialogRule| {
+        rule.button(Selector::text("继续安装"))
+            .button(Selector::text("Continue installation"))
+            .button(Selector::text("安装"))
+            .button(Selector::text("Install"))
+            .button(Selector::text("允许"))
+            .button(Selector::text("Allow"))
+    };
+
+    vec![
+        accept_buttons(
+            DialogRule::new("miui").detect(Selector::default().with_package("com.miui.securitycenter")),
+        ),
+        accept_buttons(
+            DialogRule::new("coloros").detect(Selector::default().with_package("com.coloros.safecenter")),
+        ),
+        accept_buttons(
+            DialogRule::new("vivo").detect(Selector::default().with_package("com.vivo.secime.service")),
+        ),
+        accept_buttons(
+            DialogRule::new("packageinstaller")
+                .detect(Selector::default().with_package("com.android.packageinstaller")),
+        )
+        .button(Selector::resource_id("com.android.packageinstaller:id/ok_button")),
+        accept_buttons(
+            DialogRule::new("google_packageinstaller")
+                .detect(Selector::default().with_package("com.google.android.packageinstaller")),
+        )
+        .button(Selector::resource_id("android:id/button1")),
+    ]
+}
+
+/// 检查 `adb install` 输出中的失败信息
+fn check_install_output(success: bool, stdout: String, stderr: &str) -> ADBResult<String> {
+    if !success || stdout.contains("Failure") || stderr.contains("Failure") {
+        let error_msg = if stdout.contains("Failure") {
+            format!("APK 安装失败: {}", stdout)
+        } else if !stderr.is_empty() {
+            format!("APK 安装失败: {}", stderr)
+        } else {
+            "APK 安装失败，未知错误".to_string()
+        };
+
+        return Err(ADBError::CommandError(error_msg));
+    }
+
+    Ok(stdout)
+}
+
+/// 安装选项
+#[derive(Debug, Clone)]
+pub struct InstallOptions {
+    /// 覆盖已安装的应用 (-r)
+    pub replace: bool,
+    /// 启用回滚支持 (--enable-rollback)
+    pub enable_rollback: bool,
+    /// 授予清单中的全部运行时权限 (-g)
+    pub grant_permissions: bool,
+    /// 允许降级安装 (-d)
+    pub allow_downgrade: bool,
+    /// 允许安装测试 APK (-t)
+    pub allow_test: bool,
+    /// 安装到指定用户 (--user)
+    pub user: Option<String>,
+    /// 指定 ABI (--abi)
+    pub abi: Option<String>,
+    /// 安装位置 (--install-location)
+    pub install_location: Option<InstallLocation>,
+    /// 作为免安装应用安装 (--instant)
+    pub instant: bool,
+    /// 流式安装 (--streaming) 或传统安装 (--no-streaming)，None 时由 adb 决定
+    pub streaming: Option<bool>,
+    /// 屏幕确认对话框的处理方式（不对应 `adb install` 参数）
+    pub confirmation: InstallConfirmation,
+}
+
+impl Default for InstallOptions {
+    fn default() -> Self {
+        InstallOptions {
+            replace: true,
+            enable_rollback: false,
+            grant_permissions: false,
+            allow_downgrade: false,
+            allow_test: false,
+            user: None,
+            abi: None,
+            install_location: None,
+            instant: false,
+            streaming: None,
+            confirmation: InstallConfirmation::Ignore,
+        }
+    }
+}
+
+impl InstallOptions {
+    /// 创建默认安装选项
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置是否覆盖已安装的应用
+    pub fn replace(mut self, replace: bool) -> Self {
+        self.replace = replace;
+        self
+    }
+
+    /// 设置是否启用回滚支持
+    pub fn enable_rollback(mut self, enable: bool) -> Self {
+        self.enable_rollback = enable;
+        self
+    }
+
+    /// 设置是否授予全部运行时权限
+    pub fn grant_permissions(mut self, grant: bool) -> Self {
+        self.grant_permissions = grant;
+        self
+    }
+
+    /// 设置是否允许降级
+    pub fn allow_downgrade(mut self, allow: bool) -> Self {
+        self.allow_downgrade = allow;
+        self
+    }
+
+    /// 设置是否允许测试 APK
+    pub fn allow_test(mut self, allow: bool) -> Self {
+        self.allow_test = allow;
+        self
+    }
+
+    /// 安装到指定用户（用户 ID 或 `all`、`current`）
+    pub fn user(mut self, user: &str) -> Self {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    /// 指定 ABI
+    pub fn abi(mut self, abi: &str) -> Self {
+        self.abi = Some(abi.to_string());
+        self
+    }
+
+    /// 设置安装位置
+    pub fn install_location(mut self, location: InstallLocation) -> Self {
+        self.install_location = Some(location);
+        self
+    }
+
+    /// 设置是否作为免安装应用安装
+    pub fn instant(mut self, instant: bool) -> Self {
+        self.instant = instant;
+        self
+    }
+
+    /// 设置流式安装或传统安装
+    pub fn streaming(mut self, streaming: bool) -> Self {
+        self.streaming = Some(streaming);
+        self
+    }
+
+    /// 设置屏幕确认对话框的处理方式
+    pub fn confirmation(mut self, confirmation: InstallConfirmation) -> Self {
+        self.confirmation = confirmation;
+        self
+    }
+
+    /// 转换为 `adb install` 参数
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if self.replace {
+            args.push("-r".to_string());
+        }
+        if self.enable_rollback {
+            args.push("--enable-rollback".to_string());
+        }
+        if self.grant_permissions {
+            args.push("-g".to_string());
+        }
+        if self.allow_downgrade {
+            args.push("-d".to_string());
+        }
+        if self.allow_test {
+            args.push("-t".to_string());
+        }
+        if let Some(user) = &self.user {
+            args.push("--user".to_string());
+            args.push(user.clone());
+        }
+        if let Some(abi) = &self.abi {
+            args.push("--abi".to_string());
+            args.push(abi.clone());
+        }
+        if let Some(location) = self.install_location {
+            args.push("--install-location".to_string());
+            args.push(location.code().to_string());
+        }
+        if self.instant {
+            args.push("--instant".to_string());
+        }
+        match self.streaming {
+            Some(true) => args.push("--streaming".to_string()),
+            Some(false) => args.push("--no-streaming".to_string()),
+            None => {}
+        }
+
+        args
+    }
+}
+
+/// 可回滚的包版本变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollbackPackage {
+    pub package_name: String,
+    /// 当前版本
+    pub from_version: i64,
+    /// 回滚后的版本
+    pub to_version: i64,
+}
+
+/// 回滚信息（来自 `dumpsys rollback`）
+#[derive(Debug, Clone)]
+pub struct RollbackInfo {
+    pub rollback_id: u64,
+    /// 状态，如 "enabling"、"available"、"committed"
+    pub state: String,
+    pub packages: Vec<RollbackPackage>,
+}
+
+impl RollbackInfo {
+    /// 回滚是否可用
+    pub fn is_available(&self) -> bool {
+        self.state == "available"
+    }
+}
+
+/// 解析 `dumpsys rollback` 输出
+fn parse_rollbacks(output: &str) -> Vec<RollbackInfo> {
+    let mut rollbacks: Vec<RollbackInfo> = Vec::new();
+    let mut in_packages = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        // 新的回滚条目: "<id>:"
+        if let Some(id) = trimmed.strip_suffix(':').and_then(|s| s.parse::<u64>().ok()) {
+            rollbacks.push(RollbackInfo {
+                rollback_id: id,
+                state: String::new(),
+                packages: Vec::new(),
+            });
+            in_packages = false;
+            continue;
+        }
+
+        let current = match rollbacks.last_mut() {
+            Some(current) => current,
+            None => continue,
+        };
+
+        if let Some(state) = trimmed.strip_prefix("-state:") {
+            current.state = state.trim().to_string();
+            in_packages = false;
+        } else if trimmed.starts_with("-packages:") {
+            in_packages = true;
+        } else if trimmed.starts_with('-') {
+            in_packages = false;
+        } else if in_packages {
+            // "com.example.app 2 -> 1 [0]"
+            let parts: Vec<&str> = trimmed.split_whitespace().collect();
+            if parts.len() >= 4 && parts[2] == "->" {
+                if let (Ok(from), Ok(to)) = (parts[1].parse(), parts[3].parse()) {
+                    current.packages.push(RollbackPackage {
+                        package_name: parts[0].to_string(),
+                        from_version: from,
+                        to_version: to,
+                    });
+                }
+            }
+        }
+    }
+
+    rollbacks
+}
+
+/// 增量安装能力
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncrementalSupport {
+    pub sdk_int: u32,
+    /// 设备是否声明了 android.software.incremental_delivery 特性
+    pub feature_available: bool,
+    /// 本地是否存在 v4 签名文件 (<apk>.idsig)
+    pub signature_available: bool,
+}
+
+impl IncrementalSupport {
+    /// 是否可以进行增量安装
+    pub fn is_supported(&self) -> bool {
+        self.sdk_int >= INCREMENTAL_MIN_SDK && self.feature_available && self.signature_available
+    }
+
+    /// 不支持的原因
+    pub fn unsupported_reason(&self) -> Option<String> {
+        if self.sdk_int < INCREMENTAL_MIN_SDK {
+            Some(format!("设备 SDK {} 低于 {}", self.sdk_int, INCREMENTAL_MIN_SDK))
+        } else if !self.feature_available {
+            Some("设备不支持 incremental_delivery 特性".to_string())
+        } else if !self.signature_available {
+            Some("缺少 APK v4 签名文件 (.idsig)".to_string())
+        } else {
+            None
+        }
+    }
+}
+
+impl ADB {
+    /// 执行 `adb install` 并检查输出中的失败信息
+    pub(crate) fn run_adb_install(&self, device_id: &str, args: &[&str]) -> ADBResult<String> {
+        self.with_retry(|| {
+            let mut cmd = self.adb_command();
+            if !device_id.is_empty() {
+                cmd.arg("-s").arg(device_id);
+            }
+
+            let output = cmd
+                .arg("install")
+                .args(args)
+                .output()
+                .map_err(|e| ADBError::CommandError(format!("无法安装 APK: {}", e)))?;
+
+            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            check_install_output(output.status.success(), stdout, &stderr)
+        })
+    }
+
+    /// 执行 `adb install`，同时检测并处理屏幕上的安装确认对话框
+    fn run_adb_install_confirming(
+        &self,
+        device_id: &str,
+        args: &[&str],
+        confirmation: InstallConfirmation,
+    ) -> ADBResult<String> {
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+        let mut child = cmd
+            .arg("install")
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ADBError::CommandError(format!("无法安装 APK: {}", e)))?;
+
+        let rules = install_confirmation_rules();
+        while child.try_wait()?.is_none() {
+            let button = match self.dump_ui_hierarchy(device_id) {
+                Ok(root) => rules
+                    .iter()
+                    .find_map(|rule| rule.find_button(&root).map(|b| (rule.name.clone(), b.clone()))),
+                Err(e) => {
+                    debug!("检查安装确认对话框失败: {}", e);
+                    None
+                }
+            };
+
+            if let Some((rule, button)) = button {
+                if confirmation == InstallConfirmation::FailFast {
+                    let screenshot = self.save_confirmation_screenshot(device_id);
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(ADBError::NeedsOnDeviceConfirmation {
+                        message: format!("安装被 {} 确认对话框拦截", rule),
+                        screenshot,
+                    });
+                }
+
+                info!("设备 {} 自动确认安装对话框 ({}): {}", device_id, rule, button.text);
+                if let Err(e) = self.tap_element(device_id, &button) {
+                    warn!("点击安装确认按钮失败: {}", e);
+                }
+            }
+
+            thread::sleep(CONFIRMATION_POLL_INTERVAL);
+        }
+
+        let output = child.wait_with_output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        check_install_output(output.status.success(), stdout, &stderr)
+    }
+
+    /// 保存确认对话框截图到系统临时目录，失败时返回 None
+    fn save_confirmation_screenshot(&self, device_id: &str) -> Option<PathBuf> {
+        let name: String = device_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = std::env::temp_dir().join(format!(
+            "adbkit_install_confirm_{}_{}.png",
+            name,
+            chrono::Local::now().format("%Y%m%d%H%M%S")
+        ));
+
+        match self.take_screenshot_bytes(device_id).and_then(|png| Ok(fs::write(&path, png)?)) {
+            Ok(()) => Some(path),
+            Err(e) => {
+                warn!("保存安装确认截图失败: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 使用指定选项安装应用
+    pub fn install_app_with_options(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+        options: &InstallOptions,
+    ) -> ADBResult<()> {
+        let mut args = options.to_args();
+        args.push(apk_path.to_string());
+
+        let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+        match options.confirmation {
+            InstallConfirmation::Ignore => self.run_adb_install(device_id, &args)?,
+            confirmation => self.run_adb_install_confirming(device_id, &args, confirmation)?,
+        };
+
+        // 包列表、包信息等缓存已失效
+        self.invalidate_cache(device_id);
+        debug!("成功安装 APK: {} ({:?})", apk_path, options);
+        Ok(())
+    }
+
+    /// 列出设备上的回滚记录
+    pub fn list_rollbacks(&self, device_id: &str) -> ADBResult<Vec<RollbackInfo>> {
+        let output = self.shell(device_id, "dumpsys rollback")?;
+        Ok(parse_rollbacks(&output))
+    }
+
+    /// 检查包是否有可用的回滚
+    pub fn is_rollback_available(&self, device_id: &str, package_name: &str) -> ADBResult<bool> {
+        let rollbacks = self.list_rollbacks(device_id)?;
+        Ok(rollbacks.iter().any(|r| {
+            r.is_available() && r.packages.iter().any(|p| p.package_name == package_name)
+        }))
+    }
+
+    /// 回滚应用到上一个版本 (`pm rollback-app`)
+    ///
+    /// 应用必须使用 `InstallOptions::enable_rollback` 安装过新版本
+    pub fn rollback(&self, device_id: &str, package_name: &str) -> ADBResult<()> {
+        if !self.is_rollback_available(device_id, package_name)? {
+            return Err(ADBError::AppNotFound(format!(
+                "包 {} 没有可用的回滚",
+                package_name
+            )));
+        }
+
+        let output = self.shell(device_id, &format!("pm rollback-app {}", package_name))?;
+        if !output.contains("Success") {
+            return Err(ADBError::CommandError(format!(
+                "回滚 {} 失败: {}",
+                package_name,
+                output.trim()
+            )));
+        }
+
+        info!("已回滚应用 {}", package_name);
+        Ok(())
+    }
+
+    /// 检测设备和 APK 是否支持增量安装
+    pub fn incremental_install_support(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+    ) -> ADBResult<IncrementalSupport> {
+        let profile = self.device_profile(device_id)?;
+
+        let feature_available = profile.sdk_int >= INCREMENTAL_MIN_SDK
+            && self
+                .shell(device_id, "pm list features")?
+                .lines()
+                .any(|l| l.trim() == "feature:android.software.incremental_delivery");
+
+        let signature_available = Path::new(&format!("{}.idsig", apk_path)).exists();
+
+        Ok(IncrementalSupport {
+            sdk_int: profile.sdk_int,
+            feature_available,
+            signature_available,
+        })
+    }
+
+    /// 增量安装应用（`adb install --incremental`）
+    ///
+    /// 设备或 APK 不支持增量安装、或增量安装失败时，自动回退到标准安装。
+    pub fn install_app_incremental(
+        &self,
+        device_id: &str,
+        apk_path: &str,
+    ) -> ADBResult<InstallResult> {
+        let apk_size = fs::metadata(apk_path)
+            .map_err(|e| ADBError::FileError(format!("无法读取 APK {}: {}", apk_path, e)))?
+            .len();
+
+        let support = self.incremental_install_support(device_id, apk_path)?;
+
+        let fallback_reason = if let Some(reason) = support.unsupported_reason() {
+            Some(reason)
+        } else {
+            let start = Instant::now();
+            match self.run_adb_install(device_id, &["-r", "--incremental", apk_path]) {
+                Ok(_) => {
+                    let duration = start.elapsed();
+                    info!("增量安装 {} 完成，耗时 {:?}", apk_path, duration);
+                    let expected_speedup = self
+                        .cache
+                        .get::<f64>(device_id, STANDARD_INSTALL_RATE_KEY)
+                        .filter(|_| !duration.is_zero())
+                        .map(|rate| apk_size as f64 / rate / duration.as_secs_f64());
+                    return Ok(InstallResult {
+                        apk_path: apk_path.to_string(),
+                        method: InstallMethod::Incremental,
+                        apk_size,
+                        duration,
+                        fell_back: false,
+                        fallback_reason: None,
+                        expected_speedup,
+                    });
+                }
+                Err(e) => Some(format!("增量安装失败: {}", e)),
+            }
+        };
+
+        if let Some(reason) = &fallback_reason {
+            warn!("{}，回退到标准安装", reason);
+        }
+
+        let start = Instant::now();
+        self.run_adb_install(device_id, &["-r", apk_path])?;
+        let duration = start.elapsed();
+        if !duration.is_zero() {
+            let rate = apk_size as f64 / duration.as_secs_f64();
+            self.cache.insert(device_id, STANDARD_INSTALL_RATE_KEY, rate, None);
+        }
+
+        debug!("标准安装 {} 完成，耗时 {:?}", apk_path, duration);
+        Ok(InstallResult {
+            apk_path: apk_path.to_string(),
+            method: InstallMethod::Standard,
+            apk_size,
+            duration,
+            fell_back: true,
+            fallback_reason,
+            expected_speedup: None,
+        })
+    }
+
+    /// 使用设备上已存在的 APK 执行 `pm install-incremental`
+    pub fn install_incremental_on_device(
+        &self,
+        device_id: &str,
+        device_apk_path: &str,
+    ) -> ADBResult<()> {
+        let output = self.shell(
+            device_id,
+            &format!("pm install-incremental -r {}", device_apk_path),
+        )?;
+
+        if !output.contains("Success") {
+            return Err(ADBError::CommandError(format!(
+                "pm install-incremental 失败: {}",
+                output.trim()
+            )));
+        }
+
+        debug!("成功通过 pm install-incremental 安装 {}", device_apk_path);
+        Ok(())
+    }
+}
+
+/// 从 `pm install-create` 输出中解析会话 ID
+fn parse_session_id(output: &str) -> ADBResult<u32> {
+    let start = output.find('[');
+    let end = output.find(']');
+
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => output[start + 1..end]
+            .trim()
+            .parse::<u32>()
+            .map_err(|e| ADBError::ParseError(format!("无法解析安装会话 ID: {}", e))),
+        _ => Err(ADBError::CommandError(format!(
+            "创建安装会话失败: {}",
+            output.trim()
+        ))),
+    }
+}
+
+/// 会话中的单个包
+#[derive(Debug, Clone)]
+struct SessionPackage {
+    files: Vec<String>,
+    apex: bool,
+}
+
+/// 多包安装会话构建器
+pub struct InstallSessionBuilder<'a> {
+    adb: &'a ADB,
+    device_id: String,
+    packages: Vec<SessionPackage>,
+    staged: bool,
+    extra_args: Vec<String>,
+}
+
+impl<'a> InstallSessionBuilder<'a> {
+    fn new(adb: &'a ADB, device_id: &str) -> Self {
+        Self {
+            adb,
+            device_id: device_id.to_string(),
+            packages: Vec::new(),
+            staged: false,
+            extra_args: Vec::new(),
+        }
+    }
+
+    /// 添加一个包（基础 APK 及其拆分 APK）
+    pub fn add_package(mut self, apk_paths: &[&str]) -> Self {
+        self.packages.push(SessionPackage {
+            files: apk_paths.iter().map(|p| p.to_string()).collect(),
+            apex: false,
+        });
+        self
+    }
+
+    /// 添加一个 APEX 模块（会使会话变为分阶段安装）
+    pub fn add_apex(mut self, apex_path: &str) -> Self {
+        self.packages.push(SessionPackage {
+            files: vec![apex_path.to_string()],
+            apex: true,
+        });
+        self.staged = true;
+        self
+    }
+
+    /// 设置为分阶段安装（重启后生效）
+    pub fn staged(mut self, staged: bool) -> Self {
+        self.staged = staged;
+        self
+    }
+
+    /// 添加传给子会话 `pm install-create` 的额外参数
+    pub fn add_arg(mut self, arg: &str) -> Self {
+        self.extra_args.push(arg.to_string());
+        self
+    }
+
+    /// 创建会话并写入所有包，尚未提交
+    pub fn create(self) -> ADBResult<InstallSession> {
+        if self.packages.is_empty() {
+            return Err(ADBError::ConfigError("安装会话中没有任何包".to_string()));
+        }
+
+        let staged_flag = if self.staged { " --staged" } else { "" };
+        let output = self.adb.shell(
+            &self.device_id,
+            &format!("pm install-create --multi-package{}", staged_flag),
+        )?;
+        let parent_id = parse_session_id(&output)?;
+        debug!("创建父安装会话 {}", parent_id);
+
+        let mut session = InstallSession {
+            adb: self.adb.clone(),
+            device_id: self.device_id.clone(),
+            parent_id,
+            child_ids: Vec::new(),
+            resources: self.adb.create_resource_manager(&self.device_id),
+            finished: false,
+        };
+
+        for package in &self.packages {
+            session.add_child(package, staged_flag, &self.extra_args)?;
+        }
+
+        Ok(session)
+    }
+
+    /// 创建会话并原子提交，失败时放弃整个会话
+    pub fn commit(self) -> ADBResult<u32> {
+        let session = self.create()?;
+        session.commit()
+    }
+}
+
+/// 多包安装会话
+///
+/// 未提交的会话在超出作用域时自动放弃，设备上的临时文件随之清理。
+pub struct InstallSession {
+    adb: ADB,
+    device_id: String,
+    parent_id: u32,
+    child_ids: Vec<u32>,
+    resources: crate::resource::ResourceManager,
+    finished: bool,
+}
+
+impl InstallSession {
+    /// 父会话 ID
+    pub fn id(&self) -> u32 {
+        self.parent_id
+    }
+
+    /// 子会话 ID 列表
+    pub fn child_ids(&self) -> &[u32] {
+        &self.child_ids
+    }
+
+    /// 创建子会话并写入文件
+    fn add_child(
+        &mut self,
+        package: &SessionPackage,
+        staged_flag: &str,
+        extra_args: &[String],
+    ) -> ADBResult<()> {
+        let apex_flag = if package.apex { " --apex" } else { "" };
+        let extra = extra_args
+            .iter()
+            .map(|a| format!(" {}", a))
+            .collect::<String>();
+
+        let output = self.adb.shell(
+            &self.device_id,
+            &format!("pm install-create{}{}{}", staged_flag, apex_flag, extra),
+        )?;
+        let child_id = parse_session_id(&output)?;
+        self.child_ids.push(child_id);
+
+        for (index, file) in package.files.iter().enumerate() {
+            let size = fs::metadata(file)
+                .map_err(|e| ADBError::FileError(format!("无法读取安装文件 {}: {}", file, e)))?
+                .len();
+
+            let file_name = Path::new(file)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| format!("file{}", index));
+            let device_path = format!("/data/local/tmp/adbkit_session_{}_{}", child_id, file_name);
+
+            self.resources.track_temp_file(&device_path);
+            self.adb.push(&self.device_id, file, &device_path, None)?;
+
+            let output = self.adb.shell(
+                &self.device_id,
+                &format!(
+                    "pm install-write -S {} {} {}_{} {}",
+                    size, child_id, index, file_name, device_path
+                ),
+            )?;
+            if !output.contains("Success") {
+                return Err(ADBError::CommandError(format!(
+                    "写入安装会话 {} 失败: {}",
+                    child_id,
+                    output.trim()
+                )));
+            }
+        }
+
+        let output = self.adb.shell(
+            &self.device_id,
+            &format!("pm install-add-session {} {}", self.parent_id, child_id),
+        )?;
+        if output.contains("Failure") || output.contains("Error") {
+            return Err(ADBError::CommandError(format!(
+                "添加子会话 {} 失败: {}",
+                child_id,
+                output.trim()
+            )));
+        }
+
+        debug!("子会话 {} 已加入父会话 {}", child_id, self.parent_id);
+        Ok(())
+    }
+
+    /// 原子提交会话
+    pub fn commit(mut self) -> ADBResult<u32> {
+        let output = self
+            .adb
+            .shell(&self.device_id, &format!("pm install-commit {}", self.parent_id))?;
+
+        if !output.contains("Success") {
+            let _ = self.abandon_inner();
+            return Err(ADBError::CommandError(format!(
+                "提交安装会话 {} 失败: {}",
+                self.parent_id,
+                output.trim()
+            )));
+        }
+
+        self.finished = true;
+        info!("安装会话 {} 已提交", self.parent_id);
+        Ok(self.parent_id)
+    }
+
+    /// 放弃会话
+    pub fn abandon(mut self) -> ADBResult<()> {
+        self.abandon_inner()
+    }
+
+    fn abandon_inner(&mut self) -> ADBResult<()> {
+        self.finished = true;
+        // 尚未加入父会话的子会话不会随父会话一起放弃，需要逐个放弃
+        let command = self
+            .child_ids
+            .iter()
+            .chain(std::iter::once(&self.parent_id))
+            .map(|id| format!("pm install-abandon {}", id))
+            .collect::<Vec<_>>()
+            .join("; ");
+        self.adb.shell(&self.device_id, &command)?;
+        debug!("安装会话 {} 已放弃", self.parent_id);
+        Ok(())
+    }
+}
+
+impl Drop for InstallSession {
+    fn drop(&mut self) {
+        if !self.finished {
+            warn!("安装会话 {} 未提交，自动放弃", self.parent_id);
+            let _ = self.abandon_inner();
+        }
+    }
+}
+
+impl ADB {
+    /// 创建多包安装会话构建器
+    pub fn install_session(&self, device_id: &str) -> InstallSessionBuilder<'_> {
+        InstallSessionBuilder::new(self, device_id)
+    }
+}
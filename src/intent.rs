@@ -0,0 +1,244 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
+use log::debug;
+
+/// Intent 附加数据
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntentExtra {
+    String(String),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Bool(bool),
+    /// 值为 null 的字符串
+    Null,
+}
+
+impl IntentExtra {
+    /// 对应的 `am` 参数
+    fn flag(&self) -> &'static str {
+        match self {
+            IntentExtra::String(_) => "--es",
+            IntentExtra::Int(_) => "--ei",
+            IntentExtra::Long(_) => "--el",
+            IntentExtra::Float(_) => "--ef",
+            IntentExtra::Bool(_) => "--ez",
+            IntentExtra::Null => "--esn",
+        }
+    }
+
+    fn value(&self) -> Option<String> {
+        match self {
+            IntentExtra::String(v) => Some(v.clone()),
+            IntentExtra::Int(v) => Some(v.to_string()),
+            IntentExtra::Long(v) => Some(v.to_string()),
+            IntentExtra::Float(v) => Some(v.to_string()),
+            IntentExtra::Bool(v) => Some(v.to_string()),
+            IntentExtra::Null => None,
+        }
+    }
+}
+
+/// `am` 命令的 Intent 参数构建器
+///
+/// 所有值都会经过 shell 转义，可以安全地包含空格和特殊字符
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntentBuilder {
+    pub action: Option<String>,
+    pub data: Option<String>,
+    pub mime_type: Option<String>,
+    /// 组件名，格式为 `包名/类名`（类名可以以 `.` 开头）
+    pub component: Option<String>,
+    pub package: Option<String>,
+    pub categories: Vec<String>,
+    pub extras: Vec<(String, IntentExtra)>,
+    pub flags: u32,
+    /// 目标用户
+    pub user: Option<String>,
+}
+
+impl IntentBuilder {
+    pub const FLAG_ACTIVITY_NEW_TASK: u32 = 0x1000_0000;
+    pub const FLAG_ACTIVITY_CLEAR_TOP: u32 = 0x0400_0000;
+    pub const FLAG_ACTIVITY_SINGLE_TOP: u32 = 0x2000_0000;
+    pub const FLAG_ACTIVITY_CLEAR_TASK: u32 = 0x0000_8000;
+    pub const FLAG_ACTIVITY_NO_HISTORY: u32 = 0x4000_0000;
+    pub const FLAG_INCLUDE_STOPPED_PACKAGES: u32 = 0x0000_0020;
+    pub const FLAG_RECEIVER_FOREGROUND: u32 = 0x1000_0000;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 指定 action 的 Intent
+    pub fn with_action(action: &str) -> Self {
+        Self::new().action(action)
+    }
+
+    /// 指定组件的显式 Intent
+    pub fn for_component(package_name: &str, class_name: &str) -> Self {
+        Self::new().component(package_name, class_name)
+    }
+
+    pub fn action(mut self, action: &str) -> Self {
+        self.action = Some(action.to_string());
+        self
+    }
+
+    pub fn data(mut self, uri: &str) -> Self {
+        self.data = Some(uri.to_string());
+        self
+    }
+
+    pub fn mime_type(mut self, mime_type: &str) -> Self {
+        self.mime_type = Some(mime_type.to_string());
+        self
+    }
+
+    /// 设置组件，`class_name` 以 `.` 开头时相对于包名
+    pub fn component(mut self, package_name: &str, class_name: &str) -> Self {
+        self.component = Some(format!("{}/{}", package_name, class_name));
+        self
+    }
+
+    /// 限定目标包
+    pub fn package(mut self, package_name: &str) -> Self {
+        self.package = Some(package_name.to_string());
+        self
+    }
+
+    pub fn category(mut self, category: &str) -> Self {
+        self.categories.push(category.to_string());
+        self
+    }
+
+    pub fn extra(mut self, key: &str, value: IntentExtra) -> Self {
+        self.extras.push((key.to_string(), value));
+        self
+    }
+
+    pub fn extra_string(self, key: &str, value: &str) -> Self {
+        self.extra(key, IntentExtra::String(value.to_string()))
+    }
+
+    pub fn extra_int(self, key: &str, value: i32) -> Self {
+        self.extra(key, IntentExtra::Int(value))
+    }
+
+    pub fn extra_long(self, key: &str, value: i64) -> Self {
+        self.extra(key, IntentExtra::Long(value))
+    }
+
+    pub fn extra_bool(self, key: &str, value: bool) -> Self {
+        self.extra(key, IntentExtra::Bool(value))
+    }
+
+    /// 添加 Intent 标志位（可多次调用）
+    pub fn flag(mut self, flag: u32) -> Self {
+        self.flags |= flag;
+        self
+    }
+
+    pub fn user(mut self, user: &str) -> Self {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    /// 生成已转义的 `am` Intent 参数
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(user) = &self.user {
+            args.extend(["--user".to_string(), shell_quote(user)]);
+        }
+        if let Some(action) = &self.action {
+            args.extend(["-a".to_string(), shell_quote(action)]);
+        }
+        if let Some(data) = &self.data {
+            args.extend(["-d".to_string(), shell_quote(data)]);
+        }
+        if let Some(mime_type) = &self.mime_type {
+            args.extend(["-t".to_string(), shell_quote(mime_type)]);
+        }
+        for category in &self.categories {
+            args.extend(["-c".to_string(), shell_quote(category)]);
+        }
+        for (key, value) in &self.extras {
+            args.push(value.flag().to_string());
+            args.push(shell_quote(key));
+            if let Some(value) = value.value() {
+                args.push(shell_quote(&value));
+            }
+        }
+        if self.flags != 0 {
+            args.extend(["-f".to_string(), format!("0x{:08x}", self.flags)]);
+        }
+        if let Some(component) = &self.component {
+            args.extend(["-n".to_string(), shell_quote(component)]);
+        } else if let Some(package) = &self.package {
+            // 没有组件时，最后一个位置参数作为目标包名
+            args.push(shell_quote(package));
+        }
+
+        args
+    }
+}
+
+/// 检查 `am` 输出中的错误
+fn check_am_output(command: &str, output: &str) -> ADBResult<()> {
+    let error = output.lines().map(|l| l.trim()).find(|l| {
+        l.starts_with("Error") || l.contains("Exception") || l.starts_with("Security exception")
+    });
+
+    match error {
+        Some(line) => Err(ADBError::CommandError(format!("{} 失败: {}", command, line))),
+        None => Ok(()),
+    }
+}
+
+impl ADB {
+    /// 通过 Intent 启动 Activity
+    pub fn start_activity(&self, device_id: &str, intent: &IntentBuilder) -> ADBResult<String> {
+        self.run_am(device_id, "start", intent)
+    }
+
+    /// 通过 Intent 启动 Activity 并等待启动完成 (`am start -W`)
+    pub fn start_activity_and_wait(&self, device_id: &str, intent: &IntentBuilder) -> ADBResult<String> {
+        self.run_am(device_id, "start -W", intent)
+    }
+
+    /// 发送广播
+    pub fn send_broadcast(&self, device_id: &str, intent: &IntentBuilder) -> ADBResult<String> {
+        self.run_am(device_id, "broadcast", intent)
+    }
+
+    /// 启动服务
+    pub fn start_service(&self, device_id: &str, intent: &IntentBuilder) -> ADBResult<String> {
+        // Android 8.0 起命令名改为 start-service，旧名称仍保留但部分 ROM 已移除
+        let subcommand = if self.device_profile(device_id)?.sdk_int >= 26 {
+            "start-service"
+        } else {
+            "startservice"
+        };
+        self.run_am(device_id, subcommand, intent)
+    }
+
+    /// 启动前台服务；Android 8.0 以下没有前台服务限制，按普通服务启动
+    pub fn start_foreground_service(&self, device_id: &str, intent: &IntentBuilder) -> ADBResult<String> {
+        if self.device_profile(device_id)?.sdk_int >= 26 {
+            self.run_am(device_id, "start-foreground-service", intent)
+        } else {
+            self.run_am(device_id, "startservice", intent)
+        }
+    }
+
+    fn run_am(&self, device_id: &str, subcommand: &str, intent: &IntentBuilder) -> ADBResult<String> {
+        let command = format!("am {} {}", subcommand, intent.to_args().join(" "));
+        debug!("设备 {} 执行: {}", device_id, command);
+
+        let output = self.shell(device_id, &command)?;
+        check_am_output(&format!("am {}", subcommand), &output)?;
+        Ok(output)
+    }
+}
@@ -0,0 +1,355 @@
+use crate::device::{ADBDevice, ADB};
+use crate::error::{ADBError, ADBResult};
+use crate::utils::parse_properties;
+use log::{debug, warn};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// 缓存超时时间（60秒）
+const INVENTORY_CACHE_TIMEOUT: Duration = Duration::from_secs(60);
+
+// 设备信息批量查询中各段输出的分隔标记
+const DEVICE_INFO_MARKER: &str = "__ADBKIT_INFO__";
+
+/// CSV 表头
+const CSV_HEADER: &str = "serial,status,transport_id,manufacturer,model,android_version,sdk_level,security_patch,battery_level,storage_free_bytes,collected_at,error";
+
+/// 设备清单记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceInventoryRecord {
+    pub serial: String,
+    pub status: String,
+    pub transport_id: Option<String>,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub android_version: Option<String>,
+    pub sdk_level: Option<u32>,
+    pub security_patch: Option<String>,
+    pub battery_level: Option<u8>,
+    pub storage_free_bytes: Option<u64>,
+    /// 采集时间 (RFC 3339)
+    pub collected_at: String,
+    /// 采集过程中的错误（设备离线等）
+    pub error: Option<String>,
+}
+
+impl DeviceInventoryRecord {
+    /// 根据设备列表项创建基础记录
+    fn from_device(device: &ADBDevice) -> Self {
+        Self {
+            serial: device.id.clone(),
+            status: device.status.to_string(),
+            transport_id: device.transport_id.clone(),
+            manufacturer: None,
+            model: device.model.clone(),
+            android_version: None,
+            sdk_level: None,
+            security_patch: None,
+            battery_level: None,
+            storage_free_bytes: None,
+            collected_at: chrono::Local::now().to_rfc3339(),
+            error: None,
+        }
+    }
+
+    /// 转换为 CSV 行
+    fn to_csv_row(&self) -> String {
+        let fields = [
+            self.serial.clone(),
+            self.status.clone(),
+            self.transport_id.clone().unwrap_or_default(),
+            self.manufacturer.clone().unwrap_or_default(),
+            self.model.clone().unwrap_or_default(),
+            self.android_version.clone().unwrap_or_default(),
+            self.sdk_level.map(|v| v.to_string()).unwrap_or_default(),
+            self.security_patch.clone().unwrap_or_default(),
+            self.battery_level.map(|v| v.to_string()).unwrap_or_default(),
+            self.storage_free_bytes.map(|v| v.to_string()).unwrap_or_default(),
+            self.collected_at.clone(),
+            self.error.clone().unwrap_or_default(),
+        ];
+
+        fields
+            .iter()
+            .map(|f| escape_csv_field(f))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// 转义 CSV 字段
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// 将清单序列化为 JSON
+pub fn to_json(records: &[DeviceInventoryRecord]) -> ADBResult<String> {
+    serde_json::to_string_pretty(records)
+        .map_err(|e| ADBError::ParseError(format!("JSON 序列化失败: {}", e)))
+}
+
+/// 将清单序列化为 CSV
+pub fn to_csv(records: &[DeviceInventoryRecord]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+
+    for record in records {
+        csv.push_str(&record.to_csv_row());
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// 设备形态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DeviceClass {
+    Phone,
+    Tablet,
+    Watch,
+    Tv,
+    Automotive,
+}
+
+impl DeviceClass {
+    /// 默认超时的倍数，手表和车机的 shell 响应通常明显慢于手机
+    pub fn timeout_scale(&self) -> u32 {
+        match self {
+            DeviceClass::Watch => 3,
+            DeviceClass::Automotive => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// 根据 `ro.build.characteristics` 和 `pm list features` 输出判断设备形态
+fn parse_device_class(output: &str) -> DeviceClass {
+    let has_feature = |name: &str| {
+        output
+            .lines()
+            .any(|l| l.trim().strip_prefix("feature:") == Some(name))
+    };
+
+    if has_feature("android.hardware.type.watch") {
+        DeviceClass::Watch
+    } else if has_feature("android.hardware.type.automotive") {
+        DeviceClass::Automotive
+    } else if has_feature("android.software.leanback") || has_feature("android.hardware.type.television") {
+        DeviceClass::Tv
+    } else if output.lines().next().is_some_and(|l| l.split(',').any(|c| c.trim() == "tablet")) {
+        DeviceClass::Tablet
+    } else {
+        DeviceClass::Phone
+    }
+}
+
+/// 设备信息快照
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceInfo {
+    pub device_id: String,
+    pub manufacturer: String,
+    pub model: String,
+    pub brand: String,
+    pub android_version: String,
+    pub sdk_level: u32,
+    /// 支持的 ABI，首个为主 ABI
+    pub abis: Vec<String>,
+    pub fingerprint: String,
+    /// 安全补丁级别，如 "2024-05-01"
+    pub security_patch: String,
+    /// 硬件序列号 (ro.serialno)，无权限读取时为空
+    pub serial: String,
+    /// 屏幕分辨率（宽, 高），有覆盖尺寸时为覆盖值
+    pub screen_size: Option<(u32, u32)>,
+    /// 屏幕密度 (dpi)，有覆盖密度时为覆盖值
+    pub density: Option<u32>,
+    pub total_ram_bytes: Option<u64>,
+    /// /data 分区总容量
+    pub storage_total_bytes: Option<u64>,
+    /// /data 分区可用容量
+    pub storage_free_bytes: Option<u64>,
+}
+
+/// 解析批量查询的输出
+fn parse_device_info(device_id: &str, output: &str) -> DeviceInfo {
+    let mut sections = output.split(DEVICE_INFO_MARKER);
+    let props = parse_properties(sections.next().unwrap_or(""));
+    let display = sections.next().unwrap_or("");
+    let meminfo = sections.next().unwrap_or("");
+    let df = sections.next().unwrap_or("");
+
+    let prop = |name: &str| props.get(name).map(|v| v.trim().to_string()).unwrap_or_default();
+    let abilist = prop("ro.product.cpu.abilist");
+    let abis = if abilist.is_empty() {
+        vec![prop("ro.product.cpu.abi")]
+    } else {
+        abilist.split(',').map(|a| a.trim().to_string()).collect()
+    };
+
+    let (physical, overridden) = crate::settings::parse_wm_density(display);
+
+    // MemTotal:        5772340 kB
+    let total_ram_bytes = meminfo
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("MemTotal:"))
+        .and_then(|v| v.split_whitespace().next()?.parse::<u64>().ok())
+        .map(|kb| kb * 1024);
+
+    // Filesystem 1K-blocks Used Available Use% Mounted on
+    let df_fields: Vec<&str> = df
+        .lines()
+        .rev()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("")
+        .split_whitespace()
+        .collect();
+    let df_kb = |index: usize| {
+        df_fields
+            .get(index)
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    };
+
+    DeviceInfo {
+        device_id: device_id.to_string(),
+        manufacturer: prop("ro.product.manufacturer"),
+        model: prop("ro.product.model"),
+        brand: prop("ro.product.brand"),
+        android_version: prop("ro.build.version.release"),
+        sdk_level: prop("ro.build.version.sdk").parse().unwrap_or(0),
+        abis: abis.into_iter().filter(|a| !a.is_empty()).collect(),
+        fingerprint: prop("ro.build.fingerprint"),
+        security_patch: prop("ro.build.version.security_patch"),
+        serial: prop("ro.serialno"),
+        screen_size: crate::screen::parse_wm_size(display),
+        density: overridden.or(physical),
+        total_ram_bytes,
+        storage_total_bytes: df_kb(1),
+        storage_free_bytes: df_kb(3),
+    }
+}
+
+impl ADB {
+    /// 获取设备信息快照
+    ///
+    /// 系统属性、屏幕、内存和存储信息通过一次 shell 调用获取。
+    pub fn get_device_info(&self, device_id: &str) -> ADBResult<DeviceInfo> {
+        let output = self.shell(
+            device_id,
+            &format!(
+                "getprop; echo {m}; wm size; wm density; echo {m}; grep MemTotal /proc/meminfo; \
+                 echo {m}; df -k /data | tail -1; true",
+                m = DEVICE_INFO_MARKER
+            ),
+        )?;
+        if !output.contains(DEVICE_INFO_MARKER) {
+            return Err(ADBError::ParseError(format!(
+                "无法解析设备 {} 信息: {}",
+                device_id,
+                output.trim()
+            )));
+        }
+
+        let info = parse_device_info(device_id, &output);
+        debug!("设备 {} 信息: {:?}", device_id, info);
+        Ok(info)
+    }
+
+    /// 获取设备形态，结果会被缓存
+    pub fn device_class(&self, device_id: &str) -> ADBResult<DeviceClass> {
+        if let Some(class) = self.cache.get::<DeviceClass>(device_id, "device_class") {
+            return Ok(class);
+        }
+
+        let output = self.shell(device_id, "getprop ro.build.characteristics; pm list features")?;
+        let class = parse_device_class(&output);
+        debug!("设备 {} 形态: {:?}", device_id, class);
+
+        self.cache.insert(device_id, "device_class", class, None);
+        Ok(class)
+    }
+
+    /// 按设备形态放大后的默认操作超时，无法判断形态时使用配置的超时
+    pub fn device_timeout(&self, device_id: &str) -> Duration {
+        let scale = self
+            .device_class(device_id)
+            .map(|class| class.timeout_scale())
+            .unwrap_or(1);
+        Duration::from_millis(self.config.timeout * scale as u64)
+    }
+
+    /// 获取设备清单
+    ///
+    /// 合并设备列表与各设备的型号、系统版本、安全补丁、电量和可用存储信息。
+    /// 在线设备的信息会缓存 60 秒。
+    pub fn inventory(&self) -> ADBResult<Vec<DeviceInventoryRecord>> {
+        let devices = self.list_devices()?;
+
+        let records = devices
+            .par_iter()
+            .map(|device| self.inventory_record(device))
+            .collect();
+
+        Ok(records)
+    }
+
+    /// 清除设备清单缓存
+    pub fn clear_inventory_cache(&self) {
+        self.cache.invalidate_key("inventory");
+    }
+
+    /// 获取单个设备的清单记录（优先使用缓存）
+    fn inventory_record(&self, device: &ADBDevice) -> DeviceInventoryRecord {
+        if !device.is_online() {
+            let mut record = DeviceInventoryRecord::from_device(device);
+            record.error = Some(format!("设备状态为 {}", device.status));
+            return record;
+        }
+
+        // 检查缓存
+        if let Some(record) = self.cache.get::<DeviceInventoryRecord>(&device.id, "inventory") {
+            debug!("使用缓存的设备清单: {}", device.id);
+            return record;
+        }
+
+        match self.collect_inventory_record(device) {
+            Ok(record) => {
+                self.cache
+                    .insert(&device.id, "inventory", record.clone(), Some(INVENTORY_CACHE_TIMEOUT));
+                record
+            }
+            Err(e) => {
+                warn!("采集设备 {} 清单信息失败: {}", device.id, e);
+                let mut record = DeviceInventoryRecord::from_device(device);
+                record.error = Some(e.to_string());
+                record
+            }
+        }
+    }
+
+    /// 从设备采集清单记录：型号、系统和存储信息来自 [`ADB::get_device_info`]，电量来自 [`ADB::get_battery_info`]
+    fn collect_inventory_record(&self, device: &ADBDevice) -> ADBResult<DeviceInventoryRecord> {
+        let mut record = DeviceInventoryRecord::from_device(device);
+        let info = self.get_device_info(&device.id)?;
+        let non_empty = |value: String| Some(value).filter(|v| !v.is_empty());
+
+        record.manufacturer = non_empty(info.manufacturer);
+        record.model = non_empty(info.model).or(record.model);
+        record.android_version = non_empty(info.android_version);
+        record.sdk_level = Some(info.sdk_level).filter(|&sdk| sdk > 0);
+        record.security_patch = non_empty(info.security_patch);
+        record.storage_free_bytes = info.storage_free_bytes;
+
+        match self.get_battery_info(&device.id) {
+            Ok(battery) => record.battery_level = Some(battery.level),
+            Err(e) => debug!("无法获取设备 {} 电量: {}", device.id, e),
+        }
+
+        Ok(record)
+    }
+}
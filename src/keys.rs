@@ -0,0 +1,220 @@
+//! ADB 密钥管理
+//!
+//! 主机使用 `~/.android/adbkey`（私钥）和 `adbkey.pub`（公钥）与设备认证，设备把已授权
+//! 的公钥保存在 `/data/misc/adb/adb_keys`。设备机房可以在有 root 权限的设备上预先写入主机
+//! 公钥，省去在屏幕上确认「允许 USB 调试」。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
+use log::{debug, info};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 设备上保存已授权公钥的文件
+pub const AUTHORIZED_KEYS_PATH: &str = "/data/misc/adb/adb_keys";
+
+// 默认的私钥文件名
+const ADB_KEY_NAME: &str = "adbkey";
+
+/// 主机上的 ADB 密钥对
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdbKeyPair {
+    pub private_key: PathBuf,
+    pub public_key: PathBuf,
+}
+
+impl AdbKeyPair {
+    /// 根据私钥路径得到密钥对（公钥为同名 `.pub` 文件）
+    pub fn from_private_key(path: impl Into<PathBuf>) -> Self {
+        let private_key = path.into();
+        let mut public_key = private_key.clone().into_os_string();
+        public_key.push(".pub");
+        Self {
+            private_key,
+            public_key: PathBuf::from(public_key),
+        }
+    }
+
+    /// 两个文件是否都存在
+    pub fn exists(&self) -> bool {
+        self.private_key.is_file() && self.public_key.is_file()
+    }
+
+    /// 读取公钥（`<base64> <用户@主机>` 格式）
+    pub fn public_key_string(&self) -> ADBResult<String> {
+        fs::read_to_string(&self.public_key)
+            .map(|s| s.trim().to_string())
+            .map_err(|e| {
+                ADBError::FileError(format!("无法读取公钥 {}: {}", self.public_key.display(), e))
+            })
+    }
+
+    /// 公钥指纹，与设备上「允许 USB 调试」对话框中显示的一致
+    pub fn fingerprint(&self) -> ADBResult<String> {
+        key_fingerprint(&self.public_key_string()?)
+    }
+}
+
+/// 计算公钥指纹：base64 解码后的 MD5，以冒号分隔的大写十六进制表示
+pub fn key_fingerprint(public_key: &str) -> ADBResult<String> {
+    let encoded = public_key.split_whitespace().next().unwrap_or("");
+    let decoded = decode_base64(encoded)
+        .ok_or_else(|| ADBError::ParseError("公钥不是有效的 base64".to_string()))?;
+    let digest = md5::compute(decoded);
+    Ok(digest
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+/// 解码标准 base64（允许省略末尾的 `=`）
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in input.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    Some(output)
+}
+
+/// 主机上 adb 的配置目录，与 adb 的查找顺序一致：
+/// `ANDROID_USER_HOME`、`ANDROID_SDK_HOME/.android`、用户主目录下的 `.android`
+pub fn android_user_home() -> Option<PathBuf> {
+    let var = |name: &str| env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+
+    var("ANDROID_USER_HOME")
+        .or_else(|| var("ANDROID_SDK_HOME").map(|p| p.join(".android")))
+        .or_else(|| {
+            var("HOME")
+                .or_else(|| var("USERPROFILE"))
+                .map(|p| p.join(".android"))
+        })
+}
+
+impl ADB {
+    /// 默认的 ADB 密钥对位置（文件不一定存在）
+    pub fn default_adb_keys(&self) -> ADBResult<AdbKeyPair> {
+        let home = android_user_home()
+            .ok_or_else(|| ADBError::ConfigError("无法确定 adb 配置目录".to_string()))?;
+        Ok(AdbKeyPair::from_private_key(home.join(ADB_KEY_NAME)))
+    }
+
+    /// 使用 `adb keygen` 生成密钥对
+    pub fn generate_adb_keys(&self, private_key: &Path) -> ADBResult<AdbKeyPair> {
+        if let Some(parent) = private_key.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let output = self
+            .adb_command()
+            .arg("keygen")
+            .arg(private_key)
+            .output()
+            .map_err(|e| ADBError::CommandError(format!("无法执行 adb keygen: {}", e)))?;
+        let pair = AdbKeyPair::from_private_key(private_key);
+        if !output.status.success() || !pair.exists() {
+            return Err(ADBError::CommandError(format!(
+                "生成密钥失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        info!("已生成 ADB 密钥: {}", private_key.display());
+        Ok(pair)
+    }
+
+    /// 返回默认密钥对，不存在时生成
+    ///
+    /// 新生成的密钥需要重启 adb 服务器后才会被使用。
+    pub fn ensure_adb_keys(&self) -> ADBResult<AdbKeyPair> {
+        let pair = self.default_adb_keys()?;
+        if pair.exists() {
+            return Ok(pair);
+        }
+        self.generate_adb_keys(&pair.private_key)
+    }
+
+    /// 读取设备上已授权的公钥（需要 root）
+    pub fn authorized_keys(&self, device_id: &str) -> ADBResult<Vec<String>> {
+        let command = self
+            .root_command(device_id, &format!("cat {} 2>/dev/null; true", AUTHORIZED_KEYS_PATH))
+            .ok_or_else(|| {
+                ADBError::PermissionDenied("读取已授权公钥需要 root 权限".to_string())
+            })?;
+        let output = self.shell(device_id, &command)?;
+        Ok(output
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    /// 在设备上授权公钥（需要 root），公钥已存在时不重复写入
+    ///
+    /// 返回是否新增了公钥。之后使用对应私钥的主机连接时不再需要在屏幕上确认。
+    pub fn install_vendor_key(&self, device_id: &str, public_key: &str) -> ADBResult<bool> {
+        let public_key = public_key.trim();
+        let fingerprint = key_fingerprint(public_key)?;
+
+        let existing = self.authorized_keys(device_id)?;
+        let encoded = public_key.split_whitespace().next().unwrap_or("");
+        if existing
+            .iter()
+            .any(|k| k.split_whitespace().next() == Some(encoded))
+        {
+            debug!("设备 {} 已授权公钥 {}", device_id, fingerprint);
+            return Ok(false);
+        }
+
+        let command = format!(
+            "echo {} >> {} && chown system:shell {} && chmod 640 {} && (restorecon {} 2>/dev/null; true)",
+            shell_quote(public_key),
+            AUTHORIZED_KEYS_PATH,
+            AUTHORIZED_KEYS_PATH,
+            AUTHORIZED_KEYS_PATH,
+            AUTHORIZED_KEYS_PATH
+        );
+        let command = self.root_command(device_id, &command).ok_or_else(|| {
+            ADBError::PermissionDenied("写入已授权公钥需要 root 权限".to_string())
+        })?;
+        self.shell(device_id, &command)?;
+
+        info!("已在设备 {} 上授权公钥 {}", device_id, fingerprint);
+        Ok(true)
+    }
+
+    /// 清除设备上所有已授权的公钥（需要 root）
+    ///
+    /// 当前连接不受影响，adbd 重启或重新插拔后需要重新确认授权。
+    pub fn clear_authorized_keys(&self, device_id: &str) -> ADBResult<()> {
+        let command = self
+            .root_command(device_id, &format!("rm -f {}", AUTHORIZED_KEYS_PATH))
+            .ok_or_else(|| {
+                ADBError::PermissionDenied("清除已授权公钥需要 root 权限".to_string())
+            })?;
+        self.shell(device_id, &command)?;
+
+        info!("已清除设备 {} 上的已授权公钥", device_id);
+        Ok(())
+    }
+}
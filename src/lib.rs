@@ -5,24 +5,135 @@ mod cmd;
 
 // 功能模块
 pub mod app;
+pub mod auto;
+pub mod bugreport;
+pub mod cache;
+pub mod install;
+pub mod intent;
+pub mod compat;
+pub mod deploy;
+pub mod dumpsys;
 pub mod transfer;
+pub mod transport;
+pub mod trash;
+pub mod paths;
 pub mod remote;
 pub mod media;
+pub mod memory;
+pub mod input;
+pub mod screen;
+pub mod ui;
+pub mod settings;
+pub mod accessibility;
+pub mod logcat;
+pub mod events;
+pub mod monitor;
+pub mod monkey;
 pub mod forward;
+pub mod frames;
 pub mod resource;
+pub mod runner;
+pub mod scheduler;
+pub mod service;
+pub mod session;
+pub mod shell_tools;
+pub mod sink;
 pub mod parallel;
+pub mod power;
+pub mod process;
+pub mod profiling;
+pub mod quirks;
+pub mod bench;
+pub mod chaos;
+pub mod stress;
+pub mod test;
 pub mod utils;
+pub mod wait;
+pub mod wear;
+pub mod inventory;
+pub mod keys;
+pub mod registry;
+pub mod parsers;
+pub mod script;
+
+// 示例场景库函数（需要 examples_harness 特性）
+#[cfg(feature = "examples_harness")]
+pub mod examples;
+
+// 基于 tokio 的异步接口（需要 aio 特性）
+#[cfg(feature = "aio")]
+pub mod aio;
+
+// SSH 跳板机隧道（需要 ssh 特性）
+#[cfg(feature = "ssh")]
+pub mod tunnel;
 
 // 导出主要类型
 pub use config::{ADBConfig, ADBConfigBuilder};
-pub use device::{ADB, ADBDevice, DeviceStatus};
+pub use device::{ADB, ADBDevice, DeviceHandle, DeviceStatus};
+pub use deploy::{
+    DeployItem, DeployOutcome, DeployPlan, DeployReport, DeployStrategy, NativeLibMethod, NativeLibPush,
+};
+pub use dumpsys::{DiffEntry, DiffKind, DiffReport, ServiceSnapshot, Snapshot};
 pub use error::{ADBError, ADBResult};
-pub use app::PackageInfo;
-pub use transfer::TransferOptions;
+pub use app::{
+    GameMode, GameModeInfo, MemoryPressure, MemoryPressureLevel, PackageInfo, ProcessDeathReport, ScheduledJob,
+    Residue, ResidueKind, SnapshotId, SnapshotMethod, TrimMemoryLevel, UninstallReport,
+};
+pub use auto::{CarUser, DrivingState};
+pub use cache::CachedADB;
+pub use chaos::{ChaosAction, ChaosConfig, ChaosEvent, ChaosMonkey};
+pub use cmd::ShellOutput;
+pub use install::{InstallConfirmation, InstallLocation, InstallMethod, InstallOptions, InstallResult};
+pub use events::{DeviceEvent, EventRecord, EventStream};
+pub use frames::FrameStats;
+pub use input::KeyCode;
+pub use intent::{IntentBuilder, IntentExtra};
+pub use inventory::{DeviceClass, DeviceInfo, DeviceInventoryRecord};
+pub use keys::AdbKeyPair;
+pub use logcat::{
+    LogBuffer, LogEntry, LogFormat, LogPriority, LogSource, LogcatOptions, LogcatQuery, LogcatRecorder,
+    LogcatStream, MergedTimeline, TimelineEntry,
+};
+#[cfg(feature = "image")]
+pub use media::ScreenshotStamp;
+pub use memory::{HeapUsage, MemCategory, MemInfo, SystemMemInfo};
+pub use monitor::{
+    AnrEvent, AnrResponse, AnrWatcher, CrashEvent, CrashKind, CrashWatcher, DeviceChange, DeviceTracker, Heartbeat,
+    HeartbeatEvent, HeartbeatStatus, Sampler, StrictModeViolation, ViolationKind,
+};
+pub use monkey::{MonkeyOptions, MonkeyReport};
+pub use parallel::{
+    AuditRecord, DeviceTrigger, ParallelStream, Shard, ShardReport, ShardResult, ShardStrategy, SyncTriggerReport,
+    VulnerabilityRule,
+};
+pub use power::{AdvanceMode, BatteryHealth, BatteryInfo, BatteryPlugged, BatteryStatus, TimeTravel};
+pub use process::ProcessInfo;
+pub use profiling::{HeapDump, HeapDumpKind};
+pub use quirks::{Quirk, QuirkId};
+pub use remote::ReadyProfile;
+pub use runner::{CommandRunner, ProcessRunner, RecordedCommand, RecordingRunner, ReplayRunner};
+pub use scheduler::{Priority, SchedulerConfig};
+pub use screen::{DeviceState, DisplayHandle, FoldState, ScreenGeometry};
+pub use script::{ScriptInterpreter, ScriptOptions};
+pub use service::{ParcelReader, ParcelReply, Parcelable};
+pub use session::DeviceSession;
+pub use settings::Namespace;
+pub use shell_tools::{Tool, ToolStatus};
+pub use sink::{CallbackSink, ChannelSink, FileSink, GzipSink, RotatingFileSink, Sink};
+pub use test::{InstrumentationOptions, TestResult, TestRunReport, TestStatus};
+pub use transfer::{
+    ArchiveMode, FsInfo, FsKind, SyncOptions, SyncReport, TransferOptions, TransferStats,
+};
+pub use transport::{TransportKind, TransportProbe, TransportSelection};
+pub use ui::{DialogResponse, DialogRule, Rect, ResponderHandle, ScrollDirection, Selector, UiNode};
+pub use wait::Condition;
+pub use wear::WearButton;
 
 // 便利的预导出模块
 pub mod prelude {
-    pub use super::{ADB, ADBConfig, ADBConfigBuilder, ADBDevice, ADBError, ADBResult};
+    pub use super::{ADB, ADBConfig, ADBConfigBuilder, ADBDevice, ADBError, ADBResult, DeviceHandle};
     pub use super::app::PackageInfo;
     pub use super::transfer::TransferOptions;
+    pub use super::wait::Condition;
 }
\ No newline at end of file
@@ -0,0 +1,724 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::sink::Sink;
+use crate::utils::shell_quote;
+use log::{debug, info, trace, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io::{BufRead, BufReader, Lines};
+use std::process::{Child, ChildStdout, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// `-v epoch` 格式的日志行: "1589812345.123  1000  1234 I Tag: message"
+static EPOCH_LINE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*(\d+\.\d+)\s+(\d+)\s+(\d+)\s+([VDIWEFSA])\s+(.*?)\s*:(?:\s(.*)|$)").unwrap()
+});
+
+// `-v threadtime` 或 `-v epoch` 格式的日志行，时间戳分别为 "MM-DD hh:mm:ss.mmm" 和纪元秒
+static THREADTIME_LINE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^\s*(\d\d-\d\d\s+\d\d:\d\d:\d\d\.\d+|\d+\.\d+)\s+(\d+)\s+(\d+)\s+([VDIWEFSA])\s+(.*?)\s*:(?:\s(.*)|$)",
+    )
+    .unwrap()
+});
+
+// dmesg 日志行: "<6>[  123.456789] message"
+static DMESG_LINE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:<(\d)>)?\[\s*(\d+\.\d+)\]\s?(.*)$").unwrap());
+
+/// logcat 日志缓冲区
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogBuffer {
+    Main,
+    System,
+    Radio,
+    Events,
+    Crash,
+    Kernel,
+    /// 默认缓冲区（main、system、crash）
+    Default,
+    /// 全部缓冲区
+    All,
+}
+
+impl LogBuffer {
+    /// logcat `-b` 参数值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogBuffer::Main => "main",
+            LogBuffer::System => "system",
+            LogBuffer::Radio => "radio",
+            LogBuffer::Events => "events",
+            LogBuffer::Crash => "crash",
+            LogBuffer::Kernel => "kernel",
+            LogBuffer::Default => "default",
+            LogBuffer::All => "all",
+        }
+    }
+}
+
+/// logcat 输出格式 (`-v`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Brief,
+    Process,
+    Tag,
+    Thread,
+    Raw,
+    Time,
+    ThreadTime,
+    Long,
+    /// threadtime 格式，时间戳为 Unix 纪元秒
+    Epoch,
+}
+
+impl LogFormat {
+    /// logcat `-v` 参数值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Brief => "brief",
+            LogFormat::Process => "process",
+            LogFormat::Tag => "tag",
+            LogFormat::Thread => "thread",
+            LogFormat::Raw => "raw",
+            LogFormat::Time => "time",
+            LogFormat::ThreadTime => "threadtime",
+            LogFormat::Long => "long",
+            LogFormat::Epoch => "epoch",
+        }
+    }
+}
+
+/// 日志优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogPriority {
+    Verbose,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+    Silent,
+}
+
+impl LogPriority {
+    /// 过滤表达式中使用的优先级字母
+    pub fn as_char(&self) -> char {
+        match self {
+            LogPriority::Verbose => 'V',
+            LogPriority::Debug => 'D',
+            LogPriority::Info => 'I',
+            LogPriority::Warn => 'W',
+            LogPriority::Error => 'E',
+            LogPriority::Fatal => 'F',
+            LogPriority::Silent => 'S',
+        }
+    }
+
+    /// 从优先级字母解析
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'V' => Some(LogPriority::Verbose),
+            'D' => Some(LogPriority::Debug),
+            'I' => Some(LogPriority::Info),
+            'W' => Some(LogPriority::Warn),
+            'E' => Some(LogPriority::Error),
+            'F' | 'A' => Some(LogPriority::Fatal),
+            'S' => Some(LogPriority::Silent),
+            _ => None,
+        }
+    }
+}
+
+/// logcat 时间窗口
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TimeWindow {
+    /// 最近 N 行 (`-t N`)
+    Tail(u32),
+    /// 从指定时间开始 (`-t '<time>'`)
+    Since(String),
+}
+
+/// logcat 查询构建器
+///
+/// ```no_run
+/// use adb_kit::logcat::{LogBuffer, LogcatQuery, LogPriority};
+///
+/// let query = LogcatQuery::new()
+///     .buffer(LogBuffer::Crash)
+///     .tag("ActivityManager", LogPriority::Info)
+///     .silence_others()
+///     .max_count(100);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LogcatQuery {
+    buffers: Vec<LogBuffer>,
+    format: Option<LogFormat>,
+    window: Option<TimeWindow>,
+    pid: Option<u32>,
+    regex: Option<String>,
+    max_count: Option<u32>,
+    filter_specs: Vec<String>,
+}
+
+impl LogcatQuery {
+    /// 创建空查询（等同于 `logcat -d`）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加日志缓冲区，可多次调用
+    pub fn buffer(mut self, buffer: LogBuffer) -> Self {
+        if !self.buffers.contains(&buffer) {
+            self.buffers.push(buffer);
+        }
+        self
+    }
+
+    /// 设置输出格式
+    pub fn format(mut self, format: LogFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// 只输出最近 N 行
+    pub fn tail(mut self, lines: u32) -> Self {
+        self.window = Some(TimeWindow::Tail(lines));
+        self
+    }
+
+    /// 只输出指定时间之后的日志
+    ///
+    /// 时间格式为 `MM-DD hh:mm:ss.mmm`、`YYYY-MM-DD hh:mm:ss.mmm` 或 `sssss.mmm`
+    pub fn since(mut self, time: &str) -> Self {
+        self.window = Some(TimeWindow::Since(time.to_string()));
+        self
+    }
+
+    /// 只输出指定 PID 的日志
+    pub fn pid(mut self, pid: u32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// 只输出匹配正则表达式的日志
+    pub fn regex(mut self, pattern: &str) -> Self {
+        self.regex = Some(pattern.to_string());
+        self
+    }
+
+    /// 输出指定行数后退出
+    pub fn max_count(mut self, count: u32) -> Self {
+        self.max_count = Some(count);
+        self
+    }
+
+    /// 指定标签的最低优先级
+    pub fn tag(mut self, tag: &str, priority: LogPriority) -> Self {
+        self.filter_specs
+            .push(format!("{}:{}", tag, priority.as_char()));
+        self
+    }
+
+    /// 其余标签的最低优先级 (`*:P`)
+    pub fn min_priority(mut self, priority: LogPriority) -> Self {
+        self.filter_specs.push(format!("*:{}", priority.as_char()));
+        self
+    }
+
+    /// 屏蔽未通过 [`tag`](Self::tag) 指定的标签 (`*:S`)
+    pub fn silence_others(self) -> Self {
+        self.min_priority(LogPriority::Silent)
+    }
+
+    /// 添加原始过滤表达式（如 `"MyTag:D"`）
+    pub fn filter_spec(mut self, spec: &str) -> Self {
+        self.filter_specs.push(spec.to_string());
+        self
+    }
+
+    /// 生成 logcat 参数列表（不包括 `logcat` 本身）
+    ///
+    /// `dump` 为 `true` 时添加 `-d`，读取完现有日志后退出
+    pub fn to_args(&self, dump: bool) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if dump {
+            args.push("-d".to_string());
+        }
+
+        for buffer in &self.buffers {
+            args.push("-b".to_string());
+            args.push(buffer.as_str().to_string());
+        }
+
+        if let Some(format) = self.format {
+            args.push("-v".to_string());
+            args.push(format.as_str().to_string());
+        }
+
+        match &self.window {
+            // -t 隐含 -d，实时读取时改用 -T
+            Some(TimeWindow::Tail(lines)) => {
+                args.push(if dump { "-t" } else { "-T" }.to_string());
+                args.push(lines.to_string());
+            }
+            Some(TimeWindow::Since(time)) => {
+                args.push(if dump { "-t" } else { "-T" }.to_string());
+                args.push(shell_quote(time));
+            }
+            None => {}
+        }
+
+        if let Some(pid) = self.pid {
+            args.push(format!("--pid={}", pid));
+        }
+
+        if let Some(regex) = &self.regex {
+            args.push("-e".to_string());
+            args.push(shell_quote(regex));
+        }
+
+        if let Some(count) = self.max_count {
+            args.push("-m".to_string());
+            args.push(count.to_string());
+        }
+
+        args.extend(self.filter_specs.iter().map(|spec| shell_quote(spec)));
+        args
+    }
+
+    /// 生成完整的 logcat 命令行
+    pub fn to_command(&self, dump: bool) -> String {
+        let args = self.to_args(dump);
+        if args.is_empty() {
+            "logcat".to_string()
+        } else {
+            format!("logcat {}", args.join(" "))
+        }
+    }
+}
+
+impl ADB {
+    /// 按查询条件读取当前日志
+    pub fn query_logs(&self, device_id: &str, query: &LogcatQuery) -> ADBResult<String> {
+        let command = query.to_command(true);
+        debug!("在设备 {} 上查询日志: {}", device_id, command);
+        self.shell(device_id, &command)
+    }
+}
+
+/// 流式读取日志时的选项，与 [`LogcatQuery`] 相同
+///
+/// 未指定格式或格式无法解析时使用 `-v threadtime`
+pub type LogcatOptions = LogcatQuery;
+
+/// 一条解析后的日志
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// 日志时间戳，格式取决于输出格式（threadtime 为 "MM-DD hh:mm:ss.mmm"，epoch 为纪元秒）
+    pub timestamp: String,
+    pub pid: u32,
+    pub tid: u32,
+    pub level: LogPriority,
+    pub tag: String,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// 解析 `-v threadtime` 或 `-v epoch` 格式的一行日志
+    pub fn parse(line: &str) -> Option<Self> {
+        let caps = THREADTIME_LINE_RE.captures(line.trim_end())?;
+        Some(LogEntry {
+            timestamp: caps[1].to_string(),
+            pid: caps[2].parse().ok()?,
+            tid: caps[3].parse().ok()?,
+            level: caps[4].chars().next().and_then(LogPriority::from_char)?,
+            tag: caps[5].trim().to_string(),
+            message: caps.get(6).map_or("", |m| m.as_str()).to_string(),
+        })
+    }
+}
+
+/// 实时日志流，丢弃时终止 logcat 进程
+pub struct LogcatStream {
+    child: Child,
+    lines: Lines<BufReader<ChildStdout>>,
+}
+
+impl Iterator for LogcatStream {
+    type Item = LogEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            let line = line.ok()?;
+            match LogEntry::parse(&line) {
+                Some(entry) => return Some(entry),
+                None => trace!("跳过无法解析的日志行: {}", line),
+            }
+        }
+        None
+    }
+}
+
+impl Drop for LogcatStream {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl ADB {
+    /// 实时读取日志，返回解析后的日志迭代器
+    ///
+    /// 支持缓冲区选择和标签/优先级过滤；迭代器在 logcat 退出（如设置了
+    /// [`max_count`](LogcatQuery::max_count)）或设备断开时结束
+    pub fn logcat_stream(&self, device_id: &str, options: &LogcatOptions) -> ADBResult<LogcatStream> {
+        let mut options = options.clone();
+        if !matches!(options.format, Some(LogFormat::ThreadTime) | Some(LogFormat::Epoch)) {
+            options.format = Some(LogFormat::ThreadTime);
+        }
+
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+
+        let command = options.to_command(false);
+        let mut child = cmd
+            .arg("exec-out")
+            .arg(&command)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| ADBError::CommandError(format!("无法启动日志流: {}", e)))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取日志流输出".to_string()))?;
+
+        debug!("在设备 {} 上启动日志流: {}", device_id, command);
+        Ok(LogcatStream {
+            child,
+            lines: BufReader::new(stdout).lines(),
+        })
+    }
+
+    /// 实时读取日志并对每条日志调用回调，回调返回 `false` 时停止
+    pub fn logcat_follow<F>(&self, device_id: &str, options: &LogcatOptions, mut callback: F) -> ADBResult<()>
+    where
+        F: FnMut(&LogEntry) -> bool,
+    {
+        for entry in self.logcat_stream(device_id, options)? {
+            if !callback(&entry) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// 在后台把日志逐行写入 `sink`，直到调用 [`LogcatRecorder::stop`]
+    ///
+    /// 每行以 `\n` 结尾原样写入；写入失败时停止记录，错误由 `stop()` 返回
+    pub fn record_logcat<S>(&self, device_id: &str, options: &LogcatOptions, mut sink: S) -> LogcatRecorder
+    where
+        S: Sink + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        self.jobs.register_stop_flag(stop.clone());
+        let command = options.to_command(false);
+
+        let worker = {
+            let adb = self.clone();
+            let device_id = device_id.to_string();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut lines = 0u64;
+                let mut write_error = None;
+                let result = adb.shell_stream_until(&device_id, &command, &stop, |line| {
+                    if write_error.is_some() {
+                        return;
+                    }
+                    let mut record = Vec::with_capacity(line.len() + 1);
+                    record.extend_from_slice(line.as_bytes());
+                    record.push(b'\n');
+                    match sink.write_record(&record) {
+                        Ok(()) => lines += 1,
+                        Err(e) => {
+                            warn!("设备 {} 日志写入失败，停止记录: {}", device_id, e);
+                            write_error = Some(e);
+                            stop.store(true, Ordering::SeqCst);
+                        }
+                    }
+                });
+                let flushed = sink.flush();
+                debug!("设备 {} 日志记录结束，共 {} 行", device_id, lines);
+                match write_error {
+                    Some(e) => Err(e),
+                    None => result.and(flushed).map(|_| lines),
+                }
+            })
+        };
+
+        info!("设备 {} 开始记录日志", device_id);
+        LogcatRecorder {
+            stop,
+            worker: Some(worker),
+        }
+    }
+}
+
+/// 后台日志记录，在 `stop()`、超出作用域或 [`ADB::shutdown`] 时停止
+pub struct LogcatRecorder {
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<ADBResult<u64>>>,
+}
+
+impl LogcatRecorder {
+    /// 记录线程是否仍在运行
+    pub fn is_alive(&self) -> bool {
+        self.worker.as_ref().is_some_and(|w| !w.is_finished())
+    }
+
+    /// 停止记录，返回写入的行数
+    pub fn stop(&mut self) -> ADBResult<u64> {
+        self.stop.store(true, Ordering::SeqCst);
+        match self.worker.take() {
+            Some(worker) => worker
+                .join()
+                .map_err(|_| ADBError::UnknownError("日志记录线程异常退出".to_string()))?,
+            None => Ok(0),
+        }
+    }
+}
+
+impl Drop for LogcatRecorder {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// 时间线条目的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogSource {
+    /// logcat 默认缓冲区
+    Logcat,
+    /// 内核日志（kernel 缓冲区或 dmesg）
+    Kernel,
+    /// events 缓冲区
+    Events,
+}
+
+impl LogSource {
+    /// 来源标记
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogSource::Logcat => "logcat",
+            LogSource::Kernel => "kernel",
+            LogSource::Events => "events",
+        }
+    }
+}
+
+/// 时间线上的一条日志
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    /// Unix 纪元秒（设备时钟）
+    pub timestamp: f64,
+    pub source: LogSource,
+    pub priority: Option<LogPriority>,
+    pub tag: Option<String>,
+    pub pid: Option<u32>,
+    pub message: String,
+}
+
+impl TimelineEntry {
+    /// 转换为本地时间
+    pub fn local_time(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        let secs = self.timestamp.floor() as i64;
+        let nanos = ((self.timestamp - self.timestamp.floor()) * 1e9) as u32;
+        chrono::DateTime::from_timestamp(secs, nanos).map(|t| t.with_timezone(&chrono::Local))
+    }
+}
+
+/// 按时间排序的多来源日志时间线
+#[derive(Debug, Clone, Default)]
+pub struct MergedTimeline {
+    /// 时间窗口起点（Unix 纪元秒）
+    pub start: f64,
+    /// 时间窗口终点（Unix 纪元秒）
+    pub end: f64,
+    pub entries: Vec<TimelineEntry>,
+    /// 无法读取的来源及原因（例如 dmesg 需要 root）
+    pub errors: Vec<(LogSource, String)>,
+}
+
+impl MergedTimeline {
+    /// 只返回指定来源的条目
+    pub fn by_source(&self, source: LogSource) -> impl Iterator<Item = &TimelineEntry> {
+        self.entries.iter().filter(move |e| e.source == source)
+    }
+
+    /// 格式化为文本，每行带来源标记
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for entry in &self.entries {
+            let time = entry
+                .local_time()
+                .map(|t| t.format("%m-%d %H:%M:%S%.3f").to_string())
+                .unwrap_or_else(|| format!("{:.3}", entry.timestamp));
+            let priority = entry.priority.map_or('-', |p| p.as_char());
+            text.push_str(&format!(
+                "{} [{:<6}] {} {}: {}\n",
+                time,
+                entry.source.as_str(),
+                priority,
+                entry.tag.as_deref().unwrap_or("-"),
+                entry.message
+            ));
+        }
+        text
+    }
+}
+
+/// 解析 `-v epoch` 格式的日志
+pub(crate) fn parse_epoch_logs(output: &str, source: LogSource) -> Vec<TimelineEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let caps = EPOCH_LINE_RE.captures(line)?;
+            Some(TimelineEntry {
+                timestamp: caps[1].parse().ok()?,
+                source,
+                priority: caps[4].chars().next().and_then(LogPriority::from_char),
+                tag: Some(caps[5].trim().to_string()),
+                pid: caps[2].parse().ok(),
+                message: caps.get(6).map_or("", |m| m.as_str()).to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 解析 dmesg 输出，`boot_epoch` 为设备启动时刻的纪元秒
+///
+/// dmesg 时间戳基于单调时钟，不计入休眠时间，设备休眠过时换算结果会偏早，
+/// 只在无法读取 kernel 缓冲区的旧设备上使用
+fn parse_dmesg(output: &str, boot_epoch: f64) -> Vec<TimelineEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let caps = DMESG_LINE_RE.captures(line.trim_end())?;
+            let uptime: f64 = caps[2].parse().ok()?;
+            let priority = caps.get(1).and_then(|p| p.as_str().parse::<u8>().ok()).map(|level| {
+                match level {
+                    0..=2 => LogPriority::Fatal,
+                    3 => LogPriority::Error,
+                    4 => LogPriority::Warn,
+                    5 | 6 => LogPriority::Info,
+                    _ => LogPriority::Debug,
+                }
+            });
+            Some(TimelineEntry {
+                timestamp: boot_epoch + uptime,
+                source: LogSource::Kernel,
+                priority,
+                tag: None,
+                pid: None,
+                message: caps[3].to_string(),
+            })
+        })
+        .collect()
+}
+
+impl ADB {
+    /// 获取设备当前时间和启动时刻（Unix 纪元秒）
+    pub(crate) fn device_clock(&self, device_id: &str) -> ADBResult<(f64, f64)> {
+        let output = self.shell(device_id, "date +%s; cat /proc/uptime")?;
+        let mut lines = output.lines();
+
+        let now = lines
+            .next()
+            .and_then(|l| l.trim().parse::<f64>().ok())
+            .ok_or_else(|| ADBError::ParseError(format!("无法解析设备时间: {}", output.trim())))?;
+        let uptime = lines
+            .next()
+            .and_then(|l| l.split_whitespace().next())
+            .and_then(|v| v.parse::<f64>().ok())
+            .ok_or_else(|| ADBError::ParseError(format!("无法解析设备运行时间: {}", output.trim())))?;
+
+        Ok((now, now - uptime))
+    }
+
+    /// 收集最近一段时间内的 logcat、内核和 events 日志，合并为按时间排序的时间线
+    ///
+    /// 内核日志优先从 logcat 的 kernel 缓冲区读取（带纪元时间戳），读取不到时退回 dmesg。
+    /// 某个来源读取失败时不会中断收集，失败原因记录在 [`MergedTimeline::errors`] 中
+    pub fn collect_correlated(&self, device_id: &str, window: Duration) -> ADBResult<MergedTimeline> {
+        let (now, boot_epoch) = self.device_clock(device_id)?;
+        let start = now - window.as_secs_f64();
+
+        let mut timeline = MergedTimeline {
+            start,
+            end: now,
+            ..Default::default()
+        };
+
+        let since = format!("{:.3}", start.max(0.0));
+        let mut kernel_error = None;
+        for (buffer, source) in [
+            (LogBuffer::Default, LogSource::Logcat),
+            (LogBuffer::Events, LogSource::Events),
+            (LogBuffer::Kernel, LogSource::Kernel),
+        ] {
+            let query = LogcatQuery::new()
+                .buffer(buffer)
+                .format(LogFormat::Epoch)
+                .since(&since);
+            match self.query_logs(device_id, &query) {
+                Ok(output) => timeline.entries.extend(parse_epoch_logs(&output, source)),
+                Err(e) if source == LogSource::Kernel => kernel_error = Some(e.to_string()),
+                Err(e) => {
+                    warn!("读取设备 {} 的 {} 日志失败: {}", device_id, source.as_str(), e);
+                    timeline.errors.push((source, e.to_string()));
+                }
+            }
+        }
+
+        // 旧设备没有 kernel 缓冲区，退回 dmesg（Android 8 起 shell 用户通常无权读取）
+        if !timeline.entries.iter().any(|e| e.source == LogSource::Kernel) {
+            match self.shell(device_id, "dmesg") {
+                Ok(output) if output.contains("Permission denied") || output.contains("not permitted") => {
+                    if kernel_error.is_some() {
+                        timeline.errors.push((LogSource::Kernel, output.trim().to_string()));
+                    }
+                }
+                Ok(output) => timeline.entries.extend(
+                    parse_dmesg(&output, boot_epoch)
+                        .into_iter()
+                        .filter(|e| e.timestamp >= start),
+                ),
+                Err(e) => {
+                    warn!("读取设备 {} 的内核日志失败: {}", device_id, e);
+                    timeline.errors.push((LogSource::Kernel, kernel_error.unwrap_or_else(|| e.to_string())));
+                }
+            }
+        }
+
+        timeline.entries.retain(|e| e.timestamp >= start && e.timestamp <= now + 1.0);
+        timeline
+            .entries
+            .sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+
+        debug!(
+            "设备 {} 时间线收集完成: {} 条日志, {} 个来源失败",
+            device_id,
+            timeline.entries.len(),
+            timeline.errors.len()
+        );
+        Ok(timeline)
+    }
+}
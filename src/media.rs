@@ -1,6 +1,113 @@
 use crate::device::ADB;
-use crate::error::{ADBResult};
-use log::debug;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
+use crate::logcat::{LogPriority, LogcatQuery};
+use crate::sink::Sink;
+use log::{debug, info};
+#[cfg(feature = "image")]
+use std::path::Path;
+
+// PNG 文件签名
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// 写入截图的溯源信息（需要 `image` 特性）
+#[cfg(feature = "image")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenshotStamp {
+    /// 设备序列号
+    pub serial: String,
+    /// 截图时间（RFC 3339）
+    pub timestamp: String,
+    /// 构建指纹
+    pub build: String,
+    /// 前台 Activity
+    pub activity: Option<String>,
+}
+
+#[cfg(feature = "image")]
+impl ScreenshotStamp {
+    /// 写入 PNG tEXt 块的键值对
+    pub fn text_chunks(&self) -> Vec<(&'static str, String)> {
+        let mut chunks = vec![
+            ("adbkit:serial", self.serial.clone()),
+            ("adbkit:timestamp", self.timestamp.clone()),
+            ("adbkit:build", self.build.clone()),
+        ];
+        if let Some(activity) = &self.activity {
+            chunks.push(("adbkit:activity", activity.clone()));
+        }
+        chunks
+    }
+}
+
+/// 构造一个 PNG 块（长度、类型、数据、CRC）
+#[cfg(feature = "image")]
+fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(kind);
+    hasher.update(data);
+
+    let mut chunk = Vec::with_capacity(data.len() + 12);
+    chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(&hasher.finalize().to_be_bytes());
+    chunk
+}
+
+/// 在 PNG 的 IHDR 块之后插入 tEXt 块；tEXt 只支持 Latin-1，其他字符替换为 `?`
+#[cfg(feature = "image")]
+fn insert_png_text(png: &[u8], entries: &[(&str, String)]) -> ADBResult<Vec<u8>> {
+    if png.len() < 33 || png[..8] != PNG_SIGNATURE || &png[12..16] != b"IHDR" {
+        return Err(ADBError::FileError("截图不是有效的 PNG 文件".to_string()));
+    }
+
+    let ihdr_len = u32::from_be_bytes([png[8], png[9], png[10], png[11]]) as usize;
+    let ihdr_end = 8 + 12 + ihdr_len;
+    if png.len() < ihdr_end {
+        return Err(ADBError::FileError("PNG 文件已截断".to_string()));
+    }
+
+    let mut result = Vec::with_capacity(png.len() + 256);
+    result.extend_from_slice(&png[..ihdr_end]);
+    for (keyword, value) in entries {
+        let mut data: Vec<u8> = keyword.bytes().collect();
+        data.push(0);
+        data.extend(value.chars().map(|c| if (c as u32) < 256 { c as u8 } else { b'?' }));
+        result.extend(png_chunk(b"tEXt", &data));
+    }
+    result.extend_from_slice(&png[ihdr_end..]);
+    Ok(result)
+}
+
+/// 将标签和优先级转换为 logcat 查询
+///
+/// 通过 [`LogcatQuery::tag`] 和 [`LogcatQuery::min_priority`] 构建，与直接使用构建器时
+/// 生成相同的命令行；未指定优先级的标签按 `V` 处理，无法识别的写法原样作为过滤表达式
+fn legacy_query(tag: Option<&str>, priority: &str) -> LogcatQuery {
+    let parse_priority = |p: &str| {
+        let mut chars = p.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => LogPriority::from_char(c),
+            _ => None,
+        }
+    };
+
+    let mut query = LogcatQuery::new();
+    if let Some(tag) = tag {
+        query = match tag.rsplit_once(':') {
+            Some((name, p)) => match parse_priority(p) {
+                Some(p) => query.tag(name, p),
+                None => query.filter_spec(tag),
+            },
+            None => query.tag(tag, LogPriority::Verbose),
+        };
+    }
+    match parse_priority(priority) {
+        Some(p) => query.min_priority(p),
+        None => query.filter_spec(&format!("*:{}", priority)),
+    }
+}
 
 impl ADB {
     /// 从设备截图
@@ -22,6 +129,57 @@ impl ADB {
         Ok(())
     }
 
+    /// 截图并直接返回 PNG 数据，不在设备上创建文件
+    pub fn take_screenshot_bytes(&self, device_id: &str) -> ADBResult<Vec<u8>> {
+        let png = self.exec_out(device_id, "screencap -p")?;
+        if png.len() < 8 || png[..8] != PNG_SIGNATURE {
+            return Err(ADBError::CommandError(format!(
+                "截图失败: {}",
+                String::from_utf8_lossy(&png[..png.len().min(200)]).trim()
+            )));
+        }
+
+        debug!("设备 {} 截图 {} 字节", device_id, png.len());
+        Ok(png)
+    }
+
+    /// 采集截图溯源信息：序列号、时间、构建指纹和前台 Activity（需要 `image` 特性）
+    #[cfg(feature = "image")]
+    pub fn screenshot_stamp(&self, device_id: &str) -> ADBResult<ScreenshotStamp> {
+        let output = self.shell(
+            device_id,
+            "getprop ro.serialno; getprop ro.build.fingerprint",
+        )?;
+        let mut lines = output.lines();
+        let serial = lines.next().unwrap_or("").trim();
+        let build = lines.next().unwrap_or("").trim().to_string();
+
+        Ok(ScreenshotStamp {
+            serial: if serial.is_empty() { device_id } else { serial }.to_string(),
+            timestamp: chrono::Local::now().to_rfc3339(),
+            build,
+            activity: self.foreground_activity(device_id)?,
+        })
+    }
+
+    /// 截图并将溯源信息写入 PNG tEXt 元数据块（需要 `image` 特性）
+    ///
+    /// 并行运行产生的大量截图可以据此追溯到具体设备和构建，返回写入的信息
+    #[cfg(feature = "image")]
+    pub fn take_screenshot_annotated(
+        &self,
+        device_id: &str,
+        output_path: &str,
+    ) -> ADBResult<ScreenshotStamp> {
+        let stamp = self.screenshot_stamp(device_id)?;
+        let png = insert_png_text(&self.take_screenshot_bytes(device_id)?, &stamp.text_chunks())?;
+        std::fs::write(output_path, png)
+            .map_err(|e| ADBError::FileError(format!("无法写入截图 {}: {}", output_path, e)))?;
+
+        debug!("截图 {} 已写入溯源信息: {:?}", output_path, stamp);
+        Ok(stamp)
+    }
+
     /// 录制设备屏幕
     ///
     /// # 参数
@@ -63,29 +221,69 @@ impl ADB {
         Ok(())
     }
 
+    /// 分段录制屏幕并把每段视频写入 `sink`
+    ///
+    /// screenrecord 单次最多录制 180 秒，更长的录制按 `segment_secs` 拆成 `segments` 段依次录制，
+    /// 每段录制完成后读取到本地，设备上的临时文件在结束时删除。
+    /// 每段之前调用 [`Sink::start_segment`]，配合 [`RotatingFileSink`](crate::sink::RotatingFileSink)
+    /// 可以把每段保存为单独的文件。返回写入的总字节数。
+    pub fn record_screen_segments<S: Sink>(
+        &self,
+        device_id: &str,
+        segment_secs: u32,
+        segments: u32,
+        size: Option<&str>,
+        mut sink: S,
+    ) -> ADBResult<u64> {
+        self.with_resources(device_id, |resources| {
+            // 会话临时目录下的唯一文件，避免与同一设备上的其他录制冲突，并由 shutdown 清理
+            let device_path = resources.create_temp_file("screenrecord_", ".mp4")?;
+            let path_q = shell_quote(&device_path);
+            let mut command = format!("screenrecord --time-limit {} ", segment_secs.clamp(1, 180));
+            if let Some(resolution) = size {
+                command.push_str(&format!("--size {} ", resolution));
+            }
+            command.push_str(&path_q);
+
+            info!("设备 {} 开始分段录屏: {} 段，每段 {} 秒", device_id, segments, segment_secs);
+            let mut total = 0u64;
+            for index in 0..segments {
+                self.shell(device_id, &command)?;
+                let data = self.exec_out(device_id, &format!("cat {}", path_q))?;
+
+                sink.start_segment()?;
+                sink.write_record(&data)?;
+                total += data.len() as u64;
+                debug!("第 {} 段录屏已写入 ({} 字节)", index + 1, data.len());
+            }
+            sink.flush()?;
+
+            Ok(total)
+        })
+    }
+
     /// 从设备捕获日志
+    ///
+    /// 需要缓冲区、时间窗口等更多过滤条件时请使用 [`ADB::query_logs`]
     pub fn capture_logs(
         &self,
         device_id: &str,
         tag: Option<&str>,
         priority: &str,
     ) -> ADBResult<String> {
-        let tag_filter = tag.map_or(String::new(), |t| format!(" {}", t));
-        self.shell(
-            device_id,
-            &format!("logcat -d{} *:{}", tag_filter, priority),
-        )
+        self.query_logs(device_id, &legacy_query(tag, priority))
     }
 
     /// 实时查看日志（返回立即执行的命令）
+    ///
+    /// 需要逐条读取解析后的日志时请使用 [`ADB::logcat_stream`]
     pub fn watch_logs(
         &self,
         device_id: &str,
         tag: Option<&str>,
         priority: &str,
     ) -> ADBResult<()> {
-        let tag_filter = tag.map_or(String::new(), |t| format!(" {}", t));
-        let command = format!("logcat{} *:{}", tag_filter, priority);
+        let command = legacy_query(tag, priority).to_command(false);
 
         // 启动不等待的 shell 命令
         self.shell_no_wait(device_id, &command)
@@ -96,4 +294,56 @@ impl ADB {
         self.shell(device_id, "logcat -c")?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// 触发媒体扫描，使推送的图片、视频出现在相册等应用中
+    ///
+    /// Android 10 及以上通过 MediaProvider 的 `scan_file` 调用，失败时退回到
+    /// `MEDIA_SCANNER_SCAN_FILE` 广播
+    pub fn scan_file(&self, device_id: &str, path: &str) -> ADBResult<()> {
+        let quoted = shell_quote(path);
+        let sdk = self.device_profile(device_id)?.sdk_int;
+
+        if sdk >= 29 {
+            let output = self.shell(
+                device_id,
+                &format!("content call --uri content://media --method scan_file --arg {}", quoted),
+            );
+            match output {
+                Ok(out) if !out.contains("Exception") && !out.contains("Error") => {
+                    debug!("已通过 MediaProvider 扫描 {}", path);
+                    return Ok(());
+                }
+                Ok(out) => debug!("MediaProvider 扫描失败，改用广播: {}", out.trim()),
+                Err(e) => debug!("MediaProvider 扫描失败，改用广播: {}", e),
+            }
+        }
+
+        let output = self.shell(
+            device_id,
+            &format!(
+                "am broadcast -a android.intent.action.MEDIA_SCANNER_SCAN_FILE -d {}",
+                shell_quote(&format!("file://{}", path))
+            ),
+        )?;
+        if output.contains("Exception") {
+            return Err(ADBError::CommandError(format!(
+                "触发媒体扫描失败: {}",
+                output.trim()
+            )));
+        }
+
+        debug!("已发送媒体扫描广播: {}", path);
+        Ok(())
+    }
+}
+
+/// 将溯源信息写入已有的 PNG 文件（需要 `image` 特性）
+#[cfg(feature = "image")]
+pub fn annotate_png_file(path: &Path, stamp: &ScreenshotStamp) -> ADBResult<()> {
+    let png = std::fs::read(path)
+        .map_err(|e| ADBError::FileError(format!("无法读取截图 {}: {}", path.display(), e)))?;
+    let annotated = insert_png_text(&png, &stamp.text_chunks())?;
+    std::fs::write(path, annotated)
+        .map_err(|e| ADBError::FileError(format!("无法写入截图 {}: {}", path.display(), e)))?;
+    Ok(())
+}
@@ -0,0 +1,217 @@
+//! 内存使用情况
+//!
+//! 解析 `dumpsys meminfo <包名>` 的分类明细和 App Summary，以及整机的 `/proc/meminfo`。
+//! 数值单位均为 KB。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::debug;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// 进程段落的标题，如 "** MEMINFO in pid 12345 [com.example] **"
+static MEMINFO_HEADER_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\*\* MEMINFO in pid (\d+) \[([^\]]+)\] \*\*").unwrap());
+
+/// 分类明细中的一行（Native Heap、Dalvik Heap、.so mmap 等）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemCategory {
+    pub name: String,
+    pub pss_kb: u64,
+    pub private_dirty_kb: u64,
+    pub private_clean_kb: u64,
+}
+
+/// 堆使用情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapUsage {
+    pub size_kb: u64,
+    pub alloc_kb: u64,
+    pub free_kb: u64,
+}
+
+/// 应用进程的内存使用情况
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemInfo {
+    pub package_name: String,
+    pub pid: u32,
+    pub total_pss_kb: u64,
+    /// Android 12 起才有 RSS 统计
+    pub total_rss_kb: Option<u64>,
+    /// Java (Dalvik/ART) 堆
+    pub java_heap: Option<HeapUsage>,
+    pub native_heap: Option<HeapUsage>,
+    /// App Summary 中各项的 PSS（Java Heap、Native Heap、Code、Graphics 等）
+    pub summary: BTreeMap<String, u64>,
+    pub categories: Vec<MemCategory>,
+}
+
+/// 整机内存 (`/proc/meminfo`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemMemInfo {
+    pub total_kb: u64,
+    pub free_kb: u64,
+    /// 可用内存（包括可回收的缓存），旧内核没有时为 None
+    pub available_kb: Option<u64>,
+    pub cached_kb: u64,
+    pub swap_total_kb: u64,
+    pub swap_free_kb: u64,
+    /// 全部字段
+    pub fields: BTreeMap<String, u64>,
+}
+
+/// 拆分出行首的名称和之后的数字列
+fn split_row(line: &str) -> Option<(&str, Vec<u64>)> {
+    let start = line.find(|c: char| c.is_ascii_digit())?;
+    let name = line[..start].trim().trim_end_matches(':').trim();
+    let numbers: Vec<u64> = line[start..]
+        .split_whitespace()
+        .map_while(|v| v.parse().ok())
+        .collect();
+    if name.is_empty() || numbers.is_empty() {
+        return None;
+    }
+    Some((name, numbers))
+}
+
+/// 堆所在行的最后三列为 Heap Size / Heap Alloc / Heap Free
+fn heap_usage(numbers: &[u64]) -> Option<HeapUsage> {
+    if numbers.len() < 7 {
+        return None;
+    }
+    let n = numbers.len();
+    Some(HeapUsage {
+        size_kb: numbers[n - 3],
+        alloc_kb: numbers[n - 2],
+        free_kb: numbers[n - 1],
+    })
+}
+
+/// 解析 `dumpsys meminfo <包名>` 输出，有多个进程时优先选择进程名与包名相同的
+fn parse_meminfo(output: &str, package_name: &str) -> Option<MemInfo> {
+    let headers: Vec<_> = MEMINFO_HEADER_RE.captures_iter(output).collect();
+    let header = headers
+        .iter()
+        .find(|caps| &caps[2] == package_name)
+        .or_else(|| headers.first())?;
+
+    let start = header.get(0)?.end();
+    let end = output[start..]
+        .find("** MEMINFO in pid")
+        .map(|i| start + i)
+        .unwrap_or(output.len());
+    let section = &output[start..end];
+
+    let mut info = MemInfo {
+        package_name: header[2].to_string(),
+        pid: header[1].parse().ok()?,
+        total_pss_kb: 0,
+        total_rss_kb: None,
+        java_heap: None,
+        native_heap: None,
+        summary: BTreeMap::new(),
+        categories: Vec::new(),
+    };
+
+    let mut in_summary = false;
+    for line in section.lines() {
+        let trimmed = line.trim();
+        if trimmed == "App Summary" {
+            in_summary = true;
+            continue;
+        }
+        if in_summary && (trimmed == "Objects" || trimmed.starts_with("SQL")) {
+            break;
+        }
+
+        if in_summary {
+            // "TOTAL PSS:   40000   TOTAL RSS:   90000   TOTAL SWAP PSS:   12"，旧版本为 "TOTAL:   40000"
+            if trimmed.starts_with("TOTAL") {
+                for part in trimmed.split("TOTAL") {
+                    let Some((key, rest)) = part.split_once(':') else {
+                        continue;
+                    };
+                    let Some(value) = rest.split_whitespace().next().and_then(|v| v.parse().ok()) else {
+                        continue;
+                    };
+                    match key.trim() {
+                        "PSS" | "" => info.total_pss_kb = value,
+                        "RSS" => info.total_rss_kb = Some(value),
+                        _ => {}
+                    }
+                }
+            } else if let Some((name, numbers)) = split_row(trimmed) {
+                info.summary.insert(name.to_string(), numbers[0]);
+            }
+            continue;
+        }
+
+        let Some((name, numbers)) = split_row(trimmed) else {
+            continue;
+        };
+        match name {
+            "TOTAL" => info.total_pss_kb = numbers[0],
+            "Native Heap" => info.native_heap = heap_usage(&numbers),
+            "Dalvik Heap" => info.java_heap = heap_usage(&numbers),
+            _ => {}
+        }
+        if name != "TOTAL" {
+            info.categories.push(MemCategory {
+                name: name.to_string(),
+                pss_kb: numbers[0],
+                private_dirty_kb: numbers.get(1).copied().unwrap_or(0),
+                private_clean_kb: numbers.get(2).copied().unwrap_or(0),
+            });
+        }
+    }
+
+    Some(info)
+}
+
+/// 解析 `/proc/meminfo`
+fn parse_proc_meminfo(output: &str) -> ADBResult<SystemMemInfo> {
+    let fields: BTreeMap<String, u64> = output
+        .lines()
+        .filter_map(|l| {
+            let (key, value) = l.split_once(':')?;
+            let value = value.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim().to_string(), value))
+        })
+        .collect();
+    let field = |key: &str| fields.get(key).copied();
+
+    Ok(SystemMemInfo {
+        total_kb: field("MemTotal")
+            .ok_or_else(|| ADBError::ParseError("无法读取 MemTotal".to_string()))?,
+        free_kb: field("MemFree").unwrap_or(0),
+        available_kb: field("MemAvailable"),
+        cached_kb: field("Cached").unwrap_or(0),
+        swap_total_kb: field("SwapTotal").unwrap_or(0),
+        swap_free_kb: field("SwapFree").unwrap_or(0),
+        fields,
+    })
+}
+
+impl ADB {
+    /// 获取应用进程的内存使用情况，应用未运行时返回 `AppNotFound`
+    pub fn get_meminfo(&self, device_id: &str, package_name: &str) -> ADBResult<MemInfo> {
+        let output = self.shell(device_id, &format!("dumpsys meminfo {}", package_name))?;
+        let info = parse_meminfo(&output, package_name).ok_or_else(|| {
+            ADBError::AppNotFound(format!("应用 {} 没有运行中的进程", package_name))
+        })?;
+
+        debug!(
+            "应用 {} (pid {}) PSS {} KB",
+            info.package_name, info.pid, info.total_pss_kb
+        );
+        Ok(info)
+    }
+
+    /// 获取整机内存信息
+    pub fn get_system_meminfo(&self, device_id: &str) -> ADBResult<SystemMemInfo> {
+        let output = self.shell(device_id, "cat /proc/meminfo")?;
+        parse_proc_meminfo(&output)
+    }
+}
@@ -0,0 +1,1139 @@
+use crate::device::{ADBDevice, ADB};
+use crate::error::{ADBError, ADBResult};
+use crate::logcat::{LogBuffer, LogEntry, LogFormat, LogcatQuery, LogPriority};
+use crate::sink::Sink;
+use crate::ui::Selector;
+use crate::utils::with_timeout;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+// ANR 发生后等待系统弹出对话框的时间
+const ANR_DIALOG_DELAY: Duration = Duration::from_secs(2);
+// track-devices 连接断开（如 adb server 重启）后重新连接的间隔
+const TRACK_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+// StrictMode 日志级别和全局禁用开关
+const STRICTMODE_LOG_PROP: &str = "log.tag.StrictMode";
+const STRICTMODE_DISABLE_PROP: &str = "persist.sys.strictmode.disable";
+// ANR 和 native 崩溃最多收集的日志行数
+const MAX_CRASH_LINES: usize = 64;
+
+static STRICTMODE_DURATION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"~duration=(\d+) ms").unwrap());
+
+// native 崩溃的进程行，如 "pid: 1234, tid: 1234, name: main  >>> com.example <<<"
+static NATIVE_PROCESS_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"pid: (\d+), tid: \d+, name: .*>>> (\S+) <<<").unwrap());
+
+/// 心跳检测到的设备状态变化
+#[derive(Debug, Clone)]
+pub struct HeartbeatEvent {
+    pub device_id: String,
+    /// 当前是否在线
+    pub online: bool,
+    /// 变化前的状态，首次检测时为 None
+    pub previous: Option<bool>,
+    /// 设备恢复在线时，本次离线持续的时间
+    pub downtime: Option<Duration>,
+    /// 离线时 ping 失败的原因
+    pub error: Option<String>,
+}
+
+/// 单个设备的心跳统计
+#[derive(Debug, Clone)]
+pub struct HeartbeatStatus {
+    pub online: bool,
+    /// 当前状态开始的时间
+    pub since: Instant,
+    /// 累计离线时间（不含当前这次离线）
+    pub total_downtime: Duration,
+    /// 状态变化次数
+    pub transitions: u32,
+}
+
+impl HeartbeatStatus {
+    /// 当前状态已持续的时间
+    pub fn duration(&self) -> Duration {
+        self.since.elapsed()
+    }
+}
+
+/// 后台心跳监控
+///
+/// 定期对设备执行 `echo ok`，在状态变化时调用回调。
+/// 在 `stop()`、超出作用域或 [`ADB::shutdown`] 时停止。
+pub struct Heartbeat {
+    stop: Arc<AtomicBool>,
+    status: Arc<Mutex<HashMap<String, HeartbeatStatus>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Heartbeat {
+    /// 各设备的当前状态
+    pub fn status(&self) -> HashMap<String, HeartbeatStatus> {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// 监控线程是否仍在运行
+    pub fn is_alive(&self) -> bool {
+        self.worker.as_ref().is_some_and(|w| !w.is_finished())
+    }
+
+    /// 停止监控并等待后台线程退出
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+            debug!("心跳监控已停止");
+        }
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 写入 [`Sink`] 的一条采样记录
+#[derive(Debug, Clone, Serialize)]
+struct SampleRecord<'a, T: Serialize> {
+    /// 采样时间（RFC 3339）
+    timestamp: String,
+    device_id: &'a str,
+    value: T,
+}
+
+/// 后台定时采样，在 `stop()`、超出作用域或 [`ADB::shutdown`] 时停止
+pub struct Sampler {
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<ADBResult<u64>>>,
+}
+
+impl Sampler {
+    /// 采样线程是否仍在运行
+    pub fn is_alive(&self) -> bool {
+        self.worker.as_ref().is_some_and(|w| !w.is_finished())
+    }
+
+    /// 停止采样，返回写入的记录数
+    pub fn stop(&mut self) -> ADBResult<u64> {
+        self.stop.store(true, Ordering::SeqCst);
+        match self.worker.take() {
+            Some(worker) => worker
+                .join()
+                .map_err(|_| ADBError::UnknownError("采样线程异常退出".to_string()))?,
+            None => Ok(0),
+        }
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// 可中断的等待，返回 false 表示收到停止信号
+pub(crate) fn sleep_unless_stopped(duration: Duration, stop: &AtomicBool) -> bool {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        if stop.load(Ordering::SeqCst) {
+            return false;
+        }
+        thread::sleep((deadline - Instant::now()).min(Duration::from_millis(100)));
+    }
+    !stop.load(Ordering::SeqCst)
+}
+
+impl ADB {
+    /// 启动后台心跳监控
+    ///
+    /// 每隔 `interval` 对每个设备执行一次 `echo ok`，超过 `interval`（至少 5 秒）
+    /// 未响应视为离线。首次检测和之后每次上线/离线变化都会调用 `on_change`。
+    pub fn heartbeat<F>(&self, device_ids: &[&str], interval: Duration, mut on_change: F) -> Heartbeat
+    where
+        F: FnMut(&HeartbeatEvent) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        self.jobs.register_stop_flag(stop.clone());
+        let status: Arc<Mutex<HashMap<String, HeartbeatStatus>>> = Arc::new(Mutex::new(HashMap::new()));
+        let device_ids: Vec<String> = device_ids.iter().map(|id| id.to_string()).collect();
+        let timeout_ms = interval.max(Duration::from_secs(5)).as_millis() as u64;
+
+        let worker = {
+            let adb = self.clone();
+            let stop = stop.clone();
+            let status = status.clone();
+            thread::spawn(move || loop {
+                for device_id in &device_ids {
+                    if stop.load(Ordering::SeqCst) {
+                        return;
+                    }
+
+                    let ping = {
+                        let adb = adb.clone();
+                        let device_id = device_id.clone();
+                        with_timeout(timeout_ms, move || adb.shell(&device_id, "echo ok"))
+                    };
+                    let (online, error) = match ping {
+                        Ok(output) if output.trim() == "ok" => (true, None),
+                        Ok(output) => (false, Some(format!("意外的响应: {}", output.trim()))),
+                        Err(e) => (false, Some(e.to_string())),
+                    };
+
+                    let event = {
+                        let mut status = status.lock().unwrap();
+                        match status.get_mut(device_id) {
+                            Some(current) if current.online == online => None,
+                            Some(current) => {
+                                let elapsed = current.since.elapsed();
+                                let downtime = online.then_some(elapsed);
+                                if let Some(downtime) = downtime {
+                                    current.total_downtime += downtime;
+                                }
+                                current.online = online;
+                                current.since = Instant::now();
+                                current.transitions += 1;
+                                Some(HeartbeatEvent {
+                                    device_id: device_id.clone(),
+                                    online,
+                                    previous: Some(!online),
+                                    downtime,
+                                    error,
+                                })
+                            }
+                            None => {
+                                status.insert(
+                                    device_id.clone(),
+                                    HeartbeatStatus {
+                                        online,
+                                        since: Instant::now(),
+                                        total_downtime: Duration::ZERO,
+                                        transitions: 0,
+                                    },
+                                );
+                                Some(HeartbeatEvent {
+                                    device_id: device_id.clone(),
+                                    online,
+                                    previous: None,
+                                    downtime: None,
+                                    error,
+                                })
+                            }
+                        }
+                    };
+
+                    if let Some(event) = event {
+                        info!(
+                            "设备 {} {}{}",
+                            event.device_id,
+                            if event.online { "在线" } else { "离线" },
+                            event
+                                .downtime
+                                .map(|d| format!("，离线持续 {:?}", d))
+                                .unwrap_or_default()
+                        );
+                        on_change(&event);
+                    }
+                }
+
+                if !sleep_unless_stopped(interval, &stop) {
+                    return;
+                }
+            })
+        };
+
+        Heartbeat {
+            stop,
+            status,
+            worker: Some(worker),
+        }
+    }
+
+    /// 每隔 `interval` 调用一次 `sample`，把结果以 JSON 行写入 `sink`
+    ///
+    /// 每行形如 `{"timestamp":"...","device_id":"...","value":...}`。单次采样失败只记录警告，
+    /// 写入失败时停止采样，错误由 [`Sampler::stop`] 返回。
+    pub fn sample<T, F, S>(&self, device_id: &str, interval: Duration, mut sample: F, mut sink: S) -> Sampler
+    where
+        T: Serialize,
+        F: FnMut(&ADB, &str) -> ADBResult<T> + Send + 'static,
+        S: Sink + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        self.jobs.register_stop_flag(stop.clone());
+
+        let worker = {
+            let adb = self.clone();
+            let device_id = device_id.to_string();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                let mut written = 0u64;
+                loop {
+                    match sample(&adb, &device_id) {
+                        Ok(value) => {
+                            let record = SampleRecord {
+                                timestamp: chrono::Local::now().to_rfc3339(),
+                                device_id: &device_id,
+                                value,
+                            };
+                            let mut line = serde_json::to_vec(&record)
+                                .map_err(|e| ADBError::ParseError(format!("无法序列化采样数据: {}", e)))?;
+                            line.push(b'\n');
+                            sink.write_record(&line)?;
+                            written += 1;
+                        }
+                        Err(e) => warn!("设备 {} 采样失败: {}", device_id, e),
+                    }
+
+                    if !sleep_unless_stopped(interval, &stop) {
+                        break;
+                    }
+                }
+                sink.flush()?;
+                debug!("设备 {} 采样结束，共 {} 条记录", device_id, written);
+                Ok(written)
+            })
+        };
+
+        Sampler {
+            stop,
+            worker: Some(worker),
+        }
+    }
+}
+
+/// 检测到 ANR 后对系统对话框的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnrResponse {
+    /// 不点击对话框
+    Ignore,
+    /// 点击“等待”
+    Wait,
+    /// 点击“关闭应用”
+    Close,
+}
+
+/// 一次 ANR 事件
+#[derive(Debug, Clone)]
+pub struct AnrEvent {
+    pub device_id: String,
+    /// 无响应的应用包名（进程名）
+    pub package: String,
+    pub pid: u32,
+    /// 系统记录的原因（如 "Input dispatching timed out"）
+    pub reason: String,
+    /// 日志时间戳
+    pub log_timestamp: String,
+    pub detected_at: SystemTime,
+    /// 是否检测到系统 ANR 对话框
+    pub dialog_shown: bool,
+    /// `/data/anr` 中最新的 trace，无权限读取时为 None
+    pub traces: Option<String>,
+    /// 实际执行的对话框操作
+    pub response: Option<AnrResponse>,
+}
+
+/// 解析 events 缓冲区中的 `am_anr` 记录：`[user,pid,package,flags,reason]`
+fn parse_am_anr(message: &str) -> Option<(u32, String, String)> {
+    let body = message.trim().trim_start_matches('[').trim_end_matches(']');
+    let mut fields = body.splitn(5, ',');
+    let _user = fields.next()?;
+    let pid = fields.next()?.trim().parse().ok()?;
+    let package = fields.next()?.trim().to_string();
+    let _flags = fields.next();
+    let reason = fields.next().unwrap_or("").trim().to_string();
+    Some((pid, package, reason))
+}
+
+/// 崩溃类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrashKind {
+    /// Java 未捕获异常 (`FATAL EXCEPTION`)
+    Java,
+    /// native 崩溃（tombstone）
+    Native,
+    /// 应用无响应
+    Anr,
+}
+
+/// 从日志中识别的一次崩溃
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashEvent {
+    pub kind: CrashKind,
+    /// 崩溃的进程名，应用进程为包名（或 `包名:进程名`）
+    pub package: String,
+    pub pid: Option<u32>,
+    /// 异常堆栈、native 回溯或 ANR 原因
+    pub stacktrace: String,
+    /// 日志时间戳
+    pub timestamp: String,
+}
+
+impl CrashEvent {
+    /// 是否为指定应用的进程（包括 `包名:进程名` 形式的子进程）
+    pub fn belongs_to(&self, package_name: &str) -> bool {
+        self.package == package_name
+            || self
+                .package
+                .strip_prefix(package_name)
+                .is_some_and(|rest| rest.starts_with(':'))
+    }
+}
+
+/// 从日志中逐条识别崩溃
+///
+/// 同一崩溃的日志由同一进程以同一标签连续输出，遇到其他日志时该崩溃结束。
+#[derive(Debug, Default)]
+pub(crate) struct CrashCollector {
+    // 正在收集的崩溃，以及输出它的日志 pid 和标签
+    pending: Option<(CrashEvent, u32, String)>,
+}
+
+impl CrashCollector {
+    /// 处理一条日志，返回因此结束的崩溃
+    pub(crate) fn feed(&mut self, entry: &LogEntry) -> Option<CrashEvent> {
+        let message = entry.message.as_str();
+        let started = match entry.tag.as_str() {
+            "AndroidRuntime" if message.starts_with("FATAL EXCEPTION") => {
+                Some((CrashKind::Java, "", None))
+            }
+            "ActivityManager" if message.starts_with("ANR in ") => {
+                let process = message["ANR in ".len()..].split_whitespace().next().unwrap_or("");
+                Some((CrashKind::Anr, process, None))
+            }
+            "DEBUG" => NATIVE_PROCESS_RE.captures(message).map(|caps| {
+                let process = caps.get(2).map_or("", |m| m.as_str());
+                (CrashKind::Native, process, caps[1].parse().ok())
+            }),
+            _ => None,
+        };
+
+        if let Some((kind, package, pid)) = started {
+            let event = CrashEvent {
+                kind,
+                package: package.to_string(),
+                pid,
+                stacktrace: String::new(),
+                timestamp: entry.timestamp.clone(),
+            };
+            return self
+                .pending
+                .replace((event, entry.pid, entry.tag.clone()))
+                .map(|(event, _, _)| event);
+        }
+
+        let continues = matches!(
+            &self.pending,
+            Some((_, pid, tag)) if *pid == entry.pid && *tag == entry.tag
+        );
+        if !continues {
+            return self.finish();
+        }
+        let (event, _, _) = self.pending.as_mut()?;
+
+        // "Process: com.example, PID: 1234"
+        if let Some(rest) = message.strip_prefix("Process: ") {
+            let (package, pid) = rest.split_once(", PID: ").unwrap_or((rest, ""));
+            event.package = package.trim().to_string();
+            event.pid = pid.trim().parse().ok();
+            return None;
+        }
+        if let Some(pid) = message.strip_prefix("PID: ") {
+            event.pid = pid.trim().parse().ok();
+        }
+        if event.kind == CrashKind::Java || event.stacktrace.lines().count() < MAX_CRASH_LINES {
+            if !event.stacktrace.is_empty() {
+                event.stacktrace.push('\n');
+            }
+            event.stacktrace.push_str(message);
+        }
+        None
+    }
+
+    /// 结束正在收集的崩溃
+    pub(crate) fn finish(&mut self) -> Option<CrashEvent> {
+        self.pending.take().map(|(event, _, _)| event)
+    }
+}
+
+/// 后台崩溃监控
+///
+/// 在 `stop()`、超出作用域或 [`ADB::shutdown`] 时停止
+pub struct CrashWatcher {
+    stop: Arc<AtomicBool>,
+    child: Arc<Mutex<Child>>,
+    events: Arc<Mutex<Vec<CrashEvent>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl CrashWatcher {
+    /// 已检测到的崩溃
+    pub fn events(&self) -> Vec<CrashEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// 停止监控并等待后台线程退出
+    pub fn stop(&mut self) -> Vec<CrashEvent> {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Ok(mut child) = self.child.lock() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+            debug!("崩溃监控已停止");
+        }
+        self.events()
+    }
+}
+
+impl Drop for CrashWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// StrictMode 违规类型
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ViolationKind {
+    DiskRead,
+    DiskWrite,
+    Network,
+    CustomSlowCall,
+    ResourceMismatch,
+    UnbufferedIo,
+    LeakedClosable,
+    InstanceCount,
+    CleartextNetwork,
+    ContentUriWithoutPermission,
+    UntaggedSocket,
+    NonSdkApiUsed,
+    IncorrectContextUse,
+    UnsafeIntentLaunch,
+    /// 其他违规，保存去掉 `Violation` 后缀的类名
+    Other(String),
+}
+
+impl ViolationKind {
+    /// 根据违规类名判断类型，兼容 `android.os.strictmode.DiskReadViolation` 和
+    /// 旧版本的 `android.os.StrictMode$StrictModeDiskReadViolation`
+    pub fn from_class_name(class_name: &str) -> Self {
+        let simple = class_name.rsplit(['.', '$']).next().unwrap_or(class_name);
+        let simple = simple.strip_prefix("StrictMode").unwrap_or(simple);
+        let simple = simple.strip_suffix("Violation").unwrap_or(simple);
+        match simple {
+            "DiskRead" => ViolationKind::DiskRead,
+            "DiskWrite" => ViolationKind::DiskWrite,
+            "Network" => ViolationKind::Network,
+            "CustomSlowCall" | "CustomViolation" => ViolationKind::CustomSlowCall,
+            "ResourceMismatch" => ViolationKind::ResourceMismatch,
+            "UnbufferedIo" => ViolationKind::UnbufferedIo,
+            "LeakedClosable" => ViolationKind::LeakedClosable,
+            "InstanceCount" => ViolationKind::InstanceCount,
+            "CleartextNetwork" => ViolationKind::CleartextNetwork,
+            "ContentUriWithoutPermission" => ViolationKind::ContentUriWithoutPermission,
+            "UntaggedSocket" => ViolationKind::UntaggedSocket,
+            "NonSdkApiUsed" => ViolationKind::NonSdkApiUsed,
+            "IncorrectContextUse" => ViolationKind::IncorrectContextUse,
+            "UnsafeIntentLaunch" => ViolationKind::UnsafeIntentLaunch,
+            other => ViolationKind::Other(other.to_string()),
+        }
+    }
+}
+
+/// 一次 StrictMode 违规
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrictModeViolation {
+    pub kind: ViolationKind,
+    /// 违规类名
+    pub class_name: String,
+    /// 违规说明（类名之后的文字）
+    pub message: String,
+    /// 线程策略违规的耗时
+    pub duration_ms: Option<u64>,
+    pub pid: u32,
+    /// 日志时间戳
+    pub timestamp: String,
+    pub stack: Vec<String>,
+}
+
+/// 解析 StrictMode 日志，如
+/// "StrictMode policy violation; ~duration=120 ms: android.os.strictmode.DiskReadViolation"
+fn parse_strictmode_logs(output: &str) -> Vec<StrictModeViolation> {
+    let mut violations: Vec<StrictModeViolation> = Vec::new();
+    let mut current: Option<usize> = None;
+
+    for entry in output.lines().filter_map(LogEntry::parse) {
+        if entry.tag != "StrictMode" {
+            continue;
+        }
+        if let Some(rest) = entry.message.strip_prefix("StrictMode policy violation") {
+            let duration_ms = STRICTMODE_DURATION_RE
+                .captures(rest)
+                .and_then(|caps| caps[1].parse().ok());
+            // 类名在最后一个 "ms: " 或首个 ": " 之后
+            let detail = rest
+                .split_once("ms: ")
+                .or_else(|| rest.split_once(": "))
+                .map_or("", |(_, d)| d)
+                .trim();
+            let (class_name, message) = detail.split_once(": ").unwrap_or((detail, ""));
+
+            violations.push(StrictModeViolation {
+                kind: ViolationKind::from_class_name(class_name),
+                class_name: class_name.to_string(),
+                message: message.trim().to_string(),
+                duration_ms,
+                pid: entry.pid,
+                timestamp: entry.timestamp.clone(),
+                stack: Vec::new(),
+            });
+            current = Some(violations.len() - 1);
+            continue;
+        }
+
+        match current.map(|i| &mut violations[i]) {
+            Some(violation) if violation.pid == entry.pid && entry.message.trim_start().starts_with("at ") => {
+                violation.stack.push(entry.message.trim().to_string());
+            }
+            _ => current = None,
+        }
+    }
+
+    violations
+}
+
+/// 后台 ANR 监控
+///
+/// 在 `stop()`、超出作用域或 [`ADB::shutdown`] 时停止
+pub struct AnrWatcher {
+    stop: Arc<AtomicBool>,
+    child: Arc<Mutex<Child>>,
+    events: Arc<Mutex<Vec<AnrEvent>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl AnrWatcher {
+    /// 已检测到的 ANR
+    pub fn events(&self) -> Vec<AnrEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// 停止监控并等待后台线程退出
+    pub fn stop(&mut self) -> Vec<AnrEvent> {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Ok(mut child) = self.child.lock() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+            debug!("ANR 监控已停止");
+        }
+        self.events()
+    }
+}
+
+impl Drop for AnrWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl ADB {
+    /// 监控系统 ANR（应用无响应）
+    ///
+    /// 通过 events 缓冲区中的 `am_anr` 记录发现 ANR，再通过 UI 层级确认系统对话框，
+    /// 尝试读取 `/data/anr` 中的 trace，并按 `response` 点击对话框按钮。
+    /// 每次 ANR 调用一次 `on_anr`。
+    pub fn watch_anr_dialogs<F>(
+        &self,
+        device_id: &str,
+        response: AnrResponse,
+        mut on_anr: F,
+    ) -> ADBResult<AnrWatcher>
+    where
+        F: FnMut(&AnrEvent) + Send + 'static,
+    {
+        // 只关注开始监控之后的记录
+        let now = self.shell(device_id, "date '+%m-%d %H:%M:%S.000'")?;
+        let query = LogcatQuery::new()
+            .buffer(LogBuffer::Events)
+            .format(LogFormat::ThreadTime)
+            .since(now.trim())
+            .tag("am_anr", LogPriority::Info)
+            .silence_others();
+
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+        let mut child = cmd
+            .arg("exec-out")
+            .arg(query.to_command(false))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| ADBError::CommandError(format!("无法启动 ANR 监控: {}", e)))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取 ANR 监控输出".to_string()))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        self.jobs.register_stop_flag(stop.clone());
+        let child = Arc::new(Mutex::new(child));
+        let events: Arc<Mutex<Vec<AnrEvent>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let worker = {
+            let adb = self.clone();
+            let device_id = device_id.to_string();
+            let stop = stop.clone();
+            let events = events.clone();
+
+            thread::spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(line) = line else { break };
+                    let Some(entry) = LogEntry::parse(&line).filter(|e| e.tag == "am_anr") else {
+                        continue;
+                    };
+                    let Some((pid, package, reason)) = parse_am_anr(&entry.message) else {
+                        continue;
+                    };
+
+                    warn!("设备 {} 上的 {} (pid {}) 无响应: {}", device_id, package, pid, reason);
+                    let event = adb.handle_anr(&device_id, pid, package, reason, entry.timestamp, response);
+                    on_anr(&event);
+                    events.lock().unwrap().push(event);
+                }
+            })
+        };
+
+        info!("开始监控设备 {} 的 ANR", device_id);
+        Ok(AnrWatcher {
+            stop,
+            child,
+            events,
+            worker: Some(worker),
+        })
+    }
+
+    /// 监控应用崩溃
+    ///
+    /// 在后台读取日志，识别 Java 未捕获异常、native 崩溃和 ANR。`packages` 为空时报告所有进程，
+    /// 否则只报告这些应用（包括其子进程）。崩溃日志在同一进程输出其他日志后才算结束，
+    /// 因此回调可能略晚于崩溃发生。
+    pub fn watch_crashes<F>(&self, device_id: &str, packages: &[&str], mut callback: F) -> ADBResult<CrashWatcher>
+    where
+        F: FnMut(&CrashEvent) + Send + 'static,
+    {
+        // 只关注开始监控之后的记录
+        let now = self.shell(device_id, "date '+%m-%d %H:%M:%S.000'")?;
+        let query = LogcatQuery::new()
+            .buffer(LogBuffer::Default)
+            .format(LogFormat::ThreadTime)
+            .since(now.trim());
+
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+        let mut child = cmd
+            .arg("exec-out")
+            .arg(query.to_command(false))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| ADBError::CommandError(format!("无法启动崩溃监控: {}", e)))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取崩溃监控输出".to_string()))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        self.jobs.register_stop_flag(stop.clone());
+        let child = Arc::new(Mutex::new(child));
+        let events: Arc<Mutex<Vec<CrashEvent>>> = Arc::new(Mutex::new(Vec::new()));
+        let packages: Vec<String> = packages.iter().map(|p| p.to_string()).collect();
+
+        let worker = {
+            let device_id = device_id.to_string();
+            let stop = stop.clone();
+            let events = events.clone();
+
+            thread::spawn(move || {
+                let mut collector = CrashCollector::default();
+                let mut report = |event: CrashEvent| {
+                    if !packages.is_empty() && !packages.iter().any(|p| event.belongs_to(p)) {
+                        return;
+                    }
+                    warn!(
+                        "设备 {} 上的 {} (pid {:?}) 发生 {:?} 崩溃",
+                        device_id, event.package, event.pid, event.kind
+                    );
+                    callback(&event);
+                    events.lock().unwrap().push(event);
+                };
+
+                for line in BufReader::new(stdout).lines() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(line) = line else { break };
+                    if let Some(event) = LogEntry::parse(&line).and_then(|entry| collector.feed(&entry)) {
+                        report(event);
+                    }
+                }
+                if let Some(event) = collector.finish() {
+                    report(event);
+                }
+            })
+        };
+
+        info!("开始监控设备 {} 的崩溃", device_id);
+        Ok(CrashWatcher {
+            stop,
+            child,
+            events,
+            worker: Some(worker),
+        })
+    }
+
+    /// 在 `window` 时间内收集应用的 StrictMode 违规
+    ///
+    /// 收集期间把 `StrictMode` 标签的日志级别设为 VERBOSE，全局禁用了 StrictMode 时尝试以 root
+    /// 身份重新启用，两者都在收集结束后恢复原值。违规只会由应用自身开启的 StrictMode 策略
+    /// （或调试版系统的默认策略）产生。
+    pub fn collect_strictmode_violations(
+        &self,
+        device_id: &str,
+        package_name: &str,
+        window: Duration,
+    ) -> ADBResult<Vec<StrictModeViolation>> {
+        let now = self.shell(device_id, "date '+%m-%d %H:%M:%S.000'")?;
+        let log_level = self.get_prop(device_id, STRICTMODE_LOG_PROP)?;
+        self.shell(device_id, &format!("setprop {} VERBOSE", STRICTMODE_LOG_PROP))?;
+
+        // persist 属性在重启后仍然生效，收集结束后必须恢复为原值
+        let disable_restore = if self.get_prop(device_id, STRICTMODE_DISABLE_PROP)?.trim() == "true" {
+            match self.root_command(device_id, &format!("setprop {} false", STRICTMODE_DISABLE_PROP)) {
+                Some(command) => self.shell(device_id, &command).ok().and_then(|_| {
+                    self.root_command(device_id, &format!("setprop {} true", STRICTMODE_DISABLE_PROP))
+                }),
+                None => {
+                    warn!("设备 {} 全局禁用了 StrictMode，且无 root 权限重新启用", device_id);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // 先收集结果，无论成功与否都恢复属性
+        let collected = (|| -> ADBResult<(Vec<u32>, String)> {
+            let mut pids: Vec<u32> = Vec::new();
+            let mut record_pids = || -> ADBResult<()> {
+                for process in self.app_processes(device_id, package_name)? {
+                    if !pids.contains(&(process.pid as u32)) {
+                        pids.push(process.pid as u32);
+                    }
+                }
+                Ok(())
+            };
+            record_pids()?;
+            info!("开始收集应用 {} 的 StrictMode 违规，持续 {:?}", package_name, window);
+            thread::sleep(window);
+            record_pids()?;
+
+            let query = LogcatQuery::new()
+                .buffer(LogBuffer::Main)
+                .format(LogFormat::ThreadTime)
+                .since(now.trim())
+                .tag("StrictMode", LogPriority::Verbose)
+                .silence_others();
+            let output = self.query_logs(device_id, &query)?;
+            Ok((pids, output))
+        })();
+
+        let level = log_level.trim();
+        let restored = self.shell(
+            device_id,
+            &format!(
+                "setprop {} {}",
+                STRICTMODE_LOG_PROP,
+                if level.is_empty() { "\"\"" } else { level }
+            ),
+        );
+        if let Some(command) = disable_restore {
+            if let Err(e) = self.shell(device_id, &command) {
+                warn!("设备 {} 恢复 {} 失败: {}", device_id, STRICTMODE_DISABLE_PROP, e);
+            }
+        }
+        let (pids, output) = collected?;
+        restored?;
+
+        if pids.is_empty() {
+            warn!("应用 {} 在收集期间没有运行", package_name);
+        }
+        let violations: Vec<StrictModeViolation> = parse_strictmode_logs(&output)
+            .into_iter()
+            .filter(|v| pids.contains(&v.pid))
+            .collect();
+        debug!("应用 {} 共 {} 次 StrictMode 违规", package_name, violations.len());
+        Ok(violations)
+    }
+
+    /// 确认 ANR 对话框、读取 trace 并按配置点击按钮
+    fn handle_anr(
+        &self,
+        device_id: &str,
+        pid: u32,
+        package: String,
+        reason: String,
+        log_timestamp: String,
+        response: AnrResponse,
+    ) -> AnrEvent {
+        let detected_at = SystemTime::now();
+        thread::sleep(ANR_DIALOG_DELAY);
+
+        let traces = self
+            .shell(
+                device_id,
+                "f=$(ls -t /data/anr/ 2>/dev/null | head -n 1); [ -n \"$f\" ] && cat \"/data/anr/$f\" 2>/dev/null; true",
+            )
+            .ok()
+            .filter(|t| !t.trim().is_empty());
+
+        let (wait, close) = (
+            Selector::resource_id("android:id/aerr_wait"),
+            Selector::resource_id("android:id/aerr_close"),
+        );
+        let (dialog_shown, action) = match self.dump_ui_hierarchy(device_id) {
+            Ok(root) => {
+                let wait_button = root.find(&wait).cloned();
+                let close_button = root.find(&close).cloned();
+                let shown = wait_button.is_some() || close_button.is_some();
+                let target = match response {
+                    AnrResponse::Ignore => None,
+                    AnrResponse::Wait => wait_button,
+                    AnrResponse::Close => close_button,
+                };
+                let action = target.and_then(|button| match self.tap_element(device_id, &button) {
+                    Ok(()) => Some(response),
+                    Err(e) => {
+                        warn!("点击 ANR 对话框按钮失败: {}", e);
+                        None
+                    }
+                });
+                (shown, action)
+            }
+            Err(e) => {
+                debug!("获取 UI 层级失败，无法确认 ANR 对话框: {}", e);
+                (false, None)
+            }
+        };
+
+        AnrEvent {
+            device_id: device_id.to_string(),
+            package,
+            pid,
+            reason,
+            log_timestamp,
+            detected_at,
+            dialog_shown,
+            traces,
+            response: action,
+        }
+    }
+}
+
+/// 设备连接变化
+#[derive(Debug, Clone)]
+pub enum DeviceChange {
+    /// 新设备出现
+    Connected(ADBDevice),
+    /// 设备断开
+    Disconnected(ADBDevice),
+    /// 设备状态变化（如 unauthorized -> device）
+    StateChanged(ADBDevice),
+}
+
+impl DeviceChange {
+    /// 变化涉及的设备
+    pub fn device(&self) -> &ADBDevice {
+        match self {
+            DeviceChange::Connected(device)
+            | DeviceChange::Disconnected(device)
+            | DeviceChange::StateChanged(device) => device,
+        }
+    }
+}
+
+/// 对比两次设备列表，生成变化事件
+fn diff_devices(previous: &HashMap<String, ADBDevice>, current: &HashMap<String, ADBDevice>) -> Vec<DeviceChange> {
+    let mut changes = Vec::new();
+
+    for (id, device) in current {
+        match previous.get(id) {
+            None => changes.push(DeviceChange::Connected(device.clone())),
+            Some(old) if old.status != device.status => {
+                changes.push(DeviceChange::StateChanged(device.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    for (id, device) in previous {
+        if !current.contains_key(id) {
+            changes.push(DeviceChange::Disconnected(device.clone()));
+        }
+    }
+
+    changes
+}
+
+/// 读取 track-devices 的一个数据块（4 位十六进制长度 + 设备列表），连接断开时返回 None
+fn read_track_block<R: Read>(reader: &mut R) -> Option<String> {
+    let mut header = [0u8; 4];
+    reader.read_exact(&mut header).ok()?;
+    let len = usize::from_str_radix(std::str::from_utf8(&header).ok()?, 16).ok()?;
+
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body).ok()?;
+    Some(String::from_utf8_lossy(&body).to_string())
+}
+
+/// 后台设备连接跟踪
+///
+/// 在 `stop()`、超出作用域或 [`ADB::shutdown`] 时停止
+pub struct DeviceTracker {
+    stop: Arc<AtomicBool>,
+    child: Arc<Mutex<Option<Child>>>,
+    devices: Arc<Mutex<HashMap<String, ADBDevice>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl DeviceTracker {
+    /// 当前已连接的设备
+    pub fn devices(&self) -> Vec<ADBDevice> {
+        self.devices.lock().unwrap().values().cloned().collect()
+    }
+
+    /// 跟踪线程是否仍在运行
+    pub fn is_alive(&self) -> bool {
+        self.worker.as_ref().is_some_and(|w| !w.is_finished())
+    }
+
+    /// 停止跟踪并等待后台线程退出
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(child) = self.child.lock().unwrap().as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+            debug!("设备跟踪已停止");
+        }
+    }
+}
+
+impl Drop for DeviceTracker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl ADB {
+    /// 在后台跟踪设备的连接、断开和状态变化
+    ///
+    /// 基于 `adb track-devices -l`，由 adb server 主动推送变化，无需轮询。
+    /// 启动时已连接的设备会先各产生一次 `Connected`；adb server 重启后自动重新连接。
+    pub fn track_devices<F>(&self, mut on_change: F) -> DeviceTracker
+    where
+        F: FnMut(&DeviceChange) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        self.jobs.register_stop_flag(stop.clone());
+        let child: Arc<Mutex<Option<Child>>> = Arc::new(Mutex::new(None));
+        let devices: Arc<Mutex<HashMap<String, ADBDevice>>> = Arc::new(Mutex::new(HashMap::new()));
+
+        let worker = {
+            let adb = self.clone();
+            let stop = stop.clone();
+            let child = child.clone();
+            let devices = devices.clone();
+
+            thread::spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    let spawned = adb
+                        .adb_command()
+                        .arg("track-devices")
+                        .arg("-l")
+                        .stdout(Stdio::piped())
+                        .stderr(Stdio::null())
+                        .spawn();
+                    let mut process = match spawned {
+                        Ok(process) => process,
+                        Err(e) => {
+                            warn!("无法启动 adb track-devices: {}", e);
+                            sleep_unless_stopped(TRACK_RECONNECT_DELAY, &stop);
+                            continue;
+                        }
+                    };
+                    let Some(mut stdout) = process.stdout.take() else {
+                        let _ = process.kill();
+                        break;
+                    };
+                    *child.lock().unwrap() = Some(process);
+
+                    while let Some(block) = read_track_block(&mut stdout) {
+                        if stop.load(Ordering::SeqCst) {
+                            break;
+                        }
+                        let current: HashMap<String, ADBDevice> = block
+                            .lines()
+                            .filter_map(crate::cmd::parse_device_line)
+                            .map(|d| (d.id.clone(), d))
+                            .collect();
+
+                        let changes = diff_devices(&devices.lock().unwrap(), &current);
+                        *devices.lock().unwrap() = current;
+                        for change in &changes {
+                            debug!("设备变化: {:?}", change);
+                            on_change(change);
+                        }
+                    }
+
+                    if let Some(mut process) = child.lock().unwrap().take() {
+                        let _ = process.kill();
+                        let _ = process.wait();
+                    }
+                    if !stop.load(Ordering::SeqCst) {
+                        debug!("track-devices 连接断开，重新连接");
+                        sleep_unless_stopped(TRACK_RECONNECT_DELAY, &stop);
+                    }
+                }
+            })
+        };
+
+        info!("开始跟踪设备连接");
+        DeviceTracker {
+            stop,
+            child,
+            devices,
+            worker: Some(worker),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Monkey 压力测试
+//!
+//! 运行 `monkey` 向应用注入随机事件，同时实时读取 logcat 捕获崩溃、ANR 和 native 崩溃的堆栈。
+//! 报告中记录本次使用的种子，以相同种子和选项重新运行可以复现同样的事件序列。
+
+use crate::device::ADB;
+use crate::error::ADBResult;
+use crate::logcat::{LogBuffer, LogEntry, LogFormat, LogcatQuery};
+use crate::monitor::{CrashCollector, CrashEvent, CrashKind};
+use crate::utils::shell_quote;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 启动 monkey 前等待 logcat 连接的时间
+const LOGCAT_ATTACH_DELAY: Duration = Duration::from_millis(500);
+// monkey 结束后等待崩溃日志写完的时间
+const LOGCAT_FLUSH_DELAY: Duration = Duration::from_secs(2);
+
+static EVENTS_INJECTED_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Events injected: (\d+)").unwrap());
+
+// monkey 输出中的问题，如 "// CRASH: com.example (pid 1234)"
+static MONKEY_ISSUE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^// (CRASH|NOT RESPONDING): (\S+) \(pid (\d+)\)").unwrap());
+
+/// Monkey 运行选项
+#[derive(Debug, Clone)]
+pub struct MonkeyOptions {
+    /// 被测应用包名
+    pub package: String,
+    /// 注入的事件数
+    pub event_count: u32,
+    /// 事件之间的间隔 (--throttle)
+    pub throttle: Duration,
+    /// 随机种子 (-s)，None 时随机生成并记录在报告中
+    pub seed: Option<u64>,
+    /// 允许启动的 Activity 类别 (-c)，为空时使用 monkey 默认值
+    pub categories: Vec<String>,
+}
+
+impl MonkeyOptions {
+    /// 创建默认选项：1000 个事件，间隔 100 毫秒
+    pub fn new(package: &str) -> Self {
+        MonkeyOptions {
+            package: package.to_string(),
+            event_count: 1000,
+            throttle: Duration::from_millis(100),
+            seed: None,
+            categories: Vec::new(),
+        }
+    }
+
+    /// 设置事件数
+    pub fn event_count(mut self, count: u32) -> Self {
+        self.event_count = count;
+        self
+    }
+
+    /// 设置事件间隔
+    pub fn throttle(mut self, throttle: Duration) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// 设置随机种子
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// 添加 Activity 类别
+    pub fn category(mut self, category: &str) -> Self {
+        self.categories.push(category.to_string());
+        self
+    }
+}
+
+/// Monkey 运行报告
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonkeyReport {
+    pub package: String,
+    /// 本次使用的种子
+    pub seed: u64,
+    /// 计划注入的事件数
+    pub event_count: u32,
+    /// 实际注入的事件数
+    pub events_injected: u32,
+    /// monkey 是否注入了全部事件（遇到崩溃或 ANR 时会提前中止）
+    pub completed: bool,
+    /// 捕获的崩溃和 ANR
+    pub issues: Vec<CrashEvent>,
+    pub elapsed: Duration,
+}
+
+impl MonkeyReport {
+    /// 是否没有任何崩溃或 ANR
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// 指定类型的问题
+    pub fn issues_of(&self, kind: CrashKind) -> impl Iterator<Item = &CrashEvent> {
+        self.issues.iter().filter(move |i| i.kind == kind)
+    }
+}
+
+/// 解析 monkey 输出中的崩溃和 ANR，用于补充 logcat 中缺失的问题（没有时间戳）
+fn parse_monkey_issues(output: &str) -> Vec<CrashEvent> {
+    let mut issues: Vec<CrashEvent> = Vec::new();
+    let mut in_issue = false;
+
+    for line in output.lines() {
+        if let Some(caps) = MONKEY_ISSUE_RE.captures(line) {
+            issues.push(CrashEvent {
+                kind: if &caps[1] == "CRASH" { CrashKind::Java } else { CrashKind::Anr },
+                package: caps[2].to_string(),
+                pid: caps[3].parse().ok(),
+                stacktrace: String::new(),
+                timestamp: String::new(),
+            });
+            in_issue = true;
+            continue;
+        }
+
+        match (in_issue, line.strip_prefix("// "), issues.last_mut()) {
+            (true, Some(detail), Some(issue)) => {
+                if !issue.stacktrace.is_empty() {
+                    issue.stacktrace.push('\n');
+                }
+                issue.stacktrace.push_str(detail);
+            }
+            _ => in_issue = false,
+        }
+    }
+
+    issues
+}
+
+impl ADB {
+    /// 运行 monkey 压力测试，同时从 logcat 捕获应用的崩溃和 ANR
+    pub fn run_monkey(&self, device_id: &str, options: MonkeyOptions) -> ADBResult<MonkeyReport> {
+        let seed = options.seed.unwrap_or_else(|| rand::random::<u32>() as u64);
+
+        let mut command = format!("monkey -p {} -s {}", shell_quote(&options.package), seed);
+        for category in &options.categories {
+            command.push_str(&format!(" -c {}", shell_quote(category)));
+        }
+        command.push_str(&format!(
+            " --throttle {} -v {} 2>&1; true",
+            options.throttle.as_millis(),
+            options.event_count
+        ));
+        let logcat = LogcatQuery::new()
+            .buffer(LogBuffer::Default)
+            .format(LogFormat::ThreadTime)
+            .tail(1)
+            .to_command(false);
+
+        info!(
+            "设备 {} 开始 monkey 测试: {}，{} 个事件，种子 {}",
+            device_id, options.package, options.event_count, seed
+        );
+        let start = Instant::now();
+        let cancel = AtomicBool::new(false);
+
+        let (output, crashes) = thread::scope(|scope| {
+            let watcher = scope.spawn(|| {
+                let mut collector = CrashCollector::default();
+                let mut crashes = Vec::new();
+                let result = self.shell_stream_until(device_id, &logcat, &cancel, |line| {
+                    crashes.extend(LogEntry::parse(line).and_then(|entry| collector.feed(&entry)));
+                });
+                if let Err(e) = result {
+                    warn!("设备 {} 日志监听结束: {}", device_id, e);
+                }
+                crashes.extend(collector.finish());
+                crashes
+            });
+
+            thread::sleep(LOGCAT_ATTACH_DELAY);
+            let output = self.shell(device_id, &command);
+            thread::sleep(LOGCAT_FLUSH_DELAY);
+            cancel.store(true, Ordering::SeqCst);
+            (output, watcher.join().unwrap_or_default())
+        });
+        let output = output?;
+
+        let mut issues: Vec<CrashEvent> = crashes
+            .into_iter()
+            .filter(|crash| crash.belongs_to(&options.package))
+            .collect();
+        for issue in parse_monkey_issues(&output) {
+            let duplicate = issues
+                .iter()
+                .any(|i| i.kind == issue.kind && i.pid.is_some() && i.pid == issue.pid);
+            if !duplicate {
+                debug!("logcat 中未捕获到的问题: {:?} {}", issue.kind, issue.package);
+                issues.push(issue);
+            }
+        }
+
+        let events_injected = EVENTS_INJECTED_RE
+            .captures(&output)
+            .and_then(|caps| caps[1].parse().ok())
+            .unwrap_or(0);
+        let report = MonkeyReport {
+            package: options.package.clone(),
+            seed,
+            event_count: options.event_count,
+            events_injected,
+            completed: output.contains("// Monkey finished"),
+            issues,
+            elapsed: start.elapsed(),
+        };
+
+        if report.is_clean() {
+            info!("设备 {} monkey 测试完成: 注入 {} 个事件，无崩溃", device_id, events_injected);
+        } else {
+            warn!(
+                "设备 {} monkey 测试发现 {} 个问题 (种子 {})",
+                device_id,
+                report.issues.len(),
+                seed
+            );
+        }
+        Ok(report)
+    }
+}
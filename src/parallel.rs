@@ -1,9 +1,464 @@
 use crate::device::ADB;
 use crate::error::{ADBError, ADBResult};
 use crate::app::PackageInfo;
+use crate::transfer::TransferStats;
+use chrono::NaiveDate;
 use log::{debug, warn};
 use rayon::prelude::*;
 use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+// 同步触发前确认 shell 已就绪的标记
+const SHELL_READY_MARKER: &str = "__ADBKIT_READY__";
+
+// 单个设备的触发时刻（单调时钟、系统时间）与命令输出
+type TriggerOutcome = (Instant, SystemTime, ADBResult<String>);
+
+/// 按完成顺序返回各设备结果的迭代器，由 [`ADB::parallel_stream`] 等方法创建
+///
+/// 超过截止时间仍未完成的设备以 `TimeoutError` 返回，其后台线程会继续运行到命令结束，
+/// 结果被丢弃。
+pub struct ParallelStream<T> {
+    receiver: Receiver<(String, ADBResult<T>)>,
+    /// 尚未返回结果的设备
+    pending: Vec<String>,
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl<T> ParallelStream<T> {
+    /// 尚未返回结果的设备
+    pub fn pending(&self) -> &[String] {
+        &self.pending
+    }
+
+    /// 从待返回列表中移除设备，设备已不在列表中（如已报告超时）时返回 false
+    fn take_pending(&mut self, id: &str) -> bool {
+        match self.pending.iter().position(|p| p == id) {
+            Some(index) => {
+                self.pending.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T> Iterator for ParallelStream<T> {
+    type Item = (String, ADBResult<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.pending.is_empty() {
+                return None;
+            }
+
+            let received = match self.deadline {
+                Some((deadline, _)) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    self.receiver.recv_timeout(remaining)
+                }
+                None => self.receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+
+            return match received {
+                Ok((id, result)) => {
+                    // 已报告超时的设备迟到的结果直接丢弃
+                    if !self.take_pending(&id) {
+                        debug!("丢弃设备 {} 超时后到达的结果", id);
+                        continue;
+                    }
+                    Some((id, result))
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let id = self.pending.remove(0);
+                    let duration = self.deadline.map(|(_, d)| d).unwrap_or_default();
+                    Some((
+                        id.clone(),
+                        Err(ADBError::TimeoutError {
+                            message: format!("设备 {} 未在截止时间内完成", id),
+                            duration,
+                        }),
+                    ))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    let id = self.pending.remove(0);
+                    Some((
+                        id.clone(),
+                        Err(ADBError::UnknownError(format!("设备 {} 的执行线程异常退出", id))),
+                    ))
+                }
+            };
+        }
+    }
+}
+
+/// 单个设备的同步触发结果
+#[derive(Debug)]
+pub struct DeviceTrigger {
+    /// 主机写入命令的时间
+    pub triggered_at: SystemTime,
+    /// 相对最早触发设备的延迟
+    pub skew: Duration,
+    /// 命令输出
+    pub output: ADBResult<String>,
+}
+
+/// 同步触发报告
+#[derive(Debug)]
+pub struct SyncTriggerReport {
+    pub devices: HashMap<String, DeviceTrigger>,
+    /// 最大触发偏差
+    pub max_skew: Duration,
+    /// 最大偏差是否在容差之内
+    pub within_tolerance: bool,
+}
+
+/// 已知漏洞构建规则，匹配的设备会在审计记录中标记
+#[derive(Debug, Clone)]
+pub struct VulnerabilityRule {
+    /// 规则标识（通常为 CVE 编号）
+    pub id: String,
+    pub description: String,
+    /// 安全补丁早于该日期时视为受影响
+    pub patched_in: Option<NaiveDate>,
+    /// 仅匹配指定制造商（不区分大小写）
+    pub manufacturer: Option<String>,
+    /// 仅匹配以该前缀开头的 SoC 平台（`ro.hardware`）
+    pub hardware_prefix: Option<String>,
+    /// 仅匹配以该前缀开头的构建指纹
+    pub fingerprint_prefix: Option<String>,
+    /// 仅匹配 SDK 版本不高于该值的设备
+    pub max_sdk: Option<u32>,
+}
+
+impl VulnerabilityRule {
+    /// 按安全补丁日期判断的规则
+    pub fn patched_in(id: &str, description: &str, date: NaiveDate) -> Self {
+        Self {
+            id: id.to_string(),
+            description: description.to_string(),
+            patched_in: Some(date),
+            manufacturer: None,
+            hardware_prefix: None,
+            fingerprint_prefix: None,
+            max_sdk: None,
+        }
+    }
+
+    /// 限定制造商
+    pub fn with_manufacturer(mut self, manufacturer: &str) -> Self {
+        self.manufacturer = Some(manufacturer.to_string());
+        self
+    }
+
+    /// 限定 SoC 平台前缀
+    pub fn with_hardware_prefix(mut self, prefix: &str) -> Self {
+        self.hardware_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// 限定构建指纹前缀
+    pub fn with_fingerprint_prefix(mut self, prefix: &str) -> Self {
+        self.fingerprint_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// 限定最高 SDK 版本
+    pub fn with_max_sdk(mut self, sdk: u32) -> Self {
+        self.max_sdk = Some(sdk);
+        self
+    }
+
+    /// 内置规则：几个广泛利用的本地提权漏洞
+    pub fn defaults() -> Vec<Self> {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap_or_default();
+        vec![
+            Self::patched_in("CVE-2019-2215", "Binder use-after-free 本地提权", date(2019, 10, 1)),
+            Self::patched_in("CVE-2020-0069", "MediaTek CMDQ 驱动提权 (mtk-su)", date(2020, 3, 1))
+                .with_hardware_prefix("mt"),
+            Self::patched_in("CVE-2021-1048", "epoll use-after-free 本地提权", date(2021, 11, 1)),
+            Self::patched_in("CVE-2023-21036", "Markup 截图裁剪信息泄露 (aCropalypse)", date(2023, 3, 1))
+                .with_manufacturer("google"),
+        ]
+    }
+
+    /// 判断审计记录是否受影响；缺少判断所需信息时不匹配
+    pub fn matches(&self, record: &AuditRecord) -> bool {
+        if let Some(manufacturer) = &self.manufacturer {
+            if !record.manufacturer.eq_ignore_ascii_case(manufacturer) {
+                return false;
+            }
+        }
+        if let Some(prefix) = &self.hardware_prefix {
+            if !record.hardware.to_lowercase().starts_with(&prefix.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(prefix) = &self.fingerprint_prefix {
+            if !record.fingerprint.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+        if let Some(max_sdk) = self.max_sdk {
+            if record.sdk_int == 0 || record.sdk_int > max_sdk {
+                return false;
+            }
+        }
+        match self.patched_in {
+            Some(date) => record.security_patch.is_some_and(|patch| patch < date),
+            None => true,
+        }
+    }
+}
+
+/// 单个设备的合规审计记录
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    pub device_id: String,
+    /// Android 版本号（`ro.build.version.release`）
+    pub android_version: String,
+    pub sdk_int: u32,
+    pub security_patch: Option<NaiveDate>,
+    /// 安全补丁距今天数
+    pub patch_age_days: Option<i64>,
+    /// Bootloader 是否锁定，无法判断时为 None
+    pub bootloader_locked: Option<bool>,
+    /// 验证启动状态（green / yellow / orange / red）
+    pub verified_boot_state: Option<String>,
+    /// 是否检测到 su
+    pub rooted: bool,
+    /// 是否为 test-keys 签名的构建
+    pub test_keys: bool,
+    pub manufacturer: String,
+    /// SoC 平台（`ro.hardware`）
+    pub hardware: String,
+    pub fingerprint: String,
+    /// 匹配到的已知漏洞规则 ID
+    pub vulnerabilities: Vec<String>,
+    /// 采集失败时的错误信息
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    fn failed(device_id: &str, error: &ADBError) -> Self {
+        Self {
+            device_id: device_id.to_string(),
+            android_version: String::new(),
+            sdk_int: 0,
+            security_patch: None,
+            patch_age_days: None,
+            bootloader_locked: None,
+            verified_boot_state: None,
+            rooted: false,
+            test_keys: false,
+            manufacturer: String::new(),
+            hardware: String::new(),
+            fingerprint: String::new(),
+            vulnerabilities: Vec::new(),
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// 是否通过审计：采集成功、bootloader 已锁定、未 root、非 test-keys 且没有已知漏洞
+    pub fn is_compliant(&self) -> bool {
+        self.error.is_none()
+            && self.bootloader_locked == Some(true)
+            && !self.rooted
+            && !self.test_keys
+            && self.vulnerabilities.is_empty()
+    }
+}
+
+// 审计时依次读取的属性，与 `parse_audit_output` 的行顺序一致
+const AUDIT_PROPS: [&str; 10] = [
+    "ro.build.version.release",
+    "ro.build.version.sdk",
+    "ro.build.version.security_patch",
+    "ro.boot.verifiedbootstate",
+    "ro.boot.flash.locked",
+    "ro.boot.vbmeta.device_state",
+    "ro.build.tags",
+    "ro.product.manufacturer",
+    "ro.hardware",
+    "ro.build.fingerprint",
+];
+
+/// 解析审计脚本输出：每行一个属性值，最后一行为 su 路径
+fn parse_audit_output(device_id: &str, output: &str) -> AuditRecord {
+    let lines: Vec<&str> = output.lines().map(|l| l.trim()).collect();
+    let value = |i: usize| lines.get(i).copied().unwrap_or("").to_string();
+    let non_empty = |v: String| if v.is_empty() { None } else { Some(v) };
+
+    let security_patch = NaiveDate::parse_from_str(&value(2), "%Y-%m-%d").ok();
+    let patch_age_days =
+        security_patch.map(|date| (chrono::Local::now().date_naive() - date).num_days());
+
+    let bootloader_locked = match (value(4).as_str(), value(5).as_str()) {
+        ("1", _) | (_, "locked") => Some(true),
+        ("0", _) | (_, "unlocked") => Some(false),
+        _ => match value(3).as_str() {
+            "green" => Some(true),
+            "orange" => Some(false),
+            _ => None,
+        },
+    };
+
+    AuditRecord {
+        device_id: device_id.to_string(),
+        android_version: value(0),
+        sdk_int: value(1).parse().unwrap_or(0),
+        security_patch,
+        patch_age_days,
+        bootloader_locked,
+        verified_boot_state: non_empty(value(3)),
+        rooted: !value(AUDIT_PROPS.len()).is_empty(),
+        test_keys: value(6).contains("test-keys"),
+        manufacturer: value(7),
+        hardware: value(8),
+        fingerprint: value(9),
+        vulnerabilities: Vec::new(),
+        error: None,
+    }
+}
+
+// 没有历史耗时的测试的默认估计耗时
+const DEFAULT_TEST_DURATION: Duration = Duration::from_secs(1);
+
+/// 测试分片策略
+#[derive(Debug, Clone)]
+pub enum ShardStrategy {
+    /// 按顺序轮流分配给各设备
+    RoundRobin,
+    /// 按历史耗时均衡分配（耗时长的测试优先分配给当前负载最小的设备）
+    ///
+    /// 没有历史记录的测试按已知测试的平均耗时估计。
+    ByHistoricalDuration(HashMap<String, Duration>),
+}
+
+/// 分配给单个设备的测试
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shard {
+    pub device_id: String,
+    pub tests: Vec<String>,
+    /// 按历史耗时估计的总耗时（RoundRobin 时为零）
+    pub estimated: Duration,
+}
+
+/// 单个分片的执行结果
+#[derive(Debug)]
+pub struct ShardResult<T> {
+    pub shard: Shard,
+    pub result: ADBResult<T>,
+    pub elapsed: Duration,
+}
+
+/// 分片执行报告
+#[derive(Debug)]
+pub struct ShardReport<T> {
+    pub shards: Vec<ShardResult<T>>,
+}
+
+impl<T> ShardReport<T> {
+    /// 所有分片中最长的耗时，即整体完成时间
+    pub fn makespan(&self) -> Duration {
+        self.shards.iter().map(|s| s.elapsed).max().unwrap_or_default()
+    }
+
+    /// 是否全部分片执行成功
+    pub fn all_succeeded(&self) -> bool {
+        self.shards.iter().all(|s| s.result.is_ok())
+    }
+
+    /// 用本次的分片耗时更新历史耗时，供下次 `ByHistoricalDuration` 使用
+    ///
+    /// 分片耗时按各测试原有的估计耗时比例分摊，没有历史耗时的测试与 [`plan_shards`] 一样按
+    /// 已知耗时的平均值估计。执行失败的分片不计入。
+    pub fn update_durations(&self, history: &mut HashMap<String, Duration>) {
+        let all_tests: Vec<&str> = self
+            .shards
+            .iter()
+            .flat_map(|s| s.shard.tests.iter().map(String::as_str))
+            .collect();
+        let fallback = fallback_duration(history, &all_tests);
+
+        for shard in self.shards.iter().filter(|s| s.result.is_ok()) {
+            let tests = &shard.shard.tests;
+            if tests.is_empty() {
+                continue;
+            }
+            let weights: Vec<f64> = tests
+                .iter()
+                .map(|t| history.get(t).copied().unwrap_or(fallback).as_secs_f64())
+                .collect();
+            let total: f64 = weights.iter().sum();
+
+            for (test, weight) in tests.iter().zip(weights) {
+                let share = if total > 0.0 {
+                    weight / total
+                } else {
+                    1.0 / tests.len() as f64
+                };
+                history.insert(test.clone(), shard.elapsed.mul_f64(share));
+            }
+        }
+    }
+}
+
+/// 没有历史耗时的测试的估计耗时：已知耗时的平均值，都未知时为 [`DEFAULT_TEST_DURATION`]
+fn fallback_duration(history: &HashMap<String, Duration>, test_ids: &[&str]) -> Duration {
+    let known: Vec<Duration> = test_ids.iter().filter_map(|t| history.get(*t).copied()).collect();
+    if known.is_empty() {
+        DEFAULT_TEST_DURATION
+    } else {
+        known.iter().sum::<Duration>() / known.len() as u32
+    }
+}
+
+/// 把测试分配到各设备，设备列表为空时返回空列表
+pub fn plan_shards(device_ids: &[&str], test_ids: &[&str], strategy: &ShardStrategy) -> Vec<Shard> {
+    let mut shards: Vec<Shard> = device_ids
+        .iter()
+        .map(|id| Shard {
+            device_id: id.to_string(),
+            tests: Vec::new(),
+            estimated: Duration::ZERO,
+        })
+        .collect();
+    if shards.is_empty() {
+        return shards;
+    }
+
+    match strategy {
+        ShardStrategy::RoundRobin => {
+            for (i, test) in test_ids.iter().enumerate() {
+                shards[i % device_ids.len()].tests.push(test.to_string());
+            }
+        }
+        ShardStrategy::ByHistoricalDuration(history) => {
+            let fallback = fallback_duration(history, test_ids);
+
+            let mut tests: Vec<(&str, Duration)> = test_ids
+                .iter()
+                .map(|t| (*t, history.get(*t).copied().unwrap_or(fallback)))
+                .collect();
+            tests.sort_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+
+            for (test, duration) in tests {
+                let shard = shards.iter_mut().min_by_key(|s| s.estimated).unwrap();
+                shard.tests.push(test.to_string());
+                shard.estimated += duration;
+            }
+        }
+    }
+
+    shards
+}
 
 impl ADB {
     /// 在多个设备上并行执行 shell 命令
@@ -146,7 +601,7 @@ impl ADB {
         device_ids: &[&str],
         local_path: &str,
         device_path: &str,
-    ) -> HashMap<String, ADBResult<()>> {
+    ) -> HashMap<String, ADBResult<TransferStats>> {
         device_ids
             .par_iter()
             .map(|&id| {
@@ -167,7 +622,7 @@ impl ADB {
     pub fn parallel_pull(
         &self,
         operations: &[(String, String, String)],
-    ) -> HashMap<String, ADBResult<()>> {
+    ) -> HashMap<String, ADBResult<TransferStats>> {
         operations
             .par_iter()
             .map(|(device_id, device_path, local_path)| {
@@ -244,6 +699,54 @@ impl ADB {
         Ok(results)
     }
 
+    /// 在多个设备上并行执行操作，按完成顺序返回结果
+    ///
+    /// 每个设备在单独的线程中执行，先完成的设备无需等待其他设备即可处理。指定 `deadline`
+    /// 时，超时仍未完成的设备以 `TimeoutError` 返回。
+    pub fn parallel_stream<F, T>(
+        &self,
+        device_ids: &[&str],
+        deadline: Option<Duration>,
+        operation: F,
+    ) -> ParallelStream<T>
+    where
+        F: Fn(&ADB, &str) -> ADBResult<T> + Send + Sync + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let operation = Arc::new(operation);
+
+        for &id in device_ids {
+            let adb = self.clone();
+            let operation = operation.clone();
+            let sender = sender.clone();
+            let id = id.to_string();
+            thread::spawn(move || {
+                let result = operation(&adb, &id);
+                // 接收端已释放（调用方不再关心结果）时忽略
+                let _ = sender.send((id, result));
+            });
+        }
+
+        debug!("开始在 {} 个设备上流式执行", device_ids.len());
+        ParallelStream {
+            receiver,
+            pending: device_ids.iter().map(|id| id.to_string()).collect(),
+            deadline: deadline.map(|d| (Instant::now() + d, d)),
+        }
+    }
+
+    /// 在多个设备上并行执行 shell 命令，按完成顺序返回结果
+    pub fn parallel_shell_stream(
+        &self,
+        device_ids: &[&str],
+        command: &str,
+        deadline: Option<Duration>,
+    ) -> ParallelStream<String> {
+        let command = command.to_string();
+        self.parallel_stream(device_ids, deadline, move |adb, id| adb.shell(id, &command))
+    }
+
     /// 在所有指定设备上并行执行多个命令
     pub fn parallel_commands(
         &self,
@@ -283,4 +786,248 @@ impl ADB {
             self.stop_app(device_id, package_name)
         })
     }
-}
\ No newline at end of file
+
+    /// 在多个设备上同步触发命令
+    ///
+    /// 先为每个设备启动并预热 shell 会话，所有会话就绪后在同一时刻写入由 `action`
+    /// 生成的命令，并记录各设备的主机触发时间和偏差。任一设备准备失败时不会触发任何命令。
+    /// 用于音视频同步测试和多设备交互场景。
+    pub fn synchronized<F>(
+        &self,
+        device_ids: &[&str],
+        action: F,
+        barrier_tolerance: Duration,
+    ) -> ADBResult<SyncTriggerReport>
+    where
+        F: Fn(&str) -> String + Sync,
+    {
+        let barrier = Barrier::new(device_ids.len());
+        let aborted = AtomicBool::new(false);
+
+        let results: Vec<(String, ADBResult<TriggerOutcome>)> =
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = device_ids
+                    .iter()
+                    .map(|&device_id| {
+                        let (barrier, aborted, action) = (&barrier, &aborted, &action);
+                        scope.spawn(move || {
+                            let prepared = self.prepare_trigger_shell(device_id);
+                            if prepared.is_err() {
+                                aborted.store(true, Ordering::SeqCst);
+                            }
+                            barrier.wait();
+
+                            let mut child = prepared?;
+                            if aborted.load(Ordering::SeqCst) {
+                                let _ = child.kill();
+                                let _ = child.wait();
+                                return Err(ADBError::DeviceError("其他设备准备失败，已取消触发".to_string()));
+                            }
+
+                            let command = format!("{}\nexit\n", action(device_id));
+                            let mut stdin = child.stdin.take().ok_or_else(|| {
+                                ADBError::CommandError("无法写入 shell 会话".to_string())
+                            })?;
+                            let written = stdin.write_all(command.as_bytes()).and_then(|_| stdin.flush());
+                            let (instant, system) = (Instant::now(), SystemTime::now());
+                            drop(stdin);
+
+                            let output = written
+                                .map_err(ADBError::from)
+                                .and_then(|_| {
+                                    let mut output = String::new();
+                                    if let Some(mut stdout) = child.stdout.take() {
+                                        stdout.read_to_string(&mut output)?;
+                                    }
+                                    child.wait()?;
+                                    Ok(output)
+                                });
+                            Ok((instant, system, output))
+                        })
+                    })
+                    .collect();
+
+                device_ids
+                    .iter()
+                    .zip(handles)
+                    .map(|(id, handle)| {
+                        let result = handle.join().unwrap_or_else(|_| {
+                            Err(ADBError::UnknownError("触发线程异常退出".to_string()))
+                        });
+                        (id.to_string(), result)
+                    })
+                    .collect()
+            });
+
+        if let Some((id, Err(e))) = results.iter().find(|(_, r)| r.is_err()) {
+            return Err(ADBError::DeviceError(format!("设备 {} 同步触发失败: {}", id, e)));
+        }
+
+        let triggers: Vec<(String, Instant, SystemTime, ADBResult<String>)> = results
+            .into_iter()
+            .filter_map(|(id, r)| r.ok().map(|(i, s, o)| (id, i, s, o)))
+            .collect();
+        let earliest = triggers.iter().map(|t| t.1).min().unwrap_or_else(Instant::now);
+
+        let devices: HashMap<String, DeviceTrigger> = triggers
+            .into_iter()
+            .map(|(id, instant, triggered_at, output)| {
+                let trigger = DeviceTrigger {
+                    triggered_at,
+                    skew: instant.duration_since(earliest),
+                    output,
+                };
+                (id, trigger)
+            })
+            .collect();
+
+        let max_skew = devices.values().map(|t| t.skew).max().unwrap_or_default();
+        let within_tolerance = max_skew <= barrier_tolerance;
+        if within_tolerance {
+            debug!("{} 个设备同步触发完成，最大偏差 {:?}", devices.len(), max_skew);
+        } else {
+            warn!("同步触发偏差 {:?} 超出容差 {:?}", max_skew, barrier_tolerance);
+        }
+
+        Ok(SyncTriggerReport {
+            devices,
+            max_skew,
+            within_tolerance,
+        })
+    }
+
+    /// 启动 shell 会话并等待其就绪
+    fn prepare_trigger_shell(&self, device_id: &str) -> ADBResult<std::process::Child> {
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+
+        let mut child = cmd
+            .arg("shell")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| ADBError::CommandError(format!("无法启动 shell 会话: {}", e)))?;
+
+        let ready = (|| {
+            let stdin = child.stdin.as_mut()?;
+            stdin.write_all(format!("echo {}\n", SHELL_READY_MARKER).as_bytes()).ok()?;
+            stdin.flush().ok()?;
+
+            let mut reader = BufReader::new(child.stdout.as_mut()?);
+            let mut line = String::new();
+            while reader.read_line(&mut line).ok()? > 0 {
+                if line.trim() == SHELL_READY_MARKER {
+                    return Some(());
+                }
+                line.clear();
+            }
+            None
+        })();
+
+        if ready.is_none() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(ADBError::DeviceError(format!("设备 {} 的 shell 会话未就绪", device_id)));
+        }
+
+        Ok(child)
+    }
+
+    /// 对设备池执行合规审计，使用内置漏洞规则
+    ///
+    /// 采集系统版本、安全补丁日期、bootloader 锁定状态和 root 状态，结果按输入顺序返回；
+    /// 单个设备采集失败时记录在 `error` 字段中，不影响其他设备。
+    pub fn audit_fleet(&self, device_ids: &[&str]) -> Vec<AuditRecord> {
+        self.audit_fleet_with_rules(device_ids, &VulnerabilityRule::defaults())
+    }
+
+    /// 使用自定义漏洞规则对设备池执行合规审计
+    pub fn audit_fleet_with_rules(
+        &self,
+        device_ids: &[&str],
+        rules: &[VulnerabilityRule],
+    ) -> Vec<AuditRecord> {
+        let script = AUDIT_PROPS
+            .iter()
+            .map(|prop| format!("getprop {}", prop))
+            .chain(std::iter::once("command -v su; true".to_string()))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let records: Vec<AuditRecord> = device_ids
+            .par_iter()
+            .map(|&id| match self.shell(id, &script) {
+                Ok(output) => {
+                    let mut record = parse_audit_output(id, &output);
+                    record.vulnerabilities = rules
+                        .iter()
+                        .filter(|rule| rule.matches(&record))
+                        .map(|rule| rule.id.clone())
+                        .collect();
+                    record
+                }
+                Err(e) => {
+                    warn!("设备 {} 审计失败: {}", id, e);
+                    AuditRecord::failed(id, &e)
+                }
+            })
+            .collect();
+
+        let compliant = records.iter().filter(|r| r.is_compliant()).count();
+        debug!("审计 {} 个设备，{} 个合规", records.len(), compliant);
+        records
+    }
+
+    /// 把测试分片到各设备并行执行
+    ///
+    /// `executor` 接收设备 ID 和分配给它的测试，在各设备上并行调用（没有分到测试的设备跳过）。
+    /// 返回的报告包含每个分片的耗时，可通过 [`ShardReport::update_durations`] 改进下次分配。
+    pub fn shard_tests<F, T>(
+        &self,
+        device_ids: &[&str],
+        test_ids: &[&str],
+        strategy: &ShardStrategy,
+        executor: F,
+    ) -> ADBResult<ShardReport<T>>
+    where
+        F: Fn(&ADB, &str, &[String]) -> ADBResult<T> + Send + Sync,
+        T: Send,
+    {
+        if device_ids.is_empty() {
+            return Err(ADBError::ConfigError("没有可用于分片的设备".to_string()));
+        }
+
+        let shards = plan_shards(device_ids, test_ids, strategy);
+        for shard in &shards {
+            debug!(
+                "设备 {} 分配 {} 个测试，估计耗时 {:?}",
+                shard.device_id,
+                shard.tests.len(),
+                shard.estimated
+            );
+        }
+
+        let results = shards
+            .into_par_iter()
+            .filter(|shard| !shard.tests.is_empty())
+            .map(|shard| {
+                let start = Instant::now();
+                let result = executor(self, &shard.device_id, &shard.tests);
+                let elapsed = start.elapsed();
+                if let Err(e) = &result {
+                    warn!("设备 {} 的分片执行失败: {}", shard.device_id, e);
+                }
+                ShardResult {
+                    shard,
+                    result,
+                    elapsed,
+                }
+            })
+            .collect();
+
+        Ok(ShardReport { shards: results })
+    }
+}
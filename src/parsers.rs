@@ -0,0 +1,510 @@
+use crate::app::PackageInfo;
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::{debug, trace};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// `dumpsys package <pkg>` 解析器 ID，输出类型为 [`PackageInfo`]
+pub const PACKAGE_INFO: &str = "package_info";
+
+/// `pm list packages` 解析器 ID，输出类型为 `Vec<String>`
+pub const PACKAGE_LIST: &str = "package_list";
+
+// 全局解析器与兼容性注册表
+static REGISTRY: Lazy<RwLock<ParserRegistry>> =
+    Lazy::new(|| RwLock::new(ParserRegistry::with_defaults()));
+
+/// 设备画像，用于选择解析器和兼容性配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceProfile {
+    /// SDK 版本 (ro.build.version.sdk)
+    pub sdk_int: u32,
+    /// 制造商 (ro.product.manufacturer)，统一为小写
+    pub manufacturer: String,
+}
+
+impl DeviceProfile {
+    /// 创建设备画像
+    pub fn new(sdk_int: u32, manufacturer: &str) -> Self {
+        Self {
+            sdk_int,
+            manufacturer: manufacturer.trim().to_lowercase(),
+        }
+    }
+}
+
+/// 解析上下文
+#[derive(Debug, Clone)]
+pub struct ParseContext {
+    pub profile: DeviceProfile,
+    /// 与输出相关的包名（如有）
+    pub package_name: Option<String>,
+}
+
+/// 命令输出解析器
+pub trait OutputParser<T>: Send + Sync {
+    /// 解析命令输出
+    fn parse(&self, context: &ParseContext, output: &str) -> ADBResult<T>;
+}
+
+// 允许直接使用闭包作为解析器
+impl<T, F> OutputParser<T> for F
+where
+    F: Fn(&ParseContext, &str) -> ADBResult<T> + Send + Sync,
+{
+    fn parse(&self, context: &ParseContext, output: &str) -> ADBResult<T> {
+        self(context, output)
+    }
+}
+
+/// 设备匹配规则
+///
+/// 所有字段为 `None` 时匹配任意设备。指定了制造商的规则优先于只限定 SDK 范围的规则。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuirkMatcher {
+    pub min_sdk: Option<u32>,
+    pub max_sdk: Option<u32>,
+    pub manufacturer: Option<String>,
+}
+
+impl QuirkMatcher {
+    /// 匹配任意设备
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// 限定 SDK 范围（包含边界）
+    pub fn sdk_range(min_sdk: Option<u32>, max_sdk: Option<u32>) -> Self {
+        Self {
+            min_sdk,
+            max_sdk,
+            manufacturer: None,
+        }
+    }
+
+    /// 限定制造商
+    pub fn manufacturer(manufacturer: &str) -> Self {
+        Self {
+            manufacturer: Some(manufacturer.trim().to_lowercase()),
+            ..Default::default()
+        }
+    }
+
+    /// 追加 SDK 范围限定
+    pub fn with_sdk_range(mut self, min_sdk: Option<u32>, max_sdk: Option<u32>) -> Self {
+        self.min_sdk = min_sdk;
+        self.max_sdk = max_sdk;
+        self
+    }
+
+    /// 检查设备画像是否匹配
+    pub fn matches(&self, profile: &DeviceProfile) -> bool {
+        if let Some(min) = self.min_sdk {
+            if profile.sdk_int < min {
+                return false;
+            }
+        }
+        if let Some(max) = self.max_sdk {
+            if profile.sdk_int > max {
+                return false;
+            }
+        }
+        if let Some(manufacturer) = &self.manufacturer {
+            if *manufacturer != profile.manufacturer {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 规则的具体程度，越大越优先
+    fn specificity(&self) -> u8 {
+        let mut score = 0;
+        if self.manufacturer.is_some() {
+            score += 2;
+        }
+        if self.min_sdk.is_some() || self.max_sdk.is_some() {
+            score += 1;
+        }
+        score
+    }
+}
+
+/// 设备兼容性配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quirks {
+    /// 列出全部进程的 ps 命令
+    pub ps_command: String,
+    /// 是否支持 pidof
+    pub has_pidof: bool,
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Quirks {
+            ps_command: "ps -A".to_string(),
+            has_pidof: true,
+        }
+    }
+}
+
+/// 已注册的解析器（类型擦除后存储）
+type ParserEntries = Vec<(QuirkMatcher, Box<dyn Any + Send + Sync>)>;
+
+/// 解析器与兼容性注册表
+pub struct ParserRegistry {
+    parsers: HashMap<&'static str, ParserEntries>,
+    quirks: Vec<(QuirkMatcher, Quirks)>,
+}
+
+impl ParserRegistry {
+    /// 创建空注册表
+    pub fn new() -> Self {
+        Self {
+            parsers: HashMap::new(),
+            quirks: Vec::new(),
+        }
+    }
+
+    /// 创建包含内置解析器和兼容性规则的注册表
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+
+        registry.register::<PackageInfo>(
+            PACKAGE_INFO,
+            QuirkMatcher::any(),
+            Arc::new(|ctx: &ParseContext, output: &str| {
+                crate::app::parse_package_dump(ctx.package_name.as_deref().unwrap_or(""), output)
+            }),
+        );
+        registry.register::<Vec<String>>(
+            PACKAGE_LIST,
+            QuirkMatcher::any(),
+            Arc::new(|_: &ParseContext, output: &str| Ok(crate::app::parse_package_list(output))),
+        );
+
+        registry.register_quirks(QuirkMatcher::any(), Quirks::default());
+        // Android 8 之前的 toolbox ps 默认列出全部进程，会把 -A 当作进程名过滤，且没有可靠的 pidof
+        registry.register_quirks(
+            QuirkMatcher::sdk_range(None, Some(25)),
+            Quirks {
+                ps_command: "ps".to_string(),
+                has_pidof: false,
+            },
+        );
+
+        registry
+    }
+
+    /// 注册解析器，后注册的同等具体程度规则优先
+    pub fn register<T: 'static>(
+        &mut self,
+        id: &'static str,
+        matcher: QuirkMatcher,
+        parser: Arc<dyn OutputParser<T>>,
+    ) {
+        self.parsers
+            .entry(id)
+            .or_default()
+            .push((matcher, Box::new(parser)));
+    }
+
+    /// 注册兼容性规则，后注册的同等具体程度规则优先
+    pub fn register_quirks(&mut self, matcher: QuirkMatcher, quirks: Quirks) {
+        self.quirks.push((matcher, quirks));
+    }
+
+    /// 查找适用于设备的解析器
+    pub fn lookup<T: 'static>(
+        &self,
+        id: &str,
+        profile: &DeviceProfile,
+    ) -> Option<Arc<dyn OutputParser<T>>> {
+        self.parsers
+            .get(id)?
+            .iter()
+            .enumerate()
+            .filter(|(_, (matcher, _))| matcher.matches(profile))
+            .filter_map(|(index, (matcher, parser))| {
+                parser
+                    .downcast_ref::<Arc<dyn OutputParser<T>>>()
+                    .map(|p| ((matcher.specificity(), index), p.clone()))
+            })
+            .max_by_key(|(rank, _)| *rank)
+            .map(|(_, parser)| parser)
+    }
+
+    /// 查找适用于设备的兼容性配置
+    pub fn quirks_for(&self, profile: &DeviceProfile) -> Quirks {
+        self.quirks
+            .iter()
+            .enumerate()
+            .filter(|(_, (matcher, _))| matcher.matches(profile))
+            .max_by_key(|(index, (matcher, _))| (matcher.specificity(), *index))
+            .map(|(_, (_, quirks))| quirks.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for ParserRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+/// 向全局注册表注册解析器
+///
+/// 用于为特殊设备覆盖内置解析逻辑，例如：
+/// `register_parser::<Vec<String>>(PACKAGE_LIST, QuirkMatcher::manufacturer("acme"), Arc::new(my_parser))`
+pub fn register_parser<T: 'static>(
+    id: &'static str,
+    matcher: QuirkMatcher,
+    parser: Arc<dyn OutputParser<T>>,
+) {
+    if let Ok(mut registry) = REGISTRY.write() {
+        registry.register(id, matcher, parser);
+    }
+}
+
+/// 向全局注册表注册兼容性规则
+pub fn register_quirks(matcher: QuirkMatcher, quirks: Quirks) {
+    if let Ok(mut registry) = REGISTRY.write() {
+        registry.register_quirks(matcher, quirks);
+    }
+}
+
+/// 获取适用于设备画像的兼容性配置
+pub fn quirks_for(profile: &DeviceProfile) -> Quirks {
+    REGISTRY
+        .read()
+        .map(|registry| registry.quirks_for(profile))
+        .unwrap_or_default()
+}
+
+// ANR trace 中线程的锁信息，如 "- waiting to lock <0x0abc> (a java.lang.Object) held by thread 12"
+static LOCK_LINE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^- (locked|waiting to lock|waiting on|sleeping on) (<0x[0-9a-f]+> \(a [^)]+\))(?: held by thread (\d+))?")
+        .unwrap()
+});
+
+/// ANR trace 中的一个线程
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadStack {
+    pub name: String,
+    pub daemon: bool,
+    pub priority: Option<i32>,
+    /// 虚拟机线程 ID（主线程为 1），纯 native 线程为 None
+    pub tid: Option<u32>,
+    /// 系统线程 ID
+    pub sys_tid: Option<u32>,
+    /// 线程状态，如 Runnable、Blocked、Waiting、Native、Sleeping
+    pub state: String,
+    /// 调用栈，Java 帧为 `at ...`，native 帧为 `native: #00 pc ...`
+    pub frames: Vec<String>,
+    /// 已持有的锁，如 `<0x0abc> (a java.lang.Object)`
+    pub held_locks: Vec<String>,
+    /// 正在等待的锁
+    pub waiting_on: Option<String>,
+    /// 持有所等待的锁的线程（虚拟机线程 ID）
+    pub blocked_by: Option<u32>,
+}
+
+/// 解析后的 ANR trace
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AnrTrace {
+    pub pid: Option<u32>,
+    /// 进程名 (Cmd line)
+    pub process: Option<String>,
+    /// trace 生成时间
+    pub timestamp: Option<String>,
+    /// ANR 原因（来自 dropbox 记录的 Subject），trace 文件中没有时为 None
+    pub reason: Option<String>,
+    pub threads: Vec<ThreadStack>,
+}
+
+impl AnrTrace {
+    /// 主线程
+    pub fn main_thread(&self) -> Option<&ThreadStack> {
+        self.threads
+            .iter()
+            .find(|t| t.tid == Some(1))
+            .or_else(|| self.threads.iter().find(|t| t.name == "main"))
+    }
+
+    /// 按虚拟机线程 ID 查找线程
+    pub fn thread(&self, tid: u32) -> Option<&ThreadStack> {
+        self.threads.iter().find(|t| t.tid == Some(tid))
+    }
+
+    /// 等待锁的线程
+    pub fn blocked_threads(&self) -> impl Iterator<Item = &ThreadStack> {
+        self.threads.iter().filter(|t| t.state == "Blocked" || t.blocked_by.is_some())
+    }
+}
+
+/// 解析线程标题行，如 `"main" prio=5 tid=1 Blocked`、`"HwBinder:123_1" sysTid=130`
+fn parse_thread_header(line: &str) -> Option<ThreadStack> {
+    let rest = line.strip_prefix('"')?;
+    let (name, attrs) = rest.rsplit_once('"')?;
+
+    let mut thread = ThreadStack {
+        name: name.to_string(),
+        daemon: false,
+        priority: None,
+        tid: None,
+        sys_tid: None,
+        state: String::new(),
+        frames: Vec::new(),
+        held_locks: Vec::new(),
+        waiting_on: None,
+        blocked_by: None,
+    };
+    for attr in attrs.split_whitespace() {
+        match attr.split_once('=') {
+            Some(("prio", v)) => thread.priority = v.parse().ok(),
+            Some(("tid", v)) => thread.tid = v.parse().ok(),
+            Some(("sysTid", v)) => thread.sys_tid = v.parse().ok(),
+            Some(_) => {}
+            None if attr == "daemon" => thread.daemon = true,
+            None => thread.state = attr.to_string(),
+        }
+    }
+    Some(thread)
+}
+
+/// 解析 ANR trace（`/data/anr` 中的文件或 dropbox 的 `data_app_anr` 记录）
+///
+/// 只解析第一个进程段落，通常就是发生 ANR 的进程。
+pub fn parse_anr_trace(text: &str) -> AnrTrace {
+    let mut trace = AnrTrace::default();
+    let mut current: Option<ThreadStack> = None;
+    let mut in_process = false;
+
+    for line in text.lines() {
+        let line = line.trim();
+
+        if let Some(header) = line.strip_prefix("----- pid ") {
+            if in_process {
+                break;
+            }
+            in_process = true;
+            // "----- pid 1234 at 2024-01-01 12:00:00.123 -----"
+            let header = header.trim_end_matches('-').trim();
+            let (pid, time) = header.split_once(" at ").unwrap_or((header, ""));
+            trace.pid = pid.trim().parse().ok();
+            trace.timestamp = Some(time.trim().to_string()).filter(|t| !t.is_empty());
+            continue;
+        }
+        if line.starts_with("----- end") {
+            break;
+        }
+        if let Some(subject) = line.strip_prefix("Subject: ") {
+            trace.reason = Some(subject.trim().to_string());
+            continue;
+        }
+        if let Some(cmd) = line.strip_prefix("Cmd line: ") {
+            trace.process = Some(cmd.trim().to_string());
+            continue;
+        }
+
+        if let Some(thread) = parse_thread_header(line) {
+            trace.threads.extend(current.replace(thread));
+            continue;
+        }
+        let Some(thread) = current.as_mut() else {
+            continue;
+        };
+
+        if line.is_empty() {
+            trace.threads.extend(current.take());
+        } else if let Some(attrs) = line.strip_prefix("| ") {
+            if thread.sys_tid.is_none() {
+                thread.sys_tid = attrs
+                    .split_whitespace()
+                    .find_map(|a| a.strip_prefix("sysTid="))
+                    .and_then(|v| v.parse().ok());
+            }
+        } else if let Some(caps) = LOCK_LINE_RE.captures(line) {
+            let lock = caps[2].to_string();
+            if &caps[1] == "locked" {
+                thread.held_locks.push(lock);
+            } else {
+                thread.waiting_on = Some(lock);
+                thread.blocked_by = caps.get(3).and_then(|m| m.as_str().parse().ok());
+            }
+        } else if line.starts_with("at ") || line.starts_with("native: ") {
+            thread.frames.push(line.to_string());
+        }
+    }
+    trace.threads.extend(current);
+
+    trace
+}
+
+impl ADB {
+    /// 获取设备画像（SDK 版本和制造商），结果会被缓存
+    pub fn device_profile(&self, device_id: &str) -> ADBResult<DeviceProfile> {
+        if let Some(profile) = self.cache.get::<DeviceProfile>(device_id, "profile") {
+            return Ok(profile);
+        }
+
+        let output = self.shell(
+            device_id,
+            "getprop ro.build.version.sdk; getprop ro.product.manufacturer",
+        )?;
+        let mut lines = output.lines();
+        let sdk_int = lines
+            .next()
+            .and_then(|l| l.trim().parse::<u32>().ok())
+            .unwrap_or(0);
+        let manufacturer = lines.next().unwrap_or("");
+
+        let profile = DeviceProfile::new(sdk_int, manufacturer);
+        trace!("设备 {} 画像: {:?}", device_id, profile);
+
+        self.cache.insert(device_id, "profile", profile.clone(), None);
+
+        Ok(profile)
+    }
+
+    /// 获取设备的兼容性配置
+    pub fn device_quirks(&self, device_id: &str) -> ADBResult<Quirks> {
+        let profile = self.device_profile(device_id)?;
+        Ok(quirks_for(&profile))
+    }
+
+    /// 构建解析上下文
+    pub(crate) fn parse_context(
+        &self,
+        device_id: &str,
+        package_name: Option<&str>,
+    ) -> ADBResult<ParseContext> {
+        Ok(ParseContext {
+            profile: self.device_profile(device_id)?,
+            package_name: package_name.map(|p| p.to_string()),
+        })
+    }
+
+    /// 查找适用于设备的解析器
+    pub(crate) fn parser_for<T: 'static>(
+        &self,
+        device_id: &str,
+        id: &'static str,
+    ) -> ADBResult<Arc<dyn OutputParser<T>>> {
+        let profile = self.device_profile(device_id)?;
+
+        let parser = REGISTRY
+            .read()
+            .map_err(|_| ADBError::UnknownError("解析器注册表不可用".to_string()))?
+            .lookup::<T>(id, &profile)
+            .ok_or_else(|| ADBError::ParseError(format!("没有可用的解析器: {}", id)))?;
+
+        debug!("设备 {} 使用解析器 {}", device_id, id);
+        Ok(parser)
+    }
+}
@@ -0,0 +1,182 @@
+use crate::device::ADB;
+use crate::error::ADBResult;
+use crate::transfer::{TransferOptions, TransferStats};
+use log::{debug, warn};
+
+/// 应用对设备路径的访问情况
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathAccess {
+    /// 应用可以直接访问
+    Accessible,
+    /// 共享存储受分区存储限制，应用只能通过 MediaStore/SAF 访问
+    ScopedStorage,
+    /// 其他应用的私有目录，应用无法访问
+    OtherAppPrivate(String),
+    /// 系统或 shell 专用目录，应用通常无法访问
+    Restricted,
+}
+
+impl PathAccess {
+    /// 应用是否能直接以文件路径访问
+    pub fn is_accessible(&self) -> bool {
+        *self == PathAccess::Accessible
+    }
+}
+
+/// 将 /sdcard、/storage/self/primary 等别名统一为 /storage/emulated/<user>
+fn normalize_storage_path(path: &str, user: u32) -> String {
+    let root = format!("/storage/emulated/{}", user);
+    for alias in ["/sdcard", "/storage/self/primary", "/mnt/sdcard"] {
+        if path == alias {
+            return root;
+        }
+        if let Some(rest) = path.strip_prefix(alias).filter(|r| r.starts_with('/')) {
+            return format!("{}{}", root, rest);
+        }
+    }
+    path.to_string()
+}
+
+/// 提取 `<prefix><package>/...` 中的包名
+fn package_under<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    path.strip_prefix(prefix)?.split('/').next().filter(|p| !p.is_empty())
+}
+
+impl ADB {
+    /// 获取当前前台用户 ID，无法获取时返回 0
+    pub fn current_user(&self, device_id: &str) -> ADBResult<u32> {
+        let output = self.shell(device_id, "am get-current-user")?;
+        Ok(output.trim().parse().unwrap_or(0))
+    }
+
+    /// 当前用户的外部存储根目录（`/storage/emulated/<user>`）
+    pub fn external_storage_dir(&self, device_id: &str) -> ADBResult<String> {
+        Ok(format!("/storage/emulated/{}", self.current_user(device_id)?))
+    }
+
+    /// 应用的外部私有文件目录（`Android/data/<package>/files`）
+    pub fn external_files_dir(&self, device_id: &str, package_name: &str) -> ADBResult<String> {
+        Ok(format!(
+            "{}/Android/data/{}/files",
+            self.external_storage_dir(device_id)?,
+            package_name
+        ))
+    }
+
+    /// 当前用户的下载目录
+    pub fn downloads_dir(&self, device_id: &str) -> ADBResult<String> {
+        Ok(format!("{}/Download", self.external_storage_dir(device_id)?))
+    }
+
+    /// 当前用户的相机照片目录
+    pub fn dcim_dir(&self, device_id: &str) -> ADBResult<String> {
+        Ok(format!("{}/DCIM", self.external_storage_dir(device_id)?))
+    }
+
+    /// 当前用户的图片目录
+    pub fn pictures_dir(&self, device_id: &str) -> ADBResult<String> {
+        Ok(format!("{}/Pictures", self.external_storage_dir(device_id)?))
+    }
+
+    /// 判断应用能否直接访问设备上的路径
+    ///
+    /// 考虑 Android 10 起的分区存储和 Android 11 起对 `Android/data` 的限制
+    pub fn check_app_access(
+        &self,
+        device_id: &str,
+        path: &str,
+        package_name: &str,
+    ) -> ADBResult<PathAccess> {
+        let user = self.current_user(device_id)?;
+        let path = normalize_storage_path(path, user);
+        let storage_root = format!("/storage/emulated/{}/", user);
+        let sdk = self.device_profile(device_id)?.sdk_int;
+
+        // 应用内部私有目录
+        for prefix in [
+            "/data/data/".to_string(),
+            format!("/data/user/{}/", user),
+        ] {
+            if let Some(owner) = package_under(&path, &prefix) {
+                return Ok(if owner == package_name {
+                    PathAccess::Accessible
+                } else {
+                    PathAccess::OtherAppPrivate(owner.to_string())
+                });
+            }
+        }
+
+        let Some(relative) = path.strip_prefix(&storage_root) else {
+            return Ok(PathAccess::Restricted);
+        };
+
+        // 外部存储中的应用专属目录
+        for prefix in ["Android/data/", "Android/obb/", "Android/media/"] {
+            if let Some(owner) = package_under(relative, prefix) {
+                // 分区存储之前，持有存储权限即可访问其他应用的目录
+                return Ok(if owner == package_name || sdk < 29 {
+                    PathAccess::Accessible
+                } else if prefix == "Android/media/" {
+                    PathAccess::ScopedStorage
+                } else {
+                    PathAccess::OtherAppPrivate(owner.to_string())
+                });
+            }
+        }
+
+        // 共享存储：Android 10 起，targetSdk >= 29 的应用受分区存储限制
+        if sdk >= 29 {
+            let target_sdk = self
+                .get_package_info(device_id, package_name)
+                .ok()
+                .and_then(|info| info.target_sdk)
+                .unwrap_or(sdk as i32);
+            if target_sdk >= 29 {
+                return Ok(PathAccess::ScopedStorage);
+            }
+        }
+
+        Ok(PathAccess::Accessible)
+    }
+
+    /// 为指定应用推送文件
+    ///
+    /// 目标路径应用无法直接访问时，改为推送到应用的外部私有文件目录并输出警告。
+    /// 返回实际使用的设备路径和传输统计。
+    pub fn push_for_app(
+        &self,
+        device_id: &str,
+        local_path: &str,
+        device_path: &str,
+        package_name: &str,
+        options: Option<TransferOptions>,
+    ) -> ADBResult<(String, TransferStats)> {
+        let access = self.check_app_access(device_id, device_path, package_name)?;
+
+        let target = match access {
+            PathAccess::Accessible => device_path.to_string(),
+            PathAccess::ScopedStorage => {
+                warn!(
+                    "{} 位于共享存储，应用 {} 只能通过 MediaStore 访问",
+                    device_path, package_name
+                );
+                device_path.to_string()
+            }
+            other => {
+                let files_dir = self.external_files_dir(device_id, package_name)?;
+                let file_name = device_path.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+                let rerouted = format!("{}/{}", files_dir, file_name);
+                warn!(
+                    "应用 {} 无法访问 {} ({:?})，改为推送到 {}",
+                    package_name, device_path, other, rerouted
+                );
+                self.create_directory(device_id, &files_dir)?;
+                rerouted
+            }
+        };
+
+        let stats = self.push(device_id, local_path, &target, options)?;
+        debug!("已为应用 {} 推送 {} -> {}", package_name, local_path, target);
+        Ok((target, stats))
+    }
+}
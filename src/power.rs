@@ -0,0 +1,303 @@
+//! 电池状态与时间快进
+//!
+//! 电池信息来自 `dumpsys battery`，模拟电量和拔出电源用于测试低电量场景，
+//! 测试结束后应调用 [`ADB::reset_battery`] 恢复真实状态。
+//!
+//! 修改设备系统时间以测试订阅过期、令牌刷新、定时任务等依赖时间的逻辑。
+//! `SystemClock.elapsedRealtime()` 和 `uptimeMillis()` 无法修改，依赖这两者的
+//! 定时（如 `ELAPSED_REALTIME` 闹钟）不受影响。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+// AlarmOnly 模式下等待到期闹钟分发的时间
+const ALARM_SETTLE_TIME: Duration = Duration::from_secs(3);
+// `cmd alarm set-time` 的最低 SDK 版本 (Android 9)
+const ALARM_SET_TIME_MIN_SDK: u32 = 28;
+
+/// 充电状态 (`BatteryManager.BATTERY_STATUS_*`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatteryStatus {
+    Unknown,
+    Charging,
+    Discharging,
+    NotCharging,
+    Full,
+}
+
+impl BatteryStatus {
+    fn from_code(code: u32) -> Self {
+        match code {
+            2 => BatteryStatus::Charging,
+            3 => BatteryStatus::Discharging,
+            4 => BatteryStatus::NotCharging,
+            5 => BatteryStatus::Full,
+            _ => BatteryStatus::Unknown,
+        }
+    }
+}
+
+/// 电池健康状态 (`BatteryManager.BATTERY_HEALTH_*`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatteryHealth {
+    Unknown,
+    Good,
+    Overheat,
+    Dead,
+    OverVoltage,
+    UnspecifiedFailure,
+    Cold,
+}
+
+impl BatteryHealth {
+    fn from_code(code: u32) -> Self {
+        match code {
+            2 => BatteryHealth::Good,
+            3 => BatteryHealth::Overheat,
+            4 => BatteryHealth::Dead,
+            5 => BatteryHealth::OverVoltage,
+            6 => BatteryHealth::UnspecifiedFailure,
+            7 => BatteryHealth::Cold,
+            _ => BatteryHealth::Unknown,
+        }
+    }
+}
+
+/// 充电来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatteryPlugged {
+    None,
+    Ac,
+    Usb,
+    Wireless,
+    Dock,
+}
+
+/// 电池信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatteryInfo {
+    /// 电量百分比
+    pub level: u8,
+    pub status: BatteryStatus,
+    pub health: BatteryHealth,
+    /// 温度（摄氏度）
+    pub temperature: f32,
+    /// 电压（毫伏）
+    pub voltage: u32,
+    pub plugged: BatteryPlugged,
+    /// 剩余电荷（微安时），设备不支持时为 None
+    pub charge_counter: Option<u64>,
+}
+
+/// 解析 `dumpsys battery` 输出
+fn parse_battery(output: &str) -> ADBResult<BatteryInfo> {
+    let fields: HashMap<&str, &str> = output
+        .lines()
+        .filter_map(|l| l.trim().split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    let number = |key: &str| fields.get(key).and_then(|v| v.parse::<i64>().ok());
+    let powered = |key: &str| fields.get(key) == Some(&"true");
+
+    let level = number("level")
+        .ok_or_else(|| ADBError::ParseError(format!("无法解析电池信息: {}", output.trim())))?;
+    let scale = number("scale").filter(|s| *s > 0).unwrap_or(100);
+
+    let plugged = if powered("AC powered") {
+        BatteryPlugged::Ac
+    } else if powered("USB powered") {
+        BatteryPlugged::Usb
+    } else if powered("Wireless powered") {
+        BatteryPlugged::Wireless
+    } else if powered("Dock powered") {
+        BatteryPlugged::Dock
+    } else {
+        BatteryPlugged::None
+    };
+
+    Ok(BatteryInfo {
+        level: (level * 100 / scale).clamp(0, 100) as u8,
+        status: BatteryStatus::from_code(number("status").unwrap_or(1) as u32),
+        health: BatteryHealth::from_code(number("health").unwrap_or(1) as u32),
+        // 以 0.1 摄氏度为单位
+        temperature: number("temperature").unwrap_or(0) as f32 / 10.0,
+        voltage: number("voltage").unwrap_or(0).max(0) as u32,
+        plugged,
+        charge_counter: number("Charge counter").filter(|c| *c >= 0).map(|c| c as u64),
+    })
+}
+
+/// 时间快进方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdvanceMode {
+    /// 保持快进后的时间，直到调用 [`TimeTravel::restore`] 或超出作用域
+    Clock,
+    /// 快进后等待到期的 `RTC` 闹钟分发，随即恢复时间
+    AlarmOnly,
+}
+
+/// 时间快进句柄，`restore()` 或超出作用域时恢复设备时间和自动时间设置
+pub struct TimeTravel {
+    adb: ADB,
+    device_id: String,
+    offset: Duration,
+    /// 快进前的 `auto_time` 设置
+    auto_time: Option<String>,
+    restored: bool,
+}
+
+impl TimeTravel {
+    /// 快进的时长
+    pub fn offset(&self) -> Duration {
+        self.offset
+    }
+
+    /// 时间是否已恢复
+    pub fn is_restored(&self) -> bool {
+        self.restored
+    }
+
+    /// 恢复设备时间和自动时间设置
+    pub fn restore(mut self) -> ADBResult<()> {
+        self.restore_inner()
+    }
+
+    fn restore_inner(&mut self) -> ADBResult<()> {
+        if self.restored {
+            return Ok(());
+        }
+
+        // 快进失败时时间未被修改，只需还原设置
+        if !self.offset.is_zero() {
+            let now = self.adb.device_epoch_secs(&self.device_id)?;
+            self.adb
+                .set_device_time(&self.device_id, now.saturating_sub(self.offset.as_secs()))?;
+        }
+        if let Some(auto_time) = &self.auto_time {
+            self.adb.shell(
+                &self.device_id,
+                &format!("settings put global auto_time {}", auto_time),
+            )?;
+        }
+
+        self.restored = true;
+        debug!("设备 {} 时间已恢复", self.device_id);
+        Ok(())
+    }
+}
+
+impl Drop for TimeTravel {
+    fn drop(&mut self) {
+        if let Err(e) = self.restore_inner() {
+            warn!("设备 {} 恢复时间失败: {}", self.device_id, e);
+        }
+    }
+}
+
+impl ADB {
+    /// 获取电池信息
+    pub fn get_battery_info(&self, device_id: &str) -> ADBResult<BatteryInfo> {
+        let output = self.shell(device_id, "dumpsys battery")?;
+        parse_battery(&output)
+    }
+
+    /// 模拟电池电量，直到调用 [`ADB::reset_battery`]
+    pub fn set_battery_level(&self, device_id: &str, level: u8) -> ADBResult<()> {
+        if level > 100 {
+            return Err(ADBError::ConfigError(format!("无效的电量: {}", level)));
+        }
+        self.shell(device_id, &format!("dumpsys battery set level {}", level))?;
+        debug!("设备 {} 模拟电量 {}%", device_id, level);
+        Ok(())
+    }
+
+    /// 模拟拔出电源，系统和应用会认为设备在使用电池供电
+    pub fn unplug_battery(&self, device_id: &str) -> ADBResult<()> {
+        self.shell(device_id, "dumpsys battery unplug")?;
+        debug!("设备 {} 模拟拔出电源", device_id);
+        Ok(())
+    }
+
+    /// 恢复真实的电池状态
+    pub fn reset_battery(&self, device_id: &str) -> ADBResult<()> {
+        self.shell(device_id, "dumpsys battery reset")?;
+        debug!("设备 {} 电池状态已恢复", device_id);
+        Ok(())
+    }
+
+    /// 将设备系统时间快进 `duration`
+    ///
+    /// 有 root 权限时使用 `date` 修改时间并发送 `TIME_SET` 广播；否则在 Android 9 及以上
+    /// 使用 `cmd alarm set-time`。快进期间关闭自动时间，恢复时还原。
+    pub fn advance_time(&self, device_id: &str, duration: Duration, mode: AdvanceMode) -> ADBResult<TimeTravel> {
+        let auto_time = self
+            .shell(device_id, "settings get global auto_time")?
+            .trim()
+            .to_string();
+        let auto_time = Some(auto_time).filter(|v| !v.is_empty() && v != "null");
+        self.shell(device_id, "settings put global auto_time 0")?;
+
+        let mut travel = TimeTravel {
+            adb: self.clone(),
+            device_id: device_id.to_string(),
+            offset: Duration::ZERO,
+            auto_time,
+            restored: false,
+        };
+
+        let now = self.device_epoch_secs(device_id)?;
+        self.set_device_time(device_id, now + duration.as_secs())?;
+        travel.offset = duration;
+        info!("设备 {} 时间已快进 {:?}", device_id, duration);
+
+        if mode == AdvanceMode::AlarmOnly {
+            thread::sleep(ALARM_SETTLE_TIME);
+            travel.restore_inner()?;
+        }
+
+        Ok(travel)
+    }
+
+    /// 设备当前时间（Unix 秒）
+    fn device_epoch_secs(&self, device_id: &str) -> ADBResult<u64> {
+        let output = self.shell(device_id, "date +%s")?;
+        output
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| ADBError::ParseError(format!("无法解析设备时间: {}", output.trim())))
+    }
+
+    /// 设置设备时间（Unix 秒）
+    fn set_device_time(&self, device_id: &str, epoch_secs: u64) -> ADBResult<()> {
+        let command = format!(
+            "date @{} >/dev/null && am broadcast -a android.intent.action.TIME_SET >/dev/null",
+            epoch_secs
+        );
+        if let Some(command) = self.root_command(device_id, &command) {
+            self.shell(device_id, &command)?;
+            return Ok(());
+        }
+
+        if self.device_profile(device_id)?.sdk_int < ALARM_SET_TIME_MIN_SDK {
+            return Err(ADBError::PermissionDenied(
+                "修改系统时间需要 root 权限或 Android 9 及以上的 cmd alarm".to_string(),
+            ));
+        }
+        let output = self.shell(
+            device_id,
+            &format!("cmd alarm set-time {} 2>&1; true", epoch_secs * 1000),
+        )?;
+        if output.contains("Exception") || output.contains("Error") || output.contains("false") {
+            return Err(ADBError::PermissionDenied(format!(
+                "修改系统时间失败: {}",
+                output.trim()
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+//! 进程列表
+//!
+//! Android 8 起的 toybox `ps` 支持 `-o` 指定列；更早的版本只能按默认列输出，
+//! 列的顺序和含义各不相同。这里统一按表头识别各列，是查询进程和 PID 的唯一解析入口。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
+
+// 支持 `ps -o` 的最低 SDK 版本 (Android 8)
+const PS_COLUMNS_MIN_SDK: u32 = 26;
+// toybox ps 输出的列
+const PS_COLUMNS: &str = "PID,PPID,UID,S,RSS,%CPU,NAME";
+
+/// 进程信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: i32,
+    pub ppid: i32,
+    /// 用户 ID，旧版本由用户名（如 `u0_a123`）换算，无法换算时为 None
+    pub uid: Option<u32>,
+    /// 进程名，应用进程为包名（或 `包名:进程名`）
+    pub name: String,
+    /// 进程状态（R 运行、S 睡眠、D 不可中断、Z 僵尸等）
+    pub state: String,
+    /// 常驻内存（KB）
+    pub rss_kb: u64,
+    /// 进程启动以来的平均 CPU 占用率，旧版本 ps 没有此列
+    pub cpu_percent: Option<f32>,
+}
+
+impl ProcessInfo {
+    /// 是否为指定应用的进程（包括 `包名:进程名` 形式的子进程）
+    pub fn belongs_to(&self, package_name: &str) -> bool {
+        self.name == package_name
+            || self
+                .name
+                .strip_prefix(package_name)
+                .is_some_and(|rest| rest.starts_with(':'))
+    }
+}
+
+/// 将用户名换算为 UID：`root`、`system`、`shell` 等系统用户和 `u<用户>_a<应用>` 形式的应用用户
+fn uid_from_user(user: &str) -> Option<u32> {
+    if let Ok(uid) = user.parse() {
+        return Some(uid);
+    }
+    match user {
+        "root" => return Some(0),
+        "system" => return Some(1000),
+        "radio" => return Some(1001),
+        "bluetooth" => return Some(1002),
+        "media" => return Some(1013),
+        "nfc" => return Some(1027),
+        "shell" => return Some(2000),
+        _ => {}
+    }
+
+    let (user_id, app) = user.strip_prefix('u')?.split_once('_')?;
+    let user_id: u32 = user_id.parse().ok()?;
+    let app_id = if let Some(id) = app.strip_prefix('a') {
+        10000 + id.parse::<u32>().ok()?
+    } else if let Some(id) = app.strip_prefix('i') {
+        // 隔离进程
+        99000 + id.parse::<u32>().ok()?
+    } else {
+        return None;
+    };
+    Some(user_id * 100000 + app_id)
+}
+
+/// 按表头解析 `ps` 输出，进程名为最后一列（可能包含空格）
+pub(crate) fn parse_ps(output: &str) -> Vec<ProcessInfo> {
+    let mut lines = output.lines().filter(|l| !l.trim().is_empty());
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let columns: Vec<&str> = header.split_whitespace().collect();
+    let index = |names: &[&str]| columns.iter().position(|c| names.contains(c));
+
+    let pid_col = index(&["PID"]);
+    let ppid_col = index(&["PPID"]);
+    let uid_col = index(&["UID", "USER"]);
+    let state_col = index(&["S"]);
+    let rss_col = index(&["RSS", "RES"]);
+    let cpu_col = index(&["%CPU", "CPU%"]);
+
+    lines
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // 进程名可能包含空格，名称之前的列与表头一一对应
+            let name_start = columns.len().checked_sub(1)?;
+            if fields.len() <= name_start {
+                return None;
+            }
+            // Android 7 之前的 toolbox ps 表头没有状态列，但数据行在进程名前多一列状态
+            let (state, name_start) = match state_col {
+                Some(col) => (fields.get(col).copied().unwrap_or(""), name_start),
+                None if fields.len() > columns.len() => (fields[name_start], name_start + 1),
+                None => ("", name_start),
+            };
+            let field = |col: Option<usize>| col.and_then(|c| fields.get(c).copied());
+
+            Some(ProcessInfo {
+                pid: field(pid_col)?.parse().ok()?,
+                ppid: field(ppid_col).and_then(|v| v.parse().ok()).unwrap_or(0),
+                uid: field(uid_col).and_then(uid_from_user),
+                name: fields[name_start..].join(" "),
+                state: state.to_string(),
+                rss_kb: field(rss_col).and_then(|v| v.parse().ok()).unwrap_or(0),
+                cpu_percent: field(cpu_col).and_then(|v| v.trim_end_matches('%').parse().ok()),
+            })
+        })
+        .collect()
+}
+
+impl ADB {
+    /// 列出设备上的全部进程
+    pub fn list_processes(&self, device_id: &str) -> ADBResult<Vec<ProcessInfo>> {
+        let legacy = self.device_profile(device_id)?.sdk_int < PS_COLUMNS_MIN_SDK;
+        let command = if legacy {
+            self.device_quirks(device_id)?.ps_command
+        } else {
+            format!("ps -A -o {}", PS_COLUMNS)
+        };
+
+        let output = self.shell(device_id, &command)?;
+        let processes = parse_ps(&output);
+        // 旧版本 ps 的列因设备而异，解析不出进程时按空列表处理，不视为错误
+        if processes.is_empty() && !legacy {
+            return Err(ADBError::ParseError(format!(
+                "无法解析进程列表: {}",
+                output.lines().next().unwrap_or("").trim()
+            )));
+        }
+
+        trace!("设备 {} 有 {} 个进程", device_id, processes.len());
+        Ok(processes)
+    }
+
+    /// 查找进程名与 `name` 完全相同的进程
+    pub fn find_process(&self, device_id: &str, name: &str) -> ADBResult<Option<ProcessInfo>> {
+        let process = self
+            .list_processes(device_id)?
+            .into_iter()
+            .find(|p| p.name == name);
+        debug!("设备 {} 进程 {}: {:?}", device_id, name, process.as_ref().map(|p| p.pid));
+        Ok(process)
+    }
+
+    /// 列出应用的所有进程（主进程和 `包名:进程名` 形式的子进程）
+    pub fn app_processes(&self, device_id: &str, package_name: &str) -> ADBResult<Vec<ProcessInfo>> {
+        Ok(self
+            .list_processes(device_id)?
+            .into_iter()
+            .filter(|p| p.belongs_to(package_name))
+            .collect())
+    }
+}
@@ -0,0 +1,180 @@
+//! 堆转储
+//!
+//! `am dumpheap` 让应用把 Java 堆（或加 `-n` 时的 native 堆）写入设备上的文件，
+//! 旧版本中该命令在写入完成前就返回，因此通过文件大小是否稳定判断转储是否结束。
+//! Android 格式的 hprof 需要用 SDK 中的 `hprof-conv` 转换后才能被标准工具（如 MAT）打开。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
+use log::{debug, info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 设备上的临时转储目录
+const HEAP_DUMP_DIR: &str = "/data/local/tmp";
+// 等待转储完成的超时时间
+const HEAP_DUMP_TIMEOUT: Duration = Duration::from_secs(120);
+// 检查转储文件大小的间隔
+const HEAP_DUMP_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 堆转储类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeapDumpKind {
+    /// Java 堆 (hprof)
+    Managed,
+    /// native 堆分配记录（需要应用开启 malloc debug）
+    Native,
+}
+
+/// 堆转储结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeapDump {
+    pub package_name: String,
+    pub kind: HeapDumpKind,
+    /// 本地文件路径
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// 是否已转换为标准 hprof 格式
+    pub converted: bool,
+}
+
+/// 查找 `hprof-conv`：adb 所在目录、`ANDROID_HOME`/`ANDROID_SDK_ROOT` 的 platform-tools、`PATH`
+fn find_hprof_conv(adb_path: &Path) -> Option<PathBuf> {
+    let name = if cfg!(windows) { "hprof-conv.exe" } else { "hprof-conv" };
+
+    let mut dirs: Vec<PathBuf> = adb_path.parent().map(Path::to_path_buf).into_iter().collect();
+    for var in ["ANDROID_HOME", "ANDROID_SDK_ROOT"] {
+        if let Some(home) = std::env::var_os(var) {
+            dirs.push(PathBuf::from(home).join("platform-tools"));
+        }
+    }
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+
+    dirs.into_iter().map(|dir| dir.join(name)).find(|p| p.is_file())
+}
+
+impl ADB {
+    /// 转储应用的堆并拉取到 `out_path`
+    ///
+    /// `convert` 为 true 时用 `hprof-conv` 把 Java 堆转储转换为标准 hprof 格式；找不到
+    /// `hprof-conv` 时保留 Android 格式并记录警告。native 堆转储不做转换。
+    pub fn dump_heap(
+        &self,
+        device_id: &str,
+        package_name: &str,
+        out_path: &str,
+        kind: HeapDumpKind,
+        convert: bool,
+    ) -> ADBResult<HeapDump> {
+        let pid = self.get_pid(device_id, package_name)?.ok_or_else(|| {
+            ADBError::AppNotFound(format!("应用 {} 没有运行中的进程", package_name))
+        })?;
+
+        let device_path = format!(
+            "{}/adbkit-{}-{}.{}",
+            HEAP_DUMP_DIR,
+            package_name,
+            pid,
+            if kind == HeapDumpKind::Native { "txt" } else { "hprof" }
+        );
+        let quoted = shell_quote(&device_path);
+        let flag = if kind == HeapDumpKind::Native { "-n " } else { "" };
+
+        info!("开始转储应用 {} (pid {}) 的堆: {:?}", package_name, pid, kind);
+        let _ = self.shell(device_id, &format!("rm -f {}", quoted));
+        let output = self.shell(device_id, &format!("am dumpheap {}{} {} 2>&1", flag, pid, quoted))?;
+        if output.contains("Error") || output.contains("Exception") {
+            return Err(ADBError::CommandError(format!(
+                "转储应用 {} 的堆失败: {}",
+                package_name,
+                output.trim()
+            )));
+        }
+
+        let result = self
+            .wait_heap_dump(device_id, &quoted)
+            .and_then(|_| self.pull_heap_dump(device_id, &device_path, out_path, kind, convert));
+        let _ = self.shell(device_id, &format!("rm -f {}", quoted));
+        let (path, converted) = result?;
+
+        let size_bytes = fs::metadata(&path)?.len();
+        debug!("应用 {} 的堆转储已保存到 {:?} ({} 字节)", package_name, path, size_bytes);
+        Ok(HeapDump {
+            package_name: package_name.to_string(),
+            kind,
+            path,
+            size_bytes,
+            converted,
+        })
+    }
+
+    /// 等待转储文件大小不再变化
+    fn wait_heap_dump(&self, device_id: &str, quoted_path: &str) -> ADBResult<()> {
+        let start = Instant::now();
+        let mut last_size = 0u64;
+
+        while start.elapsed() < HEAP_DUMP_TIMEOUT {
+            thread::sleep(HEAP_DUMP_POLL_INTERVAL);
+            let size = self
+                .shell(device_id, &format!("stat -c %s {} 2>/dev/null; true", quoted_path))?
+                .trim()
+                .parse::<u64>()
+                .unwrap_or(0);
+            if size > 0 && size == last_size {
+                return Ok(());
+            }
+            last_size = size;
+        }
+
+        Err(ADBError::TimeoutError {
+            message: format!("等待堆转储 {} 完成超时", quoted_path),
+            duration: HEAP_DUMP_TIMEOUT,
+        })
+    }
+
+    /// 拉取转储文件，按需转换格式，返回本地路径和是否已转换
+    fn pull_heap_dump(
+        &self,
+        device_id: &str,
+        device_path: &str,
+        out_path: &str,
+        kind: HeapDumpKind,
+        convert: bool,
+    ) -> ADBResult<(PathBuf, bool)> {
+        let hprof_conv = if convert && kind == HeapDumpKind::Managed {
+            let found = find_hprof_conv(&self.config.path);
+            if found.is_none() {
+                warn!("未找到 hprof-conv，保留 Android 格式的堆转储");
+            }
+            found
+        } else {
+            None
+        };
+
+        let Some(hprof_conv) = hprof_conv else {
+            self.pull(device_id, device_path, out_path, None)?;
+            return Ok((PathBuf::from(out_path), false));
+        };
+
+        let raw_path = format!("{}.android", out_path);
+        self.pull(device_id, device_path, &raw_path, None)?;
+        let status = Command::new(&hprof_conv).arg(&raw_path).arg(out_path).status();
+        match status {
+            Ok(status) if status.success() => {
+                let _ = fs::remove_file(&raw_path);
+                Ok((PathBuf::from(out_path), true))
+            }
+            other => {
+                warn!("hprof-conv 转换失败 ({:?})，保留 Android 格式的堆转储", other);
+                fs::rename(&raw_path, out_path)?;
+                Ok((PathBuf::from(out_path), false))
+            }
+        }
+    }
+}
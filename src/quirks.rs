@@ -0,0 +1,146 @@
+//! OEM 系统差异
+//!
+//! 部分厂商 ROM 会影响自动化：安装需要屏幕确认、后台进程被杀、输入注入被禁止等。
+//! 这里根据系统属性识别已知问题，给出处理建议，并提供稳定的 ID 供其他模块判断。
+//! 命令语法上的版本差异（`ps` 参数、`pidof` 等）见 [`ADB::device_quirks`]。
+
+use crate::device::ADB;
+use crate::error::ADBResult;
+use crate::utils::parse_properties;
+use log::debug;
+use std::collections::HashMap;
+use std::fmt;
+
+/// 已知的 OEM 问题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuirkId {
+    /// MIUI 未开启「USB 安装」，`adb install` 会失败或等待屏幕确认
+    MiuiInstallViaUsb,
+    /// MIUI 未开启「USB 调试（安全设置）」，`input` 注入和权限授予被拒绝
+    MiuiSecuritySettings,
+    /// EMUI / HarmonyOS 会主动杀死后台应用
+    EmuiAppKilling,
+    /// ColorOS 安装时需要验证并会限制后台运行
+    ColorOsInstallVerification,
+    /// 三星设备启用了 Knox
+    SamsungKnox,
+    /// 三星 Knox 保修位已熔断（设备曾被解锁或刷机）
+    SamsungKnoxWarrantyVoid,
+}
+
+impl QuirkId {
+    /// 机器可读的标识
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuirkId::MiuiInstallViaUsb => "miui.install_via_usb",
+            QuirkId::MiuiSecuritySettings => "miui.usb_debugging_security",
+            QuirkId::EmuiAppKilling => "emui.app_killing",
+            QuirkId::ColorOsInstallVerification => "coloros.install_verification",
+            QuirkId::SamsungKnox => "samsung.knox",
+            QuirkId::SamsungKnoxWarrantyVoid => "samsung.knox_warranty_void",
+        }
+    }
+}
+
+impl fmt::Display for QuirkId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 检测到的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quirk {
+    pub id: QuirkId,
+    /// 问题说明
+    pub description: String,
+    /// 处理建议
+    pub remediation: String,
+}
+
+impl Quirk {
+    fn new(id: QuirkId, description: &str, remediation: &str) -> Self {
+        Self {
+            id,
+            description: description.to_string(),
+            remediation: remediation.to_string(),
+        }
+    }
+}
+
+/// 根据系统属性判断已知问题
+fn quirks_from_properties(props: &HashMap<String, String>) -> Vec<Quirk> {
+    let prop = |key: &str| props.get(key).map(|v| v.trim()).filter(|v| !v.is_empty());
+    let mut quirks = Vec::new();
+
+    if prop("ro.miui.ui.version.name").is_some() {
+        if prop("persist.security.adbinstall") != Some("1") {
+            quirks.push(Quirk::new(
+                QuirkId::MiuiInstallViaUsb,
+                "MIUI 未开启「USB 安装」，通过 adb 安装应用会被拦截或等待屏幕确认",
+                "在 开发者选项 中开启「USB 安装」（需要登录小米账号并插入 SIM 卡）",
+            ));
+        }
+        if prop("persist.security.adbinput") != Some("1") {
+            quirks.push(Quirk::new(
+                QuirkId::MiuiSecuritySettings,
+                "MIUI 未开启「USB 调试（安全设置）」，模拟输入和授予权限会被拒绝",
+                "在 开发者选项 中开启「USB 调试（安全设置）」",
+            ));
+        }
+    }
+
+    if prop("ro.build.version.emui").is_some() || prop("ro.build.hw_emui_api_level").is_some() {
+        quirks.push(Quirk::new(
+            QuirkId::EmuiAppKilling,
+            "EMUI / HarmonyOS 会在锁屏或后台时杀死应用进程",
+            "在 手机管家 > 应用启动管理 中将被测应用设为手动管理并允许后台活动",
+        ));
+    }
+
+    if prop("ro.build.version.opporom").is_some() || prop("ro.build.version.oplusrom").is_some() {
+        quirks.push(Quirk::new(
+            QuirkId::ColorOsInstallVerification,
+            "ColorOS 通过 adb 安装时要求输入账号密码验证，并限制后台运行",
+            "在 开发者选项 中关闭「监控 ADB 安装应用」，并在电池设置中允许被测应用后台运行",
+        ));
+    }
+
+    if prop("ro.config.knox").is_some() {
+        quirks.push(Quirk::new(
+            QuirkId::SamsungKnox,
+            "设备启用了 Knox，企业策略可能禁止安装、调试或修改设置",
+            "检查设备是否受 MDM 管理，必要时在 Knox 控制台中放开对应策略",
+        ));
+        if prop("ro.boot.warranty_bit") == Some("1") {
+            quirks.push(Quirk::new(
+                QuirkId::SamsungKnoxWarrantyVoid,
+                "Knox 保修位已熔断，Samsung Pay、安全文件夹等 Knox 功能不可用",
+                "依赖 Knox 功能的测试需要换用未解锁过的设备",
+            ));
+        }
+    }
+
+    quirks
+}
+
+impl ADB {
+    /// 检测设备上影响自动化的已知 OEM 问题
+    pub fn detect_quirks(&self, device_id: &str) -> ADBResult<Vec<Quirk>> {
+        let output = self.shell(device_id, "getprop")?;
+        let quirks = quirks_from_properties(&parse_properties(&output));
+
+        debug!(
+            "设备 {} 检测到 {} 个 OEM 问题: {:?}",
+            device_id,
+            quirks.len(),
+            quirks.iter().map(|q| q.id.as_str()).collect::<Vec<_>>()
+        );
+        Ok(quirks)
+    }
+
+    /// 设备是否存在指定问题
+    pub fn has_quirk(&self, device_id: &str, id: QuirkId) -> ADBResult<bool> {
+        Ok(self.detect_quirks(device_id)?.iter().any(|q| q.id == id))
+    }
+}
@@ -0,0 +1,173 @@
+//! 设备标签
+//!
+//! 标签保存在设备上，而不是主机上，因此机架槽位、负责人等信息会随设备一起
+//! 迁移到其他主机。标签写入 `/data/local/tmp/.adbkit_tags`；设备有 root 权限时
+//! 同时写入 `persist.adbkit.tag.*` 属性。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::{parse_properties, shell_quote};
+use log::{debug, warn};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+// 设备上的标签文件
+const TAG_FILE: &str = "/data/local/tmp/.adbkit_tags";
+// 持久化属性前缀
+const TAG_PROP_PREFIX: &str = "persist.adbkit.tag.";
+// 非只读属性值的最大长度
+const PROP_VALUE_MAX: usize = 91;
+// 标签文件与属性输出之间的分隔标记
+const TAG_PROPS_MARKER: &str = "__ADBKIT_TAG_PROPS__";
+
+/// 检查标签键和值：键只能包含字母、数字、`_`、`-` 和 `.`，值不能包含换行
+fn validate_tag(key: &str, value: &str) -> ADBResult<()> {
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(ADBError::ConfigError(format!("无效的标签名: {}", key)));
+    }
+    if value.contains('\n') || value.contains('\r') {
+        return Err(ADBError::ConfigError(format!("标签 {} 的值不能包含换行", key)));
+    }
+    Ok(())
+}
+
+/// 解析标签文件（每行 `key=value`，忽略空行和 `#` 注释）
+fn parse_tag_file(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| {
+            let (key, value) = l.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// 序列化标签文件
+fn format_tag_file(tags: &BTreeMap<String, String>) -> String {
+    tags.iter()
+        .map(|(key, value)| format!("{}={}\n", key, value))
+        .collect()
+}
+
+impl ADB {
+    /// 读取设备上的全部标签，标签文件中的值优先于持久化属性
+    pub fn device_tags(&self, device_id: &str) -> ADBResult<BTreeMap<String, String>> {
+        let output = self.shell(
+            device_id,
+            &format!(
+                "cat {} 2>/dev/null; echo {}; getprop | grep '\\[{}'; true",
+                TAG_FILE, TAG_PROPS_MARKER, TAG_PROP_PREFIX
+            ),
+        )?;
+        let (file, props) = output.split_once(TAG_PROPS_MARKER).unwrap_or((&output, ""));
+
+        let mut tags: BTreeMap<String, String> = parse_properties(props)
+            .into_iter()
+            .filter_map(|(prop, value)| {
+                prop.strip_prefix(TAG_PROP_PREFIX)
+                    .map(|key| (key.to_string(), value))
+            })
+            .collect();
+        tags.extend(parse_tag_file(file));
+        Ok(tags)
+    }
+
+    /// 读取单个设备标签
+    pub fn get_device_tag(&self, device_id: &str, key: &str) -> ADBResult<Option<String>> {
+        Ok(self.device_tags(device_id)?.remove(key))
+    }
+
+    /// 设置设备标签
+    ///
+    /// 设备有 root 权限且值不超过属性长度限制时，同时写入 `persist.adbkit.tag.<key>`。
+    pub fn set_device_tag(&self, device_id: &str, key: &str, value: &str) -> ADBResult<()> {
+        validate_tag(key, value)?;
+
+        let mut tags = self.read_tag_file(device_id)?;
+        tags.insert(key.to_string(), value.to_string());
+        self.write_tag_file(device_id, &tags)?;
+
+        let setprop = format!("setprop {}{} {}", TAG_PROP_PREFIX, key, shell_quote(value));
+        if let Some(command) = self.root_command(device_id, &setprop) {
+            if value.len() <= PROP_VALUE_MAX {
+                self.shell(device_id, &command)?;
+            } else {
+                warn!("标签 {} 的值超过 {} 字节，仅写入标签文件", key, PROP_VALUE_MAX);
+            }
+        }
+
+        debug!("设备 {} 设置标签 {}={}", device_id, key, value);
+        Ok(())
+    }
+
+    /// 删除设备标签，返回标签是否存在
+    pub fn remove_device_tag(&self, device_id: &str, key: &str) -> ADBResult<bool> {
+        validate_tag(key, "")?;
+
+        let existed = self.device_tags(device_id)?.contains_key(key);
+        let mut tags = self.read_tag_file(device_id)?;
+        if tags.remove(key).is_some() {
+            self.write_tag_file(device_id, &tags)?;
+        }
+
+        let setprop = format!("setprop {}{} ''", TAG_PROP_PREFIX, key);
+        if let Some(command) = self.root_command(device_id, &setprop) {
+            self.shell(device_id, &command)?;
+        }
+
+        Ok(existed)
+    }
+
+    /// 在所有在线设备中查找标签匹配的设备
+    pub fn find_devices_by_tag(&self, key: &str, value: &str) -> ADBResult<Vec<String>> {
+        let devices = self.list_devices()?;
+        let online: Vec<String> = devices
+            .into_iter()
+            .filter(|d| d.is_online())
+            .map(|d| d.id)
+            .collect();
+
+        let matched: Vec<String> = online
+            .par_iter()
+            .filter(|id| match self.get_device_tag(id, key) {
+                Ok(tag) => tag.as_deref() == Some(value),
+                Err(e) => {
+                    warn!("读取设备 {} 标签失败: {}", id, e);
+                    false
+                }
+            })
+            .cloned()
+            .collect();
+
+        debug!("标签 {}={} 匹配 {} 个设备", key, value, matched.len());
+        Ok(matched)
+    }
+
+    /// 只读取标签文件
+    fn read_tag_file(&self, device_id: &str) -> ADBResult<BTreeMap<String, String>> {
+        let content = self.shell(device_id, &format!("cat {} 2>/dev/null; true", TAG_FILE))?;
+        Ok(parse_tag_file(&content))
+    }
+
+    /// 写入标签文件（先写临时文件再重命名）
+    fn write_tag_file(&self, device_id: &str, tags: &BTreeMap<String, String>) -> ADBResult<()> {
+        let temp = format!("{}.tmp", TAG_FILE);
+        self.shell(
+            device_id,
+            &format!(
+                "printf %s {} > {} && mv {} {}",
+                shell_quote(&format_tag_file(tags)),
+                temp,
+                temp,
+                TAG_FILE
+            ),
+        )?;
+        Ok(())
+    }
+}
@@ -1,11 +1,150 @@
-use crate::device::ADB;
+use crate::device::{DeviceHandle, ADB};
 use crate::error::{ADBError, ADBResult};
-use log::debug;
+use crate::input::KeyCode;
+use crate::wait::Condition;
+use log::{debug, info};
 use std::path::PathBuf;
 use std::thread;
 use std::time::Duration;
 
+/// 设备就绪检查配置
+#[derive(Debug, Clone)]
+pub struct ReadyProfile {
+    /// 等待设备在线的超时时间
+    pub online_timeout: Duration,
+    /// 验证 shell 可响应
+    pub check_shell: bool,
+    /// 等待系统启动完成 (sys.boot_completed)
+    pub wait_boot_completed: bool,
+    /// 等待启动完成的超时时间
+    pub boot_timeout: Duration,
+    /// 唤醒并解锁屏幕
+    pub unlock_screen: bool,
+    /// 解锁屏幕时输入的 PIN（可选）
+    pub unlock_pin: Option<String>,
+}
+
+impl Default for ReadyProfile {
+    fn default() -> Self {
+        ReadyProfile {
+            online_timeout: Duration::from_secs(30),
+            check_shell: true,
+            wait_boot_completed: true,
+            boot_timeout: Duration::from_secs(120),
+            unlock_screen: false,
+            unlock_pin: None,
+        }
+    }
+}
+
+impl ReadyProfile {
+    /// 仅检查在线状态和 shell 响应
+    pub fn minimal() -> Self {
+        ReadyProfile {
+            wait_boot_completed: false,
+            ..Default::default()
+        }
+    }
+
+    /// 检查全部项目并解锁屏幕
+    pub fn interactive(pin: Option<&str>) -> Self {
+        ReadyProfile {
+            unlock_screen: true,
+            unlock_pin: pin.map(|p| p.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
 impl ADB {
+    /// 连接远程设备并等待其真正可用
+    ///
+    /// 依次执行：连接、等待在线、验证 shell 响应、等待启动完成、（可选）解锁屏幕。
+    /// 只有全部检查通过才返回设备句柄。
+    pub fn connect_and_prepare(
+        &self,
+        ip: &str,
+        port: u16,
+        profile: ReadyProfile,
+    ) -> ADBResult<DeviceHandle> {
+        let device_id = format!("{}:{}", ip, port);
+
+        self.connect(ip, port)?;
+        self.prepare_device(&device_id, &profile)?;
+
+        info!("设备 {} 已就绪", device_id);
+        Ok(DeviceHandle::new(self.clone(), &device_id))
+    }
+
+    /// 对已连接设备执行就绪检查
+    pub fn prepare_device(&self, device_id: &str, profile: &ReadyProfile) -> ADBResult<()> {
+        // 等待设备在线
+        if !self.wait_until(device_id, &Condition::DeviceOnline, profile.online_timeout)? {
+            return Err(ADBError::TimeoutError {
+                message: format!("设备 {} 未进入在线状态", device_id),
+                duration: profile.online_timeout,
+            });
+        }
+
+        // 验证 shell 是否可响应
+        if profile.check_shell {
+            let output = self.shell(device_id, "echo ok")?;
+            if output.trim() != "ok" {
+                return Err(ADBError::DeviceError(format!(
+                    "设备 {} shell 无响应: {}",
+                    device_id,
+                    output.trim()
+                )));
+            }
+        }
+
+        // 等待系统启动完成
+        if profile.wait_boot_completed {
+            let booted = self.wait_until(
+                device_id,
+                &Condition::prop_equals("sys.boot_completed", "1"),
+                profile.boot_timeout,
+            )?;
+
+            if !booted {
+                return Err(ADBError::TimeoutError {
+                    message: format!("设备 {} 未完成启动", device_id),
+                    duration: profile.boot_timeout,
+                });
+            }
+        }
+
+        // 唤醒并解锁屏幕
+        if profile.unlock_screen {
+            self.unlock_screen(device_id, profile.unlock_pin.as_deref())?;
+        }
+
+        Ok(())
+    }
+
+    /// 唤醒并解锁屏幕
+    pub fn unlock_screen(&self, device_id: &str, pin: Option<&str>) -> ADBResult<()> {
+        // 先校验 PIN，避免在锁屏上输入无效内容
+        if let Some(pin) = pin {
+            if pin.is_empty() || !pin.chars().all(|c| c.is_ascii_digit()) {
+                return Err(ADBError::ConfigError(
+                    "解锁 PIN 只能包含数字".to_string(),
+                ));
+            }
+        }
+
+        self.key_event(device_id, KeyCode::Wakeup)?;
+        self.shell(device_id, "wm dismiss-keyguard")?;
+
+        if let Some(pin) = pin {
+            self.input_text(device_id, pin)?;
+            self.key_event(device_id, KeyCode::Enter)?;
+        }
+
+        debug!("已唤醒并解锁设备 {} 的屏幕", device_id);
+        Ok(())
+    }
+
     /// 启用设备远程调试
     pub fn enable_remote_debugging(
         &self,
@@ -199,7 +338,7 @@ impl ADB {
     /// 重启设备到正常模式
     pub fn reboot(&self, device_id: &str) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = std::process::Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
             if !device_id.is_empty() {
                 cmd.arg("-s").arg(device_id);
             }
@@ -221,7 +360,7 @@ impl ADB {
     /// 重启设备到恢复模式
     pub fn reboot_recovery(&self, device_id: &str) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = std::process::Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
             if !device_id.is_empty() {
                 cmd.arg("-s").arg(device_id);
             }
@@ -244,7 +383,7 @@ impl ADB {
     /// 重启设备到引导加载程序模式
     pub fn reboot_bootloader(&self, device_id: &str) -> ADBResult<()> {
         self.with_retry(|| {
-            let mut cmd = std::process::Command::new(&self.config.path);
+            let mut cmd = self.adb_command();
             if !device_id.is_empty() {
                 cmd.arg("-s").arg(device_id);
             }
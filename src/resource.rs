@@ -1,15 +1,99 @@
 use crate::device::ADB;
 use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
 use log::{debug, warn, info};
-use std::sync::{Arc};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Once};
 use std::time::{Duration, Instant};
 
+/// ADB 实例启动的后台任务和尚未清理的临时资源
+///
+/// 由同一 ADB 实例的所有克隆共享，[`ADB::shutdown`] 统一清理。
+/// 最后一个实例释放时会通知后台线程停止。
+#[derive(Debug)]
+pub(crate) struct BackgroundJobs {
+    /// 会话 ID，用于区分设备上不同 ADB 实例的临时目录
+    session_id: String,
+    /// 心跳等后台线程的停止标志
+    stop_flags: Mutex<Vec<Arc<AtomicBool>>>,
+    /// 带标签的端口转发：标签 -> (设备 ID, 本地端口)
+    forwards: Mutex<HashMap<String, (String, u16)>>,
+    /// 资源管理器跟踪的临时文件：设备 ID -> 路径
+    temp_files: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+impl Default for BackgroundJobs {
+    fn default() -> Self {
+        Self {
+            session_id: format!("{}-{:08x}", std::process::id(), rand::random::<u32>()),
+            stop_flags: Mutex::default(),
+            forwards: Mutex::default(),
+            temp_files: Mutex::default(),
+        }
+    }
+}
+
+impl BackgroundJobs {
+    pub(crate) fn register_stop_flag(&self, flag: Arc<AtomicBool>) {
+        let mut flags = self.stop_flags.lock().unwrap();
+        flags.retain(|f| !f.load(Ordering::SeqCst));
+        flags.push(flag);
+    }
+
+    pub(crate) fn register_forward(&self, label: &str, device_id: &str, local_port: u16) {
+        self.forwards
+            .lock()
+            .unwrap()
+            .insert(label.to_string(), (device_id.to_string(), local_port));
+    }
+
+    pub(crate) fn take_forward(&self, label: &str) -> Option<(String, u16)> {
+        self.forwards.lock().unwrap().remove(label)
+    }
+
+    pub(crate) fn track_temp_file(&self, device_id: &str, path: &str) {
+        self.temp_files
+            .lock()
+            .unwrap()
+            .entry(device_id.to_string())
+            .or_default()
+            .insert(path.to_string());
+    }
+
+    pub(crate) fn untrack_temp_file(&self, device_id: &str, path: &str) {
+        if let Some(paths) = self.temp_files.lock().unwrap().get_mut(device_id) {
+            paths.remove(path);
+        }
+    }
+
+    fn stop_all(&self) -> usize {
+        let flags: Vec<_> = self.stop_flags.lock().unwrap().drain(..).collect();
+        flags
+            .iter()
+            .filter(|flag| !flag.swap(true, Ordering::SeqCst))
+            .count()
+    }
+}
+
+impl Drop for BackgroundJobs {
+    fn drop(&mut self) {
+        self.stop_all();
+    }
+}
+
 /// 资源管理器结构体
 ///
-/// 负责跟踪和清理设备上的临时文件
+/// 负责跟踪和清理设备上的临时文件和目录
 pub struct ResourceManager {
     device_id: String,
     temp_files: Vec<String>,
+    temp_dirs: Vec<String>,
+    keep_on_failure: bool,
+    failed: bool,
     start_time: Instant,
     adb: Arc<ADB>,
 }
@@ -20,6 +104,9 @@ impl ResourceManager {
         Self {
             device_id: device_id.to_string(),
             temp_files: Vec::new(),
+            temp_dirs: Vec::new(),
+            keep_on_failure: false,
+            failed: false,
             start_time: Instant::now(),
             adb,
         }
@@ -28,25 +115,103 @@ impl ResourceManager {
     /// 添加临时文件到跟踪列表
     pub fn track_temp_file(&mut self, path: &str) {
         self.temp_files.push(path.to_string());
+        self.adb.jobs.track_temp_file(&self.device_id, path);
         debug!("添加临时文件到跟踪: {}", path);
     }
 
-    /// 手动清理所有跟踪的临时文件
+    /// 添加临时目录到跟踪列表，清理时递归删除
+    pub fn track_temp_dir(&mut self, path: &str) {
+        self.temp_dirs.push(path.to_string());
+        self.adb.jobs.track_temp_file(&self.device_id, path);
+        debug!("添加临时目录到跟踪: {}", path);
+    }
+
+    /// 本会话在设备上的临时目录（`/data/local/tmp/adbkit-<session>`）
+    pub fn session_dir(&self) -> String {
+        self.adb.session_temp_dir()
+    }
+
+    /// 生成会话目录下的唯一路径，并确保会话目录存在
+    fn allocate_temp_path(&self, prefix: &str, suffix: &str) -> ADBResult<String> {
+        let session_dir = self.session_dir();
+        self.adb
+            .shell(&self.device_id, &format!("mkdir -p {}", shell_quote(&session_dir)))?;
+        Ok(format!(
+            "{}/{}{:08x}{}",
+            session_dir,
+            prefix,
+            rand::random::<u32>(),
+            suffix
+        ))
+    }
+
+    /// 在会话目录下创建临时文件并跟踪，返回设备路径
+    pub fn create_temp_file(&mut self, prefix: &str, suffix: &str) -> ADBResult<String> {
+        let path = self.allocate_temp_path(prefix, suffix)?;
+        self.adb.shell(&self.device_id, &format!("touch {}", shell_quote(&path)))?;
+        self.track_temp_file(&path);
+        Ok(path)
+    }
+
+    /// 在会话目录下创建临时目录并跟踪，返回设备路径
+    pub fn create_temp_dir(&mut self) -> ADBResult<String> {
+        let path = self.allocate_temp_path("dir_", "")?;
+        self.adb.shell(&self.device_id, &format!("mkdir -p {}", shell_quote(&path)))?;
+        self.track_temp_dir(&path);
+        Ok(path)
+    }
+
+    /// 设置操作失败时是否保留临时文件，便于调试
+    pub fn keep_on_failure(&mut self, keep: bool) -> &mut Self {
+        self.keep_on_failure = keep;
+        self
+    }
+
+    /// 标记操作失败，开启 `keep_on_failure` 时清理会保留临时文件
+    pub fn mark_failed(&mut self) {
+        self.failed = true;
+    }
+
+    /// 手动清理所有跟踪的临时文件和目录
     pub fn cleanup(&mut self) -> ADBResult<()> {
+        if self.failed && self.keep_on_failure {
+            for path in self.temp_files.iter().chain(&self.temp_dirs) {
+                self.adb.jobs.untrack_temp_file(&self.device_id, path);
+                warn!("操作失败，保留设备 {} 上的临时文件: {}", self.device_id, path);
+            }
+            self.temp_files.clear();
+            self.temp_dirs.clear();
+            return Ok(());
+        }
+
         let mut errors = Vec::new();
+        let files = self.temp_files.iter().map(|f| (f, "rm -f"));
+        let dirs = self.temp_dirs.iter().map(|d| (d, "rm -rf"));
 
-        for file in &self.temp_files {
-            match self.adb.shell(&self.device_id, &format!("rm -f {}", file)) {
-                Ok(_) => debug!("已删除临时文件: {}", file),
+        for (path, rm) in files.chain(dirs) {
+            match self.adb.shell(&self.device_id, &format!("{} {}", rm, shell_quote(path))) {
+                Ok(_) => {
+                    self.adb.jobs.untrack_temp_file(&self.device_id, path);
+                    debug!("已删除临时文件: {}", path)
+                }
                 Err(e) => {
-                    warn!("删除临时文件 {} 失败: {}", file, e);
-                    errors.push(format!("文件 {}: {}", file, e));
+                    warn!("删除临时文件 {} 失败: {}", path, e);
+                    errors.push(format!("文件 {}: {}", path, e));
                 }
             }
         }
 
+        // 会话目录为空时一并删除
+        if !self.temp_files.is_empty() || !self.temp_dirs.is_empty() {
+            let _ = self.adb.shell(
+                &self.device_id,
+                &format!("rmdir {} 2>/dev/null; true", shell_quote(&self.session_dir())),
+            );
+        }
+
         // 清空跟踪列表
         self.temp_files.clear();
+        self.temp_dirs.clear();
 
         if errors.is_empty() {
             Ok(())
@@ -67,11 +232,11 @@ impl ResourceManager {
 // 为 ResourceManager 实现 Drop 特性，在超出作用域时自动清理资源
 impl Drop for ResourceManager {
     fn drop(&mut self) {
-        if !self.temp_files.is_empty() {
+        if !self.temp_files.is_empty() || !self.temp_dirs.is_empty() {
             info!(
                 "自动清理 {} 个设备上的临时文件 {}",
                 self.device_id,
-                self.temp_files.len()
+                self.temp_files.len() + self.temp_dirs.len()
             );
 
             // 尝试清理资源，但忽略错误（因为这是在 Drop 中）
@@ -80,8 +245,178 @@ impl Drop for ResourceManager {
     }
 }
 
+/// 主机端资源管理器
+///
+/// 跟踪主机上的临时文件和目录，在超出作用域时自动删除。
+/// 首次创建时会清理崩溃进程遗留的临时目录，见 [`sweep_host_orphans`]。
+pub struct HostResourceManager {
+    temp_files: Vec<PathBuf>,
+    temp_dirs: Vec<PathBuf>,
+    keep_on_failure: bool,
+    failed: bool,
+}
+
+/// 遗留临时目录在无法判断所属进程是否存活时的最长保留时间
+const HOST_ORPHAN_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+static HOST_SWEEP: Once = Once::new();
+
+impl Default for HostResourceManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HostResourceManager {
+    /// 创建新的主机端资源管理器
+    pub fn new() -> Self {
+        HOST_SWEEP.call_once(|| {
+            if let Ok(removed) = sweep_host_orphans(HOST_ORPHAN_MAX_AGE) {
+                if removed > 0 {
+                    info!("已清理 {} 个遗留的主机临时目录", removed);
+                }
+            }
+        });
+
+        Self {
+            temp_files: Vec::new(),
+            temp_dirs: Vec::new(),
+            keep_on_failure: false,
+            failed: false,
+        }
+    }
+
+    /// 在系统临时目录下创建临时目录并跟踪
+    pub fn create_temp_dir(&mut self, prefix: &str) -> ADBResult<PathBuf> {
+        let path = crate::utils::create_temp_dir_path(prefix)?;
+        self.track_temp_dir(&path);
+        Ok(path)
+    }
+
+    /// 添加临时文件到跟踪列表
+    pub fn track_temp_file(&mut self, path: &Path) {
+        self.temp_files.push(path.to_path_buf());
+        debug!("添加主机临时文件到跟踪: {}", path.display());
+    }
+
+    /// 添加临时目录到跟踪列表，清理时递归删除
+    pub fn track_temp_dir(&mut self, path: &Path) {
+        self.temp_dirs.push(path.to_path_buf());
+        debug!("添加主机临时目录到跟踪: {}", path.display());
+    }
+
+    /// 设置操作失败时是否保留临时文件，便于调试
+    pub fn keep_on_failure(&mut self, keep: bool) -> &mut Self {
+        self.keep_on_failure = keep;
+        self
+    }
+
+    /// 标记操作失败，开启 `keep_on_failure` 时清理会保留临时文件
+    pub fn mark_failed(&mut self) {
+        self.failed = true;
+    }
+
+    /// 手动清理所有跟踪的临时文件和目录
+    pub fn cleanup(&mut self) -> ADBResult<()> {
+        if self.failed && self.keep_on_failure {
+            for path in self.temp_files.iter().chain(&self.temp_dirs) {
+                warn!("操作失败，保留主机临时文件: {}", path.display());
+            }
+            self.temp_files.clear();
+            self.temp_dirs.clear();
+            return Ok(());
+        }
+
+        let mut errors = Vec::new();
+        for file in self.temp_files.drain(..) {
+            if let Err(e) = fs::remove_file(&file) {
+                if e.kind() != ErrorKind::NotFound {
+                    errors.push(format!("文件 {}: {}", file.display(), e));
+                }
+            }
+        }
+        for dir in self.temp_dirs.drain(..) {
+            if let Err(e) = fs::remove_dir_all(&dir) {
+                if e.kind() != ErrorKind::NotFound {
+                    errors.push(format!("目录 {}: {}", dir.display(), e));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ADBError::FileError(format!(
+                "清理主机临时文件时发生错误: {}",
+                errors.join(", ")
+            )))
+        }
+    }
+}
+
+impl Drop for HostResourceManager {
+    fn drop(&mut self) {
+        if let Err(e) = self.cleanup() {
+            warn!("{}", e);
+        }
+    }
+}
+
+/// 判断进程是否存活，无法判断时返回 None
+fn process_alive(pid: u32) -> Option<bool> {
+    if cfg!(target_os = "linux") {
+        Some(Path::new(&format!("/proc/{}", pid)).exists())
+    } else {
+        None
+    }
+}
+
+/// 清理崩溃进程遗留在系统临时目录中的本库临时目录，返回删除的数量
+///
+/// 所属进程已退出的目录会被删除；无法判断进程状态时，删除修改时间早于 `max_age` 的目录
+pub fn sweep_host_orphans(max_age: Duration) -> ADBResult<usize> {
+    let current_pid = std::process::id();
+    let mut removed = 0;
+
+    for entry in fs::read_dir(std::env::temp_dir())?.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(pid) = name
+            .strip_prefix(crate::utils::HOST_TEMP_PREFIX)
+            .and_then(|rest| rest.split('-').next())
+            .and_then(|pid| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if pid == current_pid {
+            continue;
+        }
+
+        let orphaned = match process_alive(pid) {
+            Some(alive) => !alive,
+            None => entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.elapsed().ok())
+                .is_some_and(|age| age > max_age),
+        };
+
+        if orphaned && fs::remove_dir_all(entry.path()).is_ok() {
+            debug!("已删除遗留的主机临时目录: {}", entry.path().display());
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
 // 为 ADB 添加资源管理支持
 impl ADB {
+    /// 本实例在设备上的会话临时目录（`/data/local/tmp/adbkit-<session>`）
+    pub fn session_temp_dir(&self) -> String {
+        format!("/data/local/tmp/adbkit-{}", self.jobs.session_id)
+    }
+
     /// 创建资源管理器
     pub fn create_resource_manager(&self, device_id: &str) -> ResourceManager {
         ResourceManager::new(Arc::new(self.clone()), device_id)
@@ -94,8 +429,11 @@ impl ADB {
     {
         let mut manager = self.create_resource_manager(device_id);
         let result = f(&mut manager);
+        if result.is_err() {
+            manager.mark_failed();
+        }
 
-        // 自动清理资源
+        // 自动清理资源（开启 keep_on_failure 且操作失败时保留）
         let _ = manager.cleanup();
 
         result
@@ -182,4 +520,56 @@ impl ADB {
 
         result
     }
-}
\ No newline at end of file
+
+    /// 停止并清理该 ADB 实例（及其所有克隆）启动的后台资源
+    ///
+    /// 依次停止心跳等后台线程、终止 `shell_no_wait` 启动的子进程（日志监听、录屏等）、
+    /// 移除带标签的端口转发，并删除资源管理器尚未清理的设备临时文件。
+    /// 实例释放时只会停止线程和子进程，需要清理设备状态时应显式调用本方法。
+    pub fn shutdown(&self) -> ADBResult<()> {
+        let mut errors = Vec::new();
+
+        let stopped = self.jobs.stop_all();
+        let killed = self.connections.lock().map(|mut pool| pool.kill_all()).unwrap_or(0);
+
+        let forwards: Vec<(String, (String, u16))> =
+            self.jobs.forwards.lock().unwrap().drain().collect();
+        for (label, (_, local_port)) in &forwards {
+            if let Err(e) = self.remove_forward(*local_port) {
+                warn!("移除端口转发 {} 失败: {}", label, e);
+                errors.push(format!("端口转发 {}: {}", label, e));
+            }
+        }
+
+        let temp_files: Vec<(String, HashSet<String>)> =
+            self.jobs.temp_files.lock().unwrap().drain().collect();
+        let mut removed = 0;
+        for (device_id, paths) in temp_files.iter().filter(|(_, p)| !p.is_empty()) {
+            let quoted: Vec<String> = paths.iter().map(|p| crate::utils::shell_quote(p)).collect();
+            match self.shell(device_id, &format!("rm -rf {}", quoted.join(" "))) {
+                Ok(_) => removed += paths.len(),
+                Err(e) => {
+                    warn!("清理设备 {} 上的临时文件失败: {}", device_id, e);
+                    errors.push(format!("设备 {}: {}", device_id, e));
+                }
+            }
+        }
+
+        info!(
+            "已关闭 ADB 后台资源: {} 个线程, {} 个进程, {} 个端口转发, {} 个临时文件",
+            stopped,
+            killed,
+            forwards.len(),
+            removed
+        );
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ADBError::CommandError(format!(
+                "关闭后台资源时发生错误: {}",
+                errors.join(", ")
+            )))
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! 外部命令执行后端
+//!
+//! `ADB` 通过 [`CommandRunner`] 执行所有 adb 命令，默认的 [`ProcessRunner`] 直接启动进程。
+//! 单元测试中可以注入自定义实现，或使用 [`RecordingRunner`] 录制真实设备上的输出，
+//! 再用 [`ReplayRunner`] 在没有设备的环境中回放。
+
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fmt;
+use std::io;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// 外部命令执行后端
+pub trait CommandRunner: Send + Sync + fmt::Debug {
+    /// 执行命令并等待输出
+    fn output(&self, command: &mut Command) -> io::Result<Output>;
+
+    /// 启动后台进程，默认直接启动
+    fn spawn(&self, command: &mut Command) -> io::Result<Child> {
+        command.spawn()
+    }
+}
+
+/// 默认后端：直接启动进程
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProcessRunner;
+
+impl CommandRunner for ProcessRunner {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        command.output()
+    }
+}
+
+/// 命令参数（不含程序路径）
+fn command_args(command: &Command) -> Vec<String> {
+    command
+        .get_args()
+        .map(|a| a.to_string_lossy().to_string())
+        .collect()
+}
+
+/// 一次录制的命令执行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedCommand {
+    pub args: Vec<String>,
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+impl RecordedCommand {
+    fn to_output(&self) -> Output {
+        Output {
+            status: exit_status(self.exit_code),
+            stdout: self.stdout.clone().into_bytes(),
+            stderr: self.stderr.clone().into_bytes(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+/// 录制后端：转发给内部后端执行，并记录每次命令的参数和输出
+#[derive(Debug)]
+pub struct RecordingRunner<R: CommandRunner = ProcessRunner> {
+    inner: R,
+    records: Mutex<Vec<RecordedCommand>>,
+}
+
+impl RecordingRunner<ProcessRunner> {
+    /// 录制真实进程的输出
+    pub fn new() -> Self {
+        Self::wrap(ProcessRunner)
+    }
+}
+
+impl Default for RecordingRunner<ProcessRunner> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: CommandRunner> RecordingRunner<R> {
+    /// 包装指定后端
+    pub fn wrap(inner: R) -> Self {
+        Self {
+            inner,
+            records: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 已录制的命令
+    pub fn records(&self) -> Vec<RecordedCommand> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// 将录制结果序列化为 JSON，可用 [`ReplayRunner::from_json`] 加载
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.records())
+    }
+}
+
+impl<R: CommandRunner> CommandRunner for RecordingRunner<R> {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        let output = self.inner.output(command)?;
+        self.records.lock().unwrap().push(RecordedCommand {
+            args: command_args(command),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            exit_code: output.status.code().unwrap_or(-1),
+        });
+        Ok(output)
+    }
+
+    fn spawn(&self, command: &mut Command) -> io::Result<Child> {
+        self.inner.spawn(command)
+    }
+}
+
+/// 回放后端：按参数匹配录制的命令并返回对应输出
+///
+/// 相同参数的多条记录按录制顺序依次返回，最后一条会被重复使用。
+/// 没有匹配的记录时返回 `NotFound` 错误；不支持启动后台进程。
+#[derive(Debug)]
+pub struct ReplayRunner {
+    records: Mutex<Vec<(RecordedCommand, bool)>>,
+}
+
+impl ReplayRunner {
+    /// 从录制结果创建
+    pub fn new(records: Vec<RecordedCommand>) -> Self {
+        Self {
+            records: Mutex::new(records.into_iter().map(|r| (r, false)).collect()),
+        }
+    }
+
+    /// 从 [`RecordingRunner::to_json`] 的输出创建
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// 添加一条固定响应，便于在测试中直接构造
+    pub fn respond(self, args: &[&str], stdout: &str) -> Self {
+        self.records.lock().unwrap().push((
+            RecordedCommand {
+                args: args.iter().map(|a| a.to_string()).collect(),
+                stdout: stdout.to_string(),
+                stderr: String::new(),
+                exit_code: 0,
+            },
+            false,
+        ));
+        self
+    }
+}
+
+impl CommandRunner for ReplayRunner {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        let args = command_args(command);
+        let mut records = self.records.lock().unwrap();
+
+        let index = records
+            .iter()
+            .position(|(r, used)| !used && r.args == args)
+            .or_else(|| records.iter().rposition(|(r, _)| r.args == args))
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, format!("没有录制的命令: {:?}", args))
+            })?;
+
+        records[index].1 = true;
+        Ok(records[index].0.to_output())
+    }
+
+    fn spawn(&self, command: &mut Command) -> io::Result<Child> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("回放模式不支持后台进程: {:?}", command_args(command)),
+        ))
+    }
+}
+
+/// 通过 [`CommandRunner`] 执行的 adb 命令，接口与 `std::process::Command` 一致
+pub struct AdbCommand {
+    command: Command,
+    runner: Arc<dyn CommandRunner>,
+}
+
+impl AdbCommand {
+    pub(crate) fn new(program: &OsStr, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            command: Command::new(program),
+            runner,
+        }
+    }
+
+    pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Self {
+        self.command.arg(arg);
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.command.args(args);
+        self
+    }
+
+    pub fn stdin<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
+        self.command.stdin(cfg);
+        self
+    }
+
+    pub fn stdout<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
+        self.command.stdout(cfg);
+        self
+    }
+
+    pub fn stderr<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
+        self.command.stderr(cfg);
+        self
+    }
+
+    /// 执行命令并等待输出
+    pub fn output(&mut self) -> io::Result<Output> {
+        self.runner.output(&mut self.command)
+    }
+
+    /// 启动后台进程
+    pub fn spawn(&mut self) -> io::Result<Child> {
+        self.runner.spawn(&mut self.command)
+    }
+}
+
+impl fmt::Debug for AdbCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.command.fmt(f)
+    }
+}
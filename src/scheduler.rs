@@ -0,0 +1,155 @@
+use crate::device::ADB;
+use log::trace;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+
+/// 命令优先级通道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// 交互式操作（shell、截图等），可以使用全部并发槽位
+    Interactive,
+    /// 后台批量操作（推送、拉取等），不能占用为交互式操作保留的槽位
+    Background,
+}
+
+/// 每设备命令调度配置
+#[derive(Debug, Clone, Copy)]
+pub struct SchedulerConfig {
+    /// 每个设备同时执行的最大命令数
+    pub max_concurrent: usize,
+    /// 为交互式操作保留的槽位数
+    pub reserved_interactive: usize,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            max_concurrent: 4,
+            reserved_interactive: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LaneState {
+    interactive: usize,
+    background: usize,
+    waiting_interactive: usize,
+}
+
+/// 每设备命令调度器
+///
+/// 后台操作最多占用 `max_concurrent - reserved_interactive` 个槽位，且有交互式操作
+/// 等待时不会再启动新的后台操作，因此长时间的推送不会阻塞同一设备上的快速操作。
+#[derive(Debug)]
+pub struct CommandScheduler {
+    config: SchedulerConfig,
+    lanes: Mutex<HashMap<String, LaneState>>,
+    changed: Condvar,
+}
+
+/// 调度槽位，释放时唤醒等待中的命令
+pub struct SchedulerSlot<'a> {
+    scheduler: &'a CommandScheduler,
+    device_id: String,
+    priority: Priority,
+}
+
+impl Drop for SchedulerSlot<'_> {
+    fn drop(&mut self) {
+        let mut lanes = self.scheduler.lanes.lock().unwrap();
+        if let Some(state) = lanes.get_mut(&self.device_id) {
+            match self.priority {
+                Priority::Interactive => state.interactive -= 1,
+                Priority::Background => state.background -= 1,
+            }
+        }
+        self.scheduler.changed.notify_all();
+    }
+}
+
+impl CommandScheduler {
+    /// 创建调度器，至少为后台和交互式操作各保留一个槽位
+    pub fn new(config: SchedulerConfig) -> Self {
+        let reserved_interactive = config.reserved_interactive.max(1);
+        let config = SchedulerConfig {
+            max_concurrent: config.max_concurrent.max(reserved_interactive + 1),
+            reserved_interactive,
+        };
+
+        Self {
+            config,
+            lanes: Mutex::new(HashMap::new()),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// 调度配置
+    pub fn config(&self) -> SchedulerConfig {
+        self.config
+    }
+
+    /// 等待并占用一个槽位
+    pub fn acquire(&self, device_id: &str, priority: Priority) -> SchedulerSlot<'_> {
+        let mut lanes = self.lanes.lock().unwrap();
+        let max = self.config.max_concurrent;
+        let background_max = max - self.config.reserved_interactive;
+
+        if priority == Priority::Interactive {
+            lanes.entry(device_id.to_string()).or_default().waiting_interactive += 1;
+        }
+
+        loop {
+            let state = lanes.entry(device_id.to_string()).or_default();
+            let running = state.interactive + state.background;
+            let available = match priority {
+                Priority::Interactive => running < max,
+                Priority::Background => {
+                    running < max && state.background < background_max && state.waiting_interactive == 0
+                }
+            };
+
+            if available {
+                match priority {
+                    Priority::Interactive => {
+                        state.waiting_interactive -= 1;
+                        state.interactive += 1;
+                    }
+                    Priority::Background => state.background += 1,
+                }
+                trace!("设备 {} 占用 {:?} 槽位", device_id, priority);
+                break;
+            }
+
+            lanes = self.changed.wait(lanes).unwrap();
+        }
+
+        SchedulerSlot {
+            scheduler: self,
+            device_id: device_id.to_string(),
+            priority,
+        }
+    }
+}
+
+impl ADB {
+    /// 启用每设备命令调度，`shell` 按交互式优先级、`push`/`pull` 按后台优先级排队
+    pub fn with_scheduler(mut self, config: SchedulerConfig) -> Self {
+        self.scheduler = Some(Arc::new(CommandScheduler::new(config)));
+        self
+    }
+
+    /// 按指定优先级执行操作；未启用调度时直接执行
+    pub fn schedule<F, T>(&self, device_id: &str, priority: Priority, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        match &self.scheduler {
+            Some(scheduler) => {
+                let _slot = scheduler.acquire(device_id, priority);
+                f()
+            }
+            None => f(),
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+// 修改 overlay_display_devices 时的互斥锁，避免并发读写覆盖彼此
+static OVERLAY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+static DISPLAY_ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"mDisplayId=(\d+)").unwrap());
+
+static WM_SIZE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(Physical|Override) size:\s*(\d+)x(\d+)").unwrap());
+
+static ROTATION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"SurfaceOrientation:\s*(\d)|mCurrentRotation=(?:ROTATION_)?(\d+)|mRotation=(\d)").unwrap()
+});
+
+// `cmd device_state` 输出中的设备状态，如 "DeviceState{identifier=1, name='HALF_OPENED', ...}"
+static DEVICE_STATE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"identifier=(\d+), name='([^']*)'").unwrap());
+
+// 模拟副屏的全局设置项
+const OVERLAY_SETTING: &str = "overlay_display_devices";
+
+// 等待模拟副屏出现的超时时间（毫秒）
+const VIRTUAL_DISPLAY_TIMEOUT_MS: u64 = 10_000;
+
+/// 模拟副屏句柄
+///
+/// 通过开发者选项中的“模拟辅助显示设备”创建，丢弃时自动移除
+#[derive(Debug)]
+pub struct DisplayHandle {
+    adb: ADB,
+    device_id: String,
+    display_id: u32,
+    spec: String,
+    closed: bool,
+}
+
+impl DisplayHandle {
+    /// 副屏的显示 ID，可用于 `am start --display <id>`
+    pub fn id(&self) -> u32 {
+        self.display_id
+    }
+
+    /// 副屏规格，格式 "宽x高/dpi"
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    /// 所属设备 ID
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// 移除副屏
+    pub fn close(mut self) -> ADBResult<()> {
+        self.remove()
+    }
+
+    fn remove(&mut self) -> ADBResult<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+
+        let _guard = OVERLAY_LOCK.lock();
+        let current = self.adb.overlay_displays(&self.device_id)?;
+        let mut specs: Vec<&str> = current.split(';').filter(|s| !s.is_empty()).collect();
+        if let Some(pos) = specs.iter().position(|s| *s == self.spec) {
+            specs.remove(pos);
+        }
+        self.adb.set_overlay_displays(&self.device_id, &specs.join(";"))?;
+
+        debug!("已移除设备 {} 的模拟副屏 {}", self.device_id, self.display_id);
+        Ok(())
+    }
+}
+
+impl Drop for DisplayHandle {
+    fn drop(&mut self) {
+        if let Err(e) = self.remove() {
+            warn!("移除模拟副屏 {} 失败: {}", self.display_id, e);
+        }
+    }
+}
+
+/// 屏幕几何信息
+///
+/// `width`/`height` 为自然方向（通常为竖屏）下的像素尺寸，`rotation` 为当前旋转
+/// (0-3，对应 0°/90°/180°/270°)。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenGeometry {
+    pub width: u32,
+    pub height: u32,
+    /// 屏幕密度 (dpi)
+    pub density: u32,
+    pub rotation: u8,
+}
+
+impl ScreenGeometry {
+    /// 密度缩放系数（1dp 对应的像素数）
+    pub fn scale(&self) -> f32 {
+        self.density as f32 / 160.0
+    }
+
+    /// dp 转换为像素
+    pub fn dp_to_px(&self, dp: f32) -> i32 {
+        (dp * self.scale()).round() as i32
+    }
+
+    /// 像素转换为 dp
+    pub fn px_to_dp(&self, px: i32) -> f32 {
+        px as f32 / self.scale()
+    }
+
+    /// 当前是否为横屏
+    pub fn is_landscape(&self) -> bool {
+        let (width, height) = self.current_size();
+        width > height
+    }
+
+    /// 当前旋转下的屏幕尺寸
+    pub fn current_size(&self) -> (u32, u32) {
+        if self.rotation % 2 == 1 {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    /// 将自然方向下的坐标转换为当前旋转下的坐标（`input tap` 使用的坐标系）
+    pub fn to_display(&self, x: i32, y: i32) -> (i32, i32) {
+        let (w, h) = (self.width as i32, self.height as i32);
+        match self.rotation % 4 {
+            1 => (y, w - x),
+            2 => (w - x, h - y),
+            3 => (h - y, x),
+            _ => (x, y),
+        }
+    }
+
+    /// 将当前旋转下的坐标转换回自然方向下的坐标
+    pub fn to_natural(&self, x: i32, y: i32) -> (i32, i32) {
+        let (w, h) = (self.width as i32, self.height as i32);
+        match self.rotation % 4 {
+            1 => (w - y, x),
+            2 => (w - x, h - y),
+            3 => (y, h - x),
+            _ => (x, y),
+        }
+    }
+}
+
+/// 折叠屏姿态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FoldState {
+    Closed,
+    /// 半开（笔记本、帐篷等姿态）
+    HalfOpened,
+    Opened,
+    /// 设备定义的其他状态，按名称匹配（如 `REAR_DISPLAY`）
+    Other(String),
+}
+
+impl FoldState {
+    /// 各厂商对同一姿态使用的状态名称
+    fn names(&self) -> Vec<&str> {
+        match self {
+            FoldState::Closed => vec!["CLOSED", "FOLDED"],
+            FoldState::HalfOpened => vec!["HALF_OPENED", "HALF_FOLDED", "HALF_OPEN"],
+            FoldState::Opened => vec!["OPENED", "OPEN", "UNFOLDED"],
+            FoldState::Other(name) => vec![name.as_str()],
+        }
+    }
+
+    /// 状态名称是否对应此姿态
+    pub fn matches(&self, name: &str) -> bool {
+        self.names().iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+}
+
+/// 设备状态（`cmd device_state` 中的一项）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceState {
+    pub id: u32,
+    pub name: String,
+}
+
+/// 解析 `cmd device_state` 输出中的设备状态
+fn parse_device_states(output: &str) -> Vec<DeviceState> {
+    DEVICE_STATE_RE
+        .captures_iter(output)
+        .filter_map(|caps| {
+            Some(DeviceState {
+                id: caps[1].parse().ok()?,
+                name: caps[2].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 解析 `wm size` 输出，优先使用覆盖尺寸
+pub(crate) fn parse_wm_size(output: &str) -> Option<(u32, u32)> {
+    let mut size = None;
+    for caps in WM_SIZE_RE.captures_iter(output) {
+        let parsed = (caps[2].parse().ok()?, caps[3].parse().ok()?);
+        if &caps[1] == "Override" || size.is_none() {
+            size = Some(parsed);
+        }
+    }
+    size
+}
+
+impl ADB {
+    /// 列出设备上的显示 ID
+    pub fn list_display_ids(&self, device_id: &str) -> ADBResult<BTreeSet<u32>> {
+        let output = self.shell(device_id, "dumpsys display")?;
+        Ok(DISPLAY_ID_RE
+            .captures_iter(&output)
+            .filter_map(|caps| caps[1].parse().ok())
+            .collect())
+    }
+
+    /// 读取当前的模拟副屏设置
+    fn overlay_displays(&self, device_id: &str) -> ADBResult<String> {
+        let value = self.shell(device_id, &format!("settings get global {}", OVERLAY_SETTING))?;
+        let value = value.trim();
+        Ok(if value == "null" { String::new() } else { value.to_string() })
+    }
+
+    /// 写入模拟副屏设置，空值时删除设置项
+    fn set_overlay_displays(&self, device_id: &str, value: &str) -> ADBResult<()> {
+        let command = if value.is_empty() {
+            format!("settings delete global {}", OVERLAY_SETTING)
+        } else {
+            format!("settings put global {} '{}'", OVERLAY_SETTING, value)
+        };
+        self.shell(device_id, &command)?;
+        Ok(())
+    }
+
+    /// 创建模拟副屏，用于测试 Presentation、多屏等副屏行为
+    ///
+    /// 需要 Android 4.2 (API 17) 及以上
+    pub fn create_virtual_display(
+        &self,
+        device_id: &str,
+        width: u32,
+        height: u32,
+        dpi: u32,
+    ) -> ADBResult<DisplayHandle> {
+        let profile = self.device_profile(device_id)?;
+        if profile.sdk_int != 0 && profile.sdk_int < 17 {
+            return Err(ADBError::DeviceError(format!(
+                "设备 {} 不支持模拟副屏 (SDK {})",
+                device_id, profile.sdk_int
+            )));
+        }
+
+        let spec = format!("{}x{}/{}", width, height, dpi);
+        let before = self.list_display_ids(device_id)?;
+
+        {
+            let _guard = OVERLAY_LOCK.lock();
+            let current = self.overlay_displays(device_id)?;
+            let value = if current.is_empty() {
+                spec.clone()
+            } else {
+                format!("{};{}", current, spec)
+            };
+            self.set_overlay_displays(device_id, &value)?;
+        }
+
+        let mut handle = DisplayHandle {
+            adb: self.clone(),
+            device_id: device_id.to_string(),
+            display_id: 0,
+            spec,
+            closed: false,
+        };
+
+        // 等待新显示出现
+        let new_id = Mutex::new(None);
+        let appeared = crate::utils::wait_with_polling(
+            VIRTUAL_DISPLAY_TIMEOUT_MS,
+            300,
+            || {
+                let after = self.list_display_ids(device_id)?;
+                let id = after.difference(&before).max().copied();
+                if let Ok(mut new_id) = new_id.lock() {
+                    *new_id = id;
+                }
+                Ok(id.is_some())
+            },
+            None::<fn(u64)>,
+        )?;
+
+        match new_id.into_inner().ok().flatten() {
+            Some(id) if appeared => {
+                handle.display_id = id;
+                debug!("设备 {} 已创建模拟副屏 {} ({})", device_id, id, handle.spec);
+                Ok(handle)
+            }
+            _ => Err(ADBError::TimeoutError {
+                message: format!("等待设备 {} 的模拟副屏出现超时", device_id),
+                duration: std::time::Duration::from_millis(VIRTUAL_DISPLAY_TIMEOUT_MS),
+            }),
+        }
+    }
+
+    /// 获取屏幕尺寸、密度和当前旋转
+    pub fn screen_geometry(&self, device_id: &str) -> ADBResult<ScreenGeometry> {
+        let output = self.shell(
+            device_id,
+            "wm size; wm density; dumpsys input | grep SurfaceOrientation; \
+             dumpsys window displays | grep -E 'mCurrentRotation|mRotation='; true",
+        )?;
+
+        let (width, height) = parse_wm_size(&output)
+            .ok_or_else(|| ADBError::ParseError(format!("无法解析屏幕尺寸: {}", output.trim())))?;
+        let (physical, overridden) = crate::settings::parse_wm_density(&output);
+        let rotation = ROTATION_RE
+            .captures(&output)
+            .and_then(|caps| (1..=3).find_map(|i| caps.get(i)))
+            .and_then(|m| m.as_str().parse::<u32>().ok())
+            .map(|r| if r >= 90 { r / 90 } else { r })
+            .unwrap_or(0) as u8
+            % 4;
+
+        let geometry = ScreenGeometry {
+            width,
+            height,
+            density: overridden.or(physical).unwrap_or(160),
+            rotation,
+        };
+        debug!("设备 {} 屏幕几何信息: {:?}", device_id, geometry);
+        Ok(geometry)
+    }
+
+    /// 列出设备支持的状态（折叠姿态等）
+    ///
+    /// 需要 Android 12 (API 31) 及以上，不支持时返回空列表
+    pub fn list_device_states(&self, device_id: &str) -> ADBResult<Vec<DeviceState>> {
+        let output = self.shell(device_id, "cmd device_state print-states 2>&1; true")?;
+        Ok(parse_device_states(&output))
+    }
+
+    /// 获取当前设备状态
+    pub fn current_device_state(&self, device_id: &str) -> ADBResult<Option<DeviceState>> {
+        let output = self.shell(device_id, "cmd device_state print-state 2>&1; true")?;
+        if let Some(state) = parse_device_states(&output).into_iter().next() {
+            return Ok(Some(state));
+        }
+
+        // 部分版本只输出状态 ID
+        let Ok(id) = output.trim().parse::<u32>() else {
+            return Ok(None);
+        };
+        Ok(self.list_device_states(device_id)?.into_iter().find(|s| s.id == id))
+    }
+
+    /// 设置折叠屏姿态，用于测试折叠屏布局
+    pub fn set_fold_state(&self, device_id: &str, state: FoldState) -> ADBResult<DeviceState> {
+        let states = self.list_device_states(device_id)?;
+        if states.is_empty() {
+            return Err(ADBError::DeviceError(format!("设备 {} 不支持设备状态", device_id)));
+        }
+        let target = states
+            .iter()
+            .find(|s| state.matches(&s.name))
+            .cloned()
+            .ok_or_else(|| {
+                let names: Vec<&str> = states.iter().map(|s| s.name.as_str()).collect();
+                ADBError::ConfigError(format!(
+                    "设备 {} 不支持姿态 {:?}，支持的状态: {}",
+                    device_id,
+                    state,
+                    names.join(", ")
+                ))
+            })?;
+
+        let output = self.shell(device_id, &format!("cmd device_state state {} 2>&1; true", target.id))?;
+        if output.contains("Error") || output.contains("Exception") {
+            return Err(ADBError::CommandError(format!(
+                "设置设备 {} 状态失败: {}",
+                device_id,
+                output.trim()
+            )));
+        }
+
+        debug!("设备 {} 状态已设置为 {} ({})", device_id, target.name, target.id);
+        Ok(target)
+    }
+
+    /// 取消覆盖的设备状态，恢复为传感器检测到的姿态
+    pub fn reset_fold_state(&self, device_id: &str) -> ADBResult<()> {
+        self.shell(device_id, "cmd device_state state reset")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::{shell_quote, with_timeout};
+use log::{debug, info, trace};
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::process::Stdio;
+use std::thread;
+
+/// 脚本解释器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptInterpreter {
+    /// 使用 sh 执行
+    Sh,
+    /// 使用 su 以 root 身份执行
+    Root,
+    /// 自定义解释器（如 "/system/bin/bash"）
+    Custom(String),
+}
+
+/// 脚本执行选项
+#[derive(Debug, Clone)]
+pub struct ScriptOptions {
+    /// 解释器
+    pub interpreter: ScriptInterpreter,
+    /// 传给脚本的参数
+    pub args: Vec<String>,
+    /// 执行前切换的工作目录
+    pub working_dir: Option<String>,
+    /// 额外的环境变量
+    pub env: Vec<(String, String)>,
+    /// 设备上存放脚本的目录
+    pub device_dir: String,
+}
+
+impl Default for ScriptOptions {
+    fn default() -> Self {
+        ScriptOptions {
+            interpreter: ScriptInterpreter::Sh,
+            args: Vec::new(),
+            working_dir: None,
+            env: Vec::new(),
+            device_dir: "/data/local/tmp".to_string(),
+        }
+    }
+}
+
+/// 环境变量名是否合法（`[A-Za-z_][A-Za-z0-9_]*`）
+fn is_valid_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 构建在设备上执行脚本的命令行
+fn build_script_command(script_path: &str, options: &ScriptOptions) -> ADBResult<String> {
+    let mut command = String::new();
+    let script_path = shell_quote(script_path);
+
+    if let Some(dir) = &options.working_dir {
+        command.push_str(&format!("cd {} && ", shell_quote(dir)));
+    }
+
+    for (key, value) in &options.env {
+        if !is_valid_env_key(key) {
+            return Err(ADBError::ConfigError(format!("无效的环境变量名: {}", key)));
+        }
+        command.push_str(&format!("{}={} ", key, shell_quote(value)));
+    }
+
+    let args = options
+        .args
+        .iter()
+        .map(|a| shell_quote(a))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    match &options.interpreter {
+        ScriptInterpreter::Sh => command.push_str(&format!("sh {} {}", script_path, args)),
+        ScriptInterpreter::Custom(interpreter) => {
+            command.push_str(&format!("{} {} {}", interpreter, script_path, args))
+        }
+        ScriptInterpreter::Root => {
+            let inner = format!("{}sh {} {}", command, script_path, args);
+            return Ok(format!("su -c {}", shell_quote(inner.trim())));
+        }
+    }
+
+    Ok(command.trim().to_string())
+}
+
+impl ADB {
+    /// 在设备上执行多行脚本并返回全部输出
+    pub fn shell_script(
+        &self,
+        device_id: &str,
+        script: &str,
+        options: ScriptOptions,
+    ) -> ADBResult<String> {
+        let mut output = String::new();
+        self.shell_script_stream(device_id, script, options, |line| {
+            output.push_str(line);
+            output.push('\n');
+        })?;
+        Ok(output)
+    }
+
+    /// 在设备上执行多行脚本，并逐行回调输出
+    ///
+    /// 脚本会先推送到设备上的临时文件（由资源管理器跟踪），执行完成后自动删除。
+    pub fn shell_script_stream<F>(
+        &self,
+        device_id: &str,
+        script: &str,
+        options: ScriptOptions,
+        mut on_line: F,
+    ) -> ADBResult<()>
+    where
+        F: FnMut(&str),
+    {
+        self.with_resources(device_id, |resources| {
+            let device_path = format!(
+                "{}/adbkit_script_{}_{}.sh",
+                options.device_dir.trim_end_matches('/'),
+                chrono::Local::now().format("%Y%m%d_%H%M%S"),
+                rand::random::<u32>()
+            );
+            let command = build_script_command(&device_path, &options)?;
+
+            // 写入本地临时文件
+            let local_dir = crate::utils::create_temp_dir_path("adb_script")?;
+            let local_path = local_dir.join("script.sh");
+            fs::write(&local_path, script.replace("\r\n", "\n"))
+                .map_err(|e| ADBError::FileError(format!("无法写入脚本文件: {}", e)))?;
+
+            // 推送到设备
+            resources.track_temp_file(&device_path);
+
+            let push_result = self.push(
+                device_id,
+                local_path.to_str().unwrap_or_default(),
+                &device_path,
+                None,
+            );
+            let _ = fs::remove_dir_all(&local_dir);
+            push_result?;
+
+            self.shell(device_id, &format!("chmod 755 {}", shell_quote(&device_path)))?;
+
+            // 执行并逐行读取输出
+            debug!("在设备 {} 上执行脚本: {}", device_id, command);
+
+            let mut cmd = self.adb_command();
+            if !device_id.is_empty() {
+                cmd.arg("-s").arg(device_id);
+            }
+
+            let mut child = cmd
+                .arg("shell")
+                .arg(&command)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| ADBError::DeviceError(format!("无法执行 ADB shell: {}", e)))?;
+
+            let stdout = child
+                .stdout
+                .take()
+                .ok_or_else(|| ADBError::CommandError("无法读取脚本输出".to_string()))?;
+            let mut stderr = child
+                .stderr
+                .take()
+                .ok_or_else(|| ADBError::CommandError("无法读取脚本输出".to_string()))?;
+
+            // stderr 由单独的线程读取，避免管道写满后脚本阻塞、stdout 一直读不到 EOF
+            let (read_result, stderr) = thread::scope(|scope| {
+                let stderr_reader = scope.spawn(move || {
+                    let mut buffer = Vec::new();
+                    let _ = stderr.read_to_end(&mut buffer);
+                    buffer
+                });
+
+                let read_result = BufReader::new(stdout).lines().try_for_each(|line| {
+                    let line = line?;
+                    trace!("脚本输出: {}", line);
+                    on_line(&line);
+                    Ok::<(), std::io::Error>(())
+                });
+                if read_result.is_err() {
+                    let _ = child.kill();
+                }
+
+                (read_result, stderr_reader.join().unwrap_or_default())
+            });
+
+            let status = child.wait()?;
+            read_result?;
+            if !status.success() {
+                return Err(ADBError::CommandError(format!(
+                    "脚本执行失败 ({}): {}",
+                    status,
+                    String::from_utf8_lossy(&stderr).trim()
+                )));
+            }
+
+            info!("脚本在设备 {} 上执行完成", device_id);
+            Ok(())
+        })
+    }
+
+    /// 将命令包装为以 root 身份执行：shell 已是 root 时原样返回，有 su 时使用
+    /// `su -c`，否则返回 None
+    pub(crate) fn root_command(&self, device_id: &str, command: &str) -> Option<String> {
+        if self.shell(device_id, "id -u").ok()?.trim() == "0" {
+            return Some(command.to_string());
+        }
+
+        // su 可能弹出授权提示而阻塞，限制等待时间
+        let adb = self.clone();
+        let id = device_id.to_string();
+        let su = with_timeout(3000, move || adb.shell(&id, "su -c 'id -u' 2>/dev/null; true"));
+        match su {
+            Ok(output) if output.trim() == "0" => Some(format!("su -c {}", shell_quote(command))),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
+use log::{debug, trace};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// `service call` 回复中的一行十六进制数据，如 "0x00000000: 00000000 00000001 '........'"
+static PARCEL_WORD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b([0-9a-fA-F]{8})\b").unwrap());
+
+/// `service call` 的 Parcel 参数
+#[derive(Debug, Clone, PartialEq)]
+pub enum Parcelable {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    /// UTF-16 字符串
+    String16(String),
+    /// 空的 Binder 对象
+    Null,
+}
+
+impl Parcelable {
+    /// 转换为 `service call` 参数（已转义）
+    fn to_arg(&self) -> String {
+        match self {
+            Parcelable::I32(v) => format!("i32 {}", v),
+            Parcelable::I64(v) => format!("i64 {}", v),
+            Parcelable::F32(v) => format!("f {}", v),
+            Parcelable::F64(v) => format!("d {}", v),
+            Parcelable::String16(v) => format!("s16 {}", shell_quote(v)),
+            Parcelable::Null => "null".to_string(),
+        }
+    }
+}
+
+/// `service call` 的回复 Parcel
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ParcelReply {
+    /// 回复数据（按 32 位字）
+    pub words: Vec<u32>,
+}
+
+impl ParcelReply {
+    /// 解析 `service call` 输出
+    pub fn parse(output: &str) -> ADBResult<Self> {
+        let start = output
+            .find("Parcel(")
+            .ok_or_else(|| ADBError::ParseError(format!("无法解析 service call 输出: {}", output.trim())))?;
+
+        let mut words = Vec::new();
+        for line in output[start + "Parcel(".len()..].lines() {
+            // 去掉行首偏移量和行尾的 ASCII 预览
+            let data = line.split('\'').next().unwrap_or("");
+            let data = data.split_once(": ").map_or(data, |(_, rest)| rest);
+            for caps in PARCEL_WORD_RE.captures_iter(data) {
+                words.push(u32::from_str_radix(&caps[1], 16).unwrap_or(0));
+            }
+        }
+
+        Ok(Self { words })
+    }
+
+    /// 异常码（回复的第一个字），0 表示调用成功
+    pub fn exception_code(&self) -> i32 {
+        self.words.first().map_or(0, |w| *w as i32)
+    }
+
+    /// 从异常码之后开始读取回复数据
+    pub fn reader(&self) -> ParcelReader<'_> {
+        ParcelReader {
+            words: &self.words,
+            position: 1,
+        }
+    }
+}
+
+/// 按顺序读取回复 Parcel 中的值
+#[derive(Debug)]
+pub struct ParcelReader<'a> {
+    words: &'a [u32],
+    position: usize,
+}
+
+impl ParcelReader<'_> {
+    fn next_word(&mut self) -> Option<u32> {
+        let word = self.words.get(self.position).copied();
+        self.position += 1;
+        word
+    }
+
+    pub fn read_i32(&mut self) -> Option<i32> {
+        self.next_word().map(|w| w as i32)
+    }
+
+    pub fn read_i64(&mut self) -> Option<i64> {
+        let low = self.next_word()? as u64;
+        let high = self.next_word()? as u64;
+        Some((high << 32 | low) as i64)
+    }
+
+    pub fn read_bool(&mut self) -> Option<bool> {
+        self.read_i32().map(|v| v != 0)
+    }
+
+    /// 读取 UTF-16 字符串：长度（字符数，-1 表示 null）、字符数据、结尾 0，按 4 字节对齐
+    pub fn read_string16(&mut self) -> Option<Option<String>> {
+        let len = self.read_i32()?;
+        if len < 0 {
+            return Some(None);
+        }
+
+        let len = len as usize;
+        // 包括结尾 0，每个字存放两个 UTF-16 字符
+        let word_count = (len + 1).div_ceil(2);
+        let mut units = Vec::with_capacity(word_count * 2);
+        for _ in 0..word_count {
+            let word = self.next_word()?;
+            units.push((word & 0xffff) as u16);
+            units.push((word >> 16) as u16);
+        }
+        units.truncate(len);
+        Some(Some(String::from_utf16_lossy(&units)))
+    }
+}
+
+/// 解析 `cmd -l` 输出（首行为标题，之后每行一个服务名）
+fn parse_cmd_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.ends_with(':'))
+        .map(|l| l.to_string())
+        .collect()
+}
+
+/// 解析 `service list` 输出（`12\tactivity: [android.app.IActivityManager]`）
+fn parse_service_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|l| {
+            let (_, rest) = l.split_once(char::is_whitespace)?;
+            let (name, _) = rest.trim().split_once(':')?;
+            Some(name.trim().to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+impl ADB {
+    /// 列出可以通过 `cmd` 访问的系统服务
+    ///
+    /// 优先使用 `cmd -l`，不支持时从 `service list` 中获取
+    pub fn list_cmd_services(&self, device_id: &str) -> ADBResult<Vec<String>> {
+        let output = self.shell(device_id, "cmd -l 2>/dev/null; true")?;
+        let mut services = parse_cmd_list(&output);
+
+        if services.is_empty() {
+            let output = self.shell(device_id, "service list")?;
+            services = parse_service_list(&output);
+        }
+
+        services.sort();
+        services.dedup();
+        debug!("设备 {} 上有 {} 个系统服务", device_id, services.len());
+        Ok(services)
+    }
+
+    /// 执行 `cmd <service> <args...>`，参数会逐个转义
+    ///
+    /// 用于访问本库尚未封装的 `cmd` 子命令
+    pub fn cmd_service(&self, device_id: &str, service: &str, args: &[&str]) -> ADBResult<String> {
+        let mut command = format!("cmd {}", shell_quote(service));
+        for arg in args {
+            command.push(' ');
+            command.push_str(&shell_quote(arg));
+        }
+
+        let output = self.shell(device_id, &format!("{} 2>&1", command))?;
+        trace!("{} 输出: {}", command, output);
+
+        if output.starts_with("Can't find service") {
+            return Err(ADBError::CommandError(format!("服务不存在: {}", service)));
+        }
+        Ok(output)
+    }
+
+    /// 通过 `service call` 直接调用系统服务的 Binder 接口
+    ///
+    /// `code` 为 AIDL 方法的事务码（从 1 开始，与系统版本相关）。用于在旧设备上访问
+    /// 没有 `cmd` 前端的平台服务，异常码不为 0 时返回错误。
+    pub fn service_call(
+        &self,
+        device_id: &str,
+        service: &str,
+        code: u32,
+        args: &[Parcelable],
+    ) -> ADBResult<ParcelReply> {
+        let mut command = format!("service call {} {}", shell_quote(service), code);
+        for arg in args {
+            command.push(' ');
+            command.push_str(&arg.to_arg());
+        }
+
+        let output = self.shell(device_id, &command)?;
+        trace!("{} 输出: {}", command, output);
+        if output.contains("Service") && output.contains("does not exist") {
+            return Err(ADBError::CommandError(format!("服务不存在: {}", service)));
+        }
+
+        let reply = ParcelReply::parse(&output)?;
+        if reply.exception_code() != 0 {
+            return Err(ADBError::CommandError(format!(
+                "service call {} {} 返回异常 {}",
+                service,
+                code,
+                reply.exception_code()
+            )));
+        }
+        Ok(reply)
+    }
+}
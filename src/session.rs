@@ -0,0 +1,175 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::monitor::{Heartbeat, HeartbeatEvent};
+use crate::resource::ResourceManager;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::process::{Child, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// 当前进程中已被会话占用的设备
+static DEVICE_LEASES: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 设备会话
+///
+/// 持有设备租约，并统一管理会话中创建的临时文件、端口转发和后台任务。
+/// 会话在 `close()` 或超出作用域时清理所有资源并释放租约，
+/// 同一进程中同一设备同时只能有一个会话。
+pub struct DeviceSession {
+    adb: ADB,
+    device_id: String,
+    resources: ResourceManager,
+    forwards: Vec<u16>,
+    reverses: Vec<u16>,
+    children: Vec<Child>,
+    heartbeats: Vec<Heartbeat>,
+    closed: bool,
+}
+
+impl DeviceSession {
+    /// 设备 ID
+    pub fn id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// 关联的 ADB 实例
+    pub fn adb(&self) -> &ADB {
+        &self.adb
+    }
+
+    /// 会话的资源管理器，跟踪的临时文件在会话结束时删除
+    pub fn resources(&mut self) -> &mut ResourceManager {
+        &mut self.resources
+    }
+
+    /// 在设备上执行 shell 命令
+    pub fn shell(&self, command: &str) -> ADBResult<String> {
+        self.adb.shell(&self.device_id, command)
+    }
+
+    /// 设置端口转发，会话结束时移除
+    pub fn forward(&mut self, local_port: u16, device_port: u16) -> ADBResult<()> {
+        self.adb.forward(&self.device_id, local_port, device_port)?;
+        self.forwards.push(local_port);
+        Ok(())
+    }
+
+    /// 设置反向端口转发，会话结束时移除
+    pub fn reverse(&mut self, remote_port: u16, local_port: u16) -> ADBResult<()> {
+        self.adb.reverse(&self.device_id, remote_port, local_port)?;
+        self.reverses.push(remote_port);
+        Ok(())
+    }
+
+    /// 在后台执行 shell 命令，会话结束时终止
+    pub fn spawn(&mut self, command: &str) -> ADBResult<()> {
+        let mut cmd = self.adb.adb_command();
+        if !self.device_id.is_empty() {
+            cmd.arg("-s").arg(&self.device_id);
+        }
+
+        let child = cmd
+            .arg("shell")
+            .arg(command)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| ADBError::DeviceError(format!("无法执行 ADB shell: {}", e)))?;
+
+        debug!("会话 {} 启动后台命令: {}", self.device_id, command);
+        self.children.push(child);
+        Ok(())
+    }
+
+    /// 启动设备心跳监控，会话结束时停止
+    pub fn heartbeat<F>(&mut self, interval: Duration, on_change: F)
+    where
+        F: FnMut(&HeartbeatEvent) + Send + 'static,
+    {
+        let heartbeat = self.adb.heartbeat(&[&self.device_id], interval, on_change);
+        self.heartbeats.push(heartbeat);
+    }
+
+    /// 结束会话：停止后台任务、移除端口转发、删除临时文件并释放租约
+    pub fn close(&mut self) -> ADBResult<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+
+        let mut errors = Vec::new();
+
+        for mut heartbeat in self.heartbeats.drain(..) {
+            heartbeat.stop();
+        }
+
+        for mut child in self.children.drain(..) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+
+        for port in self.forwards.drain(..) {
+            if let Err(e) = self.adb.remove_forward(port) {
+                errors.push(format!("端口转发 {}: {}", port, e));
+            }
+        }
+
+        for port in self.reverses.drain(..) {
+            if let Err(e) = self.adb.remove_reverse(&self.device_id, port) {
+                errors.push(format!("反向端口转发 {}: {}", port, e));
+            }
+        }
+
+        if let Err(e) = self.resources.cleanup() {
+            errors.push(e.to_string());
+        }
+
+        DEVICE_LEASES.lock().unwrap().remove(&self.device_id);
+        info!("设备 {} 的会话已结束", self.device_id);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ADBError::DeviceError(format!(
+                "结束会话时发生错误: {}",
+                errors.join(", ")
+            )))
+        }
+    }
+}
+
+impl Drop for DeviceSession {
+    fn drop(&mut self) {
+        if let Err(e) = self.close() {
+            warn!("{}", e);
+        }
+    }
+}
+
+impl ADB {
+    /// 为设备创建会话
+    ///
+    /// 设备已被当前进程中的其他会话占用时返回错误
+    pub fn session(&self, device_id: &str) -> ADBResult<DeviceSession> {
+        if !DEVICE_LEASES.lock().unwrap().insert(device_id.to_string()) {
+            return Err(ADBError::DeviceError(format!(
+                "设备 {} 已被其他会话占用",
+                device_id
+            )));
+        }
+
+        debug!("设备 {} 的会话已开始", device_id);
+        Ok(DeviceSession {
+            adb: self.clone(),
+            device_id: device_id.to_string(),
+            resources: self.create_resource_manager(device_id),
+            forwards: Vec::new(),
+            reverses: Vec::new(),
+            children: Vec::new(),
+            heartbeats: Vec::new(),
+            closed: false,
+        })
+    }
+}
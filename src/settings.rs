@@ -0,0 +1,224 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
+use log::debug;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// 系统设置的命名空间
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Namespace {
+    /// 所有用户共享的设备设置
+    Global,
+    /// 当前用户的安全相关设置，应用只读
+    Secure,
+    /// 当前用户的普通偏好设置
+    System,
+}
+
+impl Namespace {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Namespace::Global => "global",
+            Namespace::Secure => "secure",
+            Namespace::System => "system",
+        }
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 检查 `settings` 命令的输出，失败时输出异常信息或 "Invalid namespace" 等用法错误
+fn check_settings_output(output: &str, action: &str) -> ADBResult<()> {
+    if output.contains("Exception occurred") || output.starts_with("Invalid") {
+        return Err(ADBError::CommandError(format!("{}失败: {}", action, output.trim())));
+    }
+    Ok(())
+}
+
+/// 解析 `settings list` 输出，每行为 "key=value"
+fn parse_settings_list(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim_end_matches('\r').to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+/// 解析 `wm density` 输出，返回 (物理密度, 覆盖密度)
+pub(crate) fn parse_wm_density(output: &str) -> (Option<u32>, Option<u32>) {
+    let mut physical = None;
+    let mut overridden = None;
+
+    for line in output.lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim().parse::<u32>().ok();
+            match key.trim() {
+                "Physical density" => physical = value,
+                "Override density" => overridden = value,
+                _ => {}
+            }
+        }
+    }
+
+    (physical, overridden)
+}
+
+impl ADB {
+    /// 读取设置，未设置时返回 None
+    pub fn get_setting(&self, device_id: &str, namespace: Namespace, key: &str) -> ADBResult<Option<String>> {
+        let output = self.shell(device_id, &format!("settings get {} {}", namespace, shell_quote(key)))?;
+        check_settings_output(&output, &format!("读取设置 {}/{} ", namespace, key))?;
+        let value = output.trim_end_matches(['\r', '\n']);
+        Ok(if value == "null" { None } else { Some(value.to_string()) })
+    }
+
+    /// 读取设置并解析为指定类型，未设置时返回 None
+    pub fn get_setting_as<T: FromStr>(
+        &self,
+        device_id: &str,
+        namespace: Namespace,
+        key: &str,
+    ) -> ADBResult<Option<T>> {
+        self.get_setting(device_id, namespace, key)?
+            .map(|value| {
+                value.trim().parse().map_err(|_| {
+                    ADBError::ParseError(format!("无法解析设置 {}/{} 的值: {}", namespace, key, value))
+                })
+            })
+            .transpose()
+    }
+
+    /// 读取开关设置，"1"/"true" 为开启，未设置时返回 None
+    pub fn get_setting_bool(&self, device_id: &str, namespace: Namespace, key: &str) -> ADBResult<Option<bool>> {
+        match self.get_setting(device_id, namespace, key)? {
+            None => Ok(None),
+            Some(value) => match value.trim() {
+                "1" | "true" => Ok(Some(true)),
+                "0" | "false" => Ok(Some(false)),
+                other => Err(ADBError::ParseError(format!(
+                    "无法解析设置 {}/{} 的开关值: {}",
+                    namespace, key, other
+                ))),
+            },
+        }
+    }
+
+    /// 写入设置
+    pub fn put_setting(&self, device_id: &str, namespace: Namespace, key: &str, value: &str) -> ADBResult<()> {
+        let output = self.shell(
+            device_id,
+            &format!("settings put {} {} {}", namespace, shell_quote(key), shell_quote(value)),
+        )?;
+        check_settings_output(&output, &format!("写入设置 {}/{} ", namespace, key))?;
+        debug!("设备 {} 设置 {}/{} = {}", device_id, namespace, key, value);
+        Ok(())
+    }
+
+    /// 删除设置，恢复为未设置状态
+    pub fn delete_setting(&self, device_id: &str, namespace: Namespace, key: &str) -> ADBResult<()> {
+        let output = self.shell(device_id, &format!("settings delete {} {}", namespace, shell_quote(key)))?;
+        check_settings_output(&output, &format!("删除设置 {}/{} ", namespace, key))?;
+        debug!("设备 {} 已删除设置 {}/{}", device_id, namespace, key);
+        Ok(())
+    }
+
+    /// 列出命名空间中的全部设置
+    pub fn list_settings(&self, device_id: &str, namespace: Namespace) -> ADBResult<HashMap<String, String>> {
+        let output = self.shell(device_id, &format!("settings list {}", namespace))?;
+        check_settings_output(&output, &format!("列出 {} 设置", namespace))?;
+        Ok(parse_settings_list(&output))
+    }
+
+    /// 开启或关闭深色模式 (`cmd uimode night`)
+    ///
+    /// 需要 Android 10 (API 29) 及以上才会影响应用主题
+    pub fn set_dark_mode(&self, device_id: &str, enabled: bool) -> ADBResult<()> {
+        let mode = if enabled { "yes" } else { "no" };
+        let output = self.shell(device_id, &format!("cmd uimode night {}", mode))?;
+
+        if output.contains("Error") || output.contains("Unknown") {
+            return Err(ADBError::CommandError(format!(
+                "设置深色模式失败: {}",
+                output.trim()
+            )));
+        }
+
+        debug!("设备 {} 深色模式: {}", device_id, enabled);
+        Ok(())
+    }
+
+    /// 查询深色模式是否开启
+    ///
+    /// 自动模式 (`auto`) 视为未开启
+    pub fn get_dark_mode(&self, device_id: &str) -> ADBResult<bool> {
+        let output = self.shell(device_id, "cmd uimode night")?;
+        // 输出形如 "Night mode: yes"
+        let mode = output
+            .split(':')
+            .nth(1)
+            .map(|m| m.trim())
+            .ok_or_else(|| ADBError::ParseError(format!("无法解析深色模式: {}", output.trim())))?;
+        Ok(mode == "yes")
+    }
+
+    /// 设置字体缩放比例（1.0 为默认）
+    pub fn set_font_scale(&self, device_id: &str, scale: f32) -> ADBResult<()> {
+        if !(scale > 0.0 && scale.is_finite()) {
+            return Err(ADBError::ConfigError(format!("无效的字体缩放比例: {}", scale)));
+        }
+
+        self.put_setting(device_id, Namespace::System, "font_scale", &scale.to_string())?;
+        debug!("设备 {} 字体缩放: {}", device_id, scale);
+        Ok(())
+    }
+
+    /// 获取字体缩放比例，未设置时返回 1.0
+    pub fn get_font_scale(&self, device_id: &str) -> ADBResult<f32> {
+        Ok(self
+            .get_setting_as(device_id, Namespace::System, "font_scale")?
+            .unwrap_or(1.0))
+    }
+
+    /// 设置显示大小缩放比例（1.0 为默认，按物理密度换算为 `wm density`）
+    pub fn set_display_size_scale(&self, device_id: &str, scale: f32) -> ADBResult<()> {
+        if !(scale > 0.0 && scale.is_finite()) {
+            return Err(ADBError::ConfigError(format!("无效的显示大小缩放比例: {}", scale)));
+        }
+
+        if (scale - 1.0).abs() < f32::EPSILON {
+            self.shell(device_id, "wm density reset")?;
+        } else {
+            let output = self.shell(device_id, "wm density")?;
+            let physical = parse_wm_density(&output).0.ok_or_else(|| {
+                ADBError::ParseError(format!("无法解析物理密度: {}", output.trim()))
+            })?;
+            let density = (physical as f32 * scale).round() as u32;
+            self.shell(device_id, &format!("wm density {}", density))?;
+        }
+
+        debug!("设备 {} 显示大小缩放: {}", device_id, scale);
+        Ok(())
+    }
+
+    /// 获取显示大小缩放比例（覆盖密度 / 物理密度）
+    pub fn get_display_size_scale(&self, device_id: &str) -> ADBResult<f32> {
+        let output = self.shell(device_id, "wm density")?;
+        match parse_wm_density(&output) {
+            (Some(physical), Some(overridden)) if physical > 0 => {
+                Ok(overridden as f32 / physical as f32)
+            }
+            (Some(_), None) => Ok(1.0),
+            _ => Err(ADBError::ParseError(format!(
+                "无法解析显示密度: {}",
+                output.trim()
+            ))),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! 设备命令探测与补齐
+//!
+//! 部分厂商精简过的系统缺少 `tar`、`sha256sum` 等命令，打包传输、校验等功能因此无法使用。
+//! [`ADB::ensure_shell_tools`] 检查命令是否存在，缺失时把主机上提供的静态 busybox 推送到
+//! 设备上的 [`SHIM_BIN_DIR`]，并为缺失的命令创建链接。此后该设备上的 shell 命令都会把
+//! 这个目录加入 `PATH`。
+//!
+//! crate 不附带 busybox 二进制，需要调用方提供按 ABI 命名的文件，如 `busybox-arm64-v8a`。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
+use log::{debug, info};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// 设备上存放补齐命令的目录
+pub const SHIM_BIN_DIR: &str = "/data/local/tmp/.adbkit/bin";
+
+/// 可探测和补齐的命令
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tool {
+    Tar,
+    Sha256,
+    Busybox,
+}
+
+impl Tool {
+    /// 设备上的命令名
+    pub fn command(&self) -> &'static str {
+        match self {
+            Tool::Tar => "tar",
+            Tool::Sha256 => "sha256sum",
+            Tool::Busybox => "busybox",
+        }
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.command())
+    }
+}
+
+/// 命令的探测结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolStatus {
+    pub tool: Tool,
+    /// 命令在设备上的路径，不存在时为 None
+    pub path: Option<String>,
+}
+
+impl ToolStatus {
+    pub fn is_available(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// 是否由补齐目录提供
+    pub fn is_shim(&self) -> bool {
+        self.path
+            .as_deref()
+            .is_some_and(|p| p.starts_with(SHIM_BIN_DIR))
+    }
+}
+
+/// 解析 `command -v` 的探测输出（每行 `命令=路径`）
+fn parse_probe(output: &str, tools: &[Tool]) -> Vec<ToolStatus> {
+    tools
+        .iter()
+        .map(|tool| {
+            let path = output
+                .lines()
+                .filter_map(|l| l.trim().split_once('='))
+                .find(|(name, _)| *name == tool.command())
+                .map(|(_, path)| path.trim().to_string())
+                .filter(|p| !p.is_empty());
+            ToolStatus { tool: *tool, path }
+        })
+        .collect()
+}
+
+/// 在主机目录中查找与设备 ABI 匹配的 busybox
+fn find_busybox(shim_dir: &Path, abi: &str) -> Option<PathBuf> {
+    [format!("busybox-{}", abi), "busybox".to_string()]
+        .iter()
+        .map(|name| shim_dir.join(name))
+        .find(|path| path.is_file())
+}
+
+impl ADB {
+    /// 探测设备上的命令是否存在，包括已补齐的命令
+    pub fn probe_shell_tools(&self, device_id: &str, tools: &[Tool]) -> ADBResult<Vec<ToolStatus>> {
+        let probes: Vec<String> = tools
+            .iter()
+            .map(|t| format!("echo {}=$(command -v {})", t.command(), t.command()))
+            .collect();
+        let output = self.shell(
+            device_id,
+            &format!("export PATH={}:$PATH; {}; true", SHIM_BIN_DIR, probes.join("; ")),
+        )?;
+
+        let statuses = parse_probe(&output, tools);
+        if statuses.iter().any(|s| s.is_shim()) {
+            self.register_shim_dir(device_id);
+        }
+        Ok(statuses)
+    }
+
+    /// 确保设备上存在指定命令
+    ///
+    /// 缺失的命令由 `shim_dir` 中的静态 busybox 提供（优先使用 `busybox-<abi>`，其次
+    /// `busybox`）。未提供 `shim_dir` 或补齐后仍有命令缺失时返回错误。
+    pub fn ensure_shell_tools(
+        &self,
+        device_id: &str,
+        tools: &[Tool],
+        shim_dir: Option<&Path>,
+    ) -> ADBResult<Vec<ToolStatus>> {
+        let statuses = self.probe_shell_tools(device_id, tools)?;
+        let missing: Vec<Tool> = statuses
+            .iter()
+            .filter(|s| !s.is_available())
+            .map(|s| s.tool)
+            .collect();
+        if missing.is_empty() {
+            return Ok(statuses);
+        }
+
+        let names = missing.iter().map(|t| t.command()).collect::<Vec<_>>().join(", ");
+        let shim_dir = shim_dir.ok_or_else(|| {
+            ADBError::CommandError(format!("设备 {} 缺少命令: {}", device_id, names))
+        })?;
+
+        let abi = self.shell(device_id, "getprop ro.product.cpu.abi")?.trim().to_string();
+        let busybox = find_busybox(shim_dir, &abi).ok_or_else(|| {
+            ADBError::FileError(format!(
+                "{} 中没有适用于 {} 的 busybox",
+                shim_dir.display(),
+                abi
+            ))
+        })?;
+
+        let target = format!("{}/busybox", SHIM_BIN_DIR);
+        self.shell(device_id, &format!("mkdir -p {}", SHIM_BIN_DIR))?;
+        self.push(device_id, &busybox.to_string_lossy(), &target, None)?;
+
+        let links: Vec<String> = missing
+            .iter()
+            .filter(|t| **t != Tool::Busybox)
+            .map(|t| format!("ln -sf busybox {}", shell_quote(t.command())))
+            .collect();
+        let mut command = format!("cd {} && chmod 755 busybox", SHIM_BIN_DIR);
+        for link in &links {
+            command.push_str(" && ");
+            command.push_str(link);
+        }
+        self.shell(device_id, &command)?;
+        info!("已在设备 {} 上补齐命令: {}", device_id, names);
+
+        let statuses = self.probe_shell_tools(device_id, tools)?;
+        let still_missing: Vec<&str> = statuses
+            .iter()
+            .filter(|s| !s.is_available())
+            .map(|s| s.tool.command())
+            .collect();
+        if !still_missing.is_empty() {
+            return Err(ADBError::CommandError(format!(
+                "补齐后设备 {} 仍缺少命令: {}",
+                device_id,
+                still_missing.join(", ")
+            )));
+        }
+        Ok(statuses)
+    }
+
+    /// 此后该设备上的 shell 命令都在 `PATH` 中包含补齐目录
+    fn register_shim_dir(&self, device_id: &str) {
+        let mut devices = self.shim_devices.lock().unwrap();
+        if devices.insert(device_id.to_string()) {
+            debug!("设备 {} 的 shell 命令将使用补齐目录 {}", device_id, SHIM_BIN_DIR);
+        }
+    }
+
+    /// 在 shell 命令前加上补齐目录的 `PATH`（设备没有补齐命令时原样返回）
+    pub(crate) fn with_tool_path(&self, device_id: &str, command: &str) -> String {
+        if self.shim_devices.lock().unwrap().contains(device_id) {
+            format!("export PATH={}:$PATH; {}", SHIM_BIN_DIR, command)
+        } else {
+            command.to_string()
+        }
+    }
+}
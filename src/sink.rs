@@ -0,0 +1,260 @@
+//! 长时间采集的输出目标
+//!
+//! 日志持久化、分段录屏和监控采样都把数据按“记录”写入 [`Sink`]，由调用方决定写到文件、
+//! 按大小轮转的文件、gzip 压缩文件、通道还是回调。文本记录（日志行、JSON 采样）以 `\n` 结尾，
+//! 二进制记录（录屏分段）原样写入，并在每段之前调用 [`Sink::start_segment`]。
+
+use crate::error::{ADBError, ADBResult};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::debug;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+
+/// 采集数据的输出目标
+pub trait Sink: Send {
+    /// 写入一条记录
+    fn write_record(&mut self, record: &[u8]) -> ADBResult<()>;
+
+    /// 开始新的分段，如录屏的下一段视频；默认不做处理
+    fn start_segment(&mut self) -> ADBResult<()> {
+        Ok(())
+    }
+
+    /// 刷新缓冲的数据
+    fn flush(&mut self) -> ADBResult<()> {
+        Ok(())
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn write_record(&mut self, record: &[u8]) -> ADBResult<()> {
+        (**self).write_record(record)
+    }
+
+    fn start_segment(&mut self) -> ADBResult<()> {
+        (**self).start_segment()
+    }
+
+    fn flush(&mut self) -> ADBResult<()> {
+        (**self).flush()
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for &mut S {
+    fn write_record(&mut self, record: &[u8]) -> ADBResult<()> {
+        (**self).write_record(record)
+    }
+
+    fn start_segment(&mut self) -> ADBResult<()> {
+        (**self).start_segment()
+    }
+
+    fn flush(&mut self) -> ADBResult<()> {
+        (**self).flush()
+    }
+}
+
+fn open_file(path: &Path, append: bool) -> ADBResult<File> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .map_err(|e| ADBError::FileError(format!("无法打开输出文件 {}: {}", path.display(), e)))
+}
+
+/// 写入单个文件
+pub struct FileSink {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl FileSink {
+    /// 创建（或清空）文件
+    pub fn create<P: AsRef<Path>>(path: P) -> ADBResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let writer = BufWriter::new(open_file(&path, false)?);
+        Ok(FileSink { path, writer })
+    }
+
+    /// 追加到已有文件
+    pub fn append<P: AsRef<Path>>(path: P) -> ADBResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let writer = BufWriter::new(open_file(&path, true)?);
+        Ok(FileSink { path, writer })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Sink for FileSink {
+    fn write_record(&mut self, record: &[u8]) -> ADBResult<()> {
+        self.writer.write_all(record)?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> ADBResult<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// 按大小轮转的文件
+///
+/// 当前文件写入 `path`，轮转时依次重命名为 `path.1`、`path.2`……，最多保留 `max_files`
+/// 个旧文件。记录不会被拆分，单条记录超过 `max_bytes` 时独占一个文件。
+/// 每次 [`start_segment`](Sink::start_segment) 也会轮转，使每段录屏保存为单独的文件。
+pub struct RotatingFileSink {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    written: u64,
+    writer: BufWriter<File>,
+}
+
+impl RotatingFileSink {
+    pub fn new<P: AsRef<Path>>(path: P, max_bytes: u64, max_files: usize) -> ADBResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let writer = BufWriter::new(open_file(&path, false)?);
+        Ok(RotatingFileSink {
+            path,
+            max_bytes: max_bytes.max(1),
+            max_files,
+            written: 0,
+            writer,
+        })
+    }
+
+    /// 第 `index` 个旧文件的路径
+    pub fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> ADBResult<()> {
+        self.writer.flush()?;
+        if self.max_files == 0 {
+            self.writer = BufWriter::new(open_file(&self.path, false)?);
+        } else {
+            let _ = fs::remove_file(self.rotated_path(self.max_files));
+            for index in (1..self.max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(index + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+            self.writer = BufWriter::new(open_file(&self.path, false)?);
+        }
+        debug!("输出文件 {} 已轮转", self.path.display());
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Sink for RotatingFileSink {
+    fn write_record(&mut self, record: &[u8]) -> ADBResult<()> {
+        if self.written > 0 && self.written + record.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.writer.write_all(record)?;
+        self.written += record.len() as u64;
+        Ok(())
+    }
+
+    fn start_segment(&mut self) -> ADBResult<()> {
+        if self.written > 0 {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> ADBResult<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// gzip 压缩写入，丢弃时写入压缩流结尾
+pub struct GzipSink<W: Write + Send> {
+    encoder: GzEncoder<W>,
+}
+
+impl GzipSink<File> {
+    /// 创建（或清空）压缩文件
+    pub fn create<P: AsRef<Path>>(path: P) -> ADBResult<Self> {
+        Ok(GzipSink::new(open_file(path.as_ref(), false)?))
+    }
+}
+
+impl<W: Write + Send> GzipSink<W> {
+    pub fn new(writer: W) -> Self {
+        GzipSink {
+            encoder: GzEncoder::new(writer, Compression::default()),
+        }
+    }
+
+    /// 写入压缩流结尾并返回底层 writer
+    pub fn finish(self) -> ADBResult<W> {
+        Ok(self.encoder.finish()?)
+    }
+}
+
+impl<W: Write + Send> Sink for GzipSink<W> {
+    fn write_record(&mut self, record: &[u8]) -> ADBResult<()> {
+        self.encoder.write_all(record)?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> ADBResult<()> {
+        self.encoder.flush()?;
+        Ok(())
+    }
+}
+
+/// 把每条记录发送到通道，接收端关闭时写入失败
+pub struct ChannelSink {
+    sender: Sender<Vec<u8>>,
+}
+
+impl ChannelSink {
+    pub fn new(sender: Sender<Vec<u8>>) -> Self {
+        ChannelSink { sender }
+    }
+}
+
+impl Sink for ChannelSink {
+    fn write_record(&mut self, record: &[u8]) -> ADBResult<()> {
+        self.sender
+            .send(record.to_vec())
+            .map_err(|_| ADBError::UnknownError("输出通道的接收端已关闭".to_string()))
+    }
+}
+
+/// 对每条记录调用回调
+pub struct CallbackSink<F: FnMut(&[u8]) + Send> {
+    callback: F,
+}
+
+impl<F: FnMut(&[u8]) + Send> CallbackSink<F> {
+    pub fn new(callback: F) -> Self {
+        CallbackSink { callback }
+    }
+}
+
+impl<F: FnMut(&[u8]) + Send> Sink for CallbackSink<F> {
+    fn write_record(&mut self, record: &[u8]) -> ADBResult<()> {
+        (self.callback)(record);
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+//! 设备压力测试
+//!
+//! 不安装第三方应用，仅用 shell 自带的命令制造 CPU 和存储负载，测量负载期间的
+//! CPU 占用、频率和温度变化，用于验证设备在高负载下的稳定性。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
+use log::{debug, info, warn};
+use std::time::{Duration, Instant};
+
+// 温度传感器的合理范围（摄氏度），范围外的读数视为无效
+const VALID_TEMPERATURE: std::ops::RangeInclusive<f32> = 0.0..=150.0;
+// 负载结束前采样 CPU 频率的提前量
+const FREQ_SAMPLE_LEAD: Duration = Duration::from_secs(1);
+
+/// 温度采样
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ThermalSample {
+    /// 电池温度（摄氏度）
+    pub battery: Option<f32>,
+    /// 所有温度传感器中的最高温度（摄氏度）
+    pub max_zone: Option<f32>,
+}
+
+impl ThermalSample {
+    /// 相对于之前采样的最高温度变化
+    pub fn rise_since(&self, before: &ThermalSample) -> Option<f32> {
+        Some(self.max_zone? - before.max_zone?)
+    }
+}
+
+/// CPU 压力测试结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuStressReport {
+    /// 施加负载的线程数
+    pub workers: u32,
+    pub duration: Duration,
+    /// 负载期间整机 CPU 占用率（0-100）
+    pub cpu_busy_percent: f64,
+    /// 负载结束前各核心的当前频率（kHz）
+    pub core_freqs_khz: Vec<u64>,
+    pub before: ThermalSample,
+    pub after: ThermalSample,
+}
+
+/// 存储压力测试结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoStressReport {
+    pub path: String,
+    pub bytes_written: u64,
+    pub bytes_read: u64,
+    /// 写入吞吐量（字节/秒）
+    pub write_throughput: f64,
+    /// 读取吞吐量（字节/秒）
+    pub read_throughput: f64,
+    /// 完成的写入-读取轮数
+    pub rounds: u32,
+    pub elapsed: Duration,
+    pub before: ThermalSample,
+    pub after: ThermalSample,
+}
+
+/// 解析 `/proc/stat` 首行，返回 (空闲时间, 总时间)
+fn parse_cpu_times(output: &str) -> Option<(u64, u64)> {
+    let line = output.lines().find(|l| l.starts_with("cpu "))?;
+    let values: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    // user nice system idle iowait irq softirq ...
+    let idle = values.get(3)? + values.get(4).copied().unwrap_or(0);
+    Some((idle, values.iter().sum()))
+}
+
+/// 负载期间的 CPU 占用率
+fn busy_percent(before: (u64, u64), after: (u64, u64)) -> f64 {
+    let total = after.1.saturating_sub(before.1);
+    if total == 0 {
+        return 0.0;
+    }
+    let idle = after.0.saturating_sub(before.0);
+    (total.saturating_sub(idle)) as f64 * 100.0 / total as f64
+}
+
+impl ADB {
+    /// 在设备上运行 `workers` 个忙循环（`yes > /dev/null`），持续 `duration`
+    ///
+    /// `workers` 为 0 时使用全部 CPU 核心数。
+    pub fn stress_cpu(&self, device_id: &str, workers: u32, duration: Duration) -> ADBResult<CpuStressReport> {
+        let workers = if workers == 0 {
+            self.shell(device_id, "nproc 2>/dev/null || grep -c ^processor /proc/cpuinfo")?
+                .trim()
+                .parse::<u32>()
+                .unwrap_or(1)
+                .max(1)
+        } else {
+            workers
+        };
+
+        let before = self.thermal_sample(device_id);
+        let stat_before = self.shell(device_id, "head -1 /proc/stat")?;
+
+        // 负载结束前采样频率，此时所有核心仍处于负载中
+        let hold = duration.saturating_sub(FREQ_SAMPLE_LEAD);
+        let output = self.shell(
+            device_id,
+            &format!(
+                "pids=; for i in $(seq 1 {}); do yes > /dev/null & pids=\"$pids $!\"; done; \
+                 sleep {}; cat /sys/devices/system/cpu/cpu*/cpufreq/scaling_cur_freq 2>/dev/null; \
+                 sleep {}; kill $pids; head -1 /proc/stat; true",
+                workers,
+                hold.as_secs_f32(),
+                duration.saturating_sub(hold).as_secs_f32()
+            ),
+        )?;
+        let after = self.thermal_sample(device_id);
+
+        let core_freqs_khz = output
+            .lines()
+            .take_while(|l| !l.starts_with("cpu "))
+            .filter_map(|l| l.trim().parse::<u64>().ok())
+            .collect();
+        let cpu_busy_percent = match (parse_cpu_times(&stat_before), parse_cpu_times(&output)) {
+            (Some(b), Some(a)) => busy_percent(b, a),
+            _ => {
+                warn!("无法解析设备 {} 的 /proc/stat", device_id);
+                0.0
+            }
+        };
+
+        let report = CpuStressReport {
+            workers,
+            duration,
+            cpu_busy_percent,
+            core_freqs_khz,
+            before,
+            after,
+        };
+        info!(
+            "设备 {} CPU 压力测试完成: {} 线程，占用 {:.1}%，温升 {:?}",
+            device_id,
+            workers,
+            report.cpu_busy_percent,
+            report.after.rise_since(&report.before)
+        );
+        Ok(report)
+    }
+
+    /// 在 `path` 反复写入并读回 `size` 字节的文件，持续 `duration`，结束后删除文件
+    ///
+    /// 写入使用 `conv=fsync` 确保数据落盘；读取可能命中页缓存，读吞吐量偏高。
+    pub fn stress_io(
+        &self,
+        device_id: &str,
+        path: &str,
+        size: u64,
+        duration: Duration,
+    ) -> ADBResult<IoStressReport> {
+        const BLOCK: u64 = 1024 * 1024;
+        let blocks = size.div_ceil(BLOCK).max(1);
+        let bytes = blocks * BLOCK;
+        let quoted = shell_quote(path);
+
+        let before = self.thermal_sample(device_id);
+        let start = Instant::now();
+        let mut write_time = Duration::ZERO;
+        let mut read_time = Duration::ZERO;
+        let mut rounds = 0u32;
+
+        let result = (|| {
+            while rounds == 0 || start.elapsed() < duration {
+                let write_start = Instant::now();
+                let output = self.shell(
+                    device_id,
+                    &format!(
+                        "dd if=/dev/zero of={} bs={} count={} conv=fsync 2>&1 && echo __ADBKIT_OK__",
+                        quoted, BLOCK, blocks
+                    ),
+                )?;
+                if !output.contains("__ADBKIT_OK__") {
+                    return Err(ADBError::FileError(format!("写入 {} 失败: {}", path, output.trim())));
+                }
+                write_time += write_start.elapsed();
+
+                let read_start = Instant::now();
+                self.shell(
+                    device_id,
+                    &format!("dd if={} of=/dev/null bs={} 2>/dev/null", quoted, BLOCK),
+                )?;
+                read_time += read_start.elapsed();
+
+                rounds += 1;
+                debug!("设备 {} 存储压力第 {} 轮完成", device_id, rounds);
+            }
+            Ok(())
+        })();
+
+        let _ = self.shell(device_id, &format!("rm -f {}", quoted));
+        result?;
+        let after = self.thermal_sample(device_id);
+
+        let total = bytes * rounds as u64;
+        let report = IoStressReport {
+            path: path.to_string(),
+            bytes_written: total,
+            bytes_read: total,
+            write_throughput: total as f64 / write_time.as_secs_f64().max(0.001),
+            read_throughput: total as f64 / read_time.as_secs_f64().max(0.001),
+            rounds,
+            elapsed: start.elapsed(),
+            before,
+            after,
+        };
+        info!(
+            "设备 {} 存储压力测试完成: {} 轮，写 {:.1} MB/s，读 {:.1} MB/s",
+            device_id,
+            rounds,
+            report.write_throughput / BLOCK as f64,
+            report.read_throughput / BLOCK as f64
+        );
+        Ok(report)
+    }
+
+    /// 采样电池和温度传感器温度，读取失败的项为 None
+    pub fn thermal_sample(&self, device_id: &str) -> ThermalSample {
+        let battery = self.get_battery_info(device_id).ok().map(|b| b.temperature);
+        let max_zone = self
+            .shell(device_id, "cat /sys/class/thermal/thermal_zone*/temp 2>/dev/null; true")
+            .ok()
+            .and_then(|output| {
+                output
+                    .lines()
+                    .filter_map(|l| l.trim().parse::<f32>().ok())
+                    // 多数传感器以千分之一摄氏度为单位
+                    .map(|t| if t > 1000.0 { t / 1000.0 } else { t })
+                    .filter(|t| VALID_TEMPERATURE.contains(t))
+                    .max_by(|a, b| a.total_cmp(b))
+            });
+
+        ThermalSample { battery, max_zone }
+    }
+}
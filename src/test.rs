@@ -0,0 +1,338 @@
+//! Instrumentation 测试
+//!
+//! 运行 `am instrument -r -w` 并解析其原始输出。每个测试开始和结束时输出一个状态块：
+//! 若干 `INSTRUMENTATION_STATUS: key=value` 行（值可能跨多行，如堆栈）和一行
+//! `INSTRUMENTATION_STATUS_CODE: <code>`；运行结束时输出 `INSTRUMENTATION_RESULT` 和
+//! `INSTRUMENTATION_CODE`。
+
+use crate::device::ADB;
+use crate::error::ADBResult;
+use crate::utils::shell_quote;
+use log::{debug, info, warn};
+use std::time::{Duration, Instant};
+
+/// Instrumentation 运行选项
+#[derive(Debug, Clone, Default)]
+pub struct InstrumentationOptions {
+    /// 传给测试运行器的参数 (-e)，如 class、package、size、annotation
+    pub args: Vec<(String, String)>,
+    /// 运行期间禁用窗口动画 (--no-window-animation)
+    pub no_window_animation: bool,
+    /// 关闭隐藏 API 检查 (--no-hidden-api-checks)
+    pub no_hidden_api_checks: bool,
+    /// 以指定用户运行 (--user)
+    pub user: Option<String>,
+}
+
+impl InstrumentationOptions {
+    /// 创建默认运行选项
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加测试运行器参数
+    pub fn arg(mut self, key: &str, value: &str) -> Self {
+        self.args.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// 只运行指定的类或方法（`类名` 或 `类名#方法名`，多个用逗号分隔）
+    pub fn class(self, class: &str) -> Self {
+        self.arg("class", class)
+    }
+
+    /// 只运行指定包中的测试
+    pub fn package(self, package: &str) -> Self {
+        self.arg("package", package)
+    }
+
+    /// 设置是否禁用窗口动画
+    pub fn no_window_animation(mut self, disable: bool) -> Self {
+        self.no_window_animation = disable;
+        self
+    }
+
+    /// 设置是否关闭隐藏 API 检查
+    pub fn no_hidden_api_checks(mut self, disable: bool) -> Self {
+        self.no_hidden_api_checks = disable;
+        self
+    }
+
+    /// 以指定用户运行（用户 ID 或 `current`）
+    pub fn user(mut self, user: &str) -> Self {
+        self.user = Some(user.to_string());
+        self
+    }
+}
+
+/// 测试状态（`INSTRUMENTATION_STATUS_CODE`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestStatus {
+    Started,
+    Passed,
+    Failed,
+    Error,
+    Ignored,
+    AssumptionFailure,
+}
+
+impl TestStatus {
+    /// 从状态码转换
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            1 => Some(TestStatus::Started),
+            0 => Some(TestStatus::Passed),
+            -1 => Some(TestStatus::Error),
+            -2 => Some(TestStatus::Failed),
+            -3 => Some(TestStatus::Ignored),
+            -4 => Some(TestStatus::AssumptionFailure),
+            _ => None,
+        }
+    }
+
+    /// 是否为测试结束的状态
+    pub fn is_finished(&self) -> bool {
+        *self != TestStatus::Started
+    }
+
+    /// 是否算作失败
+    pub fn is_failure(&self) -> bool {
+        matches!(self, TestStatus::Failed | TestStatus::Error)
+    }
+}
+
+/// 单个测试的状态事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub class: String,
+    pub method: String,
+    pub status: TestStatus,
+    /// 失败时的堆栈
+    pub stack: Option<String>,
+    /// 从开始到结束的耗时（主机侧测量），开始事件为 None
+    pub duration: Option<Duration>,
+}
+
+impl TestResult {
+    /// `类名#方法名`
+    pub fn name(&self) -> String {
+        format!("{}#{}", self.class, self.method)
+    }
+}
+
+/// 测试运行汇总
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestRunReport {
+    /// 各测试的最终结果
+    pub results: Vec<TestResult>,
+    /// 测试运行器报告的测试总数
+    pub num_tests: Option<u32>,
+    /// 测试运行器报告的总耗时
+    pub runner_time: Option<Duration>,
+    /// 整体运行失败的原因（进程崩溃、找不到 instrumentation 等）
+    pub run_failure: Option<String>,
+    /// `INSTRUMENTATION_CODE`，正常结束时为 -1
+    pub code: Option<i32>,
+    pub elapsed: Duration,
+}
+
+impl TestRunReport {
+    /// 指定状态的测试数
+    pub fn count(&self, status: TestStatus) -> usize {
+        self.results.iter().filter(|r| r.status == status).count()
+    }
+
+    /// 失败的测试
+    pub fn failures(&self) -> impl Iterator<Item = &TestResult> {
+        self.results.iter().filter(|r| r.status.is_failure())
+    }
+
+    /// 运行正常结束且没有失败的测试
+    pub fn is_success(&self) -> bool {
+        self.run_failure.is_none() && self.code == Some(-1) && self.failures().next().is_none()
+    }
+}
+
+/// 将 `key=value` 追加到状态块
+fn push_entry(bundle: &mut Vec<(String, String)>, entry: &str) {
+    let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+    bundle.push((key.to_string(), value.to_string()));
+}
+
+/// 状态块中的值
+fn bundle_value<'a>(bundle: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    bundle.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+}
+
+/// 解析测试运行器输出中的 "Time: 1,234.567"
+fn parse_runner_time(stream: &str) -> Option<Duration> {
+    let value = stream
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("Time:"))?
+        .trim()
+        .replace(',', "");
+    value.parse::<f64>().ok().map(Duration::from_secs_f64)
+}
+
+/// `am instrument -r` 输出的逐行解析器
+#[derive(Debug, Default)]
+struct InstrumentationParser {
+    status: Vec<(String, String)>,
+    result: Vec<(String, String)>,
+    in_result: bool,
+    started: Option<Instant>,
+    num_tests: Option<u32>,
+    code: Option<i32>,
+    failure: Option<String>,
+}
+
+impl InstrumentationParser {
+    /// 处理一行输出，状态块结束时返回事件
+    fn feed(&mut self, line: &str) -> Option<TestResult> {
+        if let Some(entry) = line.strip_prefix("INSTRUMENTATION_STATUS: ") {
+            self.in_result = false;
+            push_entry(&mut self.status, entry);
+        } else if let Some(code) = line.strip_prefix("INSTRUMENTATION_STATUS_CODE: ") {
+            let bundle = std::mem::take(&mut self.status);
+            return self.finish_status(code.trim().parse().ok()?, &bundle);
+        } else if let Some(entry) = line.strip_prefix("INSTRUMENTATION_RESULT: ") {
+            self.in_result = true;
+            push_entry(&mut self.result, entry);
+        } else if let Some(code) = line.strip_prefix("INSTRUMENTATION_CODE: ") {
+            self.in_result = false;
+            self.code = code.trim().parse().ok();
+        } else if let Some(message) = line.strip_prefix("INSTRUMENTATION_FAILED: ") {
+            self.failure = Some(message.trim().to_string());
+        } else if line.starts_with("INSTRUMENTATION_ABORTED") {
+            self.failure = Some(line.trim().to_string());
+        } else {
+            // 上一个值的续行
+            let bundle = if self.in_result { &mut self.result } else { &mut self.status };
+            if let Some((_, value)) = bundle.last_mut() {
+                value.push('\n');
+                value.push_str(line);
+            }
+        }
+        None
+    }
+
+    fn finish_status(&mut self, code: i32, bundle: &[(String, String)]) -> Option<TestResult> {
+        let status = TestStatus::from_code(code)?;
+        if let Some(num) = bundle_value(bundle, "numtests").and_then(|v| v.trim().parse().ok()) {
+            self.num_tests = Some(num);
+        }
+
+        let duration = if status.is_finished() {
+            self.started.take().map(|start| start.elapsed())
+        } else {
+            self.started = Some(Instant::now());
+            None
+        };
+
+        Some(TestResult {
+            class: bundle_value(bundle, "class")?.trim().to_string(),
+            method: bundle_value(bundle, "test")?.trim().to_string(),
+            status,
+            stack: bundle_value(bundle, "stack")
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+            duration,
+        })
+    }
+
+    /// 汇总运行结果
+    fn into_report(self, results: Vec<TestResult>, elapsed: Duration) -> TestRunReport {
+        let stream = bundle_value(&self.result, "stream").unwrap_or("");
+        // 测试进程崩溃时结果块中只有 shortMsg/longMsg
+        let crash = bundle_value(&self.result, "shortMsg")
+            .or_else(|| bundle_value(&self.result, "longMsg"))
+            .map(|m| m.trim().to_string());
+
+        let run_failure = self.failure.or(crash).or_else(|| {
+            self.code
+                .is_none()
+                .then(|| "instrumentation 未正常结束".to_string())
+        });
+
+        TestRunReport {
+            runner_time: parse_runner_time(stream),
+            results,
+            num_tests: self.num_tests,
+            run_failure,
+            code: self.code,
+            elapsed,
+        }
+    }
+}
+
+impl ADB {
+    /// 运行 instrumentation 测试并汇总结果
+    ///
+    /// `runner` 为测试运行器类名，如 `androidx.test.runner.AndroidJUnitRunner`
+    pub fn run_instrumentation(
+        &self,
+        device_id: &str,
+        test_package: &str,
+        runner: &str,
+        options: InstrumentationOptions,
+    ) -> ADBResult<TestRunReport> {
+        self.run_instrumentation_with(device_id, test_package, runner, options, |_| {})
+    }
+
+    /// 运行 instrumentation 测试，每个测试开始和结束时回调 `on_event`
+    pub fn run_instrumentation_with<F>(
+        &self,
+        device_id: &str,
+        test_package: &str,
+        runner: &str,
+        options: InstrumentationOptions,
+        mut on_event: F,
+    ) -> ADBResult<TestRunReport>
+    where
+        F: FnMut(&TestResult),
+    {
+        let mut command = String::from("am instrument -r -w");
+        if options.no_window_animation {
+            command.push_str(" --no-window-animation");
+        }
+        if options.no_hidden_api_checks {
+            command.push_str(" --no-hidden-api-checks");
+        }
+        if let Some(user) = &options.user {
+            command.push_str(&format!(" --user {}", shell_quote(user)));
+        }
+        for (key, value) in &options.args {
+            command.push_str(&format!(" -e {} {}", shell_quote(key), shell_quote(value)));
+        }
+        command.push_str(&format!(" {}/{}", test_package, runner));
+
+        info!("设备 {} 开始运行测试 {}/{}", device_id, test_package, runner);
+        let start = Instant::now();
+        let mut parser = InstrumentationParser::default();
+        let mut results = Vec::new();
+
+        self.shell_stream(device_id, &command, |line| {
+            if let Some(event) = parser.feed(line) {
+                on_event(&event);
+                if event.status.is_finished() {
+                    debug!("测试 {} {:?}", event.name(), event.status);
+                    results.push(event);
+                }
+            }
+        })?;
+
+        let report = parser.into_report(results, start.elapsed());
+        if let Some(failure) = &report.run_failure {
+            warn!("设备 {} 测试运行失败: {}", device_id, failure);
+        }
+        info!(
+            "设备 {} 测试完成: 通过 {}，失败 {}，忽略 {}，耗时 {:?}",
+            device_id,
+            report.count(TestStatus::Passed),
+            report.failures().count(),
+            report.count(TestStatus::Ignored),
+            report.elapsed
+        );
+        Ok(report)
+    }
+}
@@ -1,10 +1,25 @@
 use crate::device::ADB;
 use crate::error::{ADBError, ADBResult};
-use log::{debug, info};
+use crate::resource::HostResourceManager;
+use crate::scheduler::Priority;
+use crate::utils::shell_quote;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::{Read, Write};
-use std::path::Path;
-use std::process::Command;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+// adb push/pull 的汇总行: "1 file pushed, 0 skipped. 35.2 MB/s (1234567 bytes in 0.034s)"
+static TRANSFER_SUMMARY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\((\d+) bytes in [\d.]+s\)").unwrap());
 
 /// 文件传输选项
 #[derive(Debug, Clone)]
@@ -17,11 +32,40 @@ pub struct TransferOptions {
     pub sync: bool,    // 使用 sync 模式进行传输 (--sync)
     pub dry_run: bool, // 干运行，不实际存储到文件系统 (-n)
 
+    pub media_scan: bool, // 推送后触发媒体扫描，使文件出现在相册等应用中
+
     // pull 专用选项
     pub preserve_timestamp: bool, // 保留文件时间戳和模式 (-a)
 
     // 内部选项，不直接映射到 ADB 命令参数
     pub chunk_size: usize, // 分块大小(单位:字节)
+    pub resume: bool,      // 分块推送时跳过设备上已完整存在的块
+    pub archive_mode: ArchiveMode, // 目录打包为单个归档后传输
+}
+
+/// 目录的归档传输方式
+///
+/// 逐个传输大量小文件时每个文件都有固定开销，打包后只需传输一个文件。
+/// 只对目录生效；设备缺少 tar 时退回到逐个文件传输。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArchiveMode {
+    /// 逐个文件传输
+    #[default]
+    None,
+    /// 打包为 tar
+    Tar,
+    /// 打包为 tar.gz，适合带宽较低的 Wi-Fi 连接
+    TarGz,
+}
+
+impl ArchiveMode {
+    /// tar 的压缩参数
+    fn tar_flag(&self) -> &'static str {
+        match self {
+            ArchiveMode::TarGz => "z",
+            _ => "",
+        }
+    }
 }
 
 impl Default for TransferOptions {
@@ -31,10 +75,310 @@ impl Default for TransferOptions {
             compression_algorithm: None,
             sync: false,
             dry_run: false,
+            media_scan: false,
             preserve_timestamp: false,
             chunk_size: 65536, // 64KB
+            resume: false,
+            archive_mode: ArchiveMode::None,
+        }
+    }
+}
+
+impl TransferOptions {
+    /// 压缩选项对应的参数
+    fn compression_args(&self) -> Vec<String> {
+        if self.compression {
+            let algorithm = self.compression_algorithm.as_deref().unwrap_or("any");
+            vec!["-z".to_string(), algorithm.to_string()]
+        } else {
+            vec!["-Z".to_string()]
+        }
+    }
+
+    /// `adb push` 的选项参数
+    pub(crate) fn push_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.sync {
+            args.push("--sync".to_string());
+        }
+        if self.dry_run {
+            args.push("-n".to_string());
+        }
+        args.extend(self.compression_args());
+        args
+    }
+
+    /// `adb pull` 的选项参数
+    pub(crate) fn pull_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.preserve_timestamp {
+            args.push("-a".to_string());
+        }
+        args.extend(self.compression_args());
+        args
+    }
+}
+
+/// 文件传输统计
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TransferStats {
+    /// 传输的字节数
+    pub bytes: u64,
+    /// 总耗时（包括重试）
+    pub duration: Duration,
+    /// 平均吞吐量（字节/秒）
+    pub avg_throughput: f64,
+    /// 重试次数
+    pub retries: u32,
+}
+
+impl TransferStats {
+    /// 根据字节数和耗时创建统计
+    pub fn new(bytes: u64, duration: Duration, retries: u32) -> Self {
+        let secs = duration.as_secs_f64();
+        Self {
+            bytes,
+            duration,
+            avg_throughput: if secs > 0.0 { bytes as f64 / secs } else { 0.0 },
+            retries,
         }
     }
+
+    /// 平均吞吐量（MB/s）
+    pub fn throughput_mb_s(&self) -> f64 {
+        self.avg_throughput / (1024.0 * 1024.0)
+    }
+}
+
+// fs_info 输出中表示路径不存在的标记
+const FS_MISSING_MARKER: &str = "__ADBKIT_MISSING__";
+// remove_path 输出中表示路径仍存在的标记
+const FS_EXISTS_MARKER: &str = "__ADBKIT_EXISTS__";
+
+// glob 批量列目录输出中的目录分隔标记
+const GLOB_DIR_MARKER: &str = "__ADBKIT_DIR__";
+
+/// 增量推送的块大小
+const DELTA_BLOCK_SIZE: u64 = 1024 * 1024;
+
+/// 增量推送时每条 shell 命令写入的块数，避免命令行超出长度限制
+const DELTA_BATCH_BLOCKS: usize = 64;
+
+/// 目录及其条目 (名称, 是否目录)
+type DirListing = (String, Vec<(String, bool)>);
+
+/// 检查路径段是否包含通配符
+fn has_wildcard(component: &str) -> bool {
+    component.contains(['*', '?', '['])
+}
+
+/// 设备上路径的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FsKind {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
+/// 设备上路径的信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsInfo {
+    pub path: String,
+    pub kind: FsKind,
+    /// 文件大小，目录为占用空间（字节）
+    pub size: u64,
+    /// 权限位（如 0o644）
+    pub mode: u32,
+    /// 修改时间（Unix 纪元秒）
+    pub modified: Option<i64>,
+}
+
+impl FsInfo {
+    /// 是否为普通文件
+    pub fn is_file(&self) -> bool {
+        self.kind == FsKind::File
+    }
+
+    /// 是否为目录
+    pub fn is_dir(&self) -> bool {
+        self.kind == FsKind::Directory
+    }
+}
+
+/// 解析 fs_info 的 shell 输出
+fn parse_fs_info(path: &str, output: &str) -> ADBResult<Option<FsInfo>> {
+    let mut lines = output.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+    let first = match lines.next() {
+        Some(line) if line == FS_MISSING_MARKER => return Ok(None),
+        Some(line) => line,
+        None => return Err(ADBError::ParseError(format!("无法获取路径信息: {}", path))),
+    };
+
+    let fields: Vec<&str> = first.split('|').collect();
+    if fields.len() < 4 {
+        return Err(ADBError::ParseError(format!(
+            "无法解析路径信息 {}: {}",
+            path, first
+        )));
+    }
+
+    let kind = match fields[0] {
+        "directory" => FsKind::Directory,
+        "symbolic link" => FsKind::Symlink,
+        f if f.starts_with("regular") => FsKind::File,
+        _ => FsKind::Other,
+    };
+
+    let mut size = fields[1].parse::<u64>().unwrap_or(0);
+    if kind == FsKind::Directory {
+        if let Some(kb) = lines.next().and_then(|l| l.parse::<u64>().ok()) {
+            size = kb * 1024;
+        }
+    }
+
+    Ok(Some(FsInfo {
+        path: path.to_string(),
+        kind,
+        size,
+        mode: u32::from_str_radix(fields[2], 8).unwrap_or(0),
+        modified: fields[3].parse().ok(),
+    }))
+}
+
+/// 从 adb 输出中解析传输字节数
+pub(crate) fn parse_transferred_bytes(output: &str) -> Option<u64> {
+    TRANSFER_SUMMARY_RE
+        .captures_iter(output)
+        .filter_map(|caps| caps[1].parse::<u64>().ok())
+        .reduce(|a, b| a + b)
+}
+
+/// 计算本地文件或目录的总大小
+pub(crate) fn local_path_size(path: &Path) -> u64 {
+    match fs::metadata(path) {
+        Ok(meta) if meta.is_dir() => fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| local_path_size(&e.path()))
+                    .sum()
+            })
+            .unwrap_or(0),
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    }
+}
+
+/// 目录同步选项
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// 排除模式，匹配文件名或目录名
+    pub exclude_patterns: Vec<String>,
+    /// 大小相同时比较 MD5 而不是修改时间
+    pub checksum: bool,
+    /// 删除设备上本地不存在的文件
+    pub delete: bool,
+}
+
+/// 目录同步结果（路径均相对于同步根目录）
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub pushed: Vec<String>,
+    /// 未变化而跳过的文件
+    pub skipped: Vec<String>,
+    pub deleted: Vec<String>,
+    /// 失败的文件及原因
+    pub errors: Vec<(String, String)>,
+    /// 推送的字节数
+    pub bytes: u64,
+}
+
+impl SyncReport {
+    /// 是否没有失败的文件
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// 待同步的本地文件
+struct LocalFile {
+    relative: String,
+    path: PathBuf,
+    size: u64,
+    modified: i64,
+}
+
+/// 递归收集本地目录下的文件，跳过名称匹配排除模式的文件和目录
+fn collect_local_files(
+    dir: &Path,
+    prefix: &str,
+    excludes: &[glob::Pattern],
+    files: &mut Vec<LocalFile>,
+) -> ADBResult<()> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| ADBError::FileError(format!("无法读取本地目录: {}", e)))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| ADBError::FileError(format!("读取目录条目失败: {}", e)))?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if excludes.iter().any(|p| p.matches(&name)) {
+            continue;
+        }
+
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let path = entry.path();
+        let metadata = fs::metadata(&path)?;
+
+        if metadata.is_dir() {
+            collect_local_files(&path, &relative, excludes, files)?;
+        } else {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs() as i64);
+            files.push(LocalFile {
+                relative,
+                path,
+                size: metadata.len(),
+                modified,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// 拆分设备路径为父目录和名称
+fn split_device_path(path: &str) -> (&str, &str) {
+    match path.rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((parent, name)) => (parent, name),
+        None => (".", path),
+    }
+}
+
+/// 流式计算本地文件的 MD5
+pub(crate) fn local_md5(path: &Path) -> ADBResult<String> {
+    let mut file = File::open(path)
+        .map_err(|e| ADBError::FileError(format!("无法打开文件 {}: {}", path.display(), e)))?;
+    let mut context = md5::Context::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let len = file.read(&mut buffer)?;
+        if len == 0 {
+            break;
+        }
+        context.consume(&buffer[..len]);
+    }
+
+    Ok(format!("{:x}", context.compute()))
 }
 
 impl ADB {
@@ -45,41 +389,35 @@ impl ADB {
         device_path: &str,
         local_path: &str,
         options: Option<TransferOptions>,
-    ) -> ADBResult<()> {
+    ) -> ADBResult<TransferStats> {
+        let device_id = &self.transfer_route(device_id);
         let options = options.unwrap_or_default();
+        if options.archive_mode != ArchiveMode::None {
+            if let Some(stats) = self.pull_archive(device_id, device_path, local_path, options.archive_mode)? {
+                return Ok(stats);
+            }
+        }
+        let start = Instant::now();
+        let attempts = Cell::new(0u32);
 
-        self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+        let output = self.with_retry(|| {
+            attempts.set(attempts.get() + 1);
+            let mut cmd = self.adb_command();
 
             // 如果指定了设备 ID 则添加
             if !device_id.is_empty() {
                 cmd.arg("-s").arg(device_id);
             }
 
-            cmd.arg("pull");
-
             // 添加传输选项
-            if options.preserve_timestamp {
-                cmd.arg("-a");
-            }
-
-            // 处理压缩选项
-            if options.compression {
-                if let Some(algorithm) = &options.compression_algorithm {
-                    cmd.arg("-z").arg(algorithm);
-                } else {
-                    cmd.arg("-z").arg("any");
-                }
-            } else {
-                cmd.arg("-Z");
-            }
+            cmd.arg("pull").args(options.pull_args());
 
             // 设置源路径和目标路径
             cmd.arg(device_path).arg(local_path);
 
             info!("开始从设备拉取文件: {} -> {}", device_path, local_path);
-            let output = cmd
-                .output()
+            let output = self
+                .schedule(device_id, Priority::Background, || cmd.output())
                 .map_err(|e| ADBError::CommandError(format!("执行 ADB pull 命令失败: {}", e)))?;
 
             if !output.status.success() {
@@ -91,8 +429,12 @@ impl ADB {
             }
 
             debug!("成功拉取文件 {} 到 {}", device_path, local_path);
-            Ok(())
-        })
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        })?;
+
+        let bytes = parse_transferred_bytes(&output)
+            .unwrap_or_else(|| local_path_size(Path::new(local_path)));
+        Ok(TransferStats::new(bytes, start.elapsed(), attempts.get().saturating_sub(1)))
     }
 
     /// 文件推送
@@ -102,45 +444,35 @@ impl ADB {
         local_path: &str,
         device_path: &str,
         options: Option<TransferOptions>,
-    ) -> ADBResult<()> {
+    ) -> ADBResult<TransferStats> {
+        let device_id = &self.transfer_route(device_id);
         let options = options.unwrap_or_default();
+        if options.archive_mode != ArchiveMode::None && !options.dry_run {
+            if let Some(stats) = self.push_archive(device_id, local_path, device_path, &options)? {
+                return Ok(stats);
+            }
+        }
+        let start = Instant::now();
+        let attempts = Cell::new(0u32);
 
-        self.with_retry(|| {
-            let mut cmd = Command::new(&self.config.path);
+        let output = self.with_retry(|| {
+            attempts.set(attempts.get() + 1);
+            let mut cmd = self.adb_command();
 
             // 如果指定了设备 ID 则添加
             if !device_id.is_empty() {
                 cmd.arg("-s").arg(device_id);
             }
 
-            cmd.arg("push");
-
             // 添加传输选项
-            if options.sync {
-                cmd.arg("--sync");
-            }
-
-            if options.dry_run {
-                cmd.arg("-n");
-            }
-
-            // 处理压缩选项
-            if options.compression {
-                if let Some(algorithm) = &options.compression_algorithm {
-                    cmd.arg("-z").arg(algorithm);
-                } else {
-                    cmd.arg("-z").arg("any");
-                }
-            } else {
-                cmd.arg("-Z");
-            }
+            cmd.arg("push").args(options.push_args());
 
             // 设置源路径和目标路径
             cmd.arg(local_path).arg(device_path);
 
             info!("开始向设备推送文件: {} -> {}", local_path, device_path);
-            let output = cmd
-                .output()
+            let output = self
+                .schedule(device_id, Priority::Background, || cmd.output())
                 .map_err(|e| ADBError::CommandError(format!("执行 ADB push 命令失败: {}", e)))?;
 
             if !output.status.success() {
@@ -152,8 +484,16 @@ impl ADB {
             }
 
             debug!("成功推送文件 {} 到 {}", local_path, device_path);
-            Ok(())
-        })
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        })?;
+
+        if options.media_scan && !options.dry_run {
+            self.scan_file(device_id, device_path)?;
+        }
+
+        let bytes = parse_transferred_bytes(&output)
+            .unwrap_or_else(|| local_path_size(Path::new(local_path)));
+        Ok(TransferStats::new(bytes, start.elapsed(), attempts.get().saturating_sub(1)))
     }
 
     /// 分块推送大文件
@@ -163,9 +503,11 @@ impl ADB {
         local_path: &str,
         device_path: &str,
         options: Option<TransferOptions>,
-    ) -> ADBResult<()> {
+    ) -> ADBResult<TransferStats> {
         let options = options.unwrap_or_default();
         let chunk_size = options.chunk_size;
+        let start = Instant::now();
+        let mut retries = 0;
 
         // 确保文件存在
         let file_path = Path::new(local_path);
@@ -189,41 +531,53 @@ impl ADB {
             ADBError::FileError(error_msg)
         })?;
 
-        info!(
-            "将文件 {} 分成 {} 块传输",
-            local_path,
-            (file_size + chunk_size - 1) / chunk_size
-        );
+        let chunks_count = file_size.div_ceil(chunk_size);
+        info!("将文件 {} 分成 {} 块传输", local_path, chunks_count);
 
-        // 创建设备上的临时目录
+        // 设备上的分块目录；续传时保留已有的块，否则重新开始
         let device_temp_dir = format!("{}.parts", device_path);
-        self.shell(device_id, &format!("mkdir -p {}", device_temp_dir))?;
-
-        // 分块传输
-        let temp_dir = crate::utils::create_temp_dir_path("adb_push")?;
+        let quoted_dir = shell_quote(&device_temp_dir);
+        let existing = if options.resume {
+            self.shell(device_id, &format!("mkdir -p {}", quoted_dir))?;
+            self.existing_chunk_sizes(device_id, &device_temp_dir)?
+        } else {
+            self.shell(device_id, &format!("rm -rf {d} && mkdir -p {d}", d = quoted_dir))?;
+            HashMap::new()
+        };
 
-        let mut buffer = vec![0u8; chunk_size];
-        let chunks_count = (file_size + chunk_size - 1) / chunk_size;
+        let mut host_resources = HostResourceManager::new();
+        let temp_dir = host_resources.create_temp_dir("adb_push")?;
 
         // 创建单独的 TransferOptions 用于块传输，可能想要禁用某些选项
-        let chunk_options = options.clone();
+        let mut chunk_options = options.clone();
+        chunk_options.media_scan = false;
+
+        let mut buffer = Vec::with_capacity(chunk_size);
+        let mut transferred = 0u64;
+        let mut skipped = 0;
 
-        // 对于部分传输可能不需要某些选项
         for i in 0..chunks_count {
-            let part_file = temp_dir.join(format!("part{}", i));
-            let bytes_read = file.read(&mut buffer[..]).map_err(|e| {
-                let error_msg = format!("读取文件块失败: {}", e);
-                ADBError::FileError(error_msg)
-            })?;
+            buffer.clear();
+            (&mut file)
+                .take(chunk_size as u64)
+                .read_to_end(&mut buffer)
+                .map_err(|e| ADBError::FileError(format!("读取文件块失败: {}", e)))?;
+
+            if existing.get(&i) == Some(&(buffer.len() as u64)) {
+                skipped += 1;
+                debug!("块 {}/{} 已存在，跳过", i + 1, chunks_count);
+                continue;
+            }
 
             // 创建临时部分文件
+            let part_file = temp_dir.join(format!("part{}", i));
             {
                 let mut part = File::create(&part_file).map_err(|e| {
                     let error_msg = format!("创建临时文件失败: {}", e);
                     ADBError::FileError(error_msg)
                 })?;
 
-                part.write_all(&buffer[..bytes_read]).map_err(|e| {
+                part.write_all(&buffer).map_err(|e| {
                     let error_msg = format!("写入临时文件失败: {}", e);
                     ADBError::FileError(error_msg)
                 })?;
@@ -233,7 +587,7 @@ impl ADB {
             let device_part_path = format!("{}/part{}", device_temp_dir, i);
             let push_result = self.push(
                 device_id,
-                part_file.to_str().unwrap(),
+                &part_file.to_string_lossy(),
                 &device_part_path,
                 Some(chunk_options.clone()),
             );
@@ -242,68 +596,95 @@ impl ADB {
             let _ = fs::remove_file(part_file);
 
             // 检查推送结果
-            push_result.map_err(|e| {
+            let chunk_stats = push_result.map_err(|e| {
                 let error_msg = format!("推送文件块失败: {}", e);
                 ADBError::CommandError(error_msg)
             })?;
+            retries += chunk_stats.retries;
+            transferred += buffer.len() as u64;
 
             debug!("已推送块 {}/{}", i + 1, chunks_count);
         }
 
-        // 合并所有部分
-        let cat_cmd = format!(
-            "cat {}/* > {} && rm -rf {}",
-            device_temp_dir, device_path, device_temp_dir
+        if skipped > 0 {
+            info!("续传跳过了 {} 个已存在的块", skipped);
+        }
+
+        // 按序号合并所有部分（通配符按字典序展开，part10 会排在 part2 之前）
+        let merge_cmd = format!(
+            "i=0; while [ $i -lt {n} ]; do cat {d}/part$i || exit 1; i=$((i+1)); done > {dest}",
+            n = chunks_count,
+            d = quoted_dir,
+            dest = shell_quote(device_path)
         );
-        self.shell(device_id, &cat_cmd)?;
+        self.shell(device_id, &merge_cmd)?;
+
+        // 校验合并结果，不一致时丢弃分块，下次从头传输
+        if !self.compare_files(device_id, local_path, device_path)? {
+            self.shell(
+                device_id,
+                &format!("rm -rf {} {}", quoted_dir, shell_quote(device_path)),
+            )?;
+            return Err(ADBError::FileError(format!(
+                "合并后的文件 {} 与本地文件 {} 校验不一致",
+                device_path, local_path
+            )));
+        }
+        self.shell(device_id, &format!("rm -rf {}", quoted_dir))?;
 
         info!("已成功推送和合并大文件 {} 到 {}", local_path, device_path);
 
-        // 清理临时目录
-        let _ = fs::remove_dir_all(temp_dir);
+        if options.media_scan && !options.dry_run {
+            self.scan_file(device_id, device_path)?;
+        }
 
-        Ok(())
+        Ok(TransferStats::new(transferred, start.elapsed(), retries))
     }
 
-    /// 文件存在性检查
-    pub fn file_exists(&self, device_id: &str, path: &str) -> ADBResult<bool> {
-        let result = self.shell(
+    /// 设备上已存在的分块及其大小
+    fn existing_chunk_sizes(&self, device_id: &str, parts_dir: &str) -> ADBResult<HashMap<usize, u64>> {
+        let output = self.shell(
             device_id,
-            &format!("[ -e {} ] && echo 'exists' || echo 'not exists'", path),
+            &format!(
+                "for f in {}/part*; do [ -f \"$f\" ] && echo \"${{f##*/}} $(stat -c %s \"$f\")\"; done; true",
+                shell_quote(parts_dir)
+            ),
         )?;
-        Ok(result.trim() == "exists")
+
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let (name, size) = line.trim().split_once(' ')?;
+                let index = name.strip_prefix("part")?.parse().ok()?;
+                Some((index, size.parse().ok()?))
+            })
+            .collect())
     }
 
-    /// 获取文件/目录大小
-    pub fn get_file_size(&self, device_id: &str, path: &str) -> ADBResult<u64> {
-        // 检查是文件还是目录
-        let is_dir = self
-            .shell(
-                device_id,
-                &format!("[ -d {} ] && echo 'true' || echo 'false'", path),
-            )?
-            .trim()
-            == "true";
+    /// 一次往返获取路径的类型、大小、权限和修改时间，路径不存在时返回 `None`
+    ///
+    /// 目录的大小为 `du -sk` 统计的占用空间
+    pub fn fs_info(&self, device_id: &str, path: &str) -> ADBResult<Option<FsInfo>> {
+        let quoted = shell_quote(path);
+        let command = format!(
+            "if [ -e {p} ] || [ -L {p} ]; then stat -c '%F|%s|%a|%Y' {p}; if [ -d {p} ] && [ ! -L {p} ]; then du -sk {p} | cut -f1; fi; else echo {missing}; fi",
+            p = quoted,
+            missing = FS_MISSING_MARKER
+        );
+        let output = self.shell(device_id, &command)?;
+        parse_fs_info(path, &output)
+    }
 
-        if is_dir {
-            // 对于目录，使用 du 命令
-            let output = self.shell(device_id, &format!("du -sk {} | cut -f1", path))?;
-            let size_kb = output
-                .trim()
-                .parse::<u64>()
-                .map_err(|_| ADBError::CommandError(format!("无法获取目录大小: {}", path)))?;
-
-            Ok(size_kb * 1024) // 转换为字节
-        } else {
-            // 对于文件，使用 wc 命令
-            let output = self.shell(device_id, &format!("wc -c < {}", path))?;
-            let size = output
-                .trim()
-                .parse::<u64>()
-                .map_err(|_| ADBError::CommandError(format!("无法获取文件大小: {}", path)))?;
+    /// 文件存在性检查
+    pub fn file_exists(&self, device_id: &str, path: &str) -> ADBResult<bool> {
+        Ok(self.fs_info(device_id, path)?.is_some())
+    }
 
-            Ok(size)
-        }
+    /// 获取文件/目录大小
+    pub fn get_file_size(&self, device_id: &str, path: &str) -> ADBResult<u64> {
+        self.fs_info(device_id, path)?
+            .map(|info| info.size)
+            .ok_or_else(|| ADBError::FileError(format!("路径不存在: {}", path)))
     }
 
     /// 创建目录
@@ -322,50 +703,42 @@ impl ADB {
 
     /// 删除文件或目录
     pub fn remove_path(&self, device_id: &str, path: &str, recursive: bool) -> ADBResult<()> {
-        // 检查路径是否存在
-        let exists = self.file_exists(device_id, path)?;
-        if !exists {
-            return Err(ADBError::CommandError(format!("路径不存在: {}", path)));
-        }
-
-        // 检查是文件还是目录
-        let is_dir = self
-            .shell(
-                device_id,
-                &format!("[ -d {} ] && echo 'true' || echo 'false'", path),
-            )?
-            .trim()
-            == "true";
+        // 检查路径是否存在及其类型
+        let info = self
+            .fs_info(device_id, path)?
+            .ok_or_else(|| ADBError::CommandError(format!("路径不存在: {}", path)))?;
+
+        let quoted = shell_quote(path);
+        let command = match (info.kind, recursive) {
+            // 递归删除目录
+            (FsKind::Directory, true) => format!("rm -rf {}", quoted),
+            // 删除空目录
+            (FsKind::Directory, false) => format!("rmdir {}", quoted),
+            // 删除文件
+            _ => format!("rm {}", quoted),
+        };
 
-        if is_dir {
-            if recursive {
-                // 递归删除目录
-                self.shell(device_id, &format!("rm -rf {}", path))?;
-            } else {
-                // 删除空目录
-                let output = self.shell(device_id, &format!("rmdir {}", path));
-
-                // 检查是否因为目录非空而失败
-                if let Err(e) = &output {
-                    if let ADBError::DeviceError(msg) = e {
-                        if msg.contains("Directory not empty") {
-                            return Err(ADBError::CommandError(
-                                "目录不为空，使用 recursive=true 递归删除".to_string(),
-                            ));
-                        }
-                    }
-                }
+        // 删除并在同一次调用中验证路径是否已删除
+        let output = self.shell(
+            device_id,
+            &format!(
+                "{} && if [ -e {q} ] || [ -L {q} ]; then echo {m}; fi",
+                command,
+                q = quoted,
+                m = FS_EXISTS_MARKER
+            ),
+        );
 
-                output?;
+        // 检查是否因为目录非空而失败
+        if let Err(ADBError::DeviceError(msg)) = &output {
+            if msg.contains("Directory not empty") {
+                return Err(ADBError::CommandError(
+                    "目录不为空，使用 recursive=true 递归删除".to_string(),
+                ));
             }
-        } else {
-            // 删除文件
-            self.shell(device_id, &format!("rm {}", path))?;
         }
 
-        // 验证路径是否已删除
-        let still_exists = self.file_exists(device_id, path)?;
-        if still_exists {
+        if output?.contains(FS_EXISTS_MARKER) {
             return Err(ADBError::CommandError(format!("无法删除路径: {}", path)));
         }
 
@@ -567,46 +940,61 @@ impl ADB {
         }
 
         // 计算本地文件的 MD5
-        let local_md5 = match std::process::Command::new("md5sum")
-            .arg(local_path)
-            .output()
-        {
-            Ok(output) => {
-                if output.status.success() {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    let parts: Vec<&str> = stdout.split_whitespace().collect();
-                    if !parts.is_empty() {
-                        parts[0].to_string()
-                    } else {
-                        return Err(ADBError::CommandError("无法计算本地文件 MD5".to_string()));
-                    }
-                } else {
-                    return Err(ADBError::CommandError("计算本地文件 MD5 失败".to_string()));
-                }
-            }
-            Err(e) => {
-                return Err(ADBError::CommandError(format!(
-                    "执行 md5sum 命令失败: {}",
-                    e
-                )))
-            }
-        };
+        let local_hash = local_md5(local_file_path)?;
 
         // 计算设备文件的 MD5
         let device_md5 = self.compute_md5(device_id, device_path)?;
 
         // 比较 MD5
-        Ok(local_md5 == device_md5)
+        Ok(local_hash == device_md5)
+    }
+
+    /// 判断本地文件与设备文件是否不同：设备文件不存在或大小不同时直接判定为不同，
+    /// 大小相同时再比较 MD5
+    pub(crate) fn file_changed(&self, device_id: &str, local_path: &Path, device_path: &str) -> ADBResult<bool> {
+        let local_size = fs::metadata(local_path)
+            .map_err(|e| ADBError::FileError(format!("无法读取 {}: {}", local_path.display(), e)))?
+            .len();
+
+        match self.fs_info(device_id, device_path)? {
+            Some(info) if info.kind == FsKind::File && info.size == local_size => {
+                let device_md5 = self.compute_md5(device_id, device_path)?;
+                Ok(local_md5(local_path)? != device_md5)
+            }
+            _ => Ok(true),
+        }
     }
 
     /// 同步目录 (本地到设备)
+    ///
+    /// 只推送设备上不存在、大小或修改时间不同的文件；需要校验和比较或删除多余文件时
+    /// 请使用 [`ADB::sync_directory_to_device_with_options`]
     pub fn sync_directory_to_device(
         &self,
         device_id: &str,
         local_dir: &str,
         device_dir: &str,
         exclude_patterns: Option<&[&str]>,
-    ) -> ADBResult<()> {
+    ) -> ADBResult<SyncReport> {
+        let mut options = SyncOptions::default();
+        if let Some(patterns) = exclude_patterns {
+            options.exclude_patterns = patterns.iter().map(|p| p.to_string()).collect();
+        }
+        self.sync_directory_to_device_with_options(device_id, local_dir, device_dir, &options)
+    }
+
+    /// 按选项同步目录 (本地到设备)
+    ///
+    /// 一次往返获取设备目录下所有文件的大小和修改时间，与本地文件比较后只推送变化的文件。
+    /// `adb push` 会保留修改时间，因此未改动的文件在下次同步时会被跳过。
+    /// 单个文件失败记录在报告中，不会中断同步。
+    pub fn sync_directory_to_device_with_options(
+        &self,
+        device_id: &str,
+        local_dir: &str,
+        device_dir: &str,
+        options: &SyncOptions,
+    ) -> ADBResult<SyncReport> {
         // 确保本地目录存在
         let local_dir_path = Path::new(local_dir);
         if !local_dir_path.exists() || !local_dir_path.is_dir() {
@@ -616,51 +1004,802 @@ impl ADB {
             )));
         }
 
+        let excludes = options
+            .exclude_patterns
+            .iter()
+            .map(|p| {
+                glob::Pattern::new(p)
+                    .map_err(|e| ADBError::FileError(format!("无效的排除模式 {}: {}", p, e)))
+            })
+            .collect::<ADBResult<Vec<_>>>()?;
+
         // 确保设备目录存在
+        let device_dir = device_dir.trim_end_matches('/');
         self.create_directory(device_id, device_dir)?;
 
-        // 读取本地目录内容
-        let entries = fs::read_dir(local_dir_path)
-            .map_err(|e| ADBError::FileError(format!("无法读取本地目录: {}", e)))?;
+        let mut local_files = Vec::new();
+        collect_local_files(local_dir_path, "", &excludes, &mut local_files)?;
+        let device_files = self.device_file_stats(device_id, device_dir)?;
+
+        let mut report = SyncReport::default();
+        let mut candidates = Vec::new();
+        for file in &local_files {
+            match device_files.get(&file.relative) {
+                None => candidates.push(file),
+                Some(&(size, _)) if size != file.size => candidates.push(file),
+                Some(&(_, mtime)) if options.checksum || mtime != file.modified => {
+                    candidates.push(file)
+                }
+                Some(_) => report.skipped.push(file.relative.clone()),
+            }
+        }
 
-        for entry in entries {
-            let entry =
-                entry.map_err(|e| ADBError::FileError(format!("读取目录条目失败: {}", e)))?;
+        // 大小相同的文件用校验和确认是否真的变化
+        if options.checksum {
+            let same_size: Vec<&str> = candidates
+                .iter()
+                .filter(|f| device_files.get(&f.relative).is_some_and(|&(size, _)| size == f.size))
+                .map(|f| f.relative.as_str())
+                .collect();
+            let device_md5 = self.device_md5_batch(device_id, device_dir, &same_size)?;
+
+            let mut changed = Vec::new();
+            for file in candidates {
+                let unchanged = match device_md5.get(&file.relative) {
+                    Some(md5) => local_md5(&file.path)? == *md5,
+                    None => false,
+                };
+                if unchanged {
+                    report.skipped.push(file.relative.clone());
+                } else {
+                    changed.push(file);
+                }
+            }
+            candidates = changed;
+        }
+
+        for file in candidates {
+            let device_path = format!("{}/{}", device_dir, file.relative);
+            match self.push(device_id, &file.path.to_string_lossy(), &device_path, None) {
+                Ok(stats) => {
+                    report.bytes += stats.bytes;
+                    report.pushed.push(file.relative.clone());
+                }
+                Err(e) => {
+                    warn!("推送 {} 失败: {}", file.relative, e);
+                    report.errors.push((file.relative.clone(), e.to_string()));
+                }
+            }
+        }
+
+        if options.delete {
+            let local_set: std::collections::HashSet<&str> =
+                local_files.iter().map(|f| f.relative.as_str()).collect();
+            for relative in device_files.keys() {
+                let excluded = relative
+                    .split('/')
+                    .any(|component| excludes.iter().any(|p| p.matches(component)));
+                if excluded || local_set.contains(relative.as_str()) {
+                    continue;
+                }
+
+                let device_path = format!("{}/{}", device_dir, relative);
+                match self.shell(device_id, &format!("rm -f {}", shell_quote(&device_path))) {
+                    Ok(_) => report.deleted.push(relative.clone()),
+                    Err(e) => report.errors.push((relative.clone(), e.to_string())),
+                }
+            }
+        }
+
+        info!(
+            "目录同步完成: 推送 {}，跳过 {}，删除 {}，失败 {}",
+            report.pushed.len(),
+            report.skipped.len(),
+            report.deleted.len(),
+            report.errors.len()
+        );
+        Ok(report)
+    }
+
+    /// 设备目录下所有文件的相对路径、大小和修改时间
+    fn device_file_stats(&self, device_id: &str, device_dir: &str) -> ADBResult<HashMap<String, (u64, i64)>> {
+        let output = self.shell(
+            device_id,
+            &format!(
+                "find {} -type f -exec stat -c '%s %Y %n' {{}} + 2>/dev/null; true",
+                shell_quote(device_dir)
+            ),
+        )?;
+        let prefix = format!("{}/", device_dir);
+
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.trim_end_matches('\r').splitn(3, ' ');
+                let size = parts.next()?.parse().ok()?;
+                let mtime = parts.next()?.parse().ok()?;
+                let relative = parts.next()?.strip_prefix(&prefix)?;
+                Some((relative.to_string(), (size, mtime)))
+            })
+            .collect())
+    }
+
+    /// 批量计算设备目录下文件的 MD5，返回相对路径到 MD5 的映射
+    fn device_md5_batch(
+        &self,
+        device_id: &str,
+        device_dir: &str,
+        relatives: &[&str],
+    ) -> ADBResult<HashMap<String, String>> {
+        let mut result = HashMap::new();
+
+        // 分批执行，避免命令行过长
+        for batch in relatives.chunks(100) {
+            let files: Vec<String> = batch.iter().map(|r| shell_quote(r)).collect();
+            let output = self.shell(
+                device_id,
+                &format!("cd {} && md5sum {}; true", shell_quote(device_dir), files.join(" ")),
+            )?;
+            for line in output.lines() {
+                if let Some((md5, path)) = line.trim().split_once(char::is_whitespace) {
+                    let path = path.trim().trim_start_matches("./");
+                    result.insert(path.to_string(), md5.to_string());
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 同步目录 (设备到本地)
+    ///
+    /// 逐层列出设备目录（每层一次往返），在本地创建相同的目录结构并拉取文件。
+    /// 排除模式匹配文件名或目录名，匹配的目录整体跳过。
+    pub fn sync_directory_from_device(
+        &self,
+        device_id: &str,
+        device_dir: &str,
+        local_dir: &str,
+        exclude_patterns: Option<&[&str]>,
+    ) -> ADBResult<()> {
+        // 确保设备目录存在
+        match self.fs_info(device_id, device_dir)? {
+            Some(info) if info.kind == FsKind::Directory => {}
+            _ => {
+                return Err(ADBError::FileError(format!(
+                    "设备目录不存在: {}",
+                    device_dir
+                )))
+            }
+        }
 
-            let file_name = entry.file_name();
-            let file_name_str = file_name.to_string_lossy();
+        let excludes = exclude_patterns
+            .unwrap_or_default()
+            .iter()
+            .map(|p| {
+                glob::Pattern::new(p)
+                    .map_err(|e| ADBError::FileError(format!("无效的排除模式 {}: {}", p, e)))
+            })
+            .collect::<ADBResult<Vec<_>>>()?;
+
+        fs::create_dir_all(local_dir)
+            .map_err(|e| ADBError::FileError(format!("无法创建本地目录 {}: {}", local_dir, e)))?;
+
+        let mut level = vec![(
+            device_dir.trim_end_matches('/').to_string(),
+            PathBuf::from(local_dir),
+        )];
+        let mut pulled = 0;
+
+        while !level.is_empty() {
+            let dirs: Vec<String> = level.iter().map(|(dir, _)| dir.clone()).collect();
+            let listings = self.list_directories_batch(device_id, &dirs)?;
+            let mut next = Vec::new();
+
+            for ((device_path, local_path), (_, entries)) in level.iter().zip(listings) {
+                for (name, is_dir) in entries {
+                    if excludes.iter().any(|p| p.matches(&name)) {
+                        continue;
+                    }
 
-            // 检查排除模式
-            if let Some(patterns) = exclude_patterns {
-                let mut skip = false;
-                for pattern in patterns {
-                    if glob::Pattern::new(pattern).unwrap().matches(&file_name_str) {
-                        skip = true;
-                        break;
+                    let child_device = format!("{}/{}", device_path, name);
+                    let child_local = local_path.join(&name);
+
+                    if is_dir {
+                        fs::create_dir_all(&child_local).map_err(|e| {
+                            ADBError::FileError(format!("无法创建本地目录 {}: {}", child_local.display(), e))
+                        })?;
+                        next.push((child_device, child_local));
+                    } else {
+                        self.pull(device_id, &child_device, &child_local.to_string_lossy(), None)?;
+                        pulled += 1;
                     }
                 }
-                if skip {
+            }
+
+            level = next;
+        }
+
+        debug!("已从设备目录 {} 同步 {} 个文件到 {}", device_dir, pulled, local_dir);
+        Ok(())
+    }
+
+    /// 在设备上展开路径通配符，返回匹配的路径（已排序）
+    ///
+    /// 目录列表在设备上获取，匹配在本地完成，路径不会经过设备 shell 的通配符展开。
+    /// 支持 `*`、`?` 和 `[...]`；以 `.` 开头的文件只有在模式本身以 `.` 开头时才会匹配。
+    pub fn glob(&self, device_id: &str, pattern: &str) -> ADBResult<Vec<String>> {
+        if !pattern.starts_with('/') {
+            return Err(ADBError::FileError(format!("通配符模式必须为绝对路径: {}", pattern)));
+        }
+
+        let components: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+        let mut current = vec![String::new()];
+        let mut needs_check = true;
+
+        for (index, component) in components.iter().enumerate() {
+            let is_last = index == components.len() - 1;
+
+            if !has_wildcard(component) {
+                for path in &mut current {
+                    path.push('/');
+                    path.push_str(component);
+                }
+                needs_check = true;
+                continue;
+            }
+
+            let matcher = glob::Pattern::new(component)
+                .map_err(|e| ADBError::FileError(format!("无效的通配符模式 {}: {}", pattern, e)))?;
+            let listings = self.list_directories_batch(device_id, &current)?;
+
+            current = listings
+                .into_iter()
+                .flat_map(|(dir, entries)| {
+                    entries
+                        .into_iter()
+                        .filter(|(name, is_dir)| {
+                            (is_last || *is_dir)
+                                && (!name.starts_with('.') || component.starts_with('.'))
+                                && matcher.matches(name)
+                        })
+                        .map(move |(name, _)| format!("{}/{}", dir, name))
+                })
+                .collect();
+            needs_check = false;
+
+            if current.is_empty() {
+                break;
+            }
+        }
+
+        // 最后一段为普通路径时需要确认是否存在
+        if needs_check && !current.is_empty() {
+            let quoted: Vec<String> = current.iter().map(|p| shell_quote(p)).collect();
+            let output = self.shell(
+                device_id,
+                &format!(
+                    "for p in {}; do if [ -e \"$p\" ]; then echo \"$p\"; fi; done",
+                    quoted.join(" ")
+                ),
+            )?;
+            current = output.lines().map(|l| l.to_string()).collect();
+        }
+
+        current.sort();
+        current.dedup();
+        debug!("通配符 {} 匹配到 {} 个路径", pattern, current.len());
+        Ok(current)
+    }
+
+    /// 一次往返列出多个目录的内容，返回 (目录, [(名称, 是否目录)])
+    fn list_directories_batch(
+        &self,
+        device_id: &str,
+        dirs: &[String],
+    ) -> ADBResult<Vec<DirListing>> {
+        let quoted: Vec<String> = dirs
+            .iter()
+            .map(|d| shell_quote(if d.is_empty() { "/" } else { d }))
+            .collect();
+        let output = self.shell(
+            device_id,
+            &format!(
+                "for d in {}; do echo \"{}$d\"; ls -1ap \"$d\" 2>/dev/null; done; true",
+                quoted.join(" "),
+                GLOB_DIR_MARKER
+            ),
+        )?;
+
+        let mut listings: Vec<DirListing> = Vec::new();
+        for line in output.lines() {
+            if let Some(dir) = line.strip_prefix(GLOB_DIR_MARKER) {
+                let dir = dir.trim_end_matches('/').to_string();
+                listings.push((dir, Vec::new()));
+                continue;
+            }
+            if let Some((_, entries)) = listings.last_mut() {
+                let name = line.trim_end_matches('\r');
+                if name.is_empty() || name == "./" || name == "../" {
                     continue;
                 }
+                match name.strip_suffix('/') {
+                    Some(dir_name) => entries.push((dir_name.to_string(), true)),
+                    None => entries.push((name.to_string(), false)),
+                }
             }
+        }
+
+        Ok(listings)
+    }
 
-            let local_path = entry.path();
-            let device_path = format!("{}/{}", device_dir.trim_end_matches('/'), file_name_str);
-
-            if local_path.is_dir() {
-                // 递归同步子目录
-                self.sync_directory_to_device(
-                    device_id,
-                    local_path.to_str().unwrap(),
-                    &device_path,
-                    exclude_patterns,
-                )?;
+    /// 拉取所有匹配通配符的路径到本地目录
+    ///
+    /// 返回每个匹配路径的传输结果
+    pub fn pull_glob(
+        &self,
+        device_id: &str,
+        pattern: &str,
+        local_dir: &str,
+        options: Option<TransferOptions>,
+    ) -> ADBResult<HashMap<String, ADBResult<TransferStats>>> {
+        fs::create_dir_all(local_dir)
+            .map_err(|e| ADBError::FileError(format!("无法创建本地目录 {}: {}", local_dir, e)))?;
+
+        Ok(self
+            .glob(device_id, pattern)?
+            .into_iter()
+            .map(|path| {
+                let result = self.pull(device_id, &path, local_dir, options.clone());
+                (path, result)
+            })
+            .collect())
+    }
+
+    /// 推送本地文件到所有匹配通配符的设备目录
+    pub fn push_to_glob(
+        &self,
+        device_id: &str,
+        local_path: &str,
+        device_dir_pattern: &str,
+        options: Option<TransferOptions>,
+    ) -> ADBResult<HashMap<String, ADBResult<TransferStats>>> {
+        Ok(self
+            .glob(device_id, device_dir_pattern)?
+            .into_iter()
+            .map(|dir| {
+                let result = self.push(device_id, local_path, &format!("{}/", dir), options.clone());
+                (dir, result)
+            })
+            .collect())
+    }
+
+    /// 删除所有匹配通配符的路径，返回已删除的路径
+    pub fn remove_glob(
+        &self,
+        device_id: &str,
+        pattern: &str,
+        recursive: bool,
+    ) -> ADBResult<Vec<String>> {
+        let paths = self.glob(device_id, pattern)?;
+        for path in &paths {
+            self.remove_path(device_id, path, recursive)?;
+        }
+        Ok(paths)
+    }
+
+    /// 检查设备是否支持 tar/gzip 压缩
+    fn supports_device_compression(&self, device_id: &str) -> ADBResult<bool> {
+        let output = self.shell(
+            device_id,
+            "if command -v tar >/dev/null && command -v gzip >/dev/null; then echo yes; else echo no; fi",
+        )?;
+        Ok(output.trim() == "yes")
+    }
+
+    /// 检查设备是否支持指定的归档方式
+    fn supports_device_archive(&self, device_id: &str, mode: ArchiveMode) -> ADBResult<bool> {
+        if mode == ArchiveMode::TarGz {
+            return self.supports_device_compression(device_id);
+        }
+        let output = self.shell(device_id, "command -v tar >/dev/null && echo yes; true")?;
+        Ok(output.trim() == "yes")
+    }
+
+    /// 在设备上把目录打包后整体拉取，本地解包
+    ///
+    /// 与 `adb pull` 相同：本地目录已存在时在其中创建同名目录，否则直接作为目标目录。
+    /// 不是目录或设备不支持时返回 None。
+    fn pull_archive(
+        &self,
+        device_id: &str,
+        device_path: &str,
+        local_path: &str,
+        mode: ArchiveMode,
+    ) -> ADBResult<Option<TransferStats>> {
+        let is_dir = matches!(self.fs_info(device_id, device_path)?, Some(info) if info.is_dir());
+        if !is_dir {
+            return Ok(None);
+        }
+        if !self.supports_device_archive(device_id, mode)? {
+            warn!("设备 {} 不支持 tar，逐个文件拉取", device_id);
+            return Ok(None);
+        }
+
+        let start = Instant::now();
+        let trimmed = device_path.trim_end_matches('/');
+        let (source_dir, entry, target) = if Path::new(local_path).is_dir() {
+            let (parent, name) = split_device_path(trimmed);
+            (parent, name, Path::new(local_path).join(name))
+        } else {
+            (trimmed, ".", PathBuf::from(local_path))
+        };
+        fs::create_dir_all(local_path)
+            .map_err(|e| ADBError::FileError(format!("无法创建本地目录 {}: {}", local_path, e)))?;
+
+        let command = format!(
+            "tar -c{}f - -C {} {}",
+            mode.tar_flag(),
+            shell_quote(source_dir),
+            shell_quote(entry)
+        );
+        info!("开始归档拉取: {} -> {}", device_path, local_path);
+
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+        let mut child = cmd
+            .arg("exec-out")
+            .arg(&command)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ADBError::CommandError(format!("执行归档拉取失败: {}", e)))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取归档数据".to_string()))?;
+        let reader = BufReader::new(stdout);
+        let unpack_result = match mode {
+            ArchiveMode::TarGz => tar::Archive::new(GzDecoder::new(reader)).unpack(local_path),
+            _ => tar::Archive::new(reader).unpack(local_path),
+        }
+        .map_err(|e| ADBError::FileError(format!("解包失败: {}", e)));
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(ADBError::CommandError(format!(
+                "设备端打包失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        unpack_result?;
+
+        let stats = TransferStats::new(local_path_size(&target), start.elapsed(), 0);
+        debug!("归档拉取完成: {} 字节", stats.bytes);
+        Ok(Some(stats))
+    }
+
+    /// 在本地把目录打包后整体推送，在设备上解包
+    ///
+    /// 与 `adb push` 相同：设备目录已存在时在其中创建同名目录，否则直接作为目标目录。
+    /// 不是目录或设备不支持时返回 None。
+    fn push_archive(
+        &self,
+        device_id: &str,
+        local_path: &str,
+        device_path: &str,
+        options: &TransferOptions,
+    ) -> ADBResult<Option<TransferStats>> {
+        let local = Path::new(local_path);
+        if !local.is_dir() {
+            return Ok(None);
+        }
+        if !self.supports_device_archive(device_id, options.archive_mode)? {
+            warn!("设备 {} 不支持 tar，逐个文件推送", device_id);
+            return Ok(None);
+        }
+
+        let start = Instant::now();
+        let trimmed = device_path.trim_end_matches('/');
+        let target = match self.fs_info(device_id, trimmed)? {
+            Some(info) if info.is_dir() => {
+                let name = local
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                format!("{}/{}", trimmed, name)
+            }
+            _ => trimmed.to_string(),
+        };
+
+        let mut host_resources = HostResourceManager::new();
+        let temp_dir = host_resources.create_temp_dir("adb_archive")?;
+        let archive_path = temp_dir.join("push.tar");
+        let file = File::create(&archive_path)
+            .map_err(|e| ADBError::FileError(format!("创建归档文件失败: {}", e)))?;
+        let build_result = match options.archive_mode {
+            ArchiveMode::TarGz => {
+                let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::fast()));
+                builder
+                    .append_dir_all(".", local)
+                    .and_then(|_| builder.into_inner())
+                    .and_then(|encoder| encoder.finish())
+                    .map(|_| ())
+            }
+            _ => {
+                let mut builder = tar::Builder::new(file);
+                builder.append_dir_all(".", local).and_then(|_| builder.finish())
+            }
+        };
+        build_result.map_err(|e| ADBError::FileError(format!("打包 {} 失败: {}", local_path, e)))?;
+
+        // 归档文件本身按普通文件推送
+        let mut archive_options = options.clone();
+        archive_options.archive_mode = ArchiveMode::None;
+        archive_options.media_scan = false;
+
+        info!("开始归档推送: {} -> {}", local_path, target);
+        let retries = self.with_resources(device_id, |resources| {
+            let device_archive = resources.create_temp_file("push_", ".tar")?;
+            let stats = self.push(
+                device_id,
+                &archive_path.to_string_lossy(),
+                &device_archive,
+                Some(archive_options),
+            )?;
+            self.shell(
+                device_id,
+                &format!(
+                    "mkdir -p {t} && tar -x{z}f {a} -C {t}",
+                    t = shell_quote(&target),
+                    z = options.archive_mode.tar_flag(),
+                    a = shell_quote(&device_archive)
+                ),
+            )?;
+            Ok(stats.retries)
+        })?;
+
+        if options.media_scan {
+            self.scan_file(device_id, &target)?;
+        }
+
+        let stats = TransferStats::new(local_path_size(local), start.elapsed(), retries);
+        debug!("归档推送完成: {} 字节", stats.bytes);
+        Ok(Some(stats))
+    }
+
+    /// 在设备上压缩后拉取，本地解压
+    ///
+    /// 目录在设备上打包为 tar.gz，文件使用 gzip 压缩，适合通过 Wi-Fi ADB 拉取包含大量小文件的目录。
+    /// 设备不支持 tar/gzip 时退回到普通的 [`ADB::pull`]。
+    /// 目录会解压到 `local_path` 下（与 `adb pull` 相同，保留目录名）。
+    pub fn pull_compressed(
+        &self,
+        device_id: &str,
+        device_path: &str,
+        local_path: &str,
+    ) -> ADBResult<TransferStats> {
+        let info = self
+            .fs_info(device_id, device_path)?
+            .ok_or_else(|| ADBError::FileError(format!("路径不存在: {}", device_path)))?;
+
+        if !self.supports_device_compression(device_id)? {
+            warn!("设备 {} 不支持 tar/gzip，使用普通拉取", device_id);
+            return self.pull(device_id, device_path, local_path, None);
+        }
+
+        let start = Instant::now();
+        let trimmed = device_path.trim_end_matches('/');
+        let (parent, name) = split_device_path(trimmed);
+
+        let command = if info.is_dir() {
+            format!("tar -czf - -C {} {}", shell_quote(parent), shell_quote(name))
+        } else {
+            format!("gzip -c {}", shell_quote(trimmed))
+        };
+
+        let mut cmd = self.adb_command();
+        if !device_id.is_empty() {
+            cmd.arg("-s").arg(device_id);
+        }
+        let mut child = cmd
+            .arg("exec-out")
+            .arg(&command)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ADBError::CommandError(format!("执行压缩拉取失败: {}", e)))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ADBError::CommandError("无法读取压缩数据".to_string()))?;
+        let decoder = GzDecoder::new(BufReader::new(stdout));
+
+        let unpack_result = if info.is_dir() {
+            fs::create_dir_all(local_path)
+                .map_err(|e| ADBError::FileError(format!("无法创建本地目录 {}: {}", local_path, e)))
+                .and_then(|_| {
+                    tar::Archive::new(decoder)
+                        .unpack(local_path)
+                        .map_err(|e| ADBError::FileError(format!("解压失败: {}", e)))
+                })
+        } else {
+            let target = if Path::new(local_path).is_dir() {
+                Path::new(local_path).join(name)
             } else {
-                // 推送文件
-                self.push(device_id, local_path.to_str().unwrap(), &device_path, None)?;
+                Path::new(local_path).to_path_buf()
+            };
+            File::create(&target)
+                .and_then(|mut file| std::io::copy(&mut { decoder }, &mut file).map(|_| ()))
+                .map_err(|e| ADBError::FileError(format!("解压失败: {}", e)))
+        };
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(ADBError::CommandError(format!(
+                "设备端压缩失败: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        unpack_result?;
+
+        let local_target = if info.is_dir() || Path::new(local_path).is_dir() {
+            Path::new(local_path).join(name)
+        } else {
+            Path::new(local_path).to_path_buf()
+        };
+        let stats = TransferStats::new(local_path_size(&local_target), start.elapsed(), 0);
+        info!(
+            "压缩拉取完成: {} -> {} ({}, {:.2} MB/s)",
+            device_path,
+            local_path,
+            crate::utils::format_size(stats.bytes),
+            stats.throughput_mb_s()
+        );
+        Ok(stats)
+    }
+
+    /// 按块计算设备文件的 MD5，文件不存在或设备不支持时返回 None
+    fn device_block_checksums(
+        &self,
+        device_id: &str,
+        device_path: &str,
+        block_size: u64,
+    ) -> ADBResult<Option<Vec<String>>> {
+        let command = format!(
+            "f={path}; if [ -f \"$f\" ] && command -v md5sum >/dev/null; then \
+             n=$(( ($(stat -c %s \"$f\") + {bs} - 1) / {bs} )); i=0; \
+             while [ $i -lt $n ]; do dd if=\"$f\" bs={bs} skip=$i count=1 2>/dev/null | md5sum; i=$((i+1)); done; \
+             else echo {missing}; fi",
+            path = shell_quote(device_path),
+            bs = block_size,
+            missing = FS_MISSING_MARKER
+        );
+        let output = self.shell(device_id, &command)?;
+        if output.contains(FS_MISSING_MARKER) {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            output
+                .lines()
+                .filter_map(|line| line.split_whitespace().next())
+                .map(|hash| hash.to_string())
+                .collect(),
+        ))
+    }
+
+    /// 增量推送：只传输与设备上现有文件不同的块，并在设备上重组文件
+    ///
+    /// 适合反复更新的大文件（数据库、应用资源包等）。设备上文件不存在或不支持 md5sum 时
+    /// 退回到普通的 [`ADB::push`]。返回的统计中 `bytes` 为实际传输的字节数。
+    pub fn push_delta(
+        &self,
+        device_id: &str,
+        local_path: &str,
+        device_path: &str,
+    ) -> ADBResult<TransferStats> {
+        let start = Instant::now();
+        let mut local = File::open(local_path)
+            .map_err(|e| ADBError::FileError(format!("无法打开本地文件 {}: {}", local_path, e)))?;
+        let local_size = local.metadata()?.len();
+
+        let remote_blocks = match self.device_block_checksums(device_id, device_path, DELTA_BLOCK_SIZE)? {
+            Some(blocks) => blocks,
+            None => {
+                debug!("设备上没有可比较的 {}，执行完整推送", device_path);
+                return self.push(device_id, local_path, device_path, None);
             }
+        };
+
+        // 收集变化的块，拼接为一个补丁文件
+        let mut host_resources = HostResourceManager::new();
+        let local_dir = host_resources.create_temp_dir("adb_delta")?;
+        let patch_path = local_dir.join("delta.patch");
+        let mut patch = File::create(&patch_path)?;
+        let mut changed = Vec::new();
+        let mut buffer = vec![0u8; DELTA_BLOCK_SIZE as usize];
+        let mut index = 0usize;
+        loop {
+            let mut len = 0;
+            while len < buffer.len() {
+                let n = local.read(&mut buffer[len..])?;
+                if n == 0 {
+                    break;
+                }
+                len += n;
+            }
+            if len == 0 {
+                break;
+            }
+
+            let hash = format!("{:x}", md5::compute(&buffer[..len]));
+            if remote_blocks.get(index) != Some(&hash) {
+                patch.write_all(&buffer[..len])?;
+                changed.push(index);
+            }
+            index += 1;
         }
+        drop(patch);
+
+        let result = self.with_resources(device_id, |resources| {
+            let mut bytes = 0;
+            let mut retries = 0;
+
+            if !changed.is_empty() {
+                let remote_patch = format!("/data/local/tmp/adbkit_delta_{}.patch", rand::random::<u32>());
+                resources.track_temp_file(&remote_patch);
+                let stats = self.push(device_id, patch_path.to_str().unwrap_or_default(), &remote_patch, None)?;
+                bytes = stats.bytes;
+                retries = stats.retries;
+
+                let script: Vec<String> = changed
+                    .iter()
+                    .enumerate()
+                    .map(|(patch_index, block)| {
+                        format!(
+                            "dd if={patch} of={target} bs={bs} skip={skip} seek={seek} count=1 conv=notrunc 2>/dev/null",
+                            patch = shell_quote(&remote_patch),
+                            target = shell_quote(device_path),
+                            bs = DELTA_BLOCK_SIZE,
+                            skip = patch_index,
+                            seek = block
+                        )
+                    })
+                    .collect();
+                for batch in script.chunks(DELTA_BATCH_BLOCKS) {
+                    self.shell(device_id, &batch.join(" && "))?;
+                }
+            }
 
-        Ok(())
+            self.shell(
+                device_id,
+                &format!("truncate -s {} {}", local_size, shell_quote(device_path)),
+            )?;
+            Ok((bytes, retries))
+        });
+        let (bytes, retries) = result?;
+
+        if !self.compare_files(device_id, local_path, device_path)? {
+            return Err(ADBError::FileError(format!(
+                "增量推送后校验失败: {}",
+                device_path
+            )));
+        }
+
+        let stats = TransferStats::new(bytes, start.elapsed(), retries);
+        info!(
+            "增量推送完成: {} -> {}，{}/{} 个块有变化，传输 {}",
+            local_path,
+            device_path,
+            changed.len(),
+            index,
+            crate::utils::format_size(bytes)
+        );
+        Ok(stats)
     }
 }
@@ -0,0 +1,179 @@
+//! 传输通道选择
+//!
+//! 同一台设备可能同时通过 USB 和无线调试连接，两条通道的序列号不同但 `ro.serialno`
+//! 相同。[`ADB::pick_best_transport`] 测量各通道的延迟和吞吐量，之后对该设备的
+//! [`ADB::push`] / [`ADB::pull`] 会改走吞吐量最高的通道。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::{debug, info, warn};
+use std::time::{Duration, Instant};
+
+// 吞吐量测试读取的数据量（1 MiB）
+const PROBE_BYTES: usize = 1024 * 1024;
+// 延迟测试的往返次数，取中位数
+const LATENCY_ROUNDS: usize = 3;
+
+/// 连接通道类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportKind {
+    Usb,
+    Tcp,
+}
+
+impl TransportKind {
+    /// 根据设备序列号判断通道类型（`host:port` 和 mDNS 服务名为 TCP）
+    pub fn from_device_id(device_id: &str) -> Self {
+        if device_id.contains(':') || device_id.contains("._adb-tls-connect.") {
+            TransportKind::Tcp
+        } else {
+            TransportKind::Usb
+        }
+    }
+}
+
+/// 单个通道的测量结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportProbe {
+    pub device_id: String,
+    pub kind: TransportKind,
+    /// 空命令往返延迟的中位数
+    pub latency: Duration,
+    /// 从设备读取数据的吞吐量（字节/秒）
+    pub throughput: f64,
+}
+
+/// 通道选择结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportSelection {
+    /// 设备硬件序列号 (ro.serialno)
+    pub serial: String,
+    /// 选中的通道（设备 ID）
+    pub best: String,
+    /// 各通道的测量结果，测量失败的通道不包含在内
+    pub probes: Vec<TransportProbe>,
+}
+
+impl ADB {
+    /// 列出与指定设备为同一硬件的所有在线通道（包括自身）
+    pub fn device_transports(&self, device_id: &str) -> ADBResult<Vec<String>> {
+        let serial = self.hardware_serial(device_id)?;
+        let mut transports = vec![device_id.to_string()];
+
+        for device in self.list_devices()? {
+            if device.id == device_id || !device.is_online() {
+                continue;
+            }
+            match self.hardware_serial(&device.id) {
+                Ok(other) if other == serial => transports.push(device.id),
+                Ok(_) => {}
+                Err(e) => debug!("无法读取设备 {} 序列号: {}", device.id, e),
+            }
+        }
+        Ok(transports)
+    }
+
+    /// 测量设备各通道的延迟和吞吐量，之后的文件传输走吞吐量最高的通道
+    pub fn pick_best_transport(&self, device_id: &str) -> ADBResult<TransportSelection> {
+        let serial = self.hardware_serial(device_id)?;
+        let transports = self.device_transports(device_id)?;
+
+        let mut probes = Vec::new();
+        for transport in &transports {
+            match self.probe_transport(transport) {
+                Ok(probe) => {
+                    debug!(
+                        "通道 {} ({:?}): 延迟 {:?}，吞吐量 {:.1} KB/s",
+                        probe.device_id,
+                        probe.kind,
+                        probe.latency,
+                        probe.throughput / 1024.0
+                    );
+                    probes.push(probe);
+                }
+                Err(e) => warn!("测量通道 {} 失败: {}", transport, e),
+            }
+        }
+
+        let best = probes
+            .iter()
+            .max_by(|a, b| a.throughput.total_cmp(&b.throughput))
+            .map(|p| p.device_id.clone())
+            .ok_or_else(|| ADBError::DeviceError(format!("设备 {} 没有可用的通道", device_id)))?;
+
+        let mut routes = self.transfer_routes.lock().unwrap();
+        for transport in &transports {
+            routes.insert(transport.clone(), best.clone());
+        }
+        drop(routes);
+
+        info!("设备 {} 的文件传输将使用通道 {}", serial, best);
+        Ok(TransportSelection {
+            serial,
+            best,
+            probes,
+        })
+    }
+
+    /// 取消通道选择，文件传输恢复使用调用时指定的设备 ID
+    pub fn clear_transport_route(&self, device_id: &str) {
+        let mut routes = self.transfer_routes.lock().unwrap();
+        if let Some(best) = routes.remove(device_id) {
+            routes.retain(|_, route| *route != best);
+        }
+    }
+
+    /// 文件传输实际使用的设备 ID
+    ///
+    /// 选定的通道离线（如 Wi-Fi 断开）时取消该设备的通道选择，退回到调用时指定的设备 ID
+    pub(crate) fn transfer_route(&self, device_id: &str) -> String {
+        let route = self.transfer_routes.lock().unwrap().get(device_id).cloned();
+        match route {
+            Some(route) if route != device_id => {
+                if self.is_device_online(&route).unwrap_or(false) {
+                    return route;
+                }
+                warn!("设备 {} 选定的传输通道 {} 已离线，改用原通道", device_id, route);
+                self.clear_transport_route(device_id);
+                device_id.to_string()
+            }
+            _ => device_id.to_string(),
+        }
+    }
+
+    /// 设备硬件序列号
+    fn hardware_serial(&self, device_id: &str) -> ADBResult<String> {
+        let serial = self.shell(device_id, "getprop ro.serialno")?.trim().to_string();
+        if serial.is_empty() {
+            return Err(ADBError::DeviceError(format!("设备 {} 没有硬件序列号", device_id)));
+        }
+        Ok(serial)
+    }
+
+    /// 测量单个通道
+    fn probe_transport(&self, device_id: &str) -> ADBResult<TransportProbe> {
+        let mut rounds = Vec::with_capacity(LATENCY_ROUNDS);
+        for _ in 0..LATENCY_ROUNDS {
+            let start = Instant::now();
+            self.exec_out(device_id, "true")?;
+            rounds.push(start.elapsed());
+        }
+        rounds.sort();
+        let latency = rounds[rounds.len() / 2];
+
+        let start = Instant::now();
+        let data = self.exec_out(
+            device_id,
+            &format!("dd if=/dev/zero bs=65536 count={} 2>/dev/null", PROBE_BYTES / 65536),
+        )?;
+        // 扣除命令本身的往返开销
+        let elapsed = start.elapsed().saturating_sub(latency).max(Duration::from_millis(1));
+
+        Ok(TransportProbe {
+            device_id: device_id.to_string(),
+            kind: TransportKind::from_device_id(device_id),
+            latency,
+            throughput: data.len() as f64 / elapsed.as_secs_f64(),
+        })
+    }
+}
@@ -0,0 +1,201 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::utils::shell_quote;
+use log::{debug, info};
+use std::time::Duration;
+
+/// 回收站目录名
+///
+/// 每个文件系统各有一个回收站，移入回收站只是同一文件系统内的重命名，
+/// 恢复时保留原有的权限和属主
+pub const TRASH_DIR_NAME: &str = ".adbkit_trash";
+
+// /data 分区上 shell 用户可写的回收站位置
+const DATA_TRASH_DIR: &str = "/data/local/tmp/.adbkit_trash";
+
+// 记录原始路径的文件名
+const ORIGIN_FILE: &str = ".origin";
+
+/// 回收站中的一项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashEntry {
+    /// 删除前的路径
+    pub original_path: String,
+    /// 回收站中的目录（`<回收站>/<时间戳>_<随机数>`）
+    pub trash_dir: String,
+    /// 放入回收站的时间（设备时钟，Unix 纪元秒）
+    pub trashed_at: i64,
+}
+
+impl TrashEntry {
+    /// 文件在回收站中的实际路径
+    pub fn trash_path(&self) -> String {
+        format!("{}/{}", self.trash_dir, base_name(&self.original_path))
+    }
+}
+
+fn base_name(path: &str) -> &str {
+    path.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(path)
+}
+
+/// 从回收站目录名 "<时间戳>_<随机数>" 中解析时间
+fn parse_trashed_at(trash_dir: &str) -> Option<i64> {
+    base_name(trash_dir).split('_').next()?.parse().ok()
+}
+
+/// 路径是否位于回收站内
+fn in_trash(path: &str) -> bool {
+    path.split('/').any(|part| part == TRASH_DIR_NAME)
+}
+
+/// 选择与 `path`（已规范化）位于同一文件系统的回收站，`mounts` 为 /proc/mounts 的内容
+fn trash_root_for(path: &str, mounts: &str) -> String {
+    // 外部存储的每个用户目录可写，但其挂载点 /storage/emulated 本身不可写
+    if let Some(rest) = path.strip_prefix("/storage/emulated/") {
+        if let Some(user) = rest.split('/').next().filter(|u| !u.is_empty()) {
+            return format!("/storage/emulated/{}/{}", user, TRASH_DIR_NAME);
+        }
+    }
+    if path.starts_with("/data/") {
+        return DATA_TRASH_DIR.to_string();
+    }
+
+    let mount = mounts
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .filter(|mount| {
+            *mount == "/"
+                || path == *mount
+                || path.strip_prefix(mount).is_some_and(|rest| rest.starts_with('/'))
+        })
+        .max_by_key(|mount| mount.len())
+        .unwrap_or("/");
+    format!("{}/{}", mount.trim_end_matches('/'), TRASH_DIR_NAME)
+}
+
+impl ADB {
+    /// 将文件或目录移入回收站而不是直接删除，可通过 [`ADB::restore_trash`] 恢复
+    pub fn remove_path_to_trash(&self, device_id: &str, path: &str) -> ADBResult<TrashEntry> {
+        let path = path.trim_end_matches('/');
+        if path.is_empty() {
+            return Err(ADBError::FileError(format!("无法移入回收站: {}", path)));
+        }
+        if !self.file_exists(device_id, path)? {
+            return Err(ADBError::CommandError(format!("路径不存在: {}", path)));
+        }
+
+        // 规范化路径，/sdcard 等别名统一为实际路径，再按所在文件系统选择回收站
+        let output = self.shell(device_id, &format!("realpath {}; cat /proc/mounts", shell_quote(path)))?;
+        let (canonical, mounts) = output.split_once('\n').unwrap_or((output.as_str(), ""));
+        let canonical = canonical.trim();
+        if !canonical.starts_with('/') {
+            return Err(ADBError::CommandError(format!("无法解析路径 {}: {}", path, canonical)));
+        }
+        if canonical == "/" || in_trash(canonical) {
+            return Err(ADBError::FileError(format!("无法移入回收站: {}", path)));
+        }
+        let trash_root = trash_root_for(canonical, mounts);
+
+        let command = format!(
+            "d={trash}/$(date +%s)_{rand}; mkdir -p \"$d\" && echo {orig} > \"$d/{origin}\" && mv {orig} \"$d/\" && echo \"$d\"",
+            trash = shell_quote(&trash_root),
+            rand = rand::random::<u32>(),
+            orig = shell_quote(canonical),
+            origin = ORIGIN_FILE
+        );
+        let output = self.shell(device_id, &command)?;
+        let trash_dir = output
+            .lines()
+            .map(|l| l.trim())
+            .rfind(|l| l.starts_with(trash_root.as_str()))
+            .ok_or_else(|| ADBError::CommandError(format!("移入回收站失败: {}", output.trim())))?
+            .to_string();
+
+        let entry = TrashEntry {
+            original_path: canonical.to_string(),
+            trashed_at: parse_trashed_at(&trash_dir).unwrap_or(0),
+            trash_dir,
+        };
+        info!("已将 {} 移入回收站 {}", path, entry.trash_dir);
+        Ok(entry)
+    }
+
+    /// 列出所有文件系统上回收站中的内容，按放入时间排序
+    pub fn list_trash(&self, device_id: &str) -> ADBResult<Vec<TrashEntry>> {
+        let command = format!(
+            "for t in {data} /storage/emulated/*/{name} $(cut -d' ' -f2 /proc/mounts | sed 's|/*$|/{name}|'); do \
+             for d in \"$t\"/*/; do [ -f \"$d{origin}\" ] && echo \"$d|$(cat \"$d{origin}\")\"; done; done; true",
+            data = DATA_TRASH_DIR,
+            name = TRASH_DIR_NAME,
+            origin = ORIGIN_FILE
+        );
+        let output = self.shell(device_id, &command)?;
+
+        let mut entries: Vec<TrashEntry> = output
+            .lines()
+            .filter_map(|line| {
+                let (dir, original) = line.trim().split_once('|')?;
+                let trash_dir = dir.trim_end_matches('/').to_string();
+                Some(TrashEntry {
+                    original_path: original.to_string(),
+                    trashed_at: parse_trashed_at(&trash_dir)?,
+                    trash_dir,
+                })
+            })
+            .collect();
+
+        // 同一目录可能通过多个挂载点被列出
+        entries.sort_by(|a, b| a.trash_dir.cmp(&b.trash_dir));
+        entries.dedup_by(|a, b| a.trash_dir == b.trash_dir);
+        entries.sort_by_key(|e| e.trashed_at);
+        Ok(entries)
+    }
+
+    /// 将回收站中的一项恢复到原始路径
+    ///
+    /// 原始路径已存在时返回错误，不会覆盖
+    pub fn restore_trash(&self, device_id: &str, entry: &TrashEntry) -> ADBResult<()> {
+        if self.file_exists(device_id, &entry.original_path)? {
+            return Err(ADBError::FileError(format!(
+                "原始路径已存在，无法恢复: {}",
+                entry.original_path
+            )));
+        }
+
+        let original = shell_quote(&entry.original_path);
+        let command = format!(
+            "mkdir -p \"$(dirname {orig})\" && mv {src} {orig} && rm -rf {dir}",
+            orig = original,
+            src = shell_quote(&entry.trash_path()),
+            dir = shell_quote(&entry.trash_dir)
+        );
+        self.shell(device_id, &command)?;
+
+        info!("已从回收站恢复 {}", entry.original_path);
+        Ok(())
+    }
+
+    /// 永久删除回收站中早于 `older_than` 的内容，返回删除的项数
+    pub fn purge_trash(&self, device_id: &str, older_than: Duration) -> ADBResult<usize> {
+        let (now, _) = self.device_clock(device_id)?;
+        let cutoff = now as i64 - older_than.as_secs() as i64;
+
+        let expired: Vec<String> = self
+            .list_trash(device_id)?
+            .into_iter()
+            .filter(|e| e.trashed_at <= cutoff)
+            .map(|e| shell_quote(&e.trash_dir))
+            .collect();
+
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        self.shell(device_id, &format!("rm -rf {}", expired.join(" ")))?;
+        debug!("已清理设备 {} 回收站中的 {} 项", device_id, expired.len());
+        Ok(expired.len())
+    }
+}
@@ -0,0 +1,363 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::{debug, info, warn};
+use ssh2::{CheckResult, KnownHostFileKind, Session};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// libssh2 的 EAGAIN 错误码
+const LIBSSH2_ERROR_EAGAIN: i32 = -37;
+
+/// 远程 ADB 服务器默认端口
+const ADB_SERVER_PORT: u16 = 5037;
+
+/// SSH 认证方式
+#[derive(Debug, Clone)]
+pub enum SshAuth {
+    /// 密码认证
+    Password(String),
+    /// 私钥认证
+    PrivateKey {
+        path: PathBuf,
+        passphrase: Option<String>,
+    },
+    /// 使用 ssh-agent
+    Agent,
+}
+
+/// SSH 跳板机配置
+#[derive(Debug, Clone)]
+pub struct SshTunnelConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub auth: SshAuth,
+    /// known_hosts 文件路径，设置后校验主机密钥
+    pub known_hosts: Option<PathBuf>,
+    /// 建立 SSH 连接的超时时间
+    pub connect_timeout: Duration,
+}
+
+impl SshTunnelConfig {
+    /// 创建跳板机配置
+    pub fn new(host: &str, username: &str, auth: SshAuth) -> Self {
+        Self {
+            host: host.to_string(),
+            port: 22,
+            username: username.to_string(),
+            auth,
+            known_hosts: None,
+            connect_timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// 设置 SSH 端口
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// 设置 known_hosts 文件
+    pub fn with_known_hosts(mut self, path: impl Into<PathBuf>) -> Self {
+        self.known_hosts = Some(path.into());
+        self
+    }
+
+    /// 设置连接超时
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+}
+
+/// SSH 隧道
+///
+/// 在本地监听一个端口，并通过跳板机将连接转发到目标地址。
+/// 隧道在 `close()` 或超出作用域时关闭。
+pub struct SshTunnel {
+    local_port: u16,
+    target_host: String,
+    target_port: u16,
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl SshTunnel {
+    /// 建立隧道
+    ///
+    /// # 参数
+    /// * `config` - 跳板机配置
+    /// * `target_host` - 从跳板机视角看到的目标主机
+    /// * `target_port` - 目标端口
+    /// * `local_port` - 本地监听端口，`None` 表示自动分配
+    pub fn open(
+        config: &SshTunnelConfig,
+        target_host: &str,
+        target_port: u16,
+        local_port: Option<u16>,
+    ) -> ADBResult<Self> {
+        let session = open_session(config)?;
+
+        let listener = TcpListener::bind(("127.0.0.1", local_port.unwrap_or(0)))
+            .map_err(|e| ADBError::ConnectionError(format!("无法监听本地端口: {}", e)))?;
+        let local_port = listener.local_addr()?.port();
+        listener.set_nonblocking(true)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let worker = {
+            let stop = stop.clone();
+            let target_host = target_host.to_string();
+            thread::spawn(move || pump(session, listener, &target_host, target_port, &stop))
+        };
+
+        info!(
+            "SSH 隧道已建立: 127.0.0.1:{} -> {}:{} (经由 {})",
+            local_port, target_host, target_port, config.host
+        );
+
+        Ok(Self {
+            local_port,
+            target_host: target_host.to_string(),
+            target_port,
+            stop,
+            worker: Some(worker),
+        })
+    }
+
+    /// 本地监听端口
+    pub fn local_port(&self) -> u16 {
+        self.local_port
+    }
+
+    /// 隧道目标地址
+    pub fn target(&self) -> String {
+        format!("{}:{}", self.target_host, self.target_port)
+    }
+
+    /// 隧道是否仍在运行
+    pub fn is_alive(&self) -> bool {
+        self.worker.as_ref().is_some_and(|w| !w.is_finished())
+    }
+
+    /// 关闭隧道
+    pub fn close(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+            debug!("SSH 隧道 127.0.0.1:{} 已关闭", self.local_port);
+        }
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// 建立并认证 SSH 会话
+fn open_session(config: &SshTunnelConfig) -> ADBResult<Session> {
+    let addr = format!("{}:{}", config.host, config.port);
+    let socket_addr = std::net::ToSocketAddrs::to_socket_addrs(&addr)
+        .map_err(|e| ADBError::ConnectionError(format!("无法解析 SSH 主机 {}: {}", addr, e)))?
+        .next()
+        .ok_or_else(|| ADBError::ConnectionError(format!("无法解析 SSH 主机 {}", addr)))?;
+
+    let tcp = TcpStream::connect_timeout(&socket_addr, config.connect_timeout)
+        .map_err(|e| ADBError::ConnectionError(format!("无法连接 SSH 主机 {}: {}", addr, e)))?;
+
+    let mut session = Session::new()
+        .map_err(|e| ADBError::ConnectionError(format!("无法创建 SSH 会话: {}", e)))?;
+    session.set_tcp_stream(tcp);
+    session
+        .handshake()
+        .map_err(|e| ADBError::ConnectionError(format!("SSH 握手失败: {}", e)))?;
+
+    if let Some(known_hosts) = &config.known_hosts {
+        verify_host_key(&session, config, known_hosts)?;
+    }
+
+    let auth_result = match &config.auth {
+        SshAuth::Password(password) => session.userauth_password(&config.username, password),
+        SshAuth::PrivateKey { path, passphrase } => session.userauth_pubkey_file(
+            &config.username,
+            None,
+            path,
+            passphrase.as_deref(),
+        ),
+        SshAuth::Agent => session.userauth_agent(&config.username),
+    };
+
+    auth_result.map_err(|e| ADBError::PermissionDenied(format!("SSH 认证失败: {}", e)))?;
+
+    if !session.authenticated() {
+        return Err(ADBError::PermissionDenied("SSH 认证失败".to_string()));
+    }
+
+    Ok(session)
+}
+
+/// 根据 known_hosts 校验主机密钥
+fn verify_host_key(
+    session: &Session,
+    config: &SshTunnelConfig,
+    known_hosts_path: &Path,
+) -> ADBResult<()> {
+    let mut known_hosts = session
+        .known_hosts()
+        .map_err(|e| ADBError::ConnectionError(format!("无法初始化 known_hosts: {}", e)))?;
+    known_hosts
+        .read_file(known_hosts_path, KnownHostFileKind::OpenSSH)
+        .map_err(|e| ADBError::FileError(format!("无法读取 known_hosts: {}", e)))?;
+
+    let (key, _) = session
+        .host_key()
+        .ok_or_else(|| ADBError::ConnectionError("无法获取 SSH 主机密钥".to_string()))?;
+
+    match known_hosts.check_port(&config.host, config.port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::Mismatch => Err(ADBError::PermissionDenied(format!(
+            "SSH 主机密钥不匹配: {}",
+            config.host
+        ))),
+        CheckResult::NotFound => Err(ADBError::PermissionDenied(format!(
+            "known_hosts 中没有主机 {} 的记录",
+            config.host
+        ))),
+        CheckResult::Failure => Err(ADBError::ConnectionError(
+            "校验 SSH 主机密钥失败".to_string(),
+        )),
+    }
+}
+
+/// 写入非阻塞流，遇到 WouldBlock 时短暂等待后重试
+fn write_all_nonblocking<W: Write>(writer: &mut W, mut data: &[u8]) -> std::io::Result<()> {
+    while !data.is_empty() {
+        match writer.write(data) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Ok(n) => data = &data[n..],
+            Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(Duration::from_millis(1)),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// 隧道工作线程：接受本地连接并在本地套接字与 SSH 通道之间转发数据
+fn pump(session: Session, listener: TcpListener, target_host: &str, target_port: u16, stop: &AtomicBool) {
+    session.set_blocking(false);
+
+    let mut connections: Vec<(TcpStream, ssh2::Channel)> = Vec::new();
+    let mut buffer = vec![0u8; 32 * 1024];
+
+    while !stop.load(Ordering::SeqCst) {
+        let mut idle = true;
+
+        // 接受新的本地连接
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                idle = false;
+                let channel = loop {
+                    match session.channel_direct_tcpip(target_host, target_port, None) {
+                        Ok(channel) => break Some(channel),
+                        Err(e) if e.code() == ssh2::ErrorCode::Session(LIBSSH2_ERROR_EAGAIN) => {
+                            thread::sleep(Duration::from_millis(1));
+                        }
+                        Err(e) => {
+                            warn!("无法打开到 {}:{} 的 SSH 通道: {}", target_host, target_port, e);
+                            break None;
+                        }
+                    }
+                };
+
+                if let Some(channel) = channel {
+                    if stream.set_nonblocking(true).is_ok() {
+                        debug!("隧道接受连接 {}", peer);
+                        connections.push((stream, channel));
+                    }
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => warn!("隧道接受连接失败: {}", e),
+        }
+
+        // 双向转发数据
+        connections.retain_mut(|(stream, channel)| {
+            // 本地 -> 远程
+            match stream.read(&mut buffer) {
+                Ok(0) => {
+                    let _ = channel.send_eof();
+                    return false;
+                }
+                Ok(n) => {
+                    idle = false;
+                    if write_all_nonblocking(channel, &buffer[..n]).is_err() {
+                        return false;
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => return false,
+            }
+
+            // 远程 -> 本地
+            match channel.read(&mut buffer) {
+                Ok(0) if channel.eof() => return false,
+                Ok(0) => {}
+                Ok(n) => {
+                    idle = false;
+                    if write_all_nonblocking(stream, &buffer[..n]).is_err() {
+                        return false;
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => return false,
+            }
+
+            true
+        });
+
+        if idle {
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    for (_, mut channel) in connections {
+        let _ = channel.close();
+    }
+}
+
+impl ADB {
+    /// 通过 SSH 隧道连接远程网段中的设备
+    ///
+    /// 返回隧道和设备序列号；隧道被释放后设备连接随之断开。
+    pub fn connect_via_tunnel(
+        &self,
+        config: &SshTunnelConfig,
+        device_host: &str,
+        device_port: u16,
+    ) -> ADBResult<(SshTunnel, String)> {
+        let tunnel = SshTunnel::open(config, device_host, device_port, None)?;
+        self.connect("127.0.0.1", tunnel.local_port())?;
+
+        let serial = format!("127.0.0.1:{}", tunnel.local_port());
+        Ok((tunnel, serial))
+    }
+
+    /// 通过 SSH 隧道使用跳板机上的 ADB 服务器
+    ///
+    /// 返回的 ADB 实例所有命令都会经隧道发往远程服务器。
+    pub fn via_server_tunnel(&self, config: &SshTunnelConfig) -> ADBResult<(SshTunnel, ADB)> {
+        let tunnel = SshTunnel::open(config, "127.0.0.1", ADB_SERVER_PORT, None)?;
+
+        let mut adb_config = self.config.clone();
+        adb_config.server_host = Some("127.0.0.1".to_string());
+        adb_config.server_port = Some(tunnel.local_port());
+
+        Ok((tunnel, ADB::new(Some(adb_config))))
+    }
+}
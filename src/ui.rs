@@ -0,0 +1,733 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::monitor::sleep_unless_stopped;
+use crate::resource::HostResourceManager;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+// dumpsys activity 中的任务行，兼容 "TaskRecord{.. #12 A=com.foo ..}" 与 "Task{.. #12 type=standard A=10123:com.foo ..}"
+static TASK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:TaskRecord|Task)\{[0-9a-f]+ #(\d+) [^}]*?A=(?:\d+:)?([\w.]+)").unwrap()
+});
+
+// uiautomator dump 输出中的节点标签
+static NODE_TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<node\b([^>]*?)(/?)>|</node>").unwrap());
+
+static NODE_ATTR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([\w-]+)="([^"]*)""#).unwrap());
+
+static BOUNDS_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[(-?\d+),(-?\d+)\]\[(-?\d+),(-?\d+)\]").unwrap());
+
+// 对话框自动处理的检查间隔
+const DIALOG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// 窗口模式（WindowConfiguration）
+const WINDOWING_MODE_SPLIT_SCREEN_PRIMARY: u32 = 3;
+const WINDOWING_MODE_SPLIT_SCREEN_SECONDARY: u32 = 4;
+// Android 7/8 的分屏栈 ID
+const DOCKED_STACK_ID: u32 = 3;
+
+/// 屏幕上的矩形区域（像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Rect {
+    /// 创建矩形
+    pub fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// 宽度
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+
+    /// 高度
+    pub fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+
+    /// 中心点
+    pub fn center(&self) -> (i32, i32) {
+        ((self.left + self.right) / 2, (self.top + self.bottom) / 2)
+    }
+
+    /// 检查点是否在矩形内
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.left && x < self.right && y >= self.top && y < self.bottom
+    }
+}
+
+/// UI 层级中的节点
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UiNode {
+    pub class: String,
+    pub text: String,
+    pub resource_id: String,
+    pub content_desc: String,
+    pub package: String,
+    pub bounds: Rect,
+    pub clickable: bool,
+    pub scrollable: bool,
+    pub enabled: bool,
+    pub children: Vec<UiNode>,
+}
+
+impl UiNode {
+    /// 深度优先查找第一个满足选择器的节点
+    pub fn find(&self, selector: &Selector) -> Option<&UiNode> {
+        if selector.matches(self) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(selector))
+    }
+
+    /// 深度优先查找所有满足选择器的节点
+    pub fn find_all<'a>(&'a self, selector: &Selector) -> Vec<&'a UiNode> {
+        let mut found = Vec::new();
+        self.collect(selector, &mut found);
+        found
+    }
+
+    fn collect<'a>(&'a self, selector: &Selector, found: &mut Vec<&'a UiNode>) {
+        if selector.matches(self) {
+            found.push(self);
+        }
+        for child in &self.children {
+            child.collect(selector, found);
+        }
+    }
+
+    /// 节点是否在屏幕上可见（边界非空）
+    pub fn is_visible(&self) -> bool {
+        self.bounds.width() > 0 && self.bounds.height() > 0
+    }
+}
+
+/// UI 节点选择器，所有已设置的条件都满足时匹配
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selector {
+    pub text: Option<String>,
+    pub text_contains: Option<String>,
+    pub resource_id: Option<String>,
+    pub content_desc: Option<String>,
+    pub class: Option<String>,
+    pub package: Option<String>,
+}
+
+impl Selector {
+    /// 按完整文本匹配
+    pub fn text(text: &str) -> Self {
+        Self::default().with_text(text)
+    }
+
+    /// 按资源 ID 匹配
+    pub fn resource_id(id: &str) -> Self {
+        Self::default().with_resource_id(id)
+    }
+
+    /// 按内容描述匹配
+    pub fn content_desc(desc: &str) -> Self {
+        Self::default().with_content_desc(desc)
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = Some(text.to_string());
+        self
+    }
+
+    pub fn with_text_contains(mut self, text: &str) -> Self {
+        self.text_contains = Some(text.to_string());
+        self
+    }
+
+    pub fn with_resource_id(mut self, id: &str) -> Self {
+        self.resource_id = Some(id.to_string());
+        self
+    }
+
+    pub fn with_content_desc(mut self, desc: &str) -> Self {
+        self.content_desc = Some(desc.to_string());
+        self
+    }
+
+    pub fn with_class(mut self, class: &str) -> Self {
+        self.class = Some(class.to_string());
+        self
+    }
+
+    pub fn with_package(mut self, package: &str) -> Self {
+        self.package = Some(package.to_string());
+        self
+    }
+
+    /// 检查节点是否满足选择器
+    pub fn matches(&self, node: &UiNode) -> bool {
+        fn eq(expected: &Option<String>, actual: &str) -> bool {
+            expected.as_deref().is_none_or(|e| e == actual)
+        }
+
+        eq(&self.text, &node.text)
+            && self.text_contains.as_deref().is_none_or(|t| node.text.contains(t))
+            && eq(&self.resource_id, &node.resource_id)
+            && eq(&self.content_desc, &node.content_desc)
+            && eq(&self.class, &node.class)
+            && eq(&self.package, &node.package)
+    }
+}
+
+/// 会弹出系统更新提示的更新程序：Google Play 服务、Play 商店和常见厂商的系统更新
+const UPDATER_PACKAGES: &[&str] = &[
+    "com.google.android.gms",
+    "com.android.vending",
+    "com.android.updater",
+    "com.sec.android.soagent",
+];
+
+/// 自动处理的对话框规则
+///
+/// 界面中满足 `detect`（未设置时不检查）且存在任一 `buttons` 节点时，点击第一个匹配的按钮
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialogRule {
+    pub name: String,
+    pub detect: Option<Selector>,
+    /// 候选按钮，按顺序尝试（可以为不同语言或系统版本各写一个）
+    pub buttons: Vec<Selector>,
+}
+
+impl DialogRule {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            detect: None,
+            buttons: Vec::new(),
+        }
+    }
+
+    pub fn detect(mut self, selector: Selector) -> Self {
+        self.detect = Some(selector);
+        self
+    }
+
+    pub fn button(mut self, selector: Selector) -> Self {
+        self.buttons.push(selector);
+        self
+    }
+
+    /// 运行时权限请求：允许（优先选择“仅在使用时允许”）
+    pub fn permission_prompt() -> Self {
+        Self::new("permission")
+            .button(Selector::resource_id(
+                "com.android.permissioncontroller:id/permission_allow_foreground_only_button",
+            ))
+            .button(Selector::resource_id(
+                "com.android.permissioncontroller:id/permission_allow_button",
+            ))
+            .button(Selector::resource_id(
+                "com.android.packageinstaller:id/permission_allow_button",
+            ))
+    }
+
+    /// 应用崩溃对话框：关闭应用
+    pub fn crash_dialog() -> Self {
+        Self::new("crash").button(Selector::resource_id("android:id/aerr_close"))
+    }
+
+    /// 应用无响应对话框：等待
+    pub fn anr_dialog() -> Self {
+        Self::new("anr").button(Selector::resource_id("android:id/aerr_wait"))
+    }
+
+    /// 系统更新提示：稍后
+    ///
+    /// 只处理 `package`（系统更新或应用商店等更新程序）弹出的提示，不会误点被测应用中的按钮
+    pub fn system_update_nag(package: &str) -> Self {
+        Self::new("system_update")
+            .detect(
+                Selector::default()
+                    .with_package(package)
+                    .with_text_contains("update"),
+            )
+            .button(Selector::text("Later").with_package(package))
+            .button(Selector::text("Not now").with_package(package))
+            .button(Selector::text("Remind me later").with_package(package))
+    }
+
+    /// 内置规则
+    pub fn defaults() -> Vec<Self> {
+        let mut rules = vec![
+            Self::permission_prompt(),
+            Self::crash_dialog(),
+            Self::anr_dialog(),
+        ];
+        rules.extend(UPDATER_PACKAGES.iter().map(|p| Self::system_update_nag(p)));
+        rules
+    }
+
+    /// 在 UI 层级中查找需要点击的按钮
+    pub fn find_button<'a>(&self, root: &'a UiNode) -> Option<&'a UiNode> {
+        if let Some(detect) = &self.detect {
+            root.find(detect)?;
+        }
+        self.buttons.iter().find_map(|button| {
+            root.find_all(button)
+                .into_iter()
+                .find(|n| n.is_visible() && n.enabled)
+        })
+    }
+}
+
+/// 一次自动点击记录
+#[derive(Debug, Clone)]
+pub struct DialogResponse {
+    /// 命中的规则名称
+    pub rule: String,
+    /// 被点击按钮的文本（没有文本时为资源 ID）
+    pub button: String,
+    pub timestamp: SystemTime,
+}
+
+/// 后台对话框自动处理任务
+///
+/// 到达指定时长、调用 `stop()`、超出作用域或 [`ADB::shutdown`] 时停止
+pub struct ResponderHandle {
+    stop: Arc<AtomicBool>,
+    responses: Arc<Mutex<Vec<DialogResponse>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl ResponderHandle {
+    /// 已处理的对话框
+    pub fn responses(&self) -> Vec<DialogResponse> {
+        self.responses.lock().unwrap().clone()
+    }
+
+    /// 后台任务是否仍在运行
+    pub fn is_alive(&self) -> bool {
+        self.worker.as_ref().is_some_and(|w| !w.is_finished())
+    }
+
+    /// 停止并等待后台线程退出，返回全部处理记录
+    pub fn stop(&mut self) -> Vec<DialogResponse> {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+            debug!("对话框自动处理已停止");
+        }
+        self.responses()
+    }
+}
+
+impl Drop for ResponderHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 滚动方向（内容移动的方向，`Down` 表示查看下方内容，手指向上滑动）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+fn parse_bounds(value: &str) -> Rect {
+    BOUNDS_RE
+        .captures(value)
+        .map(|caps| {
+            let n = |i: usize| caps[i].parse().unwrap_or(0);
+            Rect::new(n(1), n(2), n(3), n(4))
+        })
+        .unwrap_or_default()
+}
+
+fn parse_node_attrs(attrs: &str) -> UiNode {
+    let mut node = UiNode::default();
+    for caps in NODE_ATTR_RE.captures_iter(attrs) {
+        let value = unescape_xml(&caps[2]);
+        match &caps[1] {
+            "class" => node.class = value,
+            "text" => node.text = value,
+            "resource-id" => node.resource_id = value,
+            "content-desc" => node.content_desc = value,
+            "package" => node.package = value,
+            "bounds" => node.bounds = parse_bounds(&value),
+            "clickable" => node.clickable = value == "true",
+            "scrollable" => node.scrollable = value == "true",
+            "enabled" => node.enabled = value == "true",
+            _ => {}
+        }
+    }
+    node
+}
+
+/// 解析 `uiautomator dump` 生成的 XML，返回以 hierarchy 为根的节点
+pub(crate) fn parse_ui_hierarchy(xml: &str) -> ADBResult<UiNode> {
+    let mut stack = vec![UiNode {
+        class: "hierarchy".to_string(),
+        ..Default::default()
+    }];
+
+    for caps in NODE_TAG_RE.captures_iter(xml) {
+        match caps.get(1) {
+            Some(attrs) => {
+                let node = parse_node_attrs(attrs.as_str());
+                if &caps[2] == "/" {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(node);
+                    }
+                } else {
+                    stack.push(node);
+                }
+            }
+            None => {
+                if stack.len() > 1 {
+                    let node = stack.pop().unwrap_or_default();
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(node);
+                    }
+                }
+            }
+        }
+    }
+
+    if stack.len() != 1 || stack[0].children.is_empty() {
+        return Err(ADBError::ParseError("无法解析 UI 层级".to_string()));
+    }
+    Ok(stack.remove(0))
+}
+
+impl ADB {
+    /// 查找应用最上层任务的 ID
+    pub fn find_task_id(&self, device_id: &str, package_name: &str) -> ADBResult<Option<u32>> {
+        let output = self.shell(device_id, "dumpsys activity activities")?;
+        Ok(TASK_RE
+            .captures_iter(&output)
+            .find(|caps| &caps[2] == package_name)
+            .and_then(|caps| caps[1].parse().ok()))
+    }
+
+    /// 以分屏模式启动两个应用，`primary_pkg` 位于主分屏
+    ///
+    /// 支持 Android 7.0 – 12 (API 24–31)；更新版本的分屏由 SystemUI 管理，无法通过 am 命令进入
+    pub fn enter_split_screen(
+        &self,
+        device_id: &str,
+        primary_pkg: &str,
+        secondary_pkg: &str,
+    ) -> ADBResult<()> {
+        let sdk = self.device_profile(device_id)?.sdk_int;
+        let primary = self.get_launch_activity(device_id, primary_pkg)?;
+        let secondary = self.get_launch_activity(device_id, secondary_pkg)?;
+
+        match sdk {
+            24..=27 => {
+                self.shell(device_id, &format!("am start -W -n {}", primary))?;
+                let task_id = self.find_task_id(device_id, primary_pkg)?.ok_or_else(|| {
+                    ADBError::AppNotFound(format!("找不到 {} 的任务", primary_pkg))
+                })?;
+                self.shell(
+                    device_id,
+                    &format!("am stack move-task {} {} true", task_id, DOCKED_STACK_ID),
+                )?;
+                self.shell(device_id, &format!("am start -W -n {}", secondary))?;
+            }
+            28..=31 => {
+                self.shell(
+                    device_id,
+                    &format!(
+                        "am start -W --windowingMode {} -n {}",
+                        WINDOWING_MODE_SPLIT_SCREEN_PRIMARY, primary
+                    ),
+                )?;
+                self.shell(
+                    device_id,
+                    &format!(
+                        "am start -W --windowingMode {} -n {}",
+                        WINDOWING_MODE_SPLIT_SCREEN_SECONDARY, secondary
+                    ),
+                )?;
+            }
+            _ => {
+                return Err(ADBError::DeviceError(format!(
+                    "设备 {} (SDK {}) 不支持通过命令进入分屏",
+                    device_id, sdk
+                )))
+            }
+        }
+
+        debug!("设备 {} 已进入分屏: {} | {}", device_id, primary_pkg, secondary_pkg);
+        Ok(())
+    }
+
+    /// 启用或禁用自由窗口模式
+    ///
+    /// 需要 Android 7.0 (API 24) 及以上，部分设备需要重启后生效
+    pub fn set_freeform_enabled(&self, device_id: &str, enabled: bool) -> ADBResult<()> {
+        let sdk = self.device_profile(device_id)?.sdk_int;
+        if sdk != 0 && sdk < 24 {
+            return Err(ADBError::DeviceError(format!(
+                "设备 {} (SDK {}) 不支持自由窗口模式",
+                device_id, sdk
+            )));
+        }
+
+        let value = if enabled { 1 } else { 0 };
+        self.shell(
+            device_id,
+            &format!(
+                "settings put global enable_freeform_support {v} && settings put global force_resizable_activities {v}",
+                v = value
+            ),
+        )?;
+
+        debug!("设备 {} 自由窗口模式: {}", device_id, enabled);
+        Ok(())
+    }
+
+    /// 调整任务窗口的位置和大小（自由窗口或分屏中的任务）
+    pub fn resize_task(&self, device_id: &str, task_id: u32, rect: Rect) -> ADBResult<()> {
+        let sdk = self.device_profile(device_id)?.sdk_int;
+        if sdk != 0 && sdk < 24 {
+            return Err(ADBError::DeviceError(format!(
+                "设备 {} (SDK {}) 不支持调整任务窗口",
+                device_id, sdk
+            )));
+        }
+
+        let output = self.shell(
+            device_id,
+            &format!(
+                "am task resize {} {} {} {} {}",
+                task_id, rect.left, rect.top, rect.right, rect.bottom
+            ),
+        )?;
+
+        if output.contains("Error") || output.contains("Exception") {
+            return Err(ADBError::CommandError(format!(
+                "调整任务 {} 失败: {}",
+                task_id,
+                output.trim()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// 获取当前界面的 UI 层级
+    ///
+    /// 在设备上执行 `uiautomator dump`，将 XML 拉取到主机后解析为 [`UiNode`] 树
+    pub fn dump_ui_hierarchy(&self, device_id: &str) -> ADBResult<UiNode> {
+        let mut host_resources = HostResourceManager::new();
+        let local_dir = host_resources.create_temp_dir("ui_dump")?;
+        let local_path = local_dir.join("window_dump.xml");
+
+        self.with_resources(device_id, |resources| {
+            let path = resources.create_temp_file("window_dump_", ".xml")?;
+            let output = self.shell(device_id, &format!("uiautomator dump {} 2>&1; true", path))?;
+            if !output.contains("dumped to") {
+                return Err(ADBError::CommandError(format!(
+                    "uiautomator dump 失败: {}",
+                    output.trim()
+                )));
+            }
+            self.pull(device_id, &path, &local_path.to_string_lossy(), None)
+        })?;
+
+        let xml = std::fs::read_to_string(&local_path)
+            .map_err(|e| ADBError::FileError(format!("无法读取 UI 层级文件: {}", e)))?;
+        parse_ui_hierarchy(&xml)
+    }
+
+    /// 查找当前界面中第一个满足选择器的可见节点
+    pub fn find_element(&self, device_id: &str, selector: &Selector) -> ADBResult<Option<UiNode>> {
+        let root = self.dump_ui_hierarchy(device_id)?;
+        Ok(root
+            .find_all(selector)
+            .into_iter()
+            .find(|n| n.is_visible())
+            .cloned())
+    }
+
+    /// 按文本查找当前界面中的可见节点
+    pub fn find_element_by_text(&self, device_id: &str, text: &str) -> ADBResult<Option<UiNode>> {
+        self.find_element(device_id, &Selector::text(text))
+    }
+
+    /// 点击节点中心
+    pub fn tap_element(&self, device_id: &str, node: &UiNode) -> ADBResult<()> {
+        if !node.is_visible() {
+            return Err(ADBError::DeviceError(format!(
+                "节点不可见，无法点击: {} {:?}",
+                node.class, node.bounds
+            )));
+        }
+
+        let (x, y) = node.bounds.center();
+        self.tap(device_id, x, y)
+    }
+
+    /// 查找满足选择器的节点并点击，返回被点击的节点
+    pub fn tap_selector(&self, device_id: &str, selector: &Selector) -> ADBResult<UiNode> {
+        let node = self.find_element(device_id, selector)?.ok_or_else(|| {
+            ADBError::DeviceError(format!("未找到满足条件的节点: {:?}", selector))
+        })?;
+        self.tap_element(device_id, &node)?;
+        Ok(node)
+    }
+
+    /// 反复滑动并重新获取 UI 层级，直到找到满足选择器的节点
+    ///
+    /// 在第一个可滚动容器内滑动（没有时使用整个屏幕），最多滑动 `max_swipes` 次；
+    /// 滑动后界面不再变化说明已到达尽头，提前返回 `None`
+    pub fn scroll_until_visible(
+        &self,
+        device_id: &str,
+        selector: &Selector,
+        direction: ScrollDirection,
+        max_swipes: u32,
+    ) -> ADBResult<Option<UiNode>> {
+        let mut previous: Option<UiNode> = None;
+
+        for swipe in 0..=max_swipes {
+            let root = self.dump_ui_hierarchy(device_id)?;
+            if let Some(node) = root.find(selector).filter(|n| n.is_visible()) {
+                debug!("第 {} 次滑动后找到节点: {:?}", swipe, selector);
+                return Ok(Some(node.clone()));
+            }
+
+            if previous.as_ref() == Some(&root) {
+                debug!("界面已滚动到尽头，未找到节点: {:?}", selector);
+                return Ok(None);
+            }
+            if swipe == max_swipes {
+                break;
+            }
+
+            let area = root
+                .find_all(&Selector::default())
+                .into_iter()
+                .find(|n| n.scrollable && n.is_visible())
+                .map(|n| n.bounds)
+                .unwrap_or(root.children[0].bounds);
+
+            let (cx, cy) = area.center();
+            let (dx, dy) = (area.width() * 3 / 10, area.height() * 3 / 10);
+            let (from, to) = match direction {
+                ScrollDirection::Down => ((cx, cy + dy), (cx, cy - dy)),
+                ScrollDirection::Up => ((cx, cy - dy), (cx, cy + dy)),
+                ScrollDirection::Right => ((cx + dx, cy), (cx - dx, cy)),
+                ScrollDirection::Left => ((cx - dx, cy), (cx + dx, cy)),
+            };
+            self.swipe(device_id, from.0, from.1, to.0, to.1, 400)?;
+            thread::sleep(Duration::from_millis(300));
+
+            previous = Some(root);
+        }
+
+        Ok(None)
+    }
+
+    /// 在后台持续监视 UI 层级，按规则自动点击已知的对话框按钮
+    ///
+    /// 用于无人值守的长时间运行，避免被权限请求、崩溃对话框或系统更新提示卡住。
+    /// 运行 `duration` 后自动停止。
+    pub fn auto_respond_dialogs(
+        &self,
+        device_id: &str,
+        rules: Vec<DialogRule>,
+        duration: Duration,
+    ) -> ResponderHandle {
+        let stop = Arc::new(AtomicBool::new(false));
+        self.jobs.register_stop_flag(stop.clone());
+        let responses: Arc<Mutex<Vec<DialogResponse>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let worker = {
+            let adb = self.clone();
+            let device_id = device_id.to_string();
+            let stop = stop.clone();
+            let responses = responses.clone();
+
+            thread::spawn(move || {
+                let deadline = Instant::now() + duration;
+
+                while Instant::now() < deadline && !stop.load(Ordering::SeqCst) {
+                    let root = match adb.dump_ui_hierarchy(&device_id) {
+                        Ok(root) => root,
+                        Err(e) => {
+                            debug!("设备 {} 获取 UI 层级失败: {}", device_id, e);
+                            if !sleep_unless_stopped(DIALOG_POLL_INTERVAL, &stop) {
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+
+                    let hit = rules
+                        .iter()
+                        .find_map(|rule| rule.find_button(&root).map(|button| (rule, button)));
+                    match hit {
+                        Some((rule, button)) => {
+                            let label = if button.text.is_empty() {
+                                button.resource_id.clone()
+                            } else {
+                                button.text.clone()
+                            };
+                            match adb.tap_element(&device_id, button) {
+                                Ok(()) => {
+                                    info!("设备 {} 自动处理对话框 {}: 点击 {}", device_id, rule.name, label);
+                                    responses.lock().unwrap().push(DialogResponse {
+                                        rule: rule.name.clone(),
+                                        button: label,
+                                        timestamp: SystemTime::now(),
+                                    });
+                                }
+                                Err(e) => warn!("设备 {} 点击 {} 失败: {}", device_id, label, e),
+                            }
+                            // 等待对话框消失后再检查下一个
+                            thread::sleep(Duration::from_millis(500));
+                        }
+                        None => {
+                            if !sleep_unless_stopped(DIALOG_POLL_INTERVAL, &stop) {
+                                break;
+                            }
+                        }
+                    }
+                }
+            })
+        };
+
+        ResponderHandle {
+            stop,
+            responses,
+            worker: Some(worker),
+        }
+    }
+}
@@ -155,6 +155,9 @@ pub fn cleanup_partial_files(path_pattern: &str) -> ADBResult<()> {
     Ok(())
 }
 
+/// 本库在主机临时目录中创建的目录名前缀
+pub const HOST_TEMP_PREFIX: &str = "adbkit-";
+
 /// 创建临时目录
 pub fn create_temp_dir_path(prefix: &str) -> ADBResult<PathBuf> {
     let temp_dir = std::env::temp_dir();
@@ -165,7 +168,8 @@ pub fn create_temp_dir_path(prefix: &str) -> ADBResult<PathBuf> {
         .map(char::from)
         .collect();
 
-    let dir_name = format!("{}_{}", prefix, random_string);
+    // 名称中包含进程 ID，便于 sweep_host_orphans 识别崩溃进程遗留的目录
+    let dir_name = format!("{}{}-{}_{}", HOST_TEMP_PREFIX, std::process::id(), prefix, random_string);
     let full_path = temp_dir.join(dir_name);
 
     // 确保目录存在
@@ -266,4 +270,9 @@ pub fn format_duration(seconds: u64) -> String {
     let secs = seconds % 60;
 
     format!("{:02}:{:02}:{:02}", hours, minutes, secs)
-}
\ No newline at end of file
+}
+
+/// 使用单引号转义 shell 参数
+pub fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
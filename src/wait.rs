@@ -0,0 +1,287 @@
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use log::{debug, info, warn};
+use regex::Regex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 默认轮询间隔（毫秒）
+const DEFAULT_POLL_INTERVAL: u64 = 500;
+
+/// 可组合的等待条件
+///
+/// 通过 `And` / `Or` 组合多个条件，配合 [`ADB::wait_until`] 使用
+#[derive(Debug, Clone)]
+pub enum Condition {
+    /// 设备处于在线状态
+    DeviceOnline,
+    /// 设备属性等于指定值
+    PropEquals { name: String, value: String },
+    /// 包（或进程名）正在运行
+    PackageRunning(String),
+    /// 设备上的文件存在
+    FileExists(String),
+    /// 设备上的 TCP 端口处于监听状态
+    PortListening(u16),
+    /// 当前焦点窗口包含指定的包名或 Activity
+    ActivityFocused(String),
+    /// 日志中有匹配正则表达式的行
+    ///
+    /// 通过 [`ADB::wait_until`] 等待时只匹配开始等待之后的日志，
+    /// 直接调用 [`ADB::check_condition`] 时匹配整个缓冲区
+    LogcatMatches(String),
+    /// 所有子条件均满足
+    And(Vec<Condition>),
+    /// 任一子条件满足
+    Or(Vec<Condition>),
+    /// 子条件不满足
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// 设备属性等于指定值
+    pub fn prop_equals(name: &str, value: &str) -> Self {
+        Condition::PropEquals {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// 包正在运行
+    pub fn package_running(package_name: &str) -> Self {
+        Condition::PackageRunning(package_name.to_string())
+    }
+
+    /// 文件存在
+    pub fn file_exists(path: &str) -> Self {
+        Condition::FileExists(path.to_string())
+    }
+
+    /// 端口处于监听状态
+    pub fn port_listening(port: u16) -> Self {
+        Condition::PortListening(port)
+    }
+
+    /// 焦点窗口包含指定内容
+    pub fn activity_focused(pattern: &str) -> Self {
+        Condition::ActivityFocused(pattern.to_string())
+    }
+
+    /// 日志匹配正则表达式
+    pub fn logcat_matches(pattern: &str) -> Self {
+        Condition::LogcatMatches(pattern.to_string())
+    }
+
+    /// 与另一个条件组合（同时满足）
+    pub fn and(self, other: Condition) -> Self {
+        match self {
+            Condition::And(mut conditions) => {
+                conditions.push(other);
+                Condition::And(conditions)
+            }
+            _ => Condition::And(vec![self, other]),
+        }
+    }
+
+    /// 与另一个条件组合（任一满足）
+    pub fn or(self, other: Condition) -> Self {
+        match self {
+            Condition::Or(mut conditions) => {
+                conditions.push(other);
+                Condition::Or(conditions)
+            }
+            _ => Condition::Or(vec![self, other]),
+        }
+    }
+
+    /// 取反（条件不满足时成立）
+    pub fn negate(self) -> Self {
+        match self {
+            Condition::Not(inner) => *inner,
+            _ => Condition::Not(Box::new(self)),
+        }
+    }
+
+    /// 条件中是否包含日志匹配
+    fn uses_logcat(&self) -> bool {
+        match self {
+            Condition::LogcatMatches(_) => true,
+            Condition::And(conditions) | Condition::Or(conditions) => {
+                conditions.iter().any(Condition::uses_logcat)
+            }
+            Condition::Not(inner) => inner.uses_logcat(),
+            _ => false,
+        }
+    }
+}
+
+/// 检查 /proc/net/tcp 输出中是否有指定端口处于 LISTEN 状态
+fn proc_net_has_listener(output: &str, port: u16) -> bool {
+    let port_hex = format!("{:04X}", port);
+
+    for line in output.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 4 {
+            continue;
+        }
+
+        // local_address 形如 0100007F:1F90，st 为 0A 表示 LISTEN
+        let local_port = parts[1].rsplit(':').next().unwrap_or("");
+        if local_port.eq_ignore_ascii_case(&port_hex) && parts[3] == "0A" {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// 轮询时可以忽略、下次重试的错误：命令失败、设备暂时离线等。
+/// 设备不存在、参数或解析错误等会原样返回，不会表现为超时。
+fn is_transient(error: &ADBError) -> bool {
+    match error {
+        ADBError::DeviceError(message) | ADBError::CommandError(message) => {
+            !message.contains("not found") && !message.contains("no devices")
+        }
+        ADBError::TimeoutError { .. } | ADBError::ConnectionError(_) => true,
+        _ => false,
+    }
+}
+
+impl ADB {
+    /// 检查条件当前是否满足
+    pub fn check_condition(&self, device_id: &str, condition: &Condition) -> ADBResult<bool> {
+        self.check_condition_since(device_id, condition, None)
+    }
+
+    /// 检查条件，`log_since` 为设备纪元秒，指定时日志条件只匹配此后的日志
+    fn check_condition_since(
+        &self,
+        device_id: &str,
+        condition: &Condition,
+        log_since: Option<&str>,
+    ) -> ADBResult<bool> {
+        match condition {
+            Condition::DeviceOnline => self.is_device_online(device_id),
+            Condition::PropEquals { name, value } => {
+                Ok(self.get_prop(device_id, name)? == *value)
+            }
+            Condition::PackageRunning(package_name) => {
+                Ok(self.get_pid(device_id, package_name)?.is_some())
+            }
+            Condition::FileExists(path) => self.file_exists(device_id, path),
+            Condition::PortListening(port) => {
+                let output = self.shell(
+                    device_id,
+                    "cat /proc/net/tcp /proc/net/tcp6 2>/dev/null",
+                )?;
+                Ok(proc_net_has_listener(&output, *port))
+            }
+            Condition::ActivityFocused(pattern) => {
+                let output = self.shell(
+                    device_id,
+                    "dumpsys window windows | grep -E 'mCurrentFocus'; true",
+                )?;
+                Ok(output.contains(pattern.as_str()))
+            }
+            Condition::LogcatMatches(pattern) => {
+                let re = Regex::new(pattern)?;
+                let command = match log_since {
+                    Some(since) => format!("logcat -d -T '{}'", since),
+                    None => "logcat -d".to_string(),
+                };
+                let output = self.shell(device_id, &command)?;
+                Ok(output.lines().any(|line| re.is_match(line)))
+            }
+            Condition::And(conditions) => {
+                for c in conditions {
+                    if !self.check_condition_since(device_id, c, log_since)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Condition::Or(conditions) => {
+                for c in conditions {
+                    match self.check_condition_since(device_id, c, log_since) {
+                        Ok(true) => return Ok(true),
+                        Ok(false) => {}
+                        Err(e) if is_transient(&e) => debug!("检查子条件 {:?} 时出错: {}", c, e),
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(false)
+            }
+            Condition::Not(inner) => Ok(!self.check_condition_since(device_id, inner, log_since)?),
+        }
+    }
+
+    /// 等待条件满足
+    ///
+    /// # 参数
+    /// * `device_id` - 设备 ID
+    /// * `condition` - 等待的条件
+    /// * `timeout` - 最长等待时间
+    ///
+    /// # 返回值
+    ///
+    /// 条件在超时前满足返回 `true`，超时返回 `false`。检查时的临时错误会在下次轮询时重试，
+    /// 设备不存在等其他错误直接返回。
+    pub fn wait_until(
+        &self,
+        device_id: &str,
+        condition: &Condition,
+        timeout: Duration,
+    ) -> ADBResult<bool> {
+        self.wait_until_with_interval(
+            device_id,
+            condition,
+            timeout,
+            Duration::from_millis(DEFAULT_POLL_INTERVAL),
+        )
+    }
+
+    /// 以指定的轮询间隔等待条件满足
+    pub fn wait_until_with_interval(
+        &self,
+        device_id: &str,
+        condition: &Condition,
+        timeout: Duration,
+        interval: Duration,
+    ) -> ADBResult<bool> {
+        // 提前校验正则表达式，避免在轮询中反复报错
+        if let Condition::LogcatMatches(pattern) = condition {
+            Regex::new(pattern)?;
+        }
+
+        debug!("等待设备 {} 满足条件: {:?}", device_id, condition);
+
+        // 记录开始等待时的设备时间，日志条件只匹配此后输出的行
+        let log_since = if condition.uses_logcat() {
+            Some(format!("{}.000", self.shell(device_id, "date +%s")?.trim()))
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+        let result = loop {
+            match self.check_condition_since(device_id, condition, log_since.as_deref()) {
+                Ok(true) => break true,
+                Ok(false) => {}
+                Err(e) if is_transient(&e) => debug!("检查条件时出错，稍后重试: {}", e),
+                Err(e) => return Err(e),
+            }
+            if start.elapsed() >= timeout {
+                break false;
+            }
+            thread::sleep(interval);
+        };
+
+        if result {
+            info!("设备 {} 已满足条件: {:?}", device_id, condition);
+        } else {
+            warn!("等待设备 {} 条件超时 ({:?}): {:?}", device_id, timeout, condition);
+        }
+
+        Ok(result)
+    }
+}
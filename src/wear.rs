@@ -0,0 +1,154 @@
+//! Wear OS 手表
+//!
+//! 手表的物理按键、旋转表冠和微光（ambient）模式，以及手表模拟器与手机的配对。
+//! 手表的 shell 响应明显慢于手机，等待状态变化时使用按设备形态放大的超时
+//! （见 [`ADB::device_timeout`]）。
+
+use crate::device::ADB;
+use crate::error::{ADBError, ADBResult};
+use crate::inventory::DeviceClass;
+use log::{debug, info};
+
+// 手表模拟器配对使用的端口
+const WEAR_PAIRING_PORT: u16 = 5601;
+// Wear OS 配套应用
+const WEAR_COMPANION_PACKAGE: &str = "com.google.android.wearable.app";
+// 等待微光状态切换时的轮询间隔（毫秒）
+const AMBIENT_POLL_INTERVAL: u64 = 500;
+
+/// 手表物理按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WearButton {
+    /// 主按键（表冠按压）
+    Primary,
+    Stem1,
+    Stem2,
+    Stem3,
+}
+
+impl WearButton {
+    /// 数值按键码 (`KEYCODE_STEM_*`)
+    pub fn code(&self) -> u32 {
+        match self {
+            WearButton::Primary => 264,
+            WearButton::Stem1 => 265,
+            WearButton::Stem2 => 266,
+            WearButton::Stem3 => 267,
+        }
+    }
+}
+
+impl ADB {
+    /// 设备是否为 Wear OS 手表
+    pub fn is_watch(&self, device_id: &str) -> ADBResult<bool> {
+        Ok(self.device_class(device_id)? == DeviceClass::Watch)
+    }
+
+    /// 将手机与手表模拟器配对
+    ///
+    /// 在手机上转发配对端口并打开 Wear OS 配套应用，之后需要在应用中选择“与模拟器配对”。
+    pub fn pair_with_phone_emulator(&self, phone_id: &str, watch_id: &str) -> ADBResult<()> {
+        if !self.is_watch(watch_id)? {
+            return Err(ADBError::DeviceError(format!("设备 {} 不是 Wear OS 手表", watch_id)));
+        }
+        if self.device_class(phone_id)? == DeviceClass::Watch {
+            return Err(ADBError::DeviceError(format!("设备 {} 不是手机", phone_id)));
+        }
+
+        self.forward(phone_id, WEAR_PAIRING_PORT, WEAR_PAIRING_PORT)?;
+        if !self.start_app(phone_id, WEAR_COMPANION_PACKAGE, None)? {
+            return Err(ADBError::AppNotFound(format!(
+                "手机 {} 未安装 Wear OS 配套应用 {}",
+                phone_id, WEAR_COMPANION_PACKAGE
+            )));
+        }
+
+        info!("手机 {} 已准备与手表模拟器 {} 配对", phone_id, watch_id);
+        Ok(())
+    }
+
+    /// 按下手表按键
+    pub fn press_wear_button(&self, device_id: &str, button: WearButton) -> ADBResult<()> {
+        self.shell(device_id, &format!("input keyevent {}", button.code()))?;
+        debug!("手表 {} 按键 {:?}", device_id, button);
+        Ok(())
+    }
+
+    /// 长按手表按键
+    pub fn long_press_wear_button(&self, device_id: &str, button: WearButton) -> ADBResult<()> {
+        self.shell(device_id, &format!("input keyevent --longpress {}", button.code()))?;
+        Ok(())
+    }
+
+    /// 旋转表冠，`delta` 为正时向下滚动
+    ///
+    /// 需要支持 `input rotaryencoder` 的系统（Wear OS 3 起）
+    pub fn rotate_crown(&self, device_id: &str, delta: f32) -> ADBResult<()> {
+        let output = self.shell(
+            device_id,
+            &format!("input rotaryencoder scroll --axis SCROLL:{}", delta),
+        )?;
+        if output.contains("Error") || output.contains("Unknown") {
+            return Err(ADBError::CommandError(format!(
+                "手表 {} 不支持旋转输入: {}",
+                device_id,
+                output.trim()
+            )));
+        }
+        debug!("手表 {} 表冠旋转 {}", device_id, delta);
+        Ok(())
+    }
+
+    /// 设置是否允许进入微光模式（屏幕常亮）
+    pub fn set_always_on(&self, device_id: &str, enabled: bool) -> ADBResult<()> {
+        self.shell(
+            device_id,
+            &format!("settings put global ambient_enabled {}", if enabled { 1 } else { 0 }),
+        )?;
+        Ok(())
+    }
+
+    /// 手表是否处于微光模式
+    pub fn is_ambient(&self, device_id: &str) -> ADBResult<bool> {
+        let output = self.shell(device_id, "dumpsys power | grep mWakefulness=")?;
+        Ok(output.contains("mWakefulness=Dozing"))
+    }
+
+    /// 让手表进入微光模式并等待切换完成
+    ///
+    /// 需要先通过 [`ADB::set_always_on`] 允许微光模式，否则手表会直接熄屏。
+    pub fn enter_ambient(&self, device_id: &str) -> ADBResult<()> {
+        self.shell(device_id, "input keyevent KEYCODE_SLEEP")?;
+        self.wait_ambient(device_id, true)
+    }
+
+    /// 唤醒手表退出微光模式并等待切换完成
+    pub fn exit_ambient(&self, device_id: &str) -> ADBResult<()> {
+        self.shell(device_id, "input keyevent KEYCODE_WAKEUP")?;
+        self.wait_ambient(device_id, false)
+    }
+
+    /// 等待微光状态切换，超时按设备形态放大
+    fn wait_ambient(&self, device_id: &str, ambient: bool) -> ADBResult<()> {
+        let timeout = self.device_timeout(device_id);
+        let reached = crate::utils::wait_with_polling(
+            timeout.as_millis() as u64,
+            AMBIENT_POLL_INTERVAL,
+            || Ok(self.is_ambient(device_id)? == ambient),
+            None::<fn(u64)>,
+        )?;
+        if !reached {
+            return Err(ADBError::TimeoutError {
+                message: format!(
+                    "手表 {} 未能{}微光模式",
+                    device_id,
+                    if ambient { "进入" } else { "退出" }
+                ),
+                duration: timeout,
+            });
+        }
+
+        debug!("手表 {} 微光模式: {}", device_id, ambient);
+        Ok(())
+    }
+}